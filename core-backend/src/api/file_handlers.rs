@@ -15,7 +15,7 @@
 
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::io;
+use std::io::{self, Write};
 use tracing::{info, warn, error, instrument};
 
 use crate::utils::error_handling::{Result, AppError, IoError, ValidationError};
@@ -62,6 +62,11 @@ impl FileHandler {
         Self { config }
     }
     
+    /// Configuração em uso
+    pub fn config(&self) -> &FileHandlerConfig {
+        &self.config
+    }
+    
     /// Verifica se um arquivo existe de forma segura
     #[instrument(name = "file_exists", skip(self))]
     pub fn file_exists(&self, path: &str) -> Result<bool> {
@@ -118,6 +123,41 @@ impl FileHandler {
         Ok(())
     }
     
+    /// Grava um arquivo de forma segura, substituindo o conteúdo existente
+    #[instrument(name = "write_file", skip(self, bytes))]
+    pub fn write_file(&self, path: &str, bytes: &[u8]) -> Result<()> {
+        let path_buf = self.validate_path(path)?;
+        
+        fs::write(&path_buf, bytes)
+            .map_err(|e| Self::write_failed(path_buf, e))?;
+        
+        info!(path = %path, size = bytes.len(), "File written successfully");
+        Ok(())
+    }
+    
+    /// Cria um arquivo novo para escrita, falhando se o path já existir (nunca sobrescreve)
+    #[instrument(name = "create_new_file", skip(self))]
+    pub fn create_new_file(&self, path: &str) -> Result<fs::File> {
+        let path_buf = self.validate_path(path)?;
+        
+        fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path_buf)
+            .map_err(|e| Self::write_failed(path_buf, e))
+    }
+    
+    /// Grava um arquivo novo, falhando se o path já existir (nunca sobrescreve)
+    #[instrument(name = "write_new_file", skip(self, bytes))]
+    pub fn write_new_file(&self, path: &str, bytes: &[u8]) -> Result<()> {
+        self.create_new_file(path)?
+            .write_all(bytes)
+            .map_err(|e| Self::write_failed(PathBuf::from(path), e))?;
+        
+        info!(path = %path, size = bytes.len(), "File written successfully");
+        Ok(())
+    }
+    
     /// Cria um diretório temporário único
     #[instrument(name = "create_temp_dir", skip(self))]
     pub fn create_temp_dir(&self, prefix: Option<&str>) -> Result<PathBuf> {
@@ -355,6 +395,12 @@ impl FileHandler {
         Ok(path_buf)
     }
     
+    /// Mapeia uma falha de gravação para `IoError::WriteFailed`
+    fn write_failed(path: PathBuf, source: io::Error) -> AppError {
+        error!(path = %path.display(), error = %source, "Failed to write file");
+        AppError::Io(IoError::WriteFailed { path, source })
+    }
+    
     /// Verifica se um path absoluto é seguro
     fn is_safe_absolute_path(&self, path: &Path) -> bool {
         // Por padrão, permite apenas alguns diretórios conhecidos
@@ -492,6 +538,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_write_new_file_never_overwrites() -> Result<()> {
+        let handler = FileHandler::new();
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("out.txt");
+        let path = path.to_str().unwrap();
+        
+        handler.write_new_file(path, b"first")?;
+        assert!(matches!(
+            handler.write_new_file(path, b"second"),
+            Err(AppError::Io(IoError::WriteFailed { .. }))
+        ));
+        assert_eq!(fs::read(path)?, b"first");
+        
+        handler.write_file(path, b"third")?;
+        assert_eq!(fs::read(path)?, b"third");
+        
+        Ok(())
+    }
+
     #[test]
     fn test_list_files() -> Result<()> {
         let handler = FileHandler::new();
@@ -20,6 +20,18 @@ impl RouteHandler for PdfHandler {
             ApiAction::Split => self.handle_split(request).await,
             ApiAction::Validate => self.handle_validate(request).await,
             ApiAction::GetMetadata => self.handle_metadata(request).await,
+            ApiAction::Sanitize => self.handle_sanitize(request).await,
+            ApiAction::ListAnnotations => self.handle_list_annotations(request).await,
+            ApiAction::AnalyzeRevisions => self.handle_analyze_revisions(request).await,
+            ApiAction::VerifySignatures => self.handle_verify_signatures(request).await,
+            ApiAction::Sign => self.handle_sign(request).await,
+            ApiAction::ListAttachments => self.handle_list_attachments(request).await,
+            ApiAction::ExtractAttachments => self.handle_extract_attachments(request).await,
+            ApiAction::AddAttachment => self.handle_add_attachment(request).await,
+            ApiAction::RemoveAttachments => self.handle_remove_attachments(request).await,
+            ApiAction::SetMetadata => self.handle_set_metadata(request).await,
+            ApiAction::SetMetadataBulk => self.handle_set_metadata_bulk(request).await,
+            ApiAction::ExtractText => self.handle_extract_text(request).await,
             _ => Err(ApiError::unknown_action(&format!("{:?}", request.action)).into()),
         }
     }
@@ -54,6 +66,78 @@ impl PdfHandler {
         // TODO: Implementar extração de metadados usando processador
         Ok(ApiResponse::success(&request.request_id, serde_json::json!({"status": "not_implemented"})))
     }
+
+    /// Trata operação de sanitização de PDFs
+    pub async fn handle_sanitize(&self, request: ApiRequest) -> Result<ApiResponse> {
+        // TODO: Implementar sanitização usando processador
+        Ok(ApiResponse::success(&request.request_id, serde_json::json!({"status": "not_implemented"})))
+    }
+
+    /// Trata operação de listagem de anotações
+    pub async fn handle_list_annotations(&self, request: ApiRequest) -> Result<ApiResponse> {
+        // TODO: Implementar listagem de anotações usando processador
+        Ok(ApiResponse::success(&request.request_id, serde_json::json!({"status": "not_implemented"})))
+    }
+
+    /// Trata operação de análise do histórico de revisões
+    pub async fn handle_analyze_revisions(&self, request: ApiRequest) -> Result<ApiResponse> {
+        // TODO: Implementar análise de revisões usando processador
+        Ok(ApiResponse::success(&request.request_id, serde_json::json!({"status": "not_implemented"})))
+    }
+
+    /// Trata operação de verificação de assinaturas digitais
+    pub async fn handle_verify_signatures(&self, request: ApiRequest) -> Result<ApiResponse> {
+        // TODO: Implementar verificação de assinaturas usando processador
+        Ok(ApiResponse::success(&request.request_id, serde_json::json!({"status": "not_implemented"})))
+    }
+
+    /// Trata operação de assinatura digital
+    pub async fn handle_sign(&self, request: ApiRequest) -> Result<ApiResponse> {
+        // TODO: Implementar assinatura usando processador
+        Ok(ApiResponse::success(&request.request_id, serde_json::json!({"status": "not_implemented"})))
+    }
+
+    /// Trata operação de listagem de anexos
+    pub async fn handle_list_attachments(&self, request: ApiRequest) -> Result<ApiResponse> {
+        // TODO: Implementar listagem de anexos usando processador
+        Ok(ApiResponse::success(&request.request_id, serde_json::json!({"status": "not_implemented"})))
+    }
+
+    /// Trata operação de extração de anexos
+    pub async fn handle_extract_attachments(&self, request: ApiRequest) -> Result<ApiResponse> {
+        // TODO: Implementar extração de anexos usando processador
+        Ok(ApiResponse::success(&request.request_id, serde_json::json!({"status": "not_implemented"})))
+    }
+
+    /// Trata operação de inclusão de anexo
+    pub async fn handle_add_attachment(&self, request: ApiRequest) -> Result<ApiResponse> {
+        // TODO: Implementar inclusão de anexo usando processador
+        Ok(ApiResponse::success(&request.request_id, serde_json::json!({"status": "not_implemented"})))
+    }
+
+    /// Trata operação de remoção de anexos
+    pub async fn handle_remove_attachments(&self, request: ApiRequest) -> Result<ApiResponse> {
+        // TODO: Implementar remoção de anexos usando processador
+        Ok(ApiResponse::success(&request.request_id, serde_json::json!({"status": "not_implemented"})))
+    }
+
+    /// Trata operação de edição de metadados
+    pub async fn handle_set_metadata(&self, request: ApiRequest) -> Result<ApiResponse> {
+        // TODO: Implementar edição de metadados usando processador
+        Ok(ApiResponse::success(&request.request_id, serde_json::json!({"status": "not_implemented"})))
+    }
+
+    /// Trata operação de edição de metadados em lote
+    pub async fn handle_set_metadata_bulk(&self, request: ApiRequest) -> Result<ApiResponse> {
+        // TODO: Implementar edição de metadados em lote usando processador
+        Ok(ApiResponse::success(&request.request_id, serde_json::json!({"status": "not_implemented"})))
+    }
+
+    /// Trata operação de extração de texto
+    pub async fn handle_extract_text(&self, request: ApiRequest) -> Result<ApiResponse> {
+        // TODO: Implementar extração de texto usando processador
+        Ok(ApiResponse::success(&request.request_id, serde_json::json!({"status": "not_implemented"})))
+    }
 }
 
 /// Handler para operações com arquivos
@@ -117,6 +117,96 @@ impl ApiContext {
             pdf_handler.clone(),
         )).await?;
         
+        self.router.register(Route::new(
+            ApiAction::SetMetadata,
+            "PUT",
+            "/api/v1/pdf/metadata",
+            pdf_handler.clone(),
+        )).await?;
+        
+        self.router.register(Route::new(
+            ApiAction::SetMetadataBulk,
+            "PUT",
+            "/api/v1/pdf/metadata/bulk",
+            pdf_handler.clone(),
+        )).await?;
+        
+        self.router.register(Route::new(
+            ApiAction::ExtractText,
+            "POST",
+            "/api/v1/pdf/text",
+            pdf_handler.clone(),
+        )).await?;
+        
+        // Sanitize
+        self.router.register(Route::new(
+            ApiAction::Sanitize,
+            "POST",
+            "/api/v1/pdf/sanitize",
+            pdf_handler.clone(),
+        )).await?;
+        
+        // Annotations
+        self.router.register(Route::new(
+            ApiAction::ListAnnotations,
+            "GET",
+            "/api/v1/pdf/annotations",
+            pdf_handler.clone(),
+        )).await?;
+        
+        // Revisions
+        self.router.register(Route::new(
+            ApiAction::AnalyzeRevisions,
+            "POST",
+            "/api/v1/pdf/revisions",
+            pdf_handler.clone(),
+        )).await?;
+        
+        // Signatures
+        self.router.register(Route::new(
+            ApiAction::VerifySignatures,
+            "POST",
+            "/api/v1/pdf/signatures",
+            pdf_handler.clone(),
+        )).await?;
+        
+        // Sign
+        self.router.register(Route::new(
+            ApiAction::Sign,
+            "POST",
+            "/api/v1/pdf/sign",
+            pdf_handler.clone(),
+        )).await?;
+        
+        // Attachments
+        self.router.register(Route::new(
+            ApiAction::ListAttachments,
+            "GET",
+            "/api/v1/pdf/attachments",
+            pdf_handler.clone(),
+        )).await?;
+        
+        self.router.register(Route::new(
+            ApiAction::ExtractAttachments,
+            "POST",
+            "/api/v1/pdf/attachments/extract",
+            pdf_handler.clone(),
+        )).await?;
+        
+        self.router.register(Route::new(
+            ApiAction::AddAttachment,
+            "POST",
+            "/api/v1/pdf/attachments",
+            pdf_handler.clone(),
+        )).await?;
+        
+        self.router.register(Route::new(
+            ApiAction::RemoveAttachments,
+            "DELETE",
+            "/api/v1/pdf/attachments",
+            pdf_handler.clone(),
+        )).await?;
+        
         // ==================== ROTAS DE ARQUIVO ====================
         
         // List files
@@ -324,7 +414,7 @@ mod tests {
         let config_manager = Arc::new(crate::utils::config::ConfigManager::new().await?);
         let context = ApiContext::new(config_manager).await?;
         
-        assert!(context.router.route_count() > 0);
+        assert!(context.router.route_count().await > 0);
         assert!(context.middleware.count() > 0);
         
         Ok(())
@@ -79,6 +79,18 @@ pub async fn process_command(action: String, data: Value) -> Result<Value> {
         "split" => handle_split(data).await,
         "validate" => handle_validate(data).await,
         "get_metadata" => handle_get_metadata(data).await,
+        "sanitize" => handle_sanitize(data).await,
+        "list_annotations" => handle_list_annotations(data).await,
+        "analyze_revisions" => handle_analyze_revisions(data).await,
+        "verify_signatures" => handle_verify_signatures(data).await,
+        "sign" => handle_sign(data).await,
+        "list_attachments" => handle_list_attachments(data).await,
+        "extract_attachments" => handle_extract_attachments(data).await,
+        "add_attachment" => handle_add_attachment(data).await,
+        "remove_attachments" => handle_remove_attachments(data).await,
+        "set_metadata" => handle_set_metadata(data).await,
+        "set_metadata_bulk" => handle_set_metadata_bulk(data).await,
+        "extract_text" => handle_extract_text(data).await,
         "health_check" => Ok(json!({"status": "ok", "version": "0.1.0"})),
         _ => Err(AppError::unknown_action(&action)),
     }
@@ -88,12 +100,12 @@ pub async fn process_command(action: String, data: Value) -> Result<Value> {
 async fn handle_merge(data: Value) -> Result<Value> {
     tracing::info!("Handling merge request");
     
-    // Valida e converte os dados de entrada
-    let merge_request = processors::pdf_merger::MergeRequest::from_value(&data)
+    // Valida os dados de entrada antes de iniciar o processamento
+    processors::pdf_merger::MergeRequest::from_value(&data)
         .map_err(|e| AppError::validation(format!("Invalid merge request: {}", e)))?;
     
     // Executa o processamento (assíncrono)
-    let result = processors::pdf_merger::merge_pdfs(serde_json::to_value(merge_request).unwrap())
+    let result = processors::pdf_merger::merge_pdfs(data, processor_runtime().await)
         .await
         .map_err(|e| AppError::processing(format!("Merge failed: {}", e)))?;
     
@@ -106,7 +118,7 @@ async fn handle_merge(data: Value) -> Result<Value> {
 async fn handle_split(data: Value) -> Result<Value> {
     tracing::info!("Handling split request");
     
-    let result = processors::pdf_splitter::split_pdf(data)
+    let result = processors::pdf_splitter::split_pdf(data, processor_runtime().await)
         .await
         .map_err(|e| AppError::processing(format!("Split failed: {}", e)))?;
     
@@ -117,7 +129,7 @@ async fn handle_split(data: Value) -> Result<Value> {
 async fn handle_validate(data: Value) -> Result<Value> {
     tracing::debug!("Handling validate request");
     
-    let result = processors::pdf_validator::validate_pdf(data)
+    let result = processors::pdf_validator::validate_pdf(data, processor_runtime().await)
         .await
         .map_err(|e| AppError::processing(format!("Validation failed: {}", e)))?;
     
@@ -128,13 +140,161 @@ async fn handle_validate(data: Value) -> Result<Value> {
 async fn handle_get_metadata(data: Value) -> Result<Value> {
     tracing::debug!("Handling get_metadata request");
     
-    let result = processors::pdf_validator::get_pdf_metadata(data)
+    let result = processors::pdf_validator::get_pdf_metadata(data, processor_runtime().await)
         .await
         .map_err(|e| AppError::processing(format!("Failed to get metadata: {}", e)))?;
     
     Ok(result)
 }
 
+/// Handler para sanitização de PDF (assíncrono)
+async fn handle_sanitize(data: Value) -> Result<Value> {
+    tracing::info!("Handling sanitize request");
+    
+    let result = processors::pdf_sanitizer::sanitize_pdf(data, processor_runtime().await)
+        .await
+        .map_err(|e| AppError::processing(format!("Sanitize failed: {}", e)))?;
+    
+    Ok(result)
+}
+
+/// Handler para listagem de anotações (assíncrono)
+async fn handle_list_annotations(data: Value) -> Result<Value> {
+    tracing::debug!("Handling list_annotations request");
+    
+    let result = processors::pdf_validator::list_annotations(data, processor_runtime().await)
+        .await
+        .map_err(|e| AppError::processing(format!("Failed to list annotations: {}", e)))?;
+    
+    Ok(result)
+}
+
+/// Handler para análise do histórico de revisões (assíncrono)
+async fn handle_analyze_revisions(data: Value) -> Result<Value> {
+    tracing::info!("Handling analyze_revisions request");
+    
+    let result = processors::pdf_validator::analyze_revisions(data, processor_runtime().await)
+        .await
+        .map_err(|e| AppError::processing(format!("Revision analysis failed: {}", e)))?;
+    
+    Ok(result)
+}
+
+/// Handler para verificação de assinaturas digitais (assíncrono)
+async fn handle_verify_signatures(data: Value) -> Result<Value> {
+    tracing::info!("Handling verify_signatures request");
+    
+    let result = processors::pdf_validator::verify_signatures(data, processor_runtime().await)
+        .await
+        .map_err(|e| AppError::processing(format!("Signature verification failed: {}", e)))?;
+    
+    Ok(result)
+}
+
+/// Handler para assinatura digital de PDF (assíncrono)
+async fn handle_sign(data: Value) -> Result<Value> {
+    tracing::info!("Handling sign request");
+    
+    let result = processors::pdf_signer::sign_pdf(data, processor_runtime().await)
+        .await
+        .map_err(|e| AppError::processing(format!("Sign failed: {}", e)))?;
+    
+    Ok(result)
+}
+
+/// Handler para listagem de arquivos anexados (assíncrono)
+async fn handle_list_attachments(data: Value) -> Result<Value> {
+    tracing::debug!("Handling list_attachments request");
+    
+    let result = processors::pdf_attachments::list_attachments(data, processor_runtime().await)
+        .await
+        .map_err(|e| AppError::processing(format!("Failed to list attachments: {}", e)))?;
+    
+    Ok(result)
+}
+
+/// Handler para extração de arquivos anexados (assíncrono)
+async fn handle_extract_attachments(data: Value) -> Result<Value> {
+    tracing::info!("Handling extract_attachments request");
+    
+    let result = processors::pdf_attachments::extract_attachments(data, processor_runtime().await)
+        .await
+        .map_err(|e| AppError::processing(format!("Attachment extraction failed: {}", e)))?;
+    
+    Ok(result)
+}
+
+/// Handler para inclusão de arquivo anexado (assíncrono)
+async fn handle_add_attachment(data: Value) -> Result<Value> {
+    tracing::info!("Handling add_attachment request");
+    
+    let result = processors::pdf_attachments::add_attachment(data, processor_runtime().await)
+        .await
+        .map_err(|e| AppError::processing(format!("Add attachment failed: {}", e)))?;
+    
+    Ok(result)
+}
+
+/// Handler para remoção de arquivos anexados (assíncrono)
+async fn handle_remove_attachments(data: Value) -> Result<Value> {
+    tracing::info!("Handling remove_attachments request");
+    
+    let result = processors::pdf_attachments::remove_attachments(data, processor_runtime().await)
+        .await
+        .map_err(|e| AppError::processing(format!("Remove attachments failed: {}", e)))?;
+    
+    Ok(result)
+}
+
+/// Handler para edição de metadados (assíncrono)
+async fn handle_set_metadata(data: Value) -> Result<Value> {
+    tracing::info!("Handling set_metadata request");
+    
+    let result = processors::pdf_metadata::set_metadata(data, processor_runtime().await)
+        .await
+        .map_err(|e| AppError::processing(format!("Set metadata failed: {}", e)))?;
+    
+    Ok(result)
+}
+
+/// Handler para edição de metadados em lote (assíncrono)
+async fn handle_set_metadata_bulk(data: Value) -> Result<Value> {
+    tracing::info!("Handling set_metadata_bulk request");
+    
+    let result = processors::pdf_metadata::set_metadata_bulk(data, processor_runtime().await)
+        .await
+        .map_err(|e| AppError::processing(format!("Bulk set metadata failed: {}", e)))?;
+    
+    Ok(result)
+}
+
+/// Handler para extração de texto (assíncrono)
+async fn handle_extract_text(data: Value) -> Result<Value> {
+    tracing::info!("Handling extract_text request");
+    
+    let result = processors::pdf_text::extract_text(data, processor_runtime().await)
+        .await
+        .map_err(|e| AppError::processing(format!("Text extraction failed: {}", e)))?;
+    
+    Ok(result)
+}
+
+/// Runtime dos processadores, dimensionado pela configuração carregada
+///
+/// A configuração é lida uma vez (`config::get_config`) e reaproveitada pelos comandos seguintes.
+/// Se ela não puder ser carregada, os processadores usam a configuração de performance padrão
+/// em vez de recusar o comando.
+async fn processor_runtime() -> processors::runtime::ProcessorRuntime {
+    let performance = match utils::config::get_config().await {
+        Ok(config) => config.performance.clone(),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to load configuration, using default performance settings");
+            utils::config::PerformanceConfig::default()
+        }
+    };
+    processors::runtime::ProcessorRuntime::with_performance_config(&performance)
+}
+
 /// Inicializa o contexto da aplicação (assíncrono)
 ///
 /// # Example
@@ -201,9 +361,8 @@ mod tests {
         let result = process_command("unknown_action".to_string(), request).await;
         assert!(result.is_err());
         
-        if let Err(AppError::Unknown(ref msg)) = result.unwrap_err() {
-            assert!(msg.contains("unknown_action"));
-        }
+        let msg = result.unwrap_err().to_string();
+        assert!(msg.contains("unknown_action"));
     }
 
     #[tokio::test]
@@ -216,6 +375,39 @@ mod tests {
         assert_eq!(value["version"], "0.1.0");
     }
 
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_health_check_responsive_during_merge() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let first = processors::test_support::write_sample_pdf(temp_dir.path(), "a.pdf", 1500);
+        let second = processors::test_support::write_sample_pdf(temp_dir.path(), "b.pdf", 1500);
+
+        let merge = tokio::spawn(process_command("merge".to_string(), json!({
+            "files": [first, second],
+            "output": temp_dir.path().join("merged.pdf"),
+        })));
+
+        // Deixa a task do merge começar antes do health check
+        tokio::task::yield_now().await;
+
+        // Com um único worker no runtime, o health check só responde antes do
+        // fim do merge se o merge estiver rodando fora do runtime
+        let health = process_command("health_check".to_string(), json!({})).await.unwrap();
+        assert_eq!(health["status"], "ok");
+        assert!(!merge.is_finished());
+
+        merge.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_processor_runtime_follows_loaded_config() {
+        let expected = match utils::config::get_config().await {
+            Ok(config) => config.performance.cpu_threads,
+            Err(_) => utils::config::PerformanceConfig::default().cpu_threads,
+        };
+        let runtime = processor_runtime().await;
+        assert_eq!(runtime.cpu_threads(), expected);
+    }
+
     #[tokio::test]
     async fn test_init_app_creates_context() {
         // Para teste, podemos mockar a configuração
@@ -11,15 +11,20 @@
 //! ## Comunicação:
 //! O backend se comunica com o frontend via IPC (stdin/stdout) usando JSON.
 //! Cada linha recebida é um comando JSON, e cada resposta é uma linha JSON.
+//! Os comandos são executados concorrentemente e cada resposta sai assim que seu
+//! comando termina; o campo `id` é devolvido para associar resposta e comando.
 //!
 //! ## Exemplo de uso:
 //! ```bash
 //! echo '{"action": "merge", "data": {...}}' | ./dochub-backend
 //! ```
 
-use std::io::{self, BufRead};
+use std::future::Future;
+use std::io::{self, Write};
 use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
 use tokio::signal;
+use tokio::task::JoinSet;
 use tracing::{info, error, warn};
 
 use dochub_backend::process_command;
@@ -99,49 +104,103 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
 /// Loop principal de processamento de comandos
 async fn process_commands_loop() -> Result<(), Box<dyn std::error::Error>> {
-    let stdin = io::stdin();
-    let mut lines = stdin.lines();
-
     info!("Ready to process commands");
 
-    while let Some(line) = lines.next() {
-        let line = line?;
-        let command: Command = match serde_json::from_str(&line) {
-            Ok(cmd) => cmd,
-            Err(e) => {
-                let response = Response::error(format!("Invalid JSON: {}", e), None);
-                println!("{}", serde_json::to_string(&response)?);
-                continue;
-            }
-        };
+    serve(BufReader::new(tokio::io::stdin()), io::stdout(), handle_command).await?;
 
-        // Log do comando recebido
-        if let Some(ref id) = command.id {
-            info!(action = %command.action, id = %id, "Processing command");
-        } else {
-            info!(action = %command.action, "Processing command");
-        }
+    Ok(())
+}
 
-        // Processar comando
-        let result = process_command(command.action.clone(), command.data).await;
+/// Lê comandos linha a linha de `input` e escreve cada resposta em `output` assim
+/// que seu comando termina; o cliente as associa aos comandos pelo campo `id`
+async fn serve<R, W, H, F>(input: R, mut output: W, handler: H) -> io::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: Write,
+    H: Fn(Command) -> F,
+    F: Future<Output = Response> + Send + 'static,
+{
+    let mut lines = input.lines();
+    let mut tasks = JoinSet::new();
+    let mut input_open = true;
 
-        // Enviar resposta
-        let response = match result {
-            Ok(data) => Response::success(data, command.id),
-            Err(e) => {
-                error!(action = %command.action, error = %e, "Command failed");
-                Response::error(e.to_string(), command.id)
-            }
-        };
+    while input_open || !tasks.is_empty() {
+        tokio::select! {
+            line = lines.next_line(), if input_open => {
+                let Some(line) = line? else {
+                    // Se chegamos aqui, stdin foi fechado; aguarda os comandos em andamento
+                    info!("Command input stream closed");
+                    input_open = false;
+                    continue;
+                };
 
-        println!("{}", serde_json::to_string(&response)?);
+                let command: Command = match serde_json::from_str(&line) {
+                    Ok(cmd) => cmd,
+                    Err(e) => {
+                        write_response(&mut output, &Response::error(format!("Invalid JSON: {}", e), None));
+                        continue;
+                    }
+                };
+
+                // Log do comando recebido
+                if let Some(ref id) = command.id {
+                    info!(action = %command.action, id = %id, "Processing command");
+                } else {
+                    info!(action = %command.action, "Processing command");
+                }
+
+                // Cada comando roda em sua própria task, para que um merge longo não
+                // atrase a resposta dos seguintes; a task interna isola panics do comando
+                let id = command.id.clone();
+                let task = tokio::spawn(handler(command));
+                tasks.spawn(async move {
+                    task.await.unwrap_or_else(|e| {
+                        error!(error = %e, "Command task failed");
+                        Response::error("Command task failed".to_string(), id)
+                    })
+                });
+            }
+            Some(joined) = tasks.join_next() => {
+                match joined {
+                    Ok(response) => write_response(&mut output, &response),
+                    Err(e) => error!(error = %e, "Command task failed"),
+                }
+            }
+        }
     }
 
-    // Se chegamos aqui, stdin foi fechado
-    info!("Command input stream closed");
     Ok(())
 }
 
+/// Executa um comando e monta sua resposta
+async fn handle_command(command: Command) -> Response {
+    let result = process_command(command.action.clone(), command.data).await;
+
+    match result {
+        Ok(data) => Response::success(data, command.id),
+        Err(e) => {
+            error!(action = %command.action, error = %e, "Command failed");
+            Response::error(e.to_string(), command.id)
+        }
+    }
+}
+
+/// Escreve uma resposta como uma linha JSON
+fn write_response(output: &mut impl Write, response: &Response) {
+    let json = match serde_json::to_string(response) {
+        Ok(json) => json,
+        Err(e) => {
+            error!(error = %e, "Failed to serialize response");
+            return;
+        }
+    };
+
+    // Uma única escrita por linha, para que respostas não se misturem no stdout
+    if let Err(e) = output.write_all(format!("{}\n", json).as_bytes()).and_then(|_| output.flush()) {
+        error!(error = %e, "Failed to write response");
+    }
+}
+
 /// Configura handler para sinais de shutdown
 fn setup_shutdown_signal() -> impl std::future::Future<Output = ()> {
     async {
@@ -168,3 +227,53 @@ fn setup_shutdown_signal() -> impl std::future::Future<Output = ()> {
         }
     }
 }
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn response_ids(output: &[u8]) -> Vec<String> {
+        output
+            .split(|&b| b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice::<Response>(line).unwrap().id.unwrap_or_default())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_fast_command_is_not_blocked_by_slow_one() {
+        let input = concat!(
+            r#"{"action": "merge", "data": {}, "id": "slow"}"#, "\n",
+            r#"{"action": "health_check", "data": {}, "id": "fast"}"#, "\n",
+        );
+        let mut output = Vec::new();
+        serve(input.as_bytes(), &mut output, |command: Command| async move {
+            if command.action == "merge" {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+            }
+            Response::success(serde_json::json!(command.action), command.id)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(response_ids(&output), vec!["fast", "slow"]);
+    }
+
+    #[tokio::test]
+    async fn test_panicking_command_still_gets_a_response() {
+        let input = concat!(r#"{"action": "boom", "data": null, "id": "7"}"#, "\n");
+        let mut output = Vec::new();
+        serve(input.as_bytes(), &mut output, |_: Command| async move {
+            panic!("command failed");
+        })
+        .await
+        .unwrap();
+
+        let response: Response = serde_json::from_slice(output.trim_ascii_end()).unwrap();
+        assert!(!response.success);
+        assert_eq!(response.id.as_deref(), Some("7"));
+    }
+}
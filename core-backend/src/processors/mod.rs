@@ -1,3 +1,62 @@
+/// Gera os construtores comuns de um processador com um campo `runtime: ProcessorRuntime`
+///
+/// `new`, `with_file_handler`, `with_performance_config` e `with_runtime`, além
+/// de `Default`, delegam a `ProcessorRuntime`.
+macro_rules! processor_constructors {
+    ($processor:ident) => {
+        impl $processor {
+            #[doc = concat!("Cria um novo ", stringify!($processor))]
+            pub fn new() -> Self {
+                Self::with_runtime($crate::processors::runtime::ProcessorRuntime::new())
+            }
+
+            #[doc = concat!("Cria um ", stringify!($processor), " com um FileHandler específico")]
+            pub fn with_file_handler(file_handler: $crate::api::file_handlers::FileHandler) -> Self {
+                Self::with_runtime($crate::processors::runtime::ProcessorRuntime::with_file_handler(file_handler))
+            }
+
+            #[doc = concat!("Cria um ", stringify!($processor), " com o paralelismo definido na configuração de performance")]
+            pub fn with_performance_config(performance: &$crate::utils::config::PerformanceConfig) -> Self {
+                Self::with_runtime($crate::processors::runtime::ProcessorRuntime::with_performance_config(performance))
+            }
+
+            #[doc = concat!("Cria um ", stringify!($processor), " sobre um runtime já montado")]
+            pub fn with_runtime(runtime: $crate::processors::runtime::ProcessorRuntime) -> Self {
+                Self { runtime }
+            }
+        }
+
+        impl Default for $processor {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+}
+
+pub mod pdf_attachments;
+pub mod pdf_content;
+pub mod pdf_encoding;
+pub mod pdf_encryption;
+pub mod pdf_filters;
+pub mod pdf_inventory;
+pub mod pdf_linearize;
 pub mod pdf_merger;
+pub mod pdf_metadata;
+pub mod pdf_pdfa;
+pub mod pdf_pdfua;
+pub mod pdf_revisions;
+pub mod pdf_sanitizer;
+pub mod pdf_security;
+pub mod pdf_signatures;
+pub mod pdf_signer;
 pub mod pdf_splitter;
+pub mod pdf_text;
 pub mod pdf_validator;
+pub mod pdf_writer;
+pub mod pdf_xmp;
+pub mod pdf_xref;
+pub mod runtime;
+
+#[cfg(test)]
+pub(crate) mod test_support;
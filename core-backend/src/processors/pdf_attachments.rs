@@ -0,0 +1,1383 @@
+//! Arquivos anexados a PDFs para o DocHub
+//!
+//! Portfólios e anexos ficam na árvore de nomes `/EmbeddedFiles` do catálogo
+//! ou em anotações `/FileAttachment` das páginas. Este módulo lista, extrai,
+//! anexa e remove esses arquivos; a análise de segurança usa o mesmo
+//! inventário para reconhecer executáveis disfarçados, um vetor comum de
+//! malware em PDFs.
+//!
+//! ## Funcionalidades:
+//! - Listagem com nome, tamanho, tipo MIME, datas e checksum (`/Params /CheckSum`)
+//! - Extração para um diretório, com nomes de arquivo seguros
+//! - Inclusão de arquivos na árvore `/EmbeddedFiles`
+//! - Remoção por nome, na árvore e nas anotações, sem deixar os dados no arquivo
+
+// As ações devolvem o `Result` do crate, com o `AppError` de `error_handling`
+#![allow(clippy::result_large_err)]
+
+use chrono::Local;
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream, StringFormat};
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tracing::{info, error, instrument};
+
+use crate::utils::error_handling::{Result, AppError, PdfError};
+use crate::utils::error_handling::validate;
+use crate::api::file_handlers::FileHandler;
+use crate::processors::pdf_encoding;
+use crate::processors::pdf_filters;
+use crate::processors::runtime::ProcessorRuntime;
+
+/// Limite de níveis percorridos na árvore de nomes `/EmbeddedFiles`
+const MAX_NAME_TREE_DEPTH: usize = 64;
+
+/// Tipo MIME usado quando a extensão do arquivo não é reconhecida
+const DEFAULT_MIME_TYPE: &str = "application/octet-stream";
+
+/// Tipos MIME por extensão, para arquivos anexados sem tipo explícito
+const MIME_TYPES: &[(&str, &str)] = &[
+    ("pdf", "application/pdf"),
+    ("txt", "text/plain"),
+    ("csv", "text/csv"),
+    ("xml", "application/xml"),
+    ("json", "application/json"),
+    ("html", "text/html"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("zip", "application/zip"),
+    ("doc", "application/msword"),
+    ("docx", "application/vnd.openxmlformats-officedocument.wordprocessingml.document"),
+    ("xls", "application/vnd.ms-excel"),
+    ("xlsx", "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+];
+
+/// Assinaturas iniciais de executáveis (PE, ELF, Mach-O e scripts)
+const EXECUTABLE_MAGIC: &[&[u8]] = &[
+    b"MZ",
+    b"\x7FELF",
+    b"\xFE\xED\xFA\xCE",
+    b"\xFE\xED\xFA\xCF",
+    b"\xCE\xFA\xED\xFE",
+    b"\xCF\xFA\xED\xFE",
+    b"#!",
+];
+
+/// Onde o anexo foi encontrado
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AttachmentSource {
+    /// Árvore de nomes `/EmbeddedFiles` do catálogo
+    EmbeddedFiles,
+    /// Anotação `/FileAttachment` de uma página
+    Annotation,
+}
+
+/// Um arquivo anexado ao documento
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AttachmentInfo {
+    /// Nome do anexo (chave da árvore de nomes ou nome do arquivo da anotação)
+    pub name: String,
+    /// Nome do arquivo na especificação (`/UF` ou `/F`)
+    pub file_name: Option<String>,
+    /// Descrição (`/Desc` da especificação ou `/Contents` da anotação)
+    pub description: Option<String>,
+    /// Tamanho do conteúdo, em bytes
+    pub size: Option<u64>,
+    /// Tipo MIME (`/Subtype` da stream)
+    pub mime_type: Option<String>,
+    /// Data de criação (`/Params /CreationDate`)
+    pub creation_date: Option<String>,
+    /// Data de modificação (`/Params /ModDate`)
+    pub modification_date: Option<String>,
+    /// MD5 declarado em `/Params /CheckSum`, em hexadecimal
+    pub checksum: Option<String>,
+    /// O MD5 declarado confere com o conteúdo? (None = sem checksum ou conteúdo ilegível)
+    pub checksum_valid: Option<bool>,
+    /// Origem do anexo
+    pub source: AttachmentSource,
+    /// Página da anotação (None na árvore de nomes)
+    pub page: Option<u32>,
+    /// O conteúdo começa com a assinatura de um executável?
+    pub executable_content: bool,
+    /// Objeto da especificação de arquivo (None para dicionários diretos)
+    pub object_id: Option<u32>,
+}
+
+/// Anexos do documento: árvore `/EmbeddedFiles` primeiro, depois anotações por página
+pub fn list(doc: &Document) -> Vec<AttachmentInfo> {
+    read_all(doc).into_iter().map(|(info, _)| info).collect()
+}
+
+/// Anexos do documento com o conteúdo decodificado (None = stream ausente ou ilegível)
+pub fn read_all(doc: &Document) -> Vec<(AttachmentInfo, Option<Vec<u8>>)> {
+    let mut attachments = Vec::new();
+
+    if let Some(tree) = embedded_files_tree(doc) {
+        let mut entries = Vec::new();
+        collect_entries(doc, tree, 0, &mut entries);
+        for (key, value) in entries {
+            if let Ok((spec_id, Object::Dictionary(spec))) = doc.dereference(value) {
+                let name = pdf_encoding::decode_text_string(&key);
+                attachments.push(read_attachment(doc, name, spec_id, spec, AttachmentSource::EmbeddedFiles, None));
+            }
+        }
+    }
+
+    for (page_number, page_id) in doc.get_pages() {
+        for annot in page_annotations(doc, page_id) {
+            let Some((spec_id, spec)) = attachment_spec(doc, annot) else {
+                continue;
+            };
+            let mut attachment = read_attachment(
+                doc,
+                file_name(doc, spec).unwrap_or_else(|| "attachment".to_string()),
+                spec_id,
+                spec,
+                AttachmentSource::Annotation,
+                Some(page_number),
+            );
+            if attachment.0.description.is_none() {
+                attachment.0.description = text_string(doc, annot, b"Contents");
+            }
+            attachments.push(attachment);
+        }
+    }
+
+    attachments
+}
+
+/// Conteúdo decodificado de uma especificação de arquivo (`/EF /UF` ou `/EF /F`)
+pub fn file_data(doc: &Document, spec: &Dictionary) -> Option<Vec<u8>> {
+    embedded_stream(doc, spec).and_then(|stream| pdf_filters::decode_stream(doc, stream).ok().map(|decoded| decoded.data))
+}
+
+/// Nome do arquivo de uma especificação (`/UF`, senão `/F`)
+pub fn file_name(doc: &Document, spec: &Dictionary) -> Option<String> {
+    text_string(doc, spec, b"UF").or_else(|| text_string(doc, spec, b"F"))
+}
+
+/// O conteúdo começa com a assinatura de um executável (PE, ELF, Mach-O ou script `#!`)?
+pub fn is_executable_content(data: &[u8]) -> bool {
+    EXECUTABLE_MAGIC.iter().any(|magic| data.starts_with(magic))
+}
+
+/// Tipo MIME pela extensão do nome do arquivo
+pub fn guess_mime_type(name: &str) -> &'static str {
+    let extension = name.rsplit_once('.').map(|(_, extension)| extension.to_ascii_lowercase());
+    MIME_TYPES
+        .iter()
+        .find(|(known, _)| extension.as_deref() == Some(*known))
+        .map(|(_, mime_type)| *mime_type)
+        .unwrap_or(DEFAULT_MIME_TYPE)
+}
+
+/// Anexa `data` à árvore `/EmbeddedFiles` com o nome `name`
+///
+/// Um anexo de mesmo nome é substituído. A árvore é regravada como um único
+/// nó ordenado; a especificação criada é retornada.
+pub fn add(
+    doc: &mut Document,
+    name: &str,
+    data: &[u8],
+    description: Option<&str>,
+    mime_type: &str,
+) -> std::result::Result<ObjectId, String> {
+    let now = Object::string_literal(pdf_encoding::format_pdf_date(&Local::now()));
+    let params = dictionary! {
+        "Size" => data.len() as i64,
+        "CreationDate" => now.clone(),
+        "ModDate" => now,
+        "CheckSum" => Object::String(Md5::digest(data).to_vec(), StringFormat::Hexadecimal),
+    };
+    let mut stream = Stream::new(
+        dictionary! {
+            "Type" => "EmbeddedFile",
+            "Subtype" => Object::Name(mime_type.as_bytes().to_vec()),
+            "Params" => params,
+        },
+        data.to_vec(),
+    );
+    stream.compress().map_err(|e| e.to_string())?;
+    let stream_id = doc.add_object(stream);
+
+    let encoded_name = pdf_encoding::encode_text_string(name);
+    let mut spec = dictionary! {
+        "Type" => "Filespec",
+        "F" => Object::String(encoded_name.clone(), StringFormat::Literal),
+        "UF" => Object::String(encoded_name.clone(), StringFormat::Literal),
+        "EF" => dictionary! { "F" => stream_id, "UF" => stream_id },
+    };
+    if let Some(description) = description {
+        spec.set("Desc", Object::String(pdf_encoding::encode_text_string(description), StringFormat::Literal));
+    }
+    let spec_id = doc.add_object(spec);
+
+    let mut entries = tree_entries(doc);
+    entries.retain(|(key, _)| pdf_encoding::decode_text_string(key) != name);
+    entries.push((encoded_name, spec_id.into()));
+    write_tree(doc, entries)?;
+
+    Ok(spec_id)
+}
+
+/// Remove os anexos com os nomes informados, na árvore e nas anotações
+///
+/// Retorna os nomes removidos. Os objetos que ficam sem referência (inclusive
+/// o conteúdo dos arquivos) são descartados do documento.
+pub fn remove(doc: &mut Document, names: &[String]) -> std::result::Result<Vec<String>, String> {
+    let mut removed = Vec::new();
+
+    let entries = tree_entries(doc);
+    let count = entries.len();
+    let kept: Vec<_> = entries
+        .into_iter()
+        .filter(|(key, _)| {
+            let name = pdf_encoding::decode_text_string(key);
+            let matches = names.contains(&name);
+            if matches {
+                removed.push(name);
+            }
+            !matches
+        })
+        .collect();
+    if kept.len() != count {
+        write_tree(doc, kept)?;
+    }
+
+    for (_, page_id) in doc.get_pages() {
+        let annots = doc.get_dictionary(page_id).map_err(|e| e.to_string())?.get(b"Annots").ok().cloned();
+        let items = match &annots {
+            Some(Object::Reference(id)) => doc.get_object(*id).and_then(Object::as_array).cloned().unwrap_or_default(),
+            Some(Object::Array(items)) => items.clone(),
+            _ => continue,
+        };
+
+        let kept: Vec<Object> = items
+            .iter()
+            .filter(|annot| {
+                let name = doc
+                    .dereference(annot)
+                    .ok()
+                    .and_then(|(_, annot)| annot.as_dict().ok())
+                    .and_then(|annot| attachment_spec(doc, annot))
+                    .map(|(_, spec)| file_name(doc, spec).unwrap_or_else(|| "attachment".to_string()));
+                match name {
+                    Some(name) if names.contains(&name) => {
+                        removed.push(name);
+                        false
+                    }
+                    _ => true,
+                }
+            })
+            .cloned()
+            .collect();
+        if kept.len() == items.len() {
+            continue;
+        }
+
+        match annots {
+            Some(Object::Reference(id)) => *doc.get_object_mut(id).map_err(|e| e.to_string())? = Object::Array(kept),
+            _ => doc.get_dictionary_mut(page_id).map_err(|e| e.to_string())?.set("Annots", kept),
+        }
+    }
+
+    if !removed.is_empty() {
+        doc.prune_objects();
+    }
+
+    Ok(removed)
+}
+
+/// Request para listagem de anexos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentsRequest {
+    /// Caminho para o PDF
+    pub file_path: PathBuf,
+}
+
+impl AttachmentsRequest {
+    /// Cria um AttachmentsRequest a partir de JSON
+    pub fn from_value(data: &Value) -> Result<Self> {
+        let file_path = data["file"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'file' field"))?;
+
+        Ok(Self { file_path })
+    }
+}
+
+/// Resultado da listagem de anexos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentsResult {
+    /// Caminho do PDF
+    pub file_path: PathBuf,
+    /// Número de anexos encontrados
+    pub attachment_count: usize,
+    /// Soma dos tamanhos conhecidos (em bytes)
+    pub total_size: u64,
+    /// Anexos, na ordem de `list`
+    pub attachments: Vec<AttachmentInfo>,
+    /// Tempo total de processamento (em milissegundos)
+    pub processing_time_ms: u128,
+}
+
+/// Request para extração de anexos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractAttachmentsRequest {
+    /// Caminho para o PDF
+    pub file_path: PathBuf,
+    /// Diretório onde gravar os arquivos
+    pub output_dir: PathBuf,
+    /// Anexos desejados, por nome (vazio = todos)
+    #[serde(default)]
+    pub names: Vec<String>,
+}
+
+impl ExtractAttachmentsRequest {
+    /// Cria um ExtractAttachmentsRequest a partir de JSON
+    pub fn from_value(data: &Value) -> Result<Self> {
+        let file_path = data["file"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'file' field"))?;
+
+        let output_dir = data["output_dir"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'output_dir' field"))?;
+
+        let names = parse_names(data)?;
+
+        Ok(Self { file_path, output_dir, names })
+    }
+}
+
+/// Um anexo gravado em disco
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractedAttachment {
+    /// Nome do anexo no documento
+    pub name: String,
+    /// Arquivo gravado
+    pub path: PathBuf,
+    /// Tamanho gravado (em bytes)
+    pub size: u64,
+}
+
+/// Resultado da extração de anexos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractAttachmentsResult {
+    /// Caminho do PDF
+    pub file_path: PathBuf,
+    /// Diretório de destino
+    pub output_dir: PathBuf,
+    /// Arquivos gravados
+    pub extracted: Vec<ExtractedAttachment>,
+    /// Anexos sem conteúdo legível, que não foram gravados
+    pub skipped: Vec<String>,
+    /// Tempo total de processamento (em milissegundos)
+    pub processing_time_ms: u128,
+}
+
+/// Configurações do arquivo anexado
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AddAttachmentConfig {
+    /// Nome do anexo (None = nome do arquivo)
+    pub name: Option<String>,
+    /// Descrição (`/Desc`)
+    pub description: Option<String>,
+    /// Tipo MIME (None = deduzido pela extensão)
+    pub mime_type: Option<String>,
+}
+
+/// Request para anexar um arquivo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddAttachmentRequest {
+    /// Caminho do PDF de origem
+    pub file_path: PathBuf,
+    /// Caminho do PDF com o anexo
+    pub output_path: PathBuf,
+    /// Arquivo a anexar
+    pub attachment_path: PathBuf,
+    /// Configurações do anexo
+    #[serde(default)]
+    pub config: AddAttachmentConfig,
+}
+
+impl AddAttachmentRequest {
+    /// Cria um AddAttachmentRequest a partir de JSON
+    pub fn from_value(data: &Value) -> Result<Self> {
+        let file_path = data["file"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'file' field"))?;
+
+        let output_path = data["output"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'output' field"))?;
+
+        let attachment_path = data["attachment"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'attachment' field"))?;
+
+        // Configurações opcionais
+        let config = if let Some(config_val) = data.get("config") {
+            serde_json::from_value(config_val.clone())
+                .map_err(|e| AppError::validation(format!("Invalid config: {}", e)))?
+        } else {
+            AddAttachmentConfig::default()
+        };
+
+        Ok(Self {
+            file_path,
+            output_path,
+            attachment_path,
+            config,
+        })
+    }
+
+    /// Valida a request
+    pub fn validate(&self, file_handler: &FileHandler) -> Result<()> {
+        let metadata = file_handler.validate_file(self.file_path.to_str().unwrap_or(""))?;
+        validate(
+            file_handler.file_exists(self.attachment_path.to_str().unwrap_or(""))?,
+            AppError::validation(format!("Attachment not found: {}", self.attachment_path.display())),
+        )?;
+        validate(
+            self.output_path != self.file_path,
+            AppError::validation("Output path must differ from the input file"),
+        )?;
+        validate(
+            !self.attachment_name().is_empty(),
+            AppError::validation("Attachment name cannot be empty"),
+        )?;
+
+        info!(
+            path = %self.file_path.display(),
+            size = metadata.len(),
+            "Input file validated for add_attachment"
+        );
+
+        Ok(())
+    }
+
+    /// Nome do anexo no documento
+    pub fn attachment_name(&self) -> String {
+        self.config.name.clone().unwrap_or_else(|| {
+            self.attachment_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+    }
+}
+
+/// Request para remoção de anexos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoveAttachmentsRequest {
+    /// Caminho do PDF de origem
+    pub file_path: PathBuf,
+    /// Caminho do PDF sem os anexos
+    pub output_path: PathBuf,
+    /// Anexos a remover, por nome
+    pub names: Vec<String>,
+}
+
+impl RemoveAttachmentsRequest {
+    /// Cria um RemoveAttachmentsRequest a partir de JSON
+    pub fn from_value(data: &Value) -> Result<Self> {
+        let file_path = data["file"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'file' field"))?;
+
+        let output_path = data["output"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'output' field"))?;
+
+        let names = parse_names(data)?;
+
+        Ok(Self { file_path, output_path, names })
+    }
+
+    /// Valida a request
+    pub fn validate(&self, file_handler: &FileHandler) -> Result<()> {
+        let metadata = file_handler.validate_file(self.file_path.to_str().unwrap_or(""))?;
+        validate(
+            self.output_path != self.file_path,
+            AppError::validation("Output path must differ from the input file"),
+        )?;
+        validate(
+            !self.names.is_empty(),
+            AppError::validation("At least one attachment name is required"),
+        )?;
+
+        info!(
+            path = %self.file_path.display(),
+            size = metadata.len(),
+            "Input file validated for remove_attachments"
+        );
+
+        Ok(())
+    }
+}
+
+/// Resultado de uma edição de anexos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentEditResult {
+    /// Caminho do PDF gravado
+    pub output_path: PathBuf,
+    /// Anexos incluídos ou removidos
+    pub changed: Vec<String>,
+    /// Anexos do documento gravado
+    pub attachments: Vec<AttachmentInfo>,
+    /// Tamanho do PDF gravado (em bytes)
+    pub file_size: u64,
+    /// Tempo total de processamento (em milissegundos)
+    pub processing_time_ms: u128,
+}
+
+/// Processador de anexos de PDFs
+#[derive(Debug, Clone)]
+pub struct PdfAttachmentManager {
+    runtime: ProcessorRuntime,
+}
+
+processor_constructors!(PdfAttachmentManager);
+
+impl PdfAttachmentManager {
+    /// Lista os anexos de um PDF
+    #[instrument(name = "list_attachments", skip(self, request), fields(
+        file = %request.file_path.display()
+    ))]
+    pub async fn list_attachments(&self, request: AttachmentsRequest) -> Result<AttachmentsResult> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.list_attachments_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `list_attachments`, executado no pool de CPU
+    fn list_attachments_blocking(&self, request: AttachmentsRequest) -> Result<AttachmentsResult> {
+        let start_time = Instant::now();
+
+        self.runtime.file_handler().validate_file(request.file_path.to_str().unwrap_or(""))?;
+        let doc = self.load_document(&request.file_path)?;
+
+        let attachments = list(&doc);
+        let total_size = attachments.iter().filter_map(|attachment| attachment.size).sum();
+
+        info!(
+            file = %request.file_path.display(),
+            attachments = attachments.len(),
+            total_size,
+            "Attachments listed"
+        );
+
+        Ok(AttachmentsResult {
+            file_path: request.file_path,
+            attachment_count: attachments.len(),
+            total_size,
+            attachments,
+            processing_time_ms: start_time.elapsed().as_millis(),
+        })
+    }
+
+    /// Grava os anexos de um PDF em um diretório
+    #[instrument(name = "extract_attachments", skip(self, request), fields(
+        file = %request.file_path.display(),
+        output_dir = %request.output_dir.display()
+    ))]
+    pub async fn extract_attachments(&self, request: ExtractAttachmentsRequest) -> Result<ExtractAttachmentsResult> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.extract_attachments_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `extract_attachments`, executado no pool de CPU
+    fn extract_attachments_blocking(&self, request: ExtractAttachmentsRequest) -> Result<ExtractAttachmentsResult> {
+        let start_time = Instant::now();
+
+        self.runtime.file_handler().validate_file(request.file_path.to_str().unwrap_or(""))?;
+        let doc = self.load_document(&request.file_path)?;
+
+        let attachments: Vec<_> = read_all(&doc)
+            .into_iter()
+            .filter(|(info, _)| request.names.is_empty() || request.names.contains(&info.name))
+            .collect();
+        if let Some(missing) = request
+            .names
+            .iter()
+            .find(|name| !attachments.iter().any(|(info, _)| &info.name == *name))
+        {
+            return Err(AppError::validation(format!("Attachment not found: {}", missing)));
+        }
+
+        self.runtime.file_handler().create_dir(request.output_dir.to_str().unwrap_or(""))?;
+
+        // Os nomes vêm do autor do PDF: anexos de mesmo nome (ex.: na árvore e em uma
+        // anotação) não se sobrescrevem, nem sobrescrevem arquivos já presentes no destino
+        let file_handler = self.runtime.file_handler();
+        let mut used = HashSet::new();
+        let mut extracted = Vec::new();
+        let mut skipped = Vec::new();
+        for (info, data) in attachments {
+            let Some(data) = data else {
+                skipped.push(info.name);
+                continue;
+            };
+            let file_name = unique_file_name(&info.name, &mut used, |candidate| {
+                file_handler.file_exists(request.output_dir.join(candidate).to_str().unwrap_or(""))
+            })?;
+            let path = request.output_dir.join(file_name);
+            file_handler.write_new_file(path.to_str().unwrap_or(""), &data)?;
+            extracted.push(ExtractedAttachment {
+                name: info.name,
+                path,
+                size: data.len() as u64,
+            });
+        }
+
+        info!(
+            file = %request.file_path.display(),
+            extracted = extracted.len(),
+            skipped = skipped.len(),
+            "Attachments extracted"
+        );
+
+        Ok(ExtractAttachmentsResult {
+            file_path: request.file_path,
+            output_dir: request.output_dir,
+            extracted,
+            skipped,
+            processing_time_ms: start_time.elapsed().as_millis(),
+        })
+    }
+
+    /// Anexa um arquivo a um PDF
+    #[instrument(name = "add_attachment", skip(self, request), fields(
+        input_file = %request.file_path.display(),
+        output_file = %request.output_path.display()
+    ))]
+    pub async fn add_attachment(&self, request: AddAttachmentRequest) -> Result<AttachmentEditResult> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.add_attachment_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `add_attachment`, executado no pool de CPU
+    fn add_attachment_blocking(&self, request: AddAttachmentRequest) -> Result<AttachmentEditResult> {
+        let start_time = Instant::now();
+
+        request.validate(self.runtime.file_handler())?;
+
+        let mut doc = self.load_document(&request.file_path)?;
+        validate(
+            doc.trailer.get(b"Encrypt").is_err(),
+            AppError::validation("Encrypted documents cannot be edited"),
+        )?;
+
+        let data = std::fs::read(&request.attachment_path)
+            .map_err(|e| AppError::from_io_error("reading attachment", request.attachment_path.clone(), e))?;
+        let name = request.attachment_name();
+        let mime_type = request.config.mime_type.clone().unwrap_or_else(|| guess_mime_type(&name).to_string());
+
+        add(&mut doc, &name, &data, request.config.description.as_deref(), &mime_type)
+            .map_err(|reason| AppError::Pdf(PdfError::ProcessingFailed { reason }))?;
+
+        self.finish_edit(doc, &request.output_path, vec![name], start_time)
+    }
+
+    /// Remove anexos de um PDF
+    #[instrument(name = "remove_attachments", skip(self, request), fields(
+        input_file = %request.file_path.display(),
+        output_file = %request.output_path.display()
+    ))]
+    pub async fn remove_attachments(&self, request: RemoveAttachmentsRequest) -> Result<AttachmentEditResult> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.remove_attachments_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `remove_attachments`, executado no pool de CPU
+    fn remove_attachments_blocking(&self, request: RemoveAttachmentsRequest) -> Result<AttachmentEditResult> {
+        let start_time = Instant::now();
+
+        request.validate(self.runtime.file_handler())?;
+
+        let mut doc = self.load_document(&request.file_path)?;
+        validate(
+            doc.trailer.get(b"Encrypt").is_err(),
+            AppError::validation("Encrypted documents cannot be edited"),
+        )?;
+
+        let removed = remove(&mut doc, &request.names)
+            .map_err(|reason| AppError::Pdf(PdfError::ProcessingFailed { reason }))?;
+        if let Some(missing) = request.names.iter().find(|name| !removed.contains(name)) {
+            return Err(AppError::validation(format!("Attachment not found: {}", missing)));
+        }
+
+        self.finish_edit(doc, &request.output_path, removed, start_time)
+    }
+
+    // ==================== MÉTODOS PRIVADOS ====================
+
+    /// Carrega um documento PDF
+    fn load_document(&self, path: &Path) -> Result<Document> {
+        info!(path = %path.display(), "Loading PDF document");
+
+        Document::load(path)
+            .map_err(|e| {
+                error!(path = %path.display(), error = %e, "Failed to load PDF");
+                AppError::Pdf(PdfError::CorruptedPdf {
+                    path: path.to_path_buf(),
+                })
+            })
+    }
+
+    /// Grava o documento editado e monta o resultado
+    fn finish_edit(
+        &self,
+        mut doc: Document,
+        output_path: &Path,
+        changed: Vec<String>,
+        start_time: Instant,
+    ) -> Result<AttachmentEditResult> {
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes)
+            .map_err(|e| AppError::Pdf(PdfError::ProcessingFailed {
+                reason: format!("Failed to save PDF: {}", e),
+            }))?;
+        self.runtime.file_handler().write_file(output_path.to_str().unwrap_or(""), &bytes)?;
+
+        let result = AttachmentEditResult {
+            output_path: output_path.to_path_buf(),
+            changed,
+            attachments: list(&doc),
+            file_size: bytes.len() as u64,
+            processing_time_ms: start_time.elapsed().as_millis(),
+        };
+
+        info!(
+            output = %result.output_path.display(),
+            changed = result.changed.len(),
+            attachments = result.attachments.len(),
+            processing_time_ms = result.processing_time_ms,
+            "Attachments updated"
+        );
+
+        Ok(result)
+    }
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+/// Descreve uma especificação de arquivo e decodifica o seu conteúdo
+fn read_attachment(
+    doc: &Document,
+    name: String,
+    spec_id: Option<ObjectId>,
+    spec: &Dictionary,
+    source: AttachmentSource,
+    page: Option<u32>,
+) -> (AttachmentInfo, Option<Vec<u8>>) {
+    let stream = embedded_stream(doc, spec);
+    let data = file_data(doc, spec);
+    let params = stream.and_then(|stream| stream.dict.get_deref(b"Params", doc).and_then(Object::as_dict).ok());
+    let checksum = params.and_then(|params| params.get_deref(b"CheckSum", doc).and_then(Object::as_str).ok());
+    let declared_size = params
+        .and_then(|params| params.get_deref(b"Size", doc).and_then(Object::as_i64).ok())
+        .and_then(|size| u64::try_from(size).ok());
+
+    let info = AttachmentInfo {
+        name,
+        file_name: file_name(doc, spec),
+        description: text_string(doc, spec, b"Desc"),
+        size: data.as_ref().map(|data| data.len() as u64).or(declared_size),
+        mime_type: stream
+            .and_then(|stream| stream.dict.get(b"Subtype").and_then(Object::as_name).ok())
+            .map(|subtype| String::from_utf8_lossy(subtype).into_owned()),
+        creation_date: params.and_then(|params| text_string(doc, params, b"CreationDate")),
+        modification_date: params.and_then(|params| text_string(doc, params, b"ModDate")),
+        checksum: checksum.map(|checksum| checksum.iter().map(|byte| format!("{:02x}", byte)).collect()),
+        checksum_valid: checksum
+            .zip(data.as_ref())
+            .map(|(checksum, data)| Md5::digest(data).as_slice() == checksum),
+        source,
+        page,
+        executable_content: data.as_deref().is_some_and(is_executable_content),
+        object_id: spec_id.map(|id| id.0),
+    };
+
+    (info, data)
+}
+
+/// Stream com o conteúdo do arquivo (`/EF /UF`, senão `/EF /F`)
+fn embedded_stream<'a>(doc: &'a Document, spec: &'a Dictionary) -> Option<&'a Stream> {
+    let files = spec.get_deref(b"EF", doc).and_then(Object::as_dict).ok()?;
+    [b"UF".as_slice(), b"F"]
+        .iter()
+        .find_map(|key| files.get_deref(key, doc).and_then(Object::as_stream).ok())
+}
+
+/// Especificação de arquivo incorporado de uma anotação `/FileAttachment`
+fn attachment_spec<'a>(doc: &'a Document, annot: &'a Dictionary) -> Option<(Option<ObjectId>, &'a Dictionary)> {
+    if !annot.get(b"Subtype").and_then(Object::as_name).is_ok_and(|subtype| subtype == b"FileAttachment") {
+        return None;
+    }
+    match doc.dereference(annot.get(b"FS").ok()?) {
+        Ok((spec_id, Object::Dictionary(spec))) => Some((spec_id, spec)),
+        _ => None,
+    }
+}
+
+/// Anotações de uma página
+fn page_annotations(doc: &Document, page_id: ObjectId) -> Vec<&Dictionary> {
+    doc.get_dictionary(page_id)
+        .and_then(|page| page.get_deref(b"Annots", doc))
+        .and_then(Object::as_array)
+        .map(|annots| {
+            annots
+                .iter()
+                .filter_map(|annot| doc.dereference(annot).ok())
+                .filter_map(|(_, annot)| annot.as_dict().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Raiz da árvore de nomes `/EmbeddedFiles` do catálogo
+fn embedded_files_tree(doc: &Document) -> Option<&Dictionary> {
+    doc.catalog()
+        .and_then(|catalog| catalog.get_deref(b"Names", doc))
+        .and_then(Object::as_dict)
+        .and_then(|names| names.get_deref(b"EmbeddedFiles", doc))
+        .and_then(Object::as_dict)
+        .ok()
+}
+
+/// Entradas de uma árvore de nomes, na ordem da árvore
+fn collect_entries<'a>(doc: &'a Document, node: &'a Dictionary, depth: usize, entries: &mut Vec<(Vec<u8>, &'a Object)>) {
+    if depth > MAX_NAME_TREE_DEPTH {
+        return;
+    }
+
+    if let Ok(names) = node.get_deref(b"Names", doc).and_then(Object::as_array) {
+        for pair in names.chunks(2) {
+            if let [key, value] = pair {
+                if let Ok(key) = doc.dereference(key).and_then(|(_, key)| key.as_str()) {
+                    entries.push((key.to_vec(), value));
+                }
+            }
+        }
+    }
+
+    if let Ok(kids) = node.get_deref(b"Kids", doc).and_then(Object::as_array) {
+        for kid in kids {
+            if let Ok((_, Object::Dictionary(kid))) = doc.dereference(kid) {
+                collect_entries(doc, kid, depth + 1, entries);
+            }
+        }
+    }
+}
+
+/// Cópia das entradas da árvore `/EmbeddedFiles`, para regravá-la
+fn tree_entries(doc: &Document) -> Vec<(Vec<u8>, Object)> {
+    let mut entries = Vec::new();
+    if let Some(tree) = embedded_files_tree(doc) {
+        collect_entries(doc, tree, 0, &mut entries);
+    }
+    entries.into_iter().map(|(key, value)| (key, value.clone())).collect()
+}
+
+/// Regrava `/EmbeddedFiles` como um único nó ordenado (sem entradas, a árvore é removida)
+fn write_tree(doc: &mut Document, mut entries: Vec<(Vec<u8>, Object)>) -> std::result::Result<(), String> {
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    let names: Vec<Object> = entries
+        .into_iter()
+        .flat_map(|(key, value)| [Object::String(key, StringFormat::Literal), value])
+        .collect();
+
+    let catalog_id = doc.trailer.get(b"Root").and_then(Object::as_reference).map_err(|e| e.to_string())?;
+    let names_ref = doc.get_dictionary(catalog_id).map_err(|e| e.to_string())?.get(b"Names").ok().cloned();
+
+    // O dicionário `/Names` pode ser indireto ou ficar dentro do catálogo
+    let names_dict = match names_ref {
+        Some(Object::Reference(id)) => doc.get_dictionary_mut(id).map_err(|e| e.to_string())?,
+        Some(Object::Dictionary(_)) => doc
+            .get_dictionary_mut(catalog_id)
+            .and_then(|catalog| catalog.get_mut(b"Names"))
+            .and_then(Object::as_dict_mut)
+            .map_err(|e| e.to_string())?,
+        _ if names.is_empty() => return Ok(()),
+        _ => {
+            let names_id = doc.add_object(Dictionary::new());
+            doc.get_dictionary_mut(catalog_id).map_err(|e| e.to_string())?.set("Names", names_id);
+            doc.get_dictionary_mut(names_id).map_err(|e| e.to_string())?
+        }
+    };
+
+    if names.is_empty() {
+        names_dict.remove(b"EmbeddedFiles");
+    } else {
+        names_dict.set("EmbeddedFiles", dictionary! { "Names" => names });
+    }
+
+    Ok(())
+}
+
+/// Lê uma text string de um dicionário
+fn text_string(doc: &Document, dict: &Dictionary, key: &[u8]) -> Option<String> {
+    dict.get_deref(key, doc)
+        .and_then(Object::as_str)
+        .ok()
+        .map(pdf_encoding::decode_text_string)
+}
+
+/// Lista opcional `names` da request
+fn parse_names(data: &Value) -> Result<Vec<String>> {
+    match data.get("names") {
+        Some(names) => serde_json::from_value(names.clone())
+            .map_err(|e| AppError::validation(format!("Invalid attachment names: {}", e))),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Nome de arquivo seguro para gravar um anexo: sem diretórios nem caracteres reservados
+fn safe_file_name(name: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = base
+        .chars()
+        .map(|c| if c.is_control() || "<>:\"|?*".contains(c) { '_' } else { c })
+        .collect();
+    let cleaned = cleaned.trim().trim_start_matches('.');
+
+    if cleaned.is_empty() {
+        "attachment".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// Nome seguro, ainda não usado na extração e livre no destino segundo `exists`
+/// ("nome (2).ext" em caso de repetição)
+fn unique_file_name(
+    name: &str,
+    used: &mut HashSet<String>,
+    exists: impl Fn(&str) -> Result<bool>,
+) -> Result<String> {
+    let file_name = safe_file_name(name);
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem.to_string(), format!(".{}", extension)),
+        _ => (file_name.clone(), String::new()),
+    };
+
+    let mut candidate = file_name;
+    let mut counter = 2;
+    while used.contains(&candidate.to_lowercase()) || exists(&candidate)? {
+        candidate = format!("{} ({}){}", stem, counter, extension);
+        counter += 1;
+    }
+    used.insert(candidate.to_lowercase());
+    Ok(candidate)
+}
+
+// ==================== FUNÇÕES DE CONVENIÊNCIA ====================
+
+/// Função de conveniência para listagem de anexos
+#[instrument(name = "list_attachments", skip(data, runtime))]
+pub async fn list_attachments(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
+    let request = AttachmentsRequest::from_value(&data)?;
+    let manager = PdfAttachmentManager::with_runtime(runtime);
+    let result = manager.list_attachments(request).await?;
+
+    serde_json::to_value(result)
+        .map_err(|e| AppError::serialization(format!("Failed to serialize result: {}", e)))
+}
+
+/// Função de conveniência para extração de anexos
+#[instrument(name = "extract_attachments", skip(data, runtime))]
+pub async fn extract_attachments(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
+    let request = ExtractAttachmentsRequest::from_value(&data)?;
+    let manager = PdfAttachmentManager::with_runtime(runtime);
+    let result = manager.extract_attachments(request).await?;
+
+    serde_json::to_value(result)
+        .map_err(|e| AppError::serialization(format!("Failed to serialize result: {}", e)))
+}
+
+/// Função de conveniência para anexar um arquivo
+#[instrument(name = "add_attachment", skip(data, runtime))]
+pub async fn add_attachment(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
+    let request = AddAttachmentRequest::from_value(&data)?;
+    let manager = PdfAttachmentManager::with_runtime(runtime);
+    let result = manager.add_attachment(request).await?;
+
+    serde_json::to_value(result)
+        .map_err(|e| AppError::serialization(format!("Failed to serialize result: {}", e)))
+}
+
+/// Função de conveniência para remoção de anexos
+#[instrument(name = "remove_attachments", skip(data, runtime))]
+pub async fn remove_attachments(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
+    let request = RemoveAttachmentsRequest::from_value(&data)?;
+    let manager = PdfAttachmentManager::with_runtime(runtime);
+    let result = manager.remove_attachments(request).await?;
+
+    serde_json::to_value(result)
+        .map_err(|e| AppError::serialization(format!("Failed to serialize result: {}", e)))
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::test_support;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    /// Fatura Factur-X gravada como pelo Acrobat: árvore `/EmbeddedFiles` com
+    /// dois nós folha, XML comprimido com `/Params` completos e um contrato
+    /// com nome UTF-16 e `/CheckSum` desatualizado
+    const FACTURX_INVOICE: &[u8] = b"%PDF-1.7\n\
+    %\xE2\xE3\xCF\xD3\n\
+    1 0 obj\n\
+    <</AF[8 0 R]/Names 6 0 R/Pages 2 0 R/Type/Catalog>>\n\
+    endobj\n\
+    2 0 obj\n\
+    <</Count 1/Kids[3 0 R]/Type/Pages>>\n\
+    endobj\n\
+    3 0 obj\n\
+    <</Contents 4 0 R/MediaBox[0 0 595 842]/Parent 2 0 R/Resources<<>>/Type/Page>>\n\
+    endobj\n\
+    4 0 obj\n\
+    <</Length 27>>stream\n\
+    0 0 1 rg 72 720 200 20 re f\n\
+    endstream\n\
+    endobj\n\
+    6 0 obj\n\
+    <</EmbeddedFiles 7 0 R>>\n\
+    endobj\n\
+    7 0 obj\n\
+    <</Kids[10 0 R 11 0 R]>>\n\
+    endobj\n\
+    10 0 obj\n\
+    <</Limits[<FEFF0063006F006E0074007200610074006F0020201300200061006E00650078006F002E007000640066><FEFF0063006F006E0074007200610074006F0020201300200061006E00650078006F002E007000640066>]/Names[<FEFF0063006F006E0074007200610074006F0020201300200061006E00650078006F002E007000640066> 12 0 R]>>\n\
+    endobj\n\
+    11 0 obj\n\
+    <</Limits[(factur-x.xml)(factur-x.xml)]/Names[(factur-x.xml) 8 0 R]>>\n\
+    endobj\n\
+    8 0 obj\n\
+    <</AFRelationship/Alternative/Desc(Factur-X/ZUGFeRD invoice)/EF<</F 9 0 R/UF 9 0 R>>/F(factur-x.xml)/Type/Filespec/UF(factur-x.xml)>>\n\
+    endobj\n\
+    9 0 obj\n\
+    <</Filter/FlateDecode/Params<</CheckSum<075A85077D19BFB91452D5569EF94757>/CreationDate(D:20240212093000+01'00')/ModDate(D:20240212093512+01'00')/Size 336>>/Subtype/text#2Fxml/Type/EmbeddedFile/Length 222>>stream\n\
+    x\xDAm\x8F\xC1n\xC20\x0C\x86\xEF}\x8A(\x87\xDD:B\xC5a\xCA\xDA\xA0i\x8C\xAD\x87M\x93\x80\x07\xF0\x12\x0F*5\xCE\xE4\xA4\x08^l\xA7\xDDx\xB1\x15\xCA\x8DJ\x96\xF5\xCB\xF6\xFF\xFDr9?\xF8V\xEC\x91c\x13\xA8\x92\xD3{%\x05\x92\r\xAE\xA1m%7\xEBe\xFE \xE7&+9z\xFD\xCC!\xC6\x9A\\\x17\x13\x1Fk\xDA\x87\xC6\xA2\xE8\xED\x14u\xBF\xAEd\xC7\xA4\xBBs\xA1\xC5\xBE[\xFC\x06\x9B\xB4\x83\x04:& \x07\xECF\x19z\xAA\x94\xBCf\xBC\x1C\xEC\x0Eh\x8Bn\x11l\xE7\x91\x92)\x19\xBC\xAE\x17f\xF9\x94\x17\xAA\x98\xE5J\xCD\x8Arr\x1D\xF6b\xD4\x94]\\+l[\xE45\x83\xC3O\xE0t\x1CX\x1F\xE0\xD1\xBC\x9F\xFE\xCE;q\x07\xFE\xE7Q\xACN\xBF;B\xF1\xEA\xBF\xDE\x06\xF4\xE5f\x907\x90l\x08\x1D\xFB\xC4d\xFF\x93\xE6w\x1A\n\
+    endstream\n\
+    endobj\n\
+    12 0 obj\n\
+    <</Desc(Contrato de fornecimento)/EF<</F 13 0 R>>/F(contrato - anexo.pdf)/Type/Filespec/UF<FEFF0063006F006E0074007200610074006F0020201300200061006E00650078006F002E007000640066>>>\n\
+    endobj\n\
+    13 0 obj\n\
+    <</Params<</CheckSum<A269441A785477F023E06C9A8D97E2F7>/Size 36>>/Type/EmbeddedFile/Length 36>>stream\n\
+    %PDF-1.4\n\
+    %stub do contrato assinado\n\
+    \n\
+    endstream\n\
+    endobj\n\
+    xref\n\
+    0 14\n\
+    0000000000 65535 f \n\
+    0000000015 00000 n \n\
+    0000000082 00000 n \n\
+    0000000133 00000 n \n\
+    0000000227 00000 n \n\
+    0000000000 00000 f \n\
+    0000000301 00000 n \n\
+    0000000341 00000 n \n\
+    0000000770 00000 n \n\
+    0000000919 00000 n \n\
+    0000000381 00000 n \n\
+    0000000684 00000 n \n\
+    0000001379 00000 n \n\
+    0000001574 00000 n \n\
+    trailer\n\
+    <</ID[<3B7C0F1E5A9D4B2C8E6F1A0B9C8D7E6F><3B7C0F1E5A9D4B2C8E6F1A0B9C8D7E6F>]/Root 1 0 R/Size 14>>\n\
+    startxref\n\
+    1738\n\
+    %%EOF\n";
+
+    const FACTURX_XML: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <rsm:CrossIndustryInvoice xmlns:rsm=\"urn:un:unece:uncefact:data:standard:CrossIndustryInvoice:100\">\n\
+        <rsm:ExchangedDocument><ram:ID>FA-2024-0042</ram:ID></rsm:ExchangedDocument>\n\
+        <ram:SellerTradeParty><ram:Name>Müller &amp; Söhne GmbH</ram:Name></ram:SellerTradeParty>\n\
+        </rsm:CrossIndustryInvoice>\n";
+
+    /// Documento com um anexo na árvore e outro em uma anotação da página 1
+    fn document_with_attachments() -> Document {
+        let mut doc = test_support::build_sample_pdf(1);
+        add(&mut doc, "relatório.csv", b"a;b\n1;2\n", Some("Dados"), "text/csv").unwrap();
+
+        let data = doc.add_object(Stream::new(dictionary! { "Type" => "EmbeddedFile" }, b"MZ\x90\x00".to_vec()));
+        let spec = doc.add_object(dictionary! {
+            "Type" => "Filespec",
+            "F" => Object::string_literal("invoice.pdf"),
+            "EF" => dictionary! { "F" => data },
+        });
+        let annot = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "FileAttachment",
+            "Rect" => vec![10.into(), 10.into(), 30.into(), 30.into()],
+            "FS" => spec,
+            "Contents" => Object::string_literal("See invoice"),
+        });
+        let page_id = doc.get_pages()[&1];
+        doc.get_dictionary_mut(page_id).unwrap().set("Annots", vec![annot.into()]);
+        doc
+    }
+
+    fn save(doc: &mut Document, dir: &Path, name: &str) -> PathBuf {
+        let path = dir.join(name);
+        doc.save(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_list_reads_tree_and_annotations() {
+        let mut doc = document_with_attachments();
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        let attachments = read_all(&Document::load_mem(&bytes).unwrap());
+        assert_eq!(attachments.len(), 2);
+
+        let (csv, data) = &attachments[0];
+        assert_eq!(data.as_deref(), Some(&b"a;b\n1;2\n"[..]));
+        assert_eq!(csv.name, "relatório.csv");
+        assert_eq!(csv.file_name.as_deref(), Some("relatório.csv"));
+        assert_eq!(csv.source, AttachmentSource::EmbeddedFiles);
+        assert_eq!(csv.size, Some(8));
+        assert_eq!(csv.mime_type.as_deref(), Some("text/csv"));
+        assert_eq!(csv.description.as_deref(), Some("Dados"));
+        // MD5 de "a;b\n1;2\n"
+        let md5: String = Md5::digest(b"a;b\n1;2\n").iter().map(|byte| format!("{:02x}", byte)).collect();
+        assert_eq!(csv.checksum.as_deref(), Some(md5.as_str()));
+        assert_eq!(csv.checksum_valid, Some(true));
+        assert_eq!(csv.creation_date, csv.modification_date);
+        assert!(csv.modification_date.as_deref().is_some_and(|date| date.starts_with("D:") && date.len() == 23));
+        assert_eq!(csv.page, None);
+        assert!(!csv.executable_content);
+
+        let (invoice, data) = &attachments[1];
+        assert_eq!(data.as_deref(), Some(&b"MZ\x90\x00"[..]));
+        assert_eq!(invoice.name, "invoice.pdf");
+        assert_eq!(invoice.file_name.as_deref(), Some("invoice.pdf"));
+        assert_eq!(invoice.source, AttachmentSource::Annotation);
+        assert_eq!(invoice.page, Some(1));
+        assert_eq!(invoice.size, Some(4));
+        assert_eq!(invoice.mime_type, None);
+        assert_eq!(invoice.description.as_deref(), Some("See invoice"));
+        assert_eq!(invoice.checksum, None);
+        assert_eq!(invoice.checksum_valid, None);
+        assert!(invoice.executable_content);
+    }
+
+    #[test]
+    fn test_add_replaces_same_name_and_keeps_tree_sorted() {
+        let mut doc = test_support::build_sample_pdf(1);
+        add(&mut doc, "b.txt", b"old", None, "text/plain").unwrap();
+        add(&mut doc, "a.txt", b"first", None, "text/plain").unwrap();
+        add(&mut doc, "b.txt", b"new!", None, "text/plain").unwrap();
+
+        let attachments: Vec<_> = read_all(&doc)
+            .into_iter()
+            .map(|(attachment, data)| (attachment.name, data.unwrap()))
+            .collect();
+        assert_eq!(attachments, vec![
+            ("a.txt".to_string(), b"first".to_vec()),
+            ("b.txt".to_string(), b"new!".to_vec()),
+        ]);
+
+        // Um único nó folha, com as chaves em ordem
+        let tree = embedded_files_tree(&doc).unwrap();
+        let keys: Vec<&[u8]> = tree.get(b"Names").unwrap().as_array().unwrap()
+            .iter()
+            .step_by(2)
+            .map(|key| key.as_str().unwrap())
+            .collect();
+        assert_eq!(keys, vec![&b"a.txt"[..], &b"b.txt"[..]]);
+    }
+
+    #[test]
+    fn test_remove_drops_entries_and_data() {
+        let mut doc = document_with_attachments();
+        let removed = remove(&mut doc, &["relatório.csv".to_string(), "invoice.pdf".to_string()]).unwrap();
+        assert_eq!(removed, vec!["relatório.csv", "invoice.pdf"]);
+        assert!(list(&doc).is_empty());
+
+        // Nenhuma stream de arquivo incorporado sobrevive no documento
+        assert!(!doc
+            .objects
+            .values()
+            .any(|object| matches!(object, Object::Stream(stream) if stream.dict.type_is(b"EmbeddedFile"))));
+    }
+
+    #[test]
+    fn test_unique_file_name_is_safe() {
+        let mut used = HashSet::new();
+        let unique = |name: &str, used: &mut HashSet<String>| {
+            unique_file_name(name, used, |candidate| Ok(candidate == "taken.txt")).unwrap()
+        };
+        assert_eq!(unique("../../etc/passwd", &mut used), "passwd");
+        assert_eq!(unique("C:\\temp\\a?.txt", &mut used), "a_.txt");
+        assert_eq!(unique("A_.TXT", &mut used), "A_ (2).TXT");
+        assert_eq!(unique("..", &mut used), "attachment");
+        assert_eq!(unique("taken.txt", &mut used), "taken (2).txt");
+        assert_eq!(guess_mime_type("Report.PDF"), "application/pdf");
+        assert_eq!(guess_mime_type("data.bin"), DEFAULT_MIME_TYPE);
+    }
+
+    #[tokio::test]
+    async fn test_extract_add_and_remove_round_trip() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let input = save(&mut document_with_attachments(), temp_dir.path(), "input.pdf");
+        let manager = PdfAttachmentManager::new();
+
+        let extract_dir = temp_dir.path().join("attachments");
+        let extracted = manager
+            .extract_attachments(ExtractAttachmentsRequest::from_value(&json!({
+                "file": input,
+                "output_dir": extract_dir,
+            }))?)
+            .await?;
+        let written: Vec<_> = extracted.extracted.iter()
+            .map(|attachment| (attachment.name.as_str(), attachment.path.clone(), attachment.size))
+            .collect();
+        assert_eq!(written, vec![
+            ("relatório.csv", extract_dir.join("relatório.csv"), 8),
+            ("invoice.pdf", extract_dir.join("invoice.pdf"), 4),
+        ]);
+        assert_eq!(std::fs::read(extract_dir.join("relatório.csv"))?, b"a;b\n1;2\n");
+        assert_eq!(std::fs::read(extract_dir.join("invoice.pdf"))?, b"MZ\x90\x00");
+
+        // Uma segunda extração no mesmo destino não sobrescreve os arquivos existentes
+        std::fs::write(extract_dir.join("relatório.csv"), "editado")?;
+        let again = manager
+            .extract_attachments(ExtractAttachmentsRequest::from_value(&json!({
+                "file": input,
+                "output_dir": extract_dir,
+                "names": ["relatório.csv"],
+            }))?)
+            .await?;
+        assert_eq!(again.extracted[0].path, extract_dir.join("relatório (2).csv"));
+        assert_eq!(std::fs::read(extract_dir.join("relatório.csv"))?, b"editado");
+        assert_eq!(std::fs::read(extract_dir.join("relatório (2).csv"))?, b"a;b\n1;2\n");
+
+        let notes = temp_dir.path().join("notes.txt");
+        std::fs::write(&notes, "anotações")?;
+        let with_notes = temp_dir.path().join("with_notes.pdf");
+        let added = manager
+            .add_attachment(AddAttachmentRequest::from_value(&json!({
+                "file": input,
+                "output": with_notes,
+                "attachment": notes,
+                "config": { "description": "Notas" },
+            }))?)
+            .await?;
+        assert_eq!(added.changed, vec!["notes.txt"]);
+        assert_eq!(added.attachments.len(), 3);
+        let listed = manager
+            .list_attachments(AttachmentsRequest::from_value(&json!({ "file": with_notes }))?)
+            .await?;
+        assert_eq!(listed.attachment_count, 3);
+        assert_eq!(listed.total_size, 8 + "anotações".len() as u64 + 4);
+        let notes_info = listed.attachments.iter().find(|a| a.name == "notes.txt").unwrap();
+        assert_eq!(notes_info.mime_type.as_deref(), Some("text/plain"));
+        assert_eq!(notes_info.description.as_deref(), Some("Notas"));
+        let reloaded = Document::load(&with_notes).unwrap();
+        let notes_data = read_all(&reloaded).into_iter().find(|(a, _)| a.name == "notes.txt").unwrap().1;
+        assert_eq!(notes_data.as_deref(), Some("anotações".as_bytes()));
+
+        let cleaned = temp_dir.path().join("cleaned.pdf");
+        let removed = manager
+            .remove_attachments(RemoveAttachmentsRequest::from_value(&json!({
+                "file": with_notes,
+                "output": cleaned,
+                "names": ["invoice.pdf", "notes.txt"],
+            }))?)
+            .await?;
+        let remaining: Vec<_> = removed.attachments.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(remaining, vec!["relatório.csv"]);
+        assert!(!std::fs::read(&cleaned)?.windows(4).any(|w| w == b"MZ\x90\x00"));
+
+        let missing = RemoveAttachmentsRequest::from_value(&json!({
+            "file": cleaned,
+            "output": temp_dir.path().join("other.pdf"),
+            "names": ["absent.bin"],
+        }))?;
+        assert!(manager.remove_attachments(missing).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_facturx_invoice_attachments() -> Result<()> {
+        let doc = Document::load_mem(FACTURX_INVOICE).unwrap();
+        let contract = AttachmentInfo {
+            name: "contrato – anexo.pdf".to_string(),
+            file_name: Some("contrato – anexo.pdf".to_string()),
+            description: Some("Contrato de fornecimento".to_string()),
+            size: Some(36),
+            mime_type: None,
+            creation_date: None,
+            modification_date: None,
+            checksum: Some("a269441a785477f023e06c9a8d97e2f7".to_string()),
+            checksum_valid: Some(false),
+            source: AttachmentSource::EmbeddedFiles,
+            page: None,
+            executable_content: false,
+            object_id: Some(12),
+        };
+        let invoice = AttachmentInfo {
+            name: "factur-x.xml".to_string(),
+            file_name: Some("factur-x.xml".to_string()),
+            description: Some("Factur-X/ZUGFeRD invoice".to_string()),
+            size: Some(336),
+            mime_type: Some("text/xml".to_string()),
+            creation_date: Some("D:20240212093000+01'00'".to_string()),
+            modification_date: Some("D:20240212093512+01'00'".to_string()),
+            checksum: Some("075a85077d19bfb91452d5569ef94757".to_string()),
+            checksum_valid: Some(true),
+            source: AttachmentSource::EmbeddedFiles,
+            page: None,
+            executable_content: false,
+            object_id: Some(8),
+        };
+        assert_eq!(read_all(&doc), vec![
+            (contract, Some(b"%PDF-1.4\n%stub do contrato assinado\n".to_vec())),
+            (invoice, Some(FACTURX_XML.as_bytes().to_vec())),
+        ]);
+
+        let temp_dir = TempDir::new()?;
+        let input = temp_dir.path().join("fatura.pdf");
+        std::fs::write(&input, FACTURX_INVOICE)?;
+        let extract_dir = temp_dir.path().join("anexos");
+        let extracted = PdfAttachmentManager::new()
+            .extract_attachments(ExtractAttachmentsRequest::from_value(&json!({
+                "file": input,
+                "output_dir": extract_dir,
+                "names": ["factur-x.xml"],
+            }))?)
+            .await?;
+        let written: Vec<_> = extracted.extracted.iter()
+            .map(|attachment| (attachment.name.as_str(), attachment.path.clone(), attachment.size))
+            .collect();
+        assert_eq!(written, vec![("factur-x.xml", extract_dir.join("factur-x.xml"), 336)]);
+        assert!(extracted.skipped.is_empty());
+        assert_eq!(std::fs::read_to_string(extract_dir.join("factur-x.xml"))?, FACTURX_XML);
+        Ok(())
+    }
+}
@@ -0,0 +1,510 @@
+//! Leitura de content streams
+//!
+//! O parser de conteúdo do `lopdf` não aceita imagens inline (`BI ... ID ... EI`)
+//! e descarta o stream inteiro quando encontra uma. Este módulo faz uma leitura
+//! tolerante dos operadores, usada pelas análises que precisam seguir o que é
+//! desenhado na página.
+//!
+//! ## Funcionalidades:
+//! - Operandos como `lopdf::Object` (números, nomes, strings, arrays e dicionários)
+//! - Imagens inline, com as chaves e valores abreviados expandidos (`/W` → `/Width`)
+//! - Bytes que não formam um token válido são ignorados sem interromper a leitura
+
+use lopdf::content::Operation;
+use lopdf::{Dictionary, Object, StringFormat};
+
+/// Profundidade máxima de arrays/dicionários aninhados nos operandos
+const MAX_NESTING: usize = 32;
+
+/// Item de um content stream
+#[derive(Debug, Clone)]
+pub enum ContentItem {
+    /// Operador com seus operandos
+    Operation(Operation),
+    /// Imagem inline: parâmetros (com nomes completos) e dados ainda codificados
+    InlineImage { dict: Dictionary, data: Vec<u8> },
+}
+
+/// Lê os operadores de um content stream já decodificado
+pub fn parse(data: &[u8]) -> Vec<ContentItem> {
+    let mut lexer = Lexer { data, pos: 0 };
+    let mut items = Vec::new();
+    let mut operands = Vec::new();
+
+    while let Some(token) = lexer.next_token(0) {
+        match token {
+            Token::Object(object) => operands.push(object),
+            Token::Operator(operator) if operator == b"BI" => {
+                operands.clear();
+                if let Some((dict, data)) = lexer.inline_image() {
+                    items.push(ContentItem::InlineImage { dict, data });
+                }
+            }
+            Token::Operator(operator) => {
+                let operator = String::from_utf8_lossy(&operator);
+                items.push(ContentItem::Operation(Operation::new(&operator, std::mem::take(&mut operands))));
+            }
+            Token::ArrayEnd | Token::DictEnd | Token::Invalid => {}
+        }
+    }
+
+    items
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+/// Token lido do content stream
+enum Token {
+    Object(Object),
+    Operator(Vec<u8>),
+    ArrayEnd,
+    DictEnd,
+    Invalid,
+}
+
+struct Lexer<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Lexer<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(byte) = self.peek() {
+            if is_whitespace(byte) {
+                self.pos += 1;
+            } else if byte == b'%' {
+                while let Some(byte) = self.peek() {
+                    if byte == b'\r' || byte == b'\n' {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn next_token(&mut self, depth: usize) -> Option<Token> {
+        self.skip_whitespace();
+        let byte = self.peek()?;
+
+        let token = match byte {
+            b'/' => {
+                self.pos += 1;
+                Token::Object(Object::Name(self.name()))
+            }
+            b'(' => {
+                self.pos += 1;
+                Token::Object(Object::String(self.literal_string(), StringFormat::Literal))
+            }
+            b'<' if self.data.get(self.pos + 1) == Some(&b'<') => {
+                self.pos += 2;
+                if depth >= MAX_NESTING {
+                    return Some(Token::Invalid);
+                }
+                Token::Object(Object::Dictionary(self.dictionary(depth + 1)))
+            }
+            b'<' => {
+                self.pos += 1;
+                Token::Object(Object::String(self.hex_string(), StringFormat::Hexadecimal))
+            }
+            b'>' if self.data.get(self.pos + 1) == Some(&b'>') => {
+                self.pos += 2;
+                Token::DictEnd
+            }
+            b'[' => {
+                self.pos += 1;
+                if depth >= MAX_NESTING {
+                    return Some(Token::Invalid);
+                }
+                Token::Object(Object::Array(self.array(depth + 1)))
+            }
+            b']' => {
+                self.pos += 1;
+                Token::ArrayEnd
+            }
+            _ if is_delimiter(byte) => {
+                self.pos += 1;
+                Token::Invalid
+            }
+            _ => {
+                let word = self.regular_run();
+                match word {
+                    b"true" => Token::Object(Object::Boolean(true)),
+                    b"false" => Token::Object(Object::Boolean(false)),
+                    b"null" => Token::Object(Object::Null),
+                    _ if starts_number(word) => parse_number(word).map(Token::Object).unwrap_or(Token::Invalid),
+                    _ => Token::Operator(word.to_vec()),
+                }
+            }
+        };
+
+        Some(token)
+    }
+
+    fn regular_run(&mut self) -> &[u8] {
+        let start = self.pos;
+        while let Some(byte) = self.peek() {
+            if is_whitespace(byte) || is_delimiter(byte) {
+                break;
+            }
+            self.pos += 1;
+        }
+        &self.data[start..self.pos]
+    }
+
+    fn name(&mut self) -> Vec<u8> {
+        let raw = self.regular_run();
+        let mut name = Vec::with_capacity(raw.len());
+        let mut index = 0;
+
+        while index < raw.len() {
+            if raw[index] == b'#' {
+                if let Some(byte) = raw.get(index + 1..index + 3).and_then(hex_byte) {
+                    name.push(byte);
+                    index += 3;
+                    continue;
+                }
+            }
+            name.push(raw[index]);
+            index += 1;
+        }
+
+        name
+    }
+
+    fn literal_string(&mut self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut depth = 1;
+
+        while let Some(byte) = self.peek() {
+            self.pos += 1;
+            match byte {
+                b'(' => {
+                    depth += 1;
+                    bytes.push(byte);
+                }
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                    bytes.push(byte);
+                }
+                b'\\' => {
+                    let Some(escaped) = self.peek() else {
+                        break;
+                    };
+                    self.pos += 1;
+                    match escaped {
+                        b'n' => bytes.push(b'\n'),
+                        b'r' => bytes.push(b'\r'),
+                        b't' => bytes.push(b'\t'),
+                        b'b' => bytes.push(0x08),
+                        b'f' => bytes.push(0x0C),
+                        b'0'..=b'7' => {
+                            let mut value = u32::from(escaped - b'0');
+                            for _ in 0..2 {
+                                match self.peek() {
+                                    Some(digit @ b'0'..=b'7') => {
+                                        value = value * 8 + u32::from(digit - b'0');
+                                        self.pos += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            bytes.push(value as u8);
+                        }
+                        // Quebra de linha escapada continua a string
+                        b'\r' => {
+                            if self.peek() == Some(b'\n') {
+                                self.pos += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        other => bytes.push(other),
+                    }
+                }
+                _ => bytes.push(byte),
+            }
+        }
+
+        bytes
+    }
+
+    fn hex_string(&mut self) -> Vec<u8> {
+        let mut digits = Vec::new();
+
+        while let Some(byte) = self.peek() {
+            self.pos += 1;
+            if byte == b'>' {
+                break;
+            }
+            if byte.is_ascii_hexdigit() {
+                digits.push(byte);
+            }
+        }
+        if digits.len() % 2 == 1 {
+            digits.push(b'0');
+        }
+
+        digits.chunks(2).filter_map(hex_byte).collect()
+    }
+
+    fn array(&mut self, depth: usize) -> Vec<Object> {
+        let mut items = Vec::new();
+
+        while let Some(token) = self.next_token(depth) {
+            match token {
+                Token::Object(object) => items.push(object),
+                Token::ArrayEnd => break,
+                Token::Operator(_) | Token::DictEnd | Token::Invalid => {}
+            }
+        }
+
+        items
+    }
+
+    fn dictionary(&mut self, depth: usize) -> Dictionary {
+        let mut dict = Dictionary::new();
+        let mut key: Option<Vec<u8>> = None;
+
+        while let Some(token) = self.next_token(depth) {
+            match token {
+                Token::DictEnd => break,
+                Token::Object(object) => match key.take() {
+                    Some(key) => dict.set(key, object),
+                    None => {
+                        if let Object::Name(name) = object {
+                            key = Some(name);
+                        }
+                    }
+                },
+                Token::Operator(_) | Token::ArrayEnd | Token::Invalid => {}
+            }
+        }
+
+        dict
+    }
+
+    /// Lê os parâmetros e os dados de uma imagem inline, logo após `BI`
+    fn inline_image(&mut self) -> Option<(Dictionary, Vec<u8>)> {
+        let mut dict = Dictionary::new();
+        let mut key: Option<Vec<u8>> = None;
+
+        loop {
+            match self.next_token(0)? {
+                Token::Operator(operator) if operator == b"ID" => break,
+                Token::Object(object) => match key.take() {
+                    Some(key) => {
+                        let (key, value) = expand_inline_entry(key, object);
+                        dict.set(key, value);
+                    }
+                    None => {
+                        if let Object::Name(name) = object {
+                            key = Some(name);
+                        }
+                    }
+                },
+                _ => {}
+            }
+        }
+
+        // Um único espaço separa `ID` dos dados binários
+        if self.peek().is_some_and(is_whitespace) {
+            self.pos += 1;
+        }
+        let start = self.pos;
+
+        let declared = dict.get(b"Length").and_then(Object::as_i64).ok().filter(|&length| length >= 0);
+        let end = match declared {
+            Some(length) => (start + length as usize).min(self.data.len()),
+            None => self.find_inline_image_end(start),
+        };
+        let data = self.data[start..end].to_vec();
+
+        // Avança até depois do `EI`
+        self.pos = end;
+        match self.find_ei(end) {
+            Some(ei) => self.pos = ei + 2,
+            None => self.pos = self.data.len(),
+        }
+
+        Some((dict, data))
+    }
+
+    /// Fim dos dados de uma imagem inline sem `/Length`: o espaço antes de `EI`
+    fn find_inline_image_end(&self, start: usize) -> usize {
+        match self.find_ei(start) {
+            Some(ei) if ei > start && is_whitespace(self.data[ei - 1]) => ei - 1,
+            Some(ei) => ei,
+            None => self.data.len(),
+        }
+    }
+
+    /// Posição do próximo `EI` isolado (precedido por espaço e seguido por espaço/delimitador)
+    fn find_ei(&self, from: usize) -> Option<usize> {
+        (from..self.data.len().saturating_sub(1)).find(|&index| {
+            self.data[index] == b'E'
+                && self.data[index + 1] == b'I'
+                && (index == from || is_whitespace(self.data[index - 1]))
+                && self
+                    .data
+                    .get(index + 2)
+                    .is_none_or(|&next| is_whitespace(next) || is_delimiter(next))
+        })
+    }
+}
+
+/// Expande chaves e valores abreviados de uma imagem inline
+fn expand_inline_entry(key: Vec<u8>, value: Object) -> (Vec<u8>, Object) {
+    let key = match key.as_slice() {
+        b"BPC" => b"BitsPerComponent".to_vec(),
+        b"CS" => b"ColorSpace".to_vec(),
+        b"D" => b"Decode".to_vec(),
+        b"DP" => b"DecodeParms".to_vec(),
+        b"F" => b"Filter".to_vec(),
+        b"H" => b"Height".to_vec(),
+        b"IM" => b"ImageMask".to_vec(),
+        b"I" => b"Interpolate".to_vec(),
+        b"L" => b"Length".to_vec(),
+        b"W" => b"Width".to_vec(),
+        _ => key,
+    };
+
+    let expand: fn(&[u8]) -> Option<&'static [u8]> = match key.as_slice() {
+        b"ColorSpace" => expand_color_space,
+        b"Filter" => expand_filter,
+        _ => return (key, value),
+    };
+    let value = match value {
+        Object::Name(name) => Object::Name(expand(&name).map(<[u8]>::to_vec).unwrap_or(name)),
+        Object::Array(items) => Object::Array(
+            items
+                .into_iter()
+                .map(|item| match item {
+                    Object::Name(name) => Object::Name(expand(&name).map(<[u8]>::to_vec).unwrap_or(name)),
+                    other => other,
+                })
+                .collect(),
+        ),
+        other => other,
+    };
+
+    (key, value)
+}
+
+fn expand_color_space(name: &[u8]) -> Option<&'static [u8]> {
+    match name {
+        b"G" => Some(b"DeviceGray"),
+        b"RGB" => Some(b"DeviceRGB"),
+        b"CMYK" => Some(b"DeviceCMYK"),
+        b"I" => Some(b"Indexed"),
+        _ => None,
+    }
+}
+
+fn expand_filter(name: &[u8]) -> Option<&'static [u8]> {
+    match name {
+        b"AHx" => Some(b"ASCIIHexDecode"),
+        b"A85" => Some(b"ASCII85Decode"),
+        b"LZW" => Some(b"LZWDecode"),
+        b"Fl" => Some(b"FlateDecode"),
+        b"RL" => Some(b"RunLengthDecode"),
+        b"CCF" => Some(b"CCITTFaxDecode"),
+        b"DCT" => Some(b"DCTDecode"),
+        _ => None,
+    }
+}
+
+fn starts_number(word: &[u8]) -> bool {
+    matches!(word.first(), Some(b'0'..=b'9' | b'+' | b'-' | b'.'))
+}
+
+fn parse_number(word: &[u8]) -> Option<Object> {
+    let text = std::str::from_utf8(word).ok()?;
+    if text.contains('.') {
+        // Alguns geradores escrevem "-.5" ou "5."; o parse de f32 aceita os dois
+        text.parse::<f32>().ok().map(Object::Real)
+    } else {
+        text.parse::<i64>().ok().map(Object::Integer)
+    }
+}
+
+fn hex_byte(digits: &[u8]) -> Option<u8> {
+    std::str::from_utf8(digits).ok().and_then(|text| u8::from_str_radix(text, 16).ok())
+}
+
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\r' | b'\n' | b'\x0C' | b'\0')
+}
+
+fn is_delimiter(byte: u8) -> bool {
+    matches!(byte, b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%')
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operations(items: &[ContentItem]) -> Vec<&str> {
+        items
+            .iter()
+            .map(|item| match item {
+                ContentItem::Operation(operation) => operation.operator.as_str(),
+                ContentItem::InlineImage { .. } => "<inline image>",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parses_operators_and_operands() {
+        let items = parse(b"q 1 0 0 1 72 -.5 cm /Im#31 Do Q\n% comment\nBT (a\\(b\\)\\101) Tj <48 69> Tj [1 (x) -2.5] TJ ET");
+        assert_eq!(operations(&items), vec!["q", "cm", "Do", "Q", "BT", "Tj", "Tj", "TJ", "ET"]);
+
+        let ContentItem::Operation(cm) = &items[1] else { panic!() };
+        assert_eq!(cm.operands.len(), 6);
+        assert_eq!(cm.operands[5].as_float().unwrap(), -0.5);
+
+        let ContentItem::Operation(draw) = &items[2] else { panic!() };
+        assert_eq!(draw.operands[0].as_name().unwrap(), b"Im1");
+
+        let ContentItem::Operation(show) = &items[5] else { panic!() };
+        assert_eq!(show.operands[0].as_str().unwrap(), b"a(b)A");
+
+        let ContentItem::Operation(hex) = &items[6] else { panic!() };
+        assert_eq!(hex.operands[0].as_str().unwrap(), b"Hi");
+
+        let ContentItem::Operation(array) = &items[7] else { panic!() };
+        assert_eq!(array.operands[0].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_parses_inline_image() {
+        let items = parse(b"q 20 0 0 10 0 0 cm BI /W 2 /H 1 /CS /G /BPC 8 /F [/AHx] ID 00FF> EI Q");
+        assert_eq!(operations(&items), vec!["q", "cm", "<inline image>", "Q"]);
+
+        let ContentItem::InlineImage { dict, data } = &items[2] else { panic!() };
+        assert_eq!(dict.get(b"Width").unwrap().as_i64().unwrap(), 2);
+        assert_eq!(dict.get(b"ColorSpace").unwrap().as_name().unwrap(), b"DeviceGray");
+        assert_eq!(dict.get(b"Filter").unwrap().as_array().unwrap()[0].as_name().unwrap(), b"ASCIIHexDecode");
+        assert_eq!(data, b"00FF>");
+    }
+
+    #[test]
+    fn test_inline_image_binary_data_may_contain_ei() {
+        // Com /L, os dados são lidos pelo tamanho mesmo contendo "EI"
+        let items = parse(b"BI /W 4 /H 1 /BPC 8 /CS /G /L 4 ID \x01 EI EI Q");
+        assert_eq!(operations(&items), vec!["<inline image>", "Q"]);
+
+        let ContentItem::InlineImage { data, .. } = &items[0] else { panic!() };
+        assert_eq!(data, b"\x01 EI");
+    }
+}
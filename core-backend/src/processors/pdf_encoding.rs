@@ -0,0 +1,226 @@
+//! Decodificação e codificação de text strings PDF
+//!
+//! Strings de texto (títulos, bookmarks, comentários) são gravadas em
+//! PDFDocEncoding ou em UTF-16BE com BOM `FE FF`; o PDF 2.0 também aceita
+//! UTF-8 com BOM `EF BB BF`. Ler os bytes como UTF-8 estraga acentos e
+//! qualquer texto fora do ASCII.
+//!
+//! Datas (`D:AAAAMMDDHHmmSS+HH'mm'`) também são text strings e são lidas e
+//! gravadas aqui.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, Offset, TimeZone};
+use std::fmt::Display;
+
+/// Caracteres de 0x18-0x1F em PDFDocEncoding (acentos isolados)
+const PDF_DOC_0X18: [char; 8] = ['\u{02D8}', '\u{02C7}', '\u{02C6}', '\u{02D9}', '\u{02DD}', '\u{02DB}', '\u{02DA}', '\u{02DC}'];
+
+/// Caracteres de 0x80-0xA0 em PDFDocEncoding (0x9F não é definido)
+const PDF_DOC_0X80: [char; 33] = [
+    '\u{2022}', '\u{2020}', '\u{2021}', '\u{2026}', '\u{2014}', '\u{2013}', '\u{0192}', '\u{2044}',
+    '\u{2039}', '\u{203A}', '\u{2212}', '\u{2030}', '\u{201E}', '\u{201C}', '\u{201D}', '\u{2018}',
+    '\u{2019}', '\u{201A}', '\u{2122}', '\u{FB01}', '\u{FB02}', '\u{0141}', '\u{0152}', '\u{0160}',
+    '\u{0178}', '\u{017D}', '\u{0131}', '\u{0142}', '\u{0153}', '\u{0161}', '\u{017E}', '\u{FFFD}',
+    '\u{20AC}',
+];
+
+/// Decodifica uma text string PDF, detectando o BOM
+///
+/// Strings hexadecimais e sequências de escape de literais já chegam como
+/// bytes do parser. Em UTF-16 e UTF-8, os marcadores de idioma
+/// (`ESC pt BR ESC`) são removidos, assim como o terminador nulo que alguns
+/// geradores acrescentam.
+pub fn decode_text_string(bytes: &[u8]) -> String {
+    let unicode = if let Some(utf16) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = utf16
+            .chunks(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair.get(1).copied().unwrap_or(0)]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else if let Some(utf8) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        String::from_utf8_lossy(utf8).into_owned()
+    } else {
+        return bytes.iter().map(|&byte| pdf_doc_char(byte)).collect();
+    };
+
+    strip_language_escapes(&unicode).trim_end_matches('\0').to_string()
+}
+
+/// Caractere de um byte em PDFDocEncoding
+pub fn pdf_doc_char(byte: u8) -> char {
+    match byte {
+        0x18..=0x1F => PDF_DOC_0X18[usize::from(byte - 0x18)],
+        0x7F => '\u{FFFD}',
+        0x80..=0xA0 => PDF_DOC_0X80[usize::from(byte - 0x80)],
+        // Demais posições coincidem com o Latin-1
+        _ => char::from(byte),
+    }
+}
+
+/// Codifica uma text string: PDFDocEncoding quando possível, senão UTF-16BE com BOM
+pub fn encode_text_string(text: &str) -> Vec<u8> {
+    let pdf_doc = text.chars().all(|c| u8::try_from(c).is_ok_and(|byte| pdf_doc_char(byte) == c));
+    if pdf_doc {
+        return text.chars().map(|c| c as u8).collect();
+    }
+
+    let mut bytes = vec![0xFE, 0xFF];
+    bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+    bytes
+}
+
+/// Data no formato PDF (`D:AAAAMMDDHHmmSS+HH'mm'`)
+pub fn format_pdf_date<Tz: TimeZone>(time: &DateTime<Tz>) -> String
+where
+    Tz::Offset: Display,
+{
+    let offset = time.offset().fix().local_minus_utc();
+    let sign = if offset < 0 { '-' } else { '+' };
+    let minutes = offset.abs() / 60;
+    format!("D:{}{}{:02}'{:02}'", time.format("%Y%m%d%H%M%S"), sign, minutes / 60, minutes % 60)
+}
+
+/// Lê uma data PDF (`D:AAAAMMDDHHmmSS+HH'mm'`)
+///
+/// Campos omitidos assumem o início do período; sem fuso horário, a data é
+/// tratada como UTC. Variantes comuns de geradores com defeito são aceitas:
+/// prefixo ausente ou minúsculo, dígito a mais no fim, fuso com `:` ou sem
+/// apóstrofos e o bug do ano 2000 (`D:19124...` para 2024).
+pub fn parse_pdf_date(text: &str) -> Option<DateTime<FixedOffset>> {
+    let text = text.trim_matches(|c: char| c.is_whitespace() || c == '\0');
+    let text = text
+        .strip_prefix("D:")
+        .or_else(|| text.strip_prefix("d:"))
+        .unwrap_or(text)
+        .trim_start();
+    let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+    let (stamp, zone) = text.split_at(digits);
+
+    // Bug do ano 2000: "19" seguido de (ano - 1900) com três dígitos
+    let mut stamp = match stamp.get(..5) {
+        Some(year) if digits == 15 && year.starts_with("191") => {
+            format!("{}{}", 1900 + year[2..].parse::<i32>().ok()?, &stamp[5..])
+        }
+        _ => stamp.to_string(),
+    };
+    stamp.truncate(stamp.len().min(14) / 2 * 2);
+    if stamp.len() < 4 {
+        return None;
+    }
+    let field = |start: usize, default: u32| stamp.get(start..start + 2).map_or(Some(default), |v| v.parse().ok());
+
+    let date = NaiveDate::from_ymd_opt(stamp[..4].parse().ok()?, field(4, 1)?, field(6, 1)?)?;
+    let time = date.and_hms_opt(field(8, 0)?, field(10, 0)?, field(12, 0)?)?;
+
+    let zone = zone.trim();
+    let offset = match zone.as_bytes().first() {
+        Some(sign @ (b'+' | b'-')) => {
+            if !zone[1..].chars().all(|c| c.is_ascii_digit() || c == '\'' || c == ':') {
+                return None;
+            }
+            let zone: String = zone[1..].chars().filter(char::is_ascii_digit).collect();
+            let hours: i32 = zone.get(..2)?.parse().ok()?;
+            let minutes: i32 = zone.get(2..4).map_or(Some(0), |v| v.parse().ok())?;
+            let seconds = (hours * 60 + minutes) * 60;
+            FixedOffset::east_opt(if *sign == b'-' { -seconds } else { seconds })?
+        }
+        // "Z" ou fuso ausente; qualquer outra coisa não é data PDF (ex.: ISO 8601)
+        None | Some(b'Z' | b'z') => FixedOffset::east_opt(0)?,
+        Some(_) => return None,
+    };
+
+    offset.from_local_datetime(&time).single()
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+/// Remove os marcadores de idioma (`ESC` código `ESC`) de uma string Unicode
+fn strip_language_escapes(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('\u{1B}') {
+        result.push_str(&rest[..start]);
+        match rest[start + 1..].find('\u{1B}') {
+            Some(end) => rest = &rest[start + 1 + end + 1..],
+            // Marcador sem fechamento: descarta só o ESC
+            None => rest = &rest[start + 1..],
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decodes_pdf_doc_encoding() {
+        // "Relatório – versão" em PDFDocEncoding
+        let bytes = b"Relat\xF3rio \x85 vers\xE3o";
+        assert_eq!(decode_text_string(bytes), "Relatório – versão");
+        assert_eq!(decode_text_string(b"\xA0 \x92"), "€ ™");
+    }
+
+    #[test]
+    fn test_decodes_utf16_and_utf8_with_bom() {
+        let utf16: Vec<u8> = [0xFE, 0xFF]
+            .into_iter()
+            .chain("Ação 日本".encode_utf16().flat_map(u16::to_be_bytes))
+            .collect();
+        assert_eq!(decode_text_string(&utf16), "Ação 日本");
+
+        let utf8 = [&[0xEF, 0xBB, 0xBF][..], "Ação".as_bytes()].concat();
+        assert_eq!(decode_text_string(&utf8), "Ação");
+    }
+
+    #[test]
+    fn test_strips_language_escapes_and_terminator() {
+        let utf16: Vec<u8> = [0xFE, 0xFF]
+            .into_iter()
+            .chain("\u{1B}ptBR\u{1B}Relatório\u{1B}en\u{1B} Report\0".encode_utf16().flat_map(u16::to_be_bytes))
+            .collect();
+        assert_eq!(decode_text_string(&utf16), "Relatório Report");
+    }
+
+    #[test]
+    fn test_encode_text_string_round_trip() {
+        assert_eq!(encode_text_string("Aprovação"), b"Aprova\xE7\xE3o");
+        for text in ["Relatório – versão", "日本語"] {
+            assert_eq!(decode_text_string(&encode_text_string(text)), text);
+        }
+        assert!(encode_text_string("日本語").starts_with(&[0xFE, 0xFF]));
+    }
+
+    #[test]
+    fn test_format_pdf_date() {
+        let time = DateTime::parse_from_rfc3339("2024-01-01T12:00:00-03:00").unwrap();
+        assert_eq!(format_pdf_date(&time), "D:20240101120000-03'00'");
+        let time = DateTime::parse_from_rfc3339("2024-06-30T23:59:58+05:30").unwrap();
+        assert_eq!(format_pdf_date(&time), "D:20240630235958+05'30'");
+    }
+
+    #[test]
+    fn test_parse_pdf_date() {
+        let date = parse_pdf_date("D:20240101120000-03'00'").unwrap();
+        assert_eq!(date.to_rfc3339(), "2024-01-01T12:00:00-03:00");
+        assert_eq!(parse_pdf_date("D:20240630235958+05'30").unwrap().to_rfc3339(), "2024-06-30T23:59:58+05:30");
+        assert_eq!(parse_pdf_date("D:2023Z").unwrap().to_rfc3339(), "2023-01-01T00:00:00+00:00");
+        assert_eq!(parse_pdf_date("20231105").unwrap().to_rfc3339(), "2023-11-05T00:00:00+00:00");
+        assert!(parse_pdf_date("D:20231345").is_none());
+        assert!(parse_pdf_date("yesterday").is_none());
+        assert!(parse_pdf_date("2024-03-01T10:30:00-03:00").is_none());
+
+        // Variantes de geradores com defeito
+        let expected = Some(date);
+        assert_eq!(parse_pdf_date(" d:20240101120000-03:00 "), expected);
+        assert_eq!(parse_pdf_date("D:20240101120000-0300"), expected);
+        assert_eq!(parse_pdf_date("D:202401011200007-03'00'"), expected);
+        assert_eq!(parse_pdf_date("D:191240101120000-03'00'"), expected);
+        assert_eq!(parse_pdf_date("D:20240101120000-03'00'\0"), expected);
+
+        let time = DateTime::parse_from_rfc3339("2024-06-30T23:59:58+05:30").unwrap();
+        assert_eq!(parse_pdf_date(&format_pdf_date(&time)), Some(time));
+    }
+}
@@ -0,0 +1,477 @@
+//! Leitura do dicionário de criptografia (`/Encrypt`)
+//!
+//! Descreve como o documento foi protegido sem decifrar o conteúdo: handler,
+//! versão/revisão, algoritmo, tamanho da chave e os bits de permissão `/P`.
+//! Para o handler `Standard` também verifica se o arquivo abre com a senha de
+//! usuário vazia (o caso comum de PDFs "protegidos" apenas contra edição).
+//!
+//! ## Revisões suportadas na verificação de senha:
+//! - R2-R4: RC4/AES-128, chave derivada por MD5 (ISO 32000-1, algoritmos 2, 4 e 5)
+//! - R5: AES-256 com SHA-256 (extensão Adobe, obsoleta)
+//! - R6: AES-256 com o hash iterativo do ISO 32000-2 (algoritmo 2.B)
+
+use aes::cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit};
+use aes::Aes128;
+use lopdf::{Dictionary, Document, Object};
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::types::pdf_types::PdfPermissions;
+
+/// Preenchimento das senhas nas revisões 2-4 (ISO 32000-1, 7.6.3.3)
+const PASSWORD_PADDING: [u8; 32] = [
+    0x28, 0xBF, 0x4E, 0x5E, 0x4E, 0x75, 0x8A, 0x41, 0x64, 0x00, 0x4E, 0x56, 0xFF, 0xFA, 0x01, 0x08,
+    0x2E, 0x2E, 0x00, 0xB6, 0xD0, 0x68, 0x3E, 0x80, 0x2F, 0x0C, 0xA9, 0xFE, 0x64, 0x53, 0x69, 0x7A,
+];
+
+/// Algoritmo usado para cifrar strings e streams
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EncryptionCipher {
+    Rc4,
+    Aes128,
+    Aes256,
+    /// `/Identity`: o filtro declarado não cifra nada
+    Identity,
+    Unknown,
+}
+
+/// Descrição da criptografia de um documento
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptionInfo {
+    /// Handler de segurança (`/Filter`, ex.: "Standard")
+    pub filter: String,
+    /// `/SubFilter` (handlers de chave pública)
+    pub sub_filter: Option<String>,
+    /// Versão do algoritmo (`/V`)
+    pub version: i64,
+    /// Revisão do handler (`/R`)
+    pub revision: i64,
+    /// Tamanho da chave em bits
+    pub key_length: u32,
+    /// Algoritmo das streams
+    pub cipher: EncryptionCipher,
+    /// Os metadados XMP também são cifrados?
+    pub encrypt_metadata: bool,
+    /// Valor bruto de `/P`
+    pub permission_bits: i32,
+    /// Permissões derivadas de `/P`
+    pub permissions: PdfPermissions,
+    /// O documento abre sem senha (senha de usuário vazia)?
+    pub empty_user_password: bool,
+}
+
+/// Lê `/Encrypt` do trailer; `None` quando o documento não é criptografado
+pub fn analyze(doc: &Document) -> Option<EncryptionInfo> {
+    let encrypt = doc.trailer.get_deref(b"Encrypt", doc).and_then(Object::as_dict).ok()?;
+
+    let name = |key: &[u8]| {
+        encrypt
+            .get_deref(key, doc)
+            .and_then(Object::as_name)
+            .ok()
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+    };
+    let integer = |key: &[u8]| encrypt.get_deref(key, doc).and_then(Object::as_i64).ok();
+
+    let filter = name(b"Filter").unwrap_or_else(|| "Unknown".to_string());
+    let version = integer(b"V").unwrap_or(0);
+    let revision = integer(b"R").unwrap_or(0);
+    let encrypt_metadata = encrypt.get_deref(b"EncryptMetadata", doc).and_then(Object::as_bool).unwrap_or(true);
+    let (cipher, key_length) = cipher(doc, encrypt, version);
+
+    // Handlers sem `/P` (chave pública) não declaram permissões no dicionário
+    let permission_bits = integer(b"P").map(|p| p as i32);
+    let permissions = permission_bits
+        .map(|bits| PdfPermissions::from_bits(bits, revision))
+        .unwrap_or_default();
+
+    let empty_user_password = filter == "Standard"
+        && StandardHandler::from_dictionary(doc, encrypt, revision, key_length, encrypt_metadata)
+            .is_some_and(|handler| handler.authenticates_user(b""));
+
+    Some(EncryptionInfo {
+        filter,
+        sub_filter: name(b"SubFilter"),
+        version,
+        revision,
+        key_length,
+        cipher,
+        encrypt_metadata,
+        permission_bits: permission_bits.unwrap_or(0),
+        permissions,
+        empty_user_password,
+    })
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+/// Algoritmo e tamanho da chave a partir de `/V`, `/Length` e do filtro de cripta `/StdCF`
+fn cipher(doc: &Document, encrypt: &Dictionary, version: i64) -> (EncryptionCipher, u32) {
+    let length = encrypt
+        .get_deref(b"Length", doc)
+        .and_then(Object::as_i64)
+        .ok()
+        .map(|bits| bits.clamp(40, 256) as u32);
+
+    match version {
+        1 => (EncryptionCipher::Rc4, 40),
+        2 | 3 => (EncryptionCipher::Rc4, length.unwrap_or(40)),
+        4 | 5 => {
+            let crypt_filter = encrypt
+                .get_deref(b"StmF", doc)
+                .and_then(Object::as_name)
+                .ok()
+                .and_then(|stream_filter| {
+                    encrypt
+                        .get_deref(b"CF", doc)
+                        .and_then(Object::as_dict)
+                        .and_then(|filters| filters.get_deref(stream_filter, doc))
+                        .and_then(Object::as_dict)
+                        .ok()
+                });
+            let method = crypt_filter.and_then(|cf| cf.get_deref(b"CFM", doc).and_then(Object::as_name).ok());
+
+            match method {
+                Some(b"V2") => (EncryptionCipher::Rc4, length.unwrap_or(128)),
+                Some(b"AESV2") => (EncryptionCipher::Aes128, 128),
+                Some(b"AESV3") => (EncryptionCipher::Aes256, 256),
+                Some(b"None") => (EncryptionCipher::Identity, 0),
+                _ if version == 5 => (EncryptionCipher::Aes256, 256),
+                // `/StmF /Identity` ou ausente
+                _ if crypt_filter.is_none() => (EncryptionCipher::Identity, 0),
+                _ => (EncryptionCipher::Unknown, length.unwrap_or(128)),
+            }
+        }
+        _ => (EncryptionCipher::Unknown, length.unwrap_or(0)),
+    }
+}
+
+/// Valores do handler `Standard` usados na verificação de senha
+struct StandardHandler {
+    revision: i64,
+    key_bytes: usize,
+    encrypt_metadata: bool,
+    owner_key: Vec<u8>,
+    user_key: Vec<u8>,
+    permissions: i32,
+    document_id: Vec<u8>,
+}
+
+impl StandardHandler {
+    fn from_dictionary(
+        doc: &Document,
+        encrypt: &Dictionary,
+        revision: i64,
+        key_length: u32,
+        encrypt_metadata: bool,
+    ) -> Option<Self> {
+        let bytes = |key: &[u8]| encrypt.get_deref(key, doc).and_then(Object::as_str).ok().map(<[u8]>::to_vec);
+        let document_id = doc
+            .trailer
+            .get_deref(b"ID", doc)
+            .and_then(Object::as_array)
+            .ok()
+            .and_then(|ids| ids.first())
+            .and_then(|id| id.as_str().ok())
+            .map(<[u8]>::to_vec)
+            .unwrap_or_default();
+
+        Some(Self {
+            revision,
+            key_bytes: if revision == 2 { 5 } else { (key_length / 8).clamp(5, 16) as usize },
+            encrypt_metadata,
+            owner_key: bytes(b"O")?,
+            user_key: bytes(b"U")?,
+            permissions: encrypt.get_deref(b"P", doc).and_then(Object::as_i64).ok()? as i32,
+            document_id,
+        })
+    }
+
+    /// A senha informada é a senha de usuário?
+    fn authenticates_user(&self, password: &[u8]) -> bool {
+        match self.revision {
+            2..=4 => self.authenticates_legacy_user(password),
+            5 | 6 => {
+                if self.user_key.len() < 40 {
+                    return false;
+                }
+                // Senhas UTF-8 são limitadas a 127 bytes
+                let password = &password[..password.len().min(127)];
+                let validation_salt = &self.user_key[32..40];
+                let hash = if self.revision == 5 {
+                    Sha256::new().chain_update(password).chain_update(validation_salt).finalize().to_vec()
+                } else {
+                    hardened_hash(password, validation_salt, &[])
+                };
+                hash[..] == self.user_key[..32]
+            }
+            _ => false,
+        }
+    }
+
+    /// Algoritmos 4 e 5 do ISO 32000-1
+    fn authenticates_legacy_user(&self, password: &[u8]) -> bool {
+        let key = self.file_key(password);
+
+        let expected = if self.revision == 2 {
+            rc4(&key, &PASSWORD_PADDING)
+        } else {
+            let mut value = Md5::new()
+                .chain_update(PASSWORD_PADDING)
+                .chain_update(&self.document_id)
+                .finalize()
+                .to_vec();
+            for round in 0..20u8 {
+                let round_key: Vec<u8> = key.iter().map(|byte| byte ^ round).collect();
+                value = rc4(&round_key, &value);
+            }
+            value
+        };
+
+        // Nas revisões 3 e 4 apenas os 16 primeiros bytes de `/U` são significativos
+        self.user_key.len() >= expected.len() && self.user_key[..expected.len()] == expected[..]
+    }
+
+    /// Chave do arquivo derivada da senha de usuário (algoritmo 2)
+    fn file_key(&self, password: &[u8]) -> Vec<u8> {
+        let mut hasher = Md5::new()
+            .chain_update(padded_password(password))
+            .chain_update(&self.owner_key[..self.owner_key.len().min(32)])
+            .chain_update(self.permissions.to_le_bytes())
+            .chain_update(&self.document_id);
+        if self.revision >= 4 && !self.encrypt_metadata {
+            hasher.update([0xFF; 4]);
+        }
+
+        let mut key = hasher.finalize().to_vec();
+        if self.revision >= 3 {
+            for _ in 0..50 {
+                key = Md5::digest(&key[..self.key_bytes]).to_vec();
+            }
+        }
+        key.truncate(self.key_bytes);
+        key
+    }
+}
+
+/// Senha completada com `PASSWORD_PADDING` até 32 bytes
+fn padded_password(password: &[u8]) -> [u8; 32] {
+    let mut padded = PASSWORD_PADDING;
+    let length = password.len().min(32);
+    padded[..length].copy_from_slice(&password[..length]);
+    padded[length..].copy_from_slice(&PASSWORD_PADDING[..32 - length]);
+    padded
+}
+
+/// Hash iterativo da revisão 6 (ISO 32000-2, algoritmo 2.B)
+fn hardened_hash(password: &[u8], salt: &[u8], user_data: &[u8]) -> Vec<u8> {
+    let mut key = Sha256::new()
+        .chain_update(password)
+        .chain_update(salt)
+        .chain_update(user_data)
+        .finalize()
+        .to_vec();
+
+    let mut round = 0usize;
+    loop {
+        let block: Vec<u8> = [password, &key, user_data].concat();
+        let repeated = block.repeat(64);
+        let encrypted = aes128_cbc_encrypt(&key[..16], &key[16..32], &repeated);
+
+        let selector = encrypted[..16].iter().map(|&byte| u32::from(byte)).sum::<u32>() % 3;
+        key = match selector {
+            0 => Sha256::digest(&encrypted).to_vec(),
+            1 => Sha384::digest(&encrypted).to_vec(),
+            _ => Sha512::digest(&encrypted).to_vec(),
+        };
+
+        round += 1;
+        let last = usize::from(*encrypted.last().unwrap_or(&0));
+        if round >= 64 && last + 32 <= round {
+            break;
+        }
+    }
+
+    key.truncate(32);
+    key
+}
+
+/// AES-128 em modo CBC, sem preenchimento (o tamanho é sempre múltiplo de 16)
+fn aes128_cbc_encrypt(key: &[u8], iv: &[u8], data: &[u8]) -> Vec<u8> {
+    let cipher = Aes128::new(GenericArray::from_slice(key));
+    let mut previous = GenericArray::clone_from_slice(iv);
+    let mut output = Vec::with_capacity(data.len());
+
+    for chunk in data.chunks_exact(16) {
+        let mut block = GenericArray::clone_from_slice(chunk);
+        for (byte, prev) in block.iter_mut().zip(previous.iter()) {
+            *byte ^= prev;
+        }
+        cipher.encrypt_block(&mut block);
+        output.extend_from_slice(&block);
+        previous = block;
+    }
+
+    output
+}
+
+/// RC4 (usado apenas na verificação das revisões 2-4)
+fn rc4(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut state: Vec<u8> = (0..=255).collect();
+    let mut j = 0u8;
+    for i in 0..256 {
+        j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+        state.swap(i, usize::from(j));
+    }
+
+    let (mut i, mut j) = (0u8, 0u8);
+    data.iter()
+        .map(|&byte| {
+            i = i.wrapping_add(1);
+            j = j.wrapping_add(state[usize::from(i)]);
+            state.swap(usize::from(i), usize::from(j));
+            byte ^ state[usize::from(state[usize::from(i)].wrapping_add(state[usize::from(j)]))]
+        })
+        .collect()
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::test_support;
+    use lopdf::{dictionary, StringFormat};
+
+    const DOCUMENT_ID: &str = "0123456789abcdef0123456789abcdef";
+
+    fn hex(value: &str) -> Object {
+        let bytes = (0..value.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap())
+            .collect();
+        Object::String(bytes, StringFormat::Hexadecimal)
+    }
+
+    /// Documento de exemplo com o `/Encrypt` informado
+    fn encrypted(encrypt: Dictionary) -> Document {
+        let mut doc = test_support::build_sample_pdf(1);
+        let encrypt_id = doc.add_object(encrypt);
+        doc.trailer.set("Encrypt", encrypt_id);
+        doc.trailer.set("ID", vec![hex(DOCUMENT_ID), hex(DOCUMENT_ID)]);
+        doc
+    }
+
+    #[test]
+    fn test_unencrypted_document() {
+        assert!(analyze(&test_support::build_sample_pdf(1)).is_none());
+    }
+
+    #[test]
+    fn test_rc4_40_bit_with_empty_password() {
+        let doc = encrypted(dictionary! {
+            "Filter" => "Standard",
+            "V" => 1,
+            "R" => 2,
+            "P" => -64,
+            "O" => hex("c92422687facee686e373f10b5c7d04738053152f7e2ee30e11c69ec442576ab"),
+            "U" => hex("26e7fc24f027a72092670d990b1da88530dbeaa8d8406901279cdb24bfa36778"),
+        });
+
+        let info = analyze(&doc).unwrap();
+        assert_eq!(info.filter, "Standard");
+        assert_eq!((info.version, info.revision, info.key_length), (1, 2, 40));
+        assert_eq!(info.cipher, EncryptionCipher::Rc4);
+        assert!(info.empty_user_password);
+        // Bits 3-6 zerados: nada é permitido
+        assert_eq!(info.permissions, PdfPermissions::default());
+    }
+
+    #[test]
+    fn test_rc4_128_bit_forbids_assembly() {
+        let doc = encrypted(dictionary! {
+            "Filter" => "Standard",
+            "V" => 2,
+            "R" => 3,
+            "Length" => 128,
+            "P" => -1028,
+            "O" => hex("566fa873ee33c797cd3b904fdadf814afa34df9a38f6ed41b984e2c6da2aa6f5"),
+            "U" => hex("6e1ba987de98ebd10303a12278e47cf600000000000000000000000000000000"),
+        });
+
+        let info = analyze(&doc).unwrap();
+        assert_eq!(info.key_length, 128);
+        assert!(info.empty_user_password);
+        assert!(info.permissions.allow_printing && info.permissions.allow_copy_text);
+        assert!(!info.permissions.allow_document_assembly);
+        assert_eq!(info.permissions.restrictions(), vec!["page assembly"]);
+    }
+
+    #[test]
+    fn test_aes_128_with_user_password() {
+        let doc = encrypted(dictionary! {
+            "Filter" => "Standard",
+            "V" => 4,
+            "R" => 4,
+            "Length" => 128,
+            "P" => -4,
+            "CF" => dictionary! { "StdCF" => dictionary! { "CFM" => "AESV2", "Length" => 16 } },
+            "StmF" => "StdCF",
+            "StrF" => "StdCF",
+            "O" => hex("0db5855fc5326569e765906caf64e4429a4c20d6e996fdef963e9b5080f9e083"),
+            "U" => hex("d6f0a5d03de41019fb27eabb120f960b00000000000000000000000000000000"),
+        });
+
+        let info = analyze(&doc).unwrap();
+        assert_eq!(info.cipher, EncryptionCipher::Aes128);
+        assert_eq!(info.permissions, PdfPermissions::unrestricted());
+        // A senha de usuário é "secret"
+        assert!(!info.empty_user_password);
+    }
+
+    #[test]
+    fn test_aes_256_revisions_5_and_6() {
+        let aes256 = |revision: i64, user_key: &str| {
+            encrypted(dictionary! {
+                "Filter" => "Standard",
+                "V" => 5,
+                "R" => revision,
+                "Length" => 256,
+                "P" => -4,
+                "CF" => dictionary! { "StdCF" => dictionary! { "CFM" => "AESV3", "Length" => 32 } },
+                "StmF" => "StdCF",
+                "StrF" => "StdCF",
+                "O" => hex(&"00".repeat(48)),
+                "U" => hex(user_key),
+            })
+        };
+
+        let r6 = analyze(&aes256(
+            6,
+            "a2c3f538af12f3ed1ef75e57bbdadb6eae425d47b3548917738b2ae9a801706f11223344556677888877665544332211",
+        ))
+        .unwrap();
+        assert_eq!((r6.cipher, r6.key_length), (EncryptionCipher::Aes256, 256));
+        assert!(r6.empty_user_password);
+
+        let r5 = analyze(&aes256(
+            5,
+            "1dce6604591efb439d5e87418a1d00dbfd014327d8c4dea862815714b76ae9a511223344556677888877665544332211",
+        ))
+        .unwrap();
+        assert!(r5.empty_user_password);
+
+        // Um `/U` que não corresponde à senha vazia
+        let protected = analyze(&aes256(6, &"ab".repeat(48))).unwrap();
+        assert!(!protected.empty_user_password);
+    }
+
+    #[test]
+    fn test_permission_bits_revision_2() {
+        // Bit 4 (modificação) controla a montagem na revisão 2
+        let permissions = PdfPermissions::from_bits(-64 | 0b1000, 2);
+        assert!(permissions.allow_modification && permissions.allow_document_assembly);
+        assert!(!permissions.allow_printing);
+    }
+}
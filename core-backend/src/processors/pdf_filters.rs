@@ -0,0 +1,654 @@
+//! Decodificação dos filtros de stream PDF
+//!
+//! O `lopdf` só descomprime FlateDecode e LZWDecode; este módulo cobre a cadeia
+//! completa de filtros usada pelas análises que precisam do conteúdo real das
+//! streams (validação profunda, xref streams, etc.).
+//!
+//! ## Funcionalidades:
+//! - FlateDecode e LZWDecode, com preditores PNG e TIFF
+//! - ASCIIHexDecode, ASCII85Decode e RunLengthDecode
+//! - Cadeias de filtros (`/Filter [/A85 /Fl]`) com `/DecodeParms` por filtro
+//! - Codecs de imagem (DCT, JPX, CCITT, JBIG2) são identificados e mantidos codificados
+//! - Limite de bytes decodificados por stream contra bombas de compressão
+
+use flate2::read::ZlibDecoder;
+use lopdf::{Dictionary, Document, Object, Stream};
+use std::io::Read;
+use thiserror::Error;
+
+/// Limite padrão de bytes decodificados por stream (o mesmo de `max_size_for_deep_analysis`)
+pub const DEFAULT_MAX_DECODED_SIZE: usize = 50 * 1024 * 1024;
+
+/// Maior `/Colors` aceito pelos preditores
+const MAX_COLORS: usize = 32;
+
+/// Maior `/Columns` aceito pelos preditores
+const MAX_COLUMNS: usize = 1 << 24;
+
+/// Erros de decodificação de stream
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum FilterError {
+    /// Filtro desconhecido ou não suportado (ex.: `/Crypt`)
+    #[error("Unsupported filter /{filter}")]
+    Unsupported { filter: String },
+
+    /// Os dados não puderam ser decodificados pelo filtro
+    #[error("Failed to decode /{filter}: {reason}")]
+    Corrupt { filter: String, reason: String },
+
+    /// `/Filter` ou `/DecodeParms` mal formados
+    #[error("Invalid filter specification: {0}")]
+    InvalidSpecification(String),
+
+    /// Os dados decodificados passariam do limite configurado
+    #[error("Decoded /{filter} data exceeds the {limit} byte limit")]
+    LimitExceeded { filter: String, limit: usize },
+}
+
+/// Parâmetros de `/DecodeParms` usados pelos filtros suportados
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeParams {
+    /// `/Predictor` (1 = nenhum, 2 = TIFF, 10-15 = PNG)
+    pub predictor: u32,
+    /// `/Colors`
+    pub colors: usize,
+    /// `/BitsPerComponent`
+    pub bits_per_component: usize,
+    /// `/Columns`
+    pub columns: usize,
+    /// `/EarlyChange` (apenas LZW)
+    pub early_change: bool,
+}
+
+impl Default for DecodeParams {
+    fn default() -> Self {
+        Self {
+            predictor: 1,
+            colors: 1,
+            bits_per_component: 8,
+            columns: 1,
+            early_change: true,
+        }
+    }
+}
+
+impl DecodeParams {
+    /// Lê os parâmetros de um dicionário `/DecodeParms`
+    pub fn from_dictionary(dict: &Dictionary) -> Self {
+        let int = |key: &[u8]| dict.get(key).and_then(Object::as_i64).ok().filter(|&v| v >= 0);
+        let defaults = Self::default();
+
+        Self {
+            predictor: int(b"Predictor").map(|v| v as u32).unwrap_or(defaults.predictor),
+            colors: int(b"Colors").map(|v| v as usize).unwrap_or(defaults.colors),
+            bits_per_component: int(b"BitsPerComponent").map(|v| v as usize).unwrap_or(defaults.bits_per_component),
+            columns: int(b"Columns").map(|v| v as usize).unwrap_or(defaults.columns),
+            early_change: int(b"EarlyChange").map(|v| v != 0).unwrap_or(defaults.early_change),
+        }
+    }
+
+    /// Confere se os parâmetros do preditor estão dentro do que a especificação permite
+    fn check_predictor(&self) -> Result<(), String> {
+        if !(1..=MAX_COLORS).contains(&self.colors) {
+            return Err(format!("invalid predictor /Colors {}", self.colors));
+        }
+        if !matches!(self.bits_per_component, 1 | 2 | 4 | 8 | 16) {
+            return Err(format!("invalid predictor /BitsPerComponent {}", self.bits_per_component));
+        }
+        if !(1..=MAX_COLUMNS).contains(&self.columns) {
+            return Err(format!("invalid predictor /Columns {}", self.columns));
+        }
+        Ok(())
+    }
+}
+
+/// Resultado da decodificação de uma stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedStream {
+    /// Dados após os filtros aplicados
+    pub data: Vec<u8>,
+    /// Codecs de imagem que ficaram sem aplicar (os dados continuam codificados neles)
+    pub image_codecs: Vec<String>,
+}
+
+/// Indica se o filtro é um codec de imagem, que não é decodificado aqui
+pub fn is_image_codec(filter: &[u8]) -> bool {
+    matches!(
+        filter,
+        b"DCTDecode" | b"DCT" | b"JPXDecode" | b"CCITTFaxDecode" | b"CCF" | b"JBIG2Decode"
+    )
+}
+
+/// Aplica um único filtro, produzindo no máximo `limit` bytes
+pub fn apply_filter(filter: &[u8], data: &[u8], params: &DecodeParams, limit: usize) -> Result<Vec<u8>, FilterError> {
+    let name = String::from_utf8_lossy(filter).to_string();
+    let corrupt = |reason: String| FilterError::Corrupt { filter: name.clone(), reason };
+    let too_large = || FilterError::LimitExceeded { filter: name.clone(), limit };
+
+    let decoded = match filter {
+        b"FlateDecode" | b"Fl" => {
+            // Lê um byte além do limite para distinguir "cabe exatamente" de "passou"
+            let mut decoded = Vec::new();
+            ZlibDecoder::new(data)
+                .take(limit as u64 + 1)
+                .read_to_end(&mut decoded)
+                .map_err(|e| corrupt(e.to_string()))?;
+            if decoded.len() > limit {
+                return Err(too_large());
+            }
+            unpredict(decoded, params).map_err(corrupt)?
+        }
+        b"LZWDecode" | b"LZW" => {
+            let decoded = lzw_decode(data, params.early_change, limit).map_err(corrupt)?;
+            if decoded.len() > limit {
+                return Err(too_large());
+            }
+            unpredict(decoded, params).map_err(corrupt)?
+        }
+        b"ASCIIHexDecode" | b"AHx" => ascii_hex_decode(data).map_err(corrupt)?,
+        b"ASCII85Decode" | b"A85" => ascii85_decode(data).map_err(corrupt)?,
+        b"RunLengthDecode" | b"RL" => run_length_decode(data).map_err(corrupt)?,
+        _ => return Err(FilterError::Unsupported { filter: name }),
+    };
+
+    if decoded.len() > limit {
+        return Err(too_large());
+    }
+    Ok(decoded)
+}
+
+/// Decodifica o conteúdo de uma stream seguindo `/Filter` e `/DecodeParms`
+///
+/// Referências indiretas nos dois campos são resolvidas em `doc`. A cadeia para
+/// no primeiro codec de imagem, cujos dados são devolvidos ainda codificados.
+/// Usa o limite [`DEFAULT_MAX_DECODED_SIZE`].
+pub fn decode_stream(doc: &Document, stream: &Stream) -> Result<DecodedStream, FilterError> {
+    decode_stream_with_limit(doc, stream, DEFAULT_MAX_DECODED_SIZE)
+}
+
+/// Como [`decode_stream`], recusando streams que decodificam para mais de `limit` bytes
+pub fn decode_stream_with_limit(doc: &Document, stream: &Stream, limit: usize) -> Result<DecodedStream, FilterError> {
+    let filters = filter_names(doc, &stream.dict)?;
+    let params = decode_params(doc, &stream.dict, filters.len());
+
+    let mut data = stream.content.clone();
+    for (index, filter) in filters.iter().enumerate() {
+        if is_image_codec(filter) {
+            return Ok(DecodedStream {
+                data,
+                image_codecs: filters[index..]
+                    .iter()
+                    .map(|f| String::from_utf8_lossy(f).to_string())
+                    .collect(),
+            });
+        }
+        data = apply_filter(filter, &data, &params[index], limit)?;
+    }
+
+    Ok(DecodedStream { data, image_codecs: Vec::new() })
+}
+
+// ==================== MÉTODOS PRIVADOS ====================
+
+fn resolve<'a>(doc: &'a Document, object: &'a Object) -> &'a Object {
+    match object {
+        Object::Reference(id) => doc.get_object(*id).unwrap_or(object),
+        _ => object,
+    }
+}
+
+/// Lista os nomes em `/Filter`
+pub(crate) fn filter_names(doc: &Document, dict: &Dictionary) -> Result<Vec<Vec<u8>>, FilterError> {
+    let Ok(filter) = dict.get(b"Filter") else {
+        return Ok(Vec::new());
+    };
+
+    match resolve(doc, filter) {
+        Object::Name(name) => Ok(vec![name.clone()]),
+        Object::Array(items) => items
+            .iter()
+            .map(|item| match resolve(doc, item) {
+                Object::Name(name) => Ok(name.clone()),
+                other => Err(FilterError::InvalidSpecification(format!(
+                    "filter array entry is not a name: {:?}",
+                    other
+                ))),
+            })
+            .collect(),
+        Object::Null => Ok(Vec::new()),
+        other => Err(FilterError::InvalidSpecification(format!(
+            "/Filter is not a name or array: {:?}",
+            other
+        ))),
+    }
+}
+
+/// Lê `/DecodeParms`, um conjunto de parâmetros por filtro
+fn decode_params(doc: &Document, dict: &Dictionary, filter_count: usize) -> Vec<DecodeParams> {
+    let mut params = vec![DecodeParams::default(); filter_count];
+
+    match dict.get(b"DecodeParms").map(|p| resolve(doc, p)) {
+        Ok(Object::Dictionary(single)) => {
+            if let Some(first) = params.first_mut() {
+                *first = DecodeParams::from_dictionary(single);
+            }
+        }
+        Ok(Object::Array(items)) => {
+            for (slot, item) in params.iter_mut().zip(items) {
+                if let Object::Dictionary(entry) = resolve(doc, item) {
+                    *slot = DecodeParams::from_dictionary(entry);
+                }
+            }
+        }
+        _ => {}
+    }
+
+    params
+}
+
+/// Desfaz o preditor configurado (TIFF ou PNG)
+fn unpredict(data: Vec<u8>, params: &DecodeParams) -> Result<Vec<u8>, String> {
+    if params.predictor != 1 {
+        params.check_predictor()?;
+    }
+
+    match params.predictor {
+        1 => Ok(data),
+        2 => tiff_unpredict(data, params),
+        10..=15 => {
+            let bits = params
+                .colors
+                .checked_mul(params.bits_per_component)
+                .ok_or_else(|| "predictor /Colors and /BitsPerComponent overflow".to_string())?;
+            let bytes_per_pixel = bits.div_ceil(8).max(1);
+            let row_length = params
+                .columns
+                .checked_mul(bits)
+                .map(|row_bits| row_bits.div_ceil(8))
+                .ok_or_else(|| "predictor /Columns overflow".to_string())?;
+            png_unpredict(&data, row_length, bytes_per_pixel)
+        }
+        other => Err(format!("unsupported predictor {}", other)),
+    }
+}
+
+fn tiff_unpredict(mut data: Vec<u8>, params: &DecodeParams) -> Result<Vec<u8>, String> {
+    if params.bits_per_component != 8 {
+        return Err(format!(
+            "TIFF predictor with {} bits per component is not supported",
+            params.bits_per_component
+        ));
+    }
+
+    let row_length = params
+        .columns
+        .checked_mul(params.colors)
+        .ok_or_else(|| "predictor /Columns overflow".to_string())?;
+    if row_length == 0 {
+        return Err("invalid predictor /Columns".to_string());
+    }
+
+    for row in data.chunks_mut(row_length) {
+        for i in params.colors..row.len() {
+            row[i] = row[i].wrapping_add(row[i - params.colors]);
+        }
+    }
+
+    Ok(data)
+}
+
+/// Desfaz os filtros PNG aplicados linha a linha
+fn png_unpredict(data: &[u8], row_length: usize, bytes_per_pixel: usize) -> Result<Vec<u8>, String> {
+    if row_length == 0 {
+        return Err("invalid predictor /Columns".to_string());
+    }
+    // Cada linha ocupa row_length + 1 bytes; uma linha maior que os dados vem de /Columns forjado
+    if !data.is_empty() && row_length >= data.len() {
+        return Err(format!(
+            "predictor row of {} bytes exceeds the {} bytes of data",
+            row_length,
+            data.len()
+        ));
+    }
+
+    let mut output = Vec::with_capacity(data.len());
+    let mut previous = vec![0u8; row_length];
+
+    for chunk in data.chunks(row_length + 1) {
+        if chunk.len() < row_length + 1 {
+            break;
+        }
+        let (filter, encoded) = (chunk[0], &chunk[1..]);
+        let mut row = vec![0u8; row_length];
+
+        for i in 0..row_length {
+            let left = if i >= bytes_per_pixel { row[i - bytes_per_pixel] } else { 0 };
+            let up = previous[i];
+            let upper_left = if i >= bytes_per_pixel { previous[i - bytes_per_pixel] } else { 0 };
+
+            let predicted = match filter {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                4 => paeth(left, up, upper_left),
+                other => return Err(format!("invalid PNG predictor filter {}", other)),
+            };
+            row[i] = encoded[i].wrapping_add(predicted);
+        }
+
+        output.extend_from_slice(&row);
+        previous = row;
+    }
+
+    Ok(output)
+}
+
+fn paeth(left: u8, up: u8, upper_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - upper_left as i16;
+    let distance_left = (estimate - left as i16).abs();
+    let distance_up = (estimate - up as i16).abs();
+    let distance_upper_left = (estimate - upper_left as i16).abs();
+
+    if distance_left <= distance_up && distance_left <= distance_upper_left {
+        left
+    } else if distance_up <= distance_upper_left {
+        up
+    } else {
+        upper_left
+    }
+}
+
+/// LZW com códigos de 9 a 12 bits (`256` limpa a tabela, `257` encerra)
+///
+/// Para assim que a saída passa de `limit` bytes; quem chama confere o tamanho.
+fn lzw_decode(data: &[u8], early_change: bool, limit: usize) -> Result<Vec<u8>, String> {
+    const CLEAR: usize = 256;
+    const END: usize = 257;
+
+    let initial_table = || -> Vec<Vec<u8>> {
+        let mut table: Vec<Vec<u8>> = (0..=255u8).map(|b| vec![b]).collect();
+        table.push(Vec::new());
+        table.push(Vec::new());
+        table
+    };
+
+    let mut table = initial_table();
+    let mut output = Vec::new();
+    let mut previous: Option<Vec<u8>> = None;
+    let mut code_length = 9;
+    let mut buffer: u32 = 0;
+    let mut buffered_bits = 0;
+
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        buffered_bits += 8;
+
+        while buffered_bits >= code_length {
+            buffered_bits -= code_length;
+            let code = ((buffer >> buffered_bits) & ((1 << code_length) - 1)) as usize;
+            buffer &= (1 << buffered_bits) - 1;
+
+            match code {
+                CLEAR => {
+                    table = initial_table();
+                    code_length = 9;
+                    previous = None;
+                }
+                END => return Ok(output),
+                _ => {
+                    let entry = if code < table.len() {
+                        table[code].clone()
+                    } else if code == table.len() {
+                        let prefix = previous.as_ref().ok_or_else(|| format!("invalid LZW code {}", code))?;
+                        let mut entry = prefix.clone();
+                        entry.push(prefix[0]);
+                        entry
+                    } else {
+                        return Err(format!("invalid LZW code {}", code));
+                    };
+
+                    output.extend_from_slice(&entry);
+                    if output.len() > limit {
+                        return Ok(output);
+                    }
+                    if let Some(mut prefix) = previous.take() {
+                        prefix.push(entry[0]);
+                        table.push(prefix);
+                    }
+                    previous = Some(entry);
+
+                    let next_code = table.len() + usize::from(early_change);
+                    if next_code >= (1 << code_length) && code_length < 12 {
+                        code_length += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+fn ascii_hex_decode(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut output = Vec::with_capacity(data.len() / 2);
+    let mut high: Option<u8> = None;
+
+    for &byte in data {
+        let nibble = match byte {
+            b'0'..=b'9' => byte - b'0',
+            b'a'..=b'f' => byte - b'a' + 10,
+            b'A'..=b'F' => byte - b'A' + 10,
+            b'>' => break,
+            b if b.is_ascii_whitespace() || b == 0 => continue,
+            other => return Err(format!("invalid hex digit 0x{:02x}", other)),
+        };
+
+        match high.take() {
+            Some(h) => output.push((h << 4) | nibble),
+            None => high = Some(nibble),
+        }
+    }
+
+    // Dígito final sem par é completado com zero
+    if let Some(h) = high {
+        output.push(h << 4);
+    }
+
+    Ok(output)
+}
+
+fn ascii85_decode(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut output = Vec::with_capacity(data.len() * 4 / 5);
+    let mut group = [0u8; 5];
+    let mut filled = 0;
+
+    let mut bytes = data.iter().copied().peekable();
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'~' => {
+                if bytes.peek() == Some(&b'>') {
+                    break;
+                }
+                return Err("'~' not followed by '>'".to_string());
+            }
+            b'z' if filled == 0 => output.extend_from_slice(&[0, 0, 0, 0]),
+            b'!'..=b'u' => {
+                group[filled] = byte - b'!';
+                filled += 1;
+                if filled == 5 {
+                    output.extend_from_slice(&ascii85_group(&group)?);
+                    filled = 0;
+                }
+            }
+            b if b.is_ascii_whitespace() || b == 0 => {}
+            other => return Err(format!("invalid ASCII85 character 0x{:02x}", other)),
+        }
+    }
+
+    match filled {
+        0 => {}
+        1 => return Err("final ASCII85 group has a single character".to_string()),
+        _ => {
+            // Completa o grupo com 'u' e descarta os bytes excedentes
+            group[filled..].fill(b'u' - b'!');
+            let decoded = ascii85_group(&group)?;
+            output.extend_from_slice(&decoded[..filled - 1]);
+        }
+    }
+
+    Ok(output)
+}
+
+fn ascii85_group(group: &[u8; 5]) -> Result<[u8; 4], String> {
+    let value = group
+        .iter()
+        .try_fold(0u64, |acc, &digit| Some(acc * 85 + digit as u64))
+        .filter(|&v| v <= u32::MAX as u64)
+        .ok_or_else(|| "ASCII85 group out of range".to_string())?;
+    Ok((value as u32).to_be_bytes())
+}
+
+fn run_length_decode(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut output = Vec::new();
+    let mut position = 0;
+
+    while position < data.len() {
+        let length = data[position] as usize;
+        position += 1;
+
+        match length {
+            128 => break,
+            0..=127 => {
+                let end = position + length + 1;
+                let run = data.get(position..end).ok_or_else(|| "literal run past end of data".to_string())?;
+                output.extend_from_slice(run);
+                position = end;
+            }
+            _ => {
+                let byte = *data.get(position).ok_or_else(|| "repeat run past end of data".to_string())?;
+                output.extend(std::iter::repeat_n(byte, 257 - length));
+                position += 1;
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use lopdf::dictionary;
+    use std::io::Write;
+
+    fn deflate(data: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_lzw_decode_spec_example() {
+        // Exemplo da especificação PDF (seção 7.4.4.2)
+        let encoded = [0x80, 0x0B, 0x60, 0x50, 0x22, 0x0C, 0x0C, 0x85, 0x01];
+        let decoded = apply_filter(b"LZWDecode", &encoded, &DecodeParams::default(), DEFAULT_MAX_DECODED_SIZE).unwrap();
+        assert_eq!(decoded, b"-----A---B");
+    }
+
+    #[test]
+    fn test_ascii_filters() {
+        let params = DecodeParams::default();
+        assert_eq!(apply_filter(b"AHx", b"48 65 6C6c6F7>", &params, DEFAULT_MAX_DECODED_SIZE).unwrap(), b"Hellop");
+        assert_eq!(apply_filter(b"A85", b"87cURD]i,\"Ebo80~>", &params, DEFAULT_MAX_DECODED_SIZE).unwrap(), b"Hello World!");
+        assert_eq!(apply_filter(b"A85", b"z~>", &params, DEFAULT_MAX_DECODED_SIZE).unwrap(), vec![0, 0, 0, 0]);
+        assert_eq!(apply_filter(b"RL", &[2, b'a', b'b', b'c', 254, b'x', 128], &params, DEFAULT_MAX_DECODED_SIZE).unwrap(), b"abcxxx");
+    }
+
+    #[test]
+    fn test_decode_stream_filter_chain() {
+        let doc = Document::with_version("1.5");
+        let hex: String = deflate(b"chained content").iter().map(|b| format!("{:02x}", b)).collect();
+        let stream = Stream::new(
+            dictionary! { "Filter" => vec![Object::Name(b"ASCIIHexDecode".to_vec()), Object::Name(b"FlateDecode".to_vec())] },
+            format!("{}>", hex).into_bytes(),
+        );
+
+        let decoded = decode_stream(&doc, &stream).unwrap();
+        assert_eq!(decoded.data, b"chained content");
+        assert!(decoded.image_codecs.is_empty());
+    }
+
+    #[test]
+    fn test_decode_stream_keeps_image_codec_data() {
+        let doc = Document::with_version("1.5");
+        let stream = Stream::new(
+            dictionary! { "Filter" => vec![Object::Name(b"FlateDecode".to_vec()), Object::Name(b"DCTDecode".to_vec())] },
+            deflate(&[0xFF, 0xD8, 0xFF]),
+        );
+
+        let decoded = decode_stream(&doc, &stream).unwrap();
+        assert_eq!(decoded.data, vec![0xFF, 0xD8, 0xFF]);
+        assert_eq!(decoded.image_codecs, vec!["DCTDecode".to_string()]);
+    }
+
+    #[test]
+    fn test_decode_stream_reports_corrupt_and_unsupported() {
+        let doc = Document::with_version("1.5");
+
+        let corrupt = Stream::new(dictionary! { "Filter" => "FlateDecode" }, b"not zlib".to_vec());
+        assert!(matches!(decode_stream(&doc, &corrupt), Err(FilterError::Corrupt { .. })));
+
+        let unsupported = Stream::new(dictionary! { "Filter" => "Crypt" }, b"data".to_vec());
+        assert_eq!(
+            decode_stream(&doc, &unsupported),
+            Err(FilterError::Unsupported { filter: "Crypt".to_string() })
+        );
+    }
+
+    #[test]
+    fn test_png_predictor() {
+        // Duas linhas de 3 bytes com o filtro "Up"
+        let encoded = deflate(&[2, 1, 2, 3, 2, 1, 1, 1]);
+        let params = DecodeParams { predictor: 12, columns: 3, ..DecodeParams::default() };
+        assert_eq!(apply_filter(b"FlateDecode", &encoded, &params, DEFAULT_MAX_DECODED_SIZE).unwrap(), vec![1, 2, 3, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_decoded_size_is_limited() {
+        let doc = Document::with_version("1.5");
+        let bomb = Stream::new(dictionary! { "Filter" => "FlateDecode" }, deflate(&vec![0u8; 64 * 1024]));
+
+        assert_eq!(decode_stream_with_limit(&doc, &bomb, 64 * 1024).unwrap().data.len(), 64 * 1024);
+        assert_eq!(
+            decode_stream_with_limit(&doc, &bomb, 1024),
+            Err(FilterError::LimitExceeded { filter: "FlateDecode".to_string(), limit: 1024 })
+        );
+
+        let repeated = [129u8, b'x'].repeat(64);
+        assert!(matches!(
+            apply_filter(b"RL", &repeated, &DecodeParams::default(), 1024),
+            Err(FilterError::LimitExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn test_predictor_params_are_bounded() {
+        let encoded = deflate(&[2, 1, 2, 3, 2, 1, 1, 1]);
+        for params in [
+            DecodeParams { predictor: 12, columns: 4398046511104, ..DecodeParams::default() },
+            DecodeParams { predictor: 12, colors: 1 << 40, columns: 3, ..DecodeParams::default() },
+            DecodeParams { predictor: 12, bits_per_component: 1 << 40, columns: 3, ..DecodeParams::default() },
+            DecodeParams { predictor: 2, columns: 1 << 30, ..DecodeParams::default() },
+        ] {
+            assert!(matches!(
+                apply_filter(b"FlateDecode", &encoded, &params, DEFAULT_MAX_DECODED_SIZE),
+                Err(FilterError::Corrupt { .. })
+            ), "{:?}", params);
+        }
+    }
+}
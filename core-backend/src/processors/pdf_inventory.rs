@@ -0,0 +1,1357 @@
+//! Inventário de recursos de um PDF
+//!
+//! Percorre as páginas e seus recursos para montar as listas detalhadas dos
+//! tipos de `types::pdf_types` usadas nos metadados.
+//!
+//! ## Inventários:
+//! - Fontes (`FontInfo`): nome, tipo, incorporação, subconjunto e páginas de uso
+//! - Imagens (`ImageInfo`): XObjects e imagens inline, com a resolução efetiva de cada uso
+//! - Anotações (`AnnotationInfo`): tipo, área, conteúdo, autor e data de cada `/Annots`
+//! - Bookmarks (`BookmarkInfo`): árvore de `/Outlines` com o destino resolvido para a página
+//! - Páginas (`PageInfo`): caixas herdadas, rotação, formato e presença de texto/imagens/anotações
+//!
+//! Os recursos de cada página seguem a herança da árvore de páginas e incluem
+//! os recursos dos Form XObjects usados por ela.
+
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use crate::processors::pdf_content::{self, ContentItem};
+use crate::processors::pdf_encoding;
+use crate::processors::pdf_filters;
+use crate::types::pdf_types::{
+    AnnotationInfo, AnnotationType, BookmarkInfo, ColorSpace, FontInfo, FontType, ImageFormat, ImageInfo,
+    ImagePlacement, PageInfo, PageOrientation,
+};
+
+/// Limite de níveis percorridos na árvore de páginas ao herdar `/Resources`
+const MAX_PAGE_TREE_DEPTH: usize = 64;
+
+/// Limite de Form XObjects aninhados seguidos ao percorrer o conteúdo
+const MAX_FORM_DEPTH: usize = 16;
+
+/// Limite de `Do` e imagens inline executados por página, somando todos os Forms
+///
+/// A profundidade sozinha não basta: um Form que chama outro N vezes por nível
+/// gera N^16 execuções.
+pub(crate) const MAX_DRAWS_PER_PAGE: usize = 10_000;
+
+/// Limite de níveis percorridos em `/Outlines` e nas árvores de nomes
+const MAX_OUTLINE_DEPTH: usize = 64;
+
+/// `/MediaBox` assumido quando a página não declara nenhum (US Letter)
+const DEFAULT_MEDIA_BOX: Rect = (0.0, 0.0, 612.0, 792.0);
+
+/// Tolerância, em pontos, ao reconhecer formatos de papel
+const PAPER_SIZE_TOLERANCE: f64 = 3.0;
+
+/// Formatos de papel reconhecidos (largura x altura em pontos, retrato)
+const PAPER_SIZES: [(&str, f64, f64); 7] = [
+    ("A3", 842.0, 1191.0),
+    ("A4", 595.0, 842.0),
+    ("A5", 420.0, 595.0),
+    ("B5", 499.0, 709.0),
+    ("Letter", 612.0, 792.0),
+    ("Legal", 612.0, 1008.0),
+    ("Tabloid", 792.0, 1224.0),
+];
+
+/// Retângulo `(x1, y1, x2, y2)` normalizado
+type Rect = (f64, f64, f64, f64);
+
+/// Matriz de transformação `[a b c d e f]`
+pub(crate) type Matrix = [f64; 6];
+
+pub(crate) const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/// Lista as fontes usadas pelo documento, na ordem do primeiro uso
+pub fn fonts(doc: &Document) -> Vec<FontInfo> {
+    let mut inventory = FontInventory::default();
+
+    for (page_number, page_id) in doc.get_pages() {
+        let Some(resources) = page_resources(doc, page_id) else {
+            continue;
+        };
+
+        for resources in resource_tree(doc, resources) {
+            let Ok(fonts) = resources.get_deref(b"Font", doc).and_then(Object::as_dict) else {
+                continue;
+            };
+
+            for (_, font) in fonts.iter() {
+                if let Ok((font_id, Object::Dictionary(font))) = doc.dereference(font) {
+                    inventory.record(doc, font_id, font, page_number);
+                }
+            }
+        }
+    }
+
+    inventory.fonts
+}
+
+/// Lista as imagens do documento, na ordem do primeiro uso
+///
+/// Cada Image XObject aparece uma vez, com um `ImagePlacement` por `Do` que o
+/// desenha (inclusive dentro de Form XObjects); cada imagem inline é uma entrada
+/// própria. Imagens presentes nos recursos mas nunca desenhadas são listadas sem
+/// usos.
+pub fn images(doc: &Document) -> Vec<ImageInfo> {
+    let mut inventory = ImageInventory::default();
+
+    for (page_number, page_id) in doc.get_pages() {
+        let Some(resources) = page_resources(doc, page_id) else {
+            continue;
+        };
+        let user_unit = doc
+            .get_dictionary(page_id)
+            .and_then(|page| page.get(b"UserUnit"))
+            .and_then(Object::as_float)
+            .map(f64::from)
+            .unwrap_or(1.0);
+
+        let mut walker = ImageWalker {
+            doc,
+            page_number,
+            user_unit,
+            inventory: &mut inventory,
+            forms: Vec::new(),
+            decoded_forms: HashMap::new(),
+            draws_left: MAX_DRAWS_PER_PAGE,
+        };
+        walker.walk(&page_content(doc, page_id), resources, IDENTITY);
+
+        for resources in resource_tree(doc, resources) {
+            let Ok(xobjects) = resources.get_deref(b"XObject", doc).and_then(Object::as_dict) else {
+                continue;
+            };
+            for (_, xobject) in xobjects.iter() {
+                if let Ok((Some(id), Object::Stream(stream))) = doc.dereference(xobject) {
+                    if is_subtype(&stream.dict, b"Image") {
+                        inventory.entry(doc, id, stream);
+                    }
+                }
+            }
+        }
+    }
+
+    inventory.images
+}
+
+/// Lista as anotações de todas as páginas, na ordem de `/Annots`
+pub fn annotations(doc: &Document) -> Vec<AnnotationInfo> {
+    let mut annotations = Vec::new();
+
+    for (page_number, page_id) in doc.get_pages() {
+        let Ok(annots) = doc
+            .get_dictionary(page_id)
+            .and_then(|page| page.get_deref(b"Annots", doc))
+            .and_then(Object::as_array)
+        else {
+            continue;
+        };
+
+        for annot in annots {
+            if let Ok((annot_id, Object::Dictionary(annot))) = doc.dereference(annot) {
+                annotations.push(describe_annotation(doc, annot_id, annot, page_number));
+            }
+        }
+    }
+
+    annotations
+}
+
+/// Monta a árvore de bookmarks a partir de `/Outlines`
+///
+/// Os destinos (explícitos, nomeados ou em ações `/GoTo`) são resolvidos para o
+/// número da página; `page` fica 0 quando o destino não aponta para uma página
+/// do documento.
+pub fn bookmarks(doc: &Document) -> Vec<BookmarkInfo> {
+    let Ok(outlines) = doc
+        .catalog()
+        .and_then(|catalog| catalog.get_deref(b"Outlines", doc))
+        .and_then(Object::as_dict)
+    else {
+        return Vec::new();
+    };
+
+    let resolver = DestinationResolver::new(doc);
+    let mut visited = HashSet::new();
+    outline_children(doc, outlines, 1, &resolver, &mut visited)
+}
+
+/// Descreve cada página do documento, em ordem
+pub fn pages(doc: &Document) -> Vec<PageInfo> {
+    // Páginas que desenham alguma imagem
+    let pages_with_images: HashSet<u32> = images(doc)
+        .iter()
+        .flat_map(|image| image.placements.iter().map(|placement| placement.page))
+        .collect();
+
+    doc.get_pages()
+        .into_iter()
+        .map(|(page_number, page_id)| {
+            let inherited_rect = |key: &[u8]| inherited(doc, page_id, key).and_then(|object| rect(doc, object));
+
+            let media_box = inherited_rect(b"MediaBox").unwrap_or(DEFAULT_MEDIA_BOX);
+            let crop_box = inherited_rect(b"CropBox")
+                .and_then(|crop| intersect(crop, media_box))
+                .unwrap_or(media_box);
+            let rotation = inherited(doc, page_id, b"Rotate")
+                .and_then(|rotate| rotate.as_i64().ok())
+                .map(normalize_rotation)
+                .unwrap_or(0);
+            let user_unit = doc
+                .get_dictionary(page_id)
+                .and_then(|page| page.get(b"UserUnit"))
+                .and_then(Object::as_float)
+                .map(f64::from)
+                .unwrap_or(1.0);
+
+            let width = (crop_box.2 - crop_box.0) * user_unit;
+            let height = (crop_box.3 - crop_box.1) * user_unit;
+            let (displayed_width, displayed_height) = if rotation % 180 == 90 { (height, width) } else { (width, height) };
+
+            let has_annotations = doc
+                .get_dictionary(page_id)
+                .and_then(|page| page.get_deref(b"Annots", doc))
+                .and_then(Object::as_array)
+                .is_ok_and(|annots| !annots.is_empty());
+
+            PageInfo {
+                page_number,
+                dimensions: (width, height),
+                rotation,
+                media_box,
+                crop_box,
+                orientation: PageOrientation::from_dimensions(displayed_width, displayed_height),
+                paper_size: paper_size(width, height).map(str::to_string),
+                content: None,
+                object_count: page_object_count(doc, page_id),
+                has_annotations,
+                has_images: pages_with_images.contains(&page_number),
+                has_text: page_has_text(doc, page_id),
+            }
+        })
+        .collect()
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+/// Recursos efetivos de uma página, herdados do nó mais próximo que os define
+pub(crate) fn page_resources(doc: &Document, page_id: ObjectId) -> Option<&Dictionary> {
+    inherited(doc, page_id, b"Resources").and_then(|resources| resources.as_dict().ok())
+}
+
+/// Atributo herdável da página (`/Resources`, `/MediaBox`, `/CropBox`, `/Rotate`)
+pub(crate) fn inherited<'a>(doc: &'a Document, page_id: ObjectId, key: &[u8]) -> Option<&'a Object> {
+    let mut node = doc.get_dictionary(page_id).ok()?;
+
+    for _ in 0..MAX_PAGE_TREE_DEPTH {
+        if let Ok(value) = node.get_deref(key, doc) {
+            return Some(value);
+        }
+        node = node.get_deref(b"Parent", doc).and_then(Object::as_dict).ok()?;
+    }
+
+    None
+}
+
+/// Conteúdo decodificado de uma página, com os streams de `/Contents` concatenados
+pub(crate) fn page_content(doc: &Document, page_id: ObjectId) -> Vec<u8> {
+    let mut content = Vec::new();
+
+    for content_id in doc.get_page_contents(page_id) {
+        if let Ok(Object::Stream(stream)) = doc.get_object(content_id) {
+            if let Ok(decoded) = pdf_filters::decode_stream(doc, stream) {
+                content.extend_from_slice(&decoded.data);
+                content.push(b'\n');
+            }
+        }
+    }
+
+    content
+}
+
+/// Dicionários de recursos da página e dos Form XObjects alcançáveis a partir dela
+pub(crate) fn resource_tree<'a>(doc: &'a Document, resources: &'a Dictionary) -> Vec<&'a Dictionary> {
+    let mut found = vec![resources];
+    found.extend(
+        form_xobjects(doc, resources)
+            .into_iter()
+            .filter_map(|form| form.dict.get_deref(b"Resources", doc).and_then(Object::as_dict).ok()),
+    );
+    found
+}
+
+/// Form XObjects alcançáveis a partir dos recursos, inclusive os aninhados
+pub(crate) fn form_xobjects<'a>(doc: &'a Document, resources: &'a Dictionary) -> Vec<&'a Stream> {
+    let mut forms: Vec<&Stream> = Vec::new();
+    let mut pending = vec![resources];
+    let mut visited = HashSet::new();
+
+    while let Some(current) = pending.pop() {
+        let Ok(xobjects) = current.get_deref(b"XObject", doc).and_then(Object::as_dict) else {
+            continue;
+        };
+
+        for (_, xobject) in xobjects.iter() {
+            let Ok(id) = xobject.as_reference() else {
+                continue;
+            };
+            if !visited.insert(id) {
+                continue;
+            }
+
+            let Ok(Object::Stream(form)) = doc.get_object(id) else {
+                continue;
+            };
+            if !is_subtype(&form.dict, b"Form") {
+                continue;
+            }
+            forms.push(form);
+            if let Ok(form_resources) = form.dict.get_deref(b"Resources", doc).and_then(Object::as_dict) {
+                pending.push(form_resources);
+            }
+        }
+    }
+
+    forms
+}
+
+/// Lê um retângulo `[x1 y1 x2 y2]`, normalizando os cantos
+fn rect(doc: &Document, object: &Object) -> Option<Rect> {
+    let items = doc.dereference(object).ok()?.1.as_array().ok()?;
+    let numbers = items
+        .iter()
+        .map(|item| doc.dereference(item).ok()?.1.as_float().ok().map(f64::from))
+        .collect::<Option<Vec<_>>>()?;
+
+    match numbers[..] {
+        [x1, y1, x2, y2] => Some((x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2))),
+        _ => None,
+    }
+}
+
+/// Interseção de dois retângulos (`None` se forem disjuntos)
+fn intersect(a: Rect, b: Rect) -> Option<Rect> {
+    let result = (a.0.max(b.0), a.1.max(b.1), a.2.min(b.2), a.3.min(b.3));
+    (result.0 < result.2 && result.1 < result.3).then_some(result)
+}
+
+/// `/Rotate` como 0, 90, 180 ou 270 (valores negativos e fora de múltiplos de 90 inclusos)
+pub(crate) fn normalize_rotation(rotate: i64) -> u32 {
+    let quarter_turns = (rotate as f64 / 90.0).round() as i64;
+    (quarter_turns.rem_euclid(4) * 90) as u32
+}
+
+/// Nome do formato de papel, em qualquer orientação
+fn paper_size(width: f64, height: f64) -> Option<&'static str> {
+    let (short, long) = (width.min(height), width.max(height));
+
+    PAPER_SIZES
+        .iter()
+        .find(|(_, w, h)| (short - w).abs() <= PAPER_SIZE_TOLERANCE && (long - h).abs() <= PAPER_SIZE_TOLERANCE)
+        .map(|(name, _, _)| *name)
+}
+
+/// A página mostra texto (no conteúdo ou em um Form XObject dos seus recursos)?
+fn page_has_text(doc: &Document, page_id: ObjectId) -> bool {
+    let shows_text = |content: &[u8]| {
+        pdf_content::parse(content).iter().any(|item| {
+            matches!(item, ContentItem::Operation(operation)
+                if matches!(operation.operator.as_str(), "Tj" | "TJ" | "'" | "\""))
+        })
+    };
+
+    if shows_text(&page_content(doc, page_id)) {
+        return true;
+    }
+
+    page_resources(doc, page_id).is_some_and(|resources| {
+        form_xobjects(doc, resources)
+            .into_iter()
+            .any(|form| pdf_filters::decode_stream(doc, form).is_ok_and(|decoded| shows_text(&decoded.data)))
+    })
+}
+
+/// Objetos indiretos alcançáveis a partir da página (conteúdo, recursos, anotações)
+///
+/// Não segue `/Parent` nem referências a outras páginas, para não contar a
+/// árvore de páginas inteira.
+fn page_object_count(doc: &Document, page_id: ObjectId) -> usize {
+    let Ok(page) = doc.get_dictionary(page_id) else {
+        return 0;
+    };
+    let mut visited = HashSet::from([page_id]);
+    let mut pending: Vec<&Object> = page
+        .iter()
+        .filter(|(key, _)| key.as_slice() != b"Parent")
+        .map(|(_, value)| value)
+        .collect();
+
+    // Recursos herdados de um nó `/Pages` também pertencem à página
+    if !page.has(b"Resources") {
+        let mut node = page;
+        for _ in 0..MAX_PAGE_TREE_DEPTH {
+            let Ok(parent) = node.get_deref(b"Parent", doc).and_then(Object::as_dict) else {
+                break;
+            };
+            if let Ok(resources) = parent.get(b"Resources") {
+                pending.push(resources);
+                break;
+            }
+            node = parent;
+        }
+    }
+
+    while let Some(object) = pending.pop() {
+        match object {
+            Object::Reference(id) => {
+                if !visited.insert(*id) {
+                    continue;
+                }
+                match doc.get_object(*id) {
+                    Ok(Object::Dictionary(dict)) if dict.type_is(b"Page") || dict.type_is(b"Pages") => {
+                        visited.remove(id);
+                    }
+                    Ok(target) => pending.push(target),
+                    Err(_) => {
+                        visited.remove(id);
+                    }
+                }
+            }
+            Object::Array(items) => pending.extend(items),
+            Object::Dictionary(dict) => pending.extend(dict.iter().map(|(_, value)| value)),
+            Object::Stream(stream) => pending.extend(stream.dict.iter().map(|(_, value)| value)),
+            _ => {}
+        }
+    }
+
+    visited.len()
+}
+
+/// Identifica uma fonte: objeto indireto ou dicionário direto dentro dos recursos
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum FontKey {
+    Indirect(ObjectId),
+    Direct(*const Dictionary),
+}
+
+/// Fontes encontradas até agora, sem repetição
+#[derive(Default)]
+struct FontInventory {
+    fonts: Vec<FontInfo>,
+    positions: HashMap<FontKey, usize>,
+}
+
+impl FontInventory {
+    /// Registra o uso de uma fonte (e das descendentes de uma Type0) em uma página
+    fn record(&mut self, doc: &Document, font_id: Option<ObjectId>, font: &Dictionary, page_number: u32) {
+        let key = match font_id {
+            Some(id) => FontKey::Indirect(id),
+            None => FontKey::Direct(font as *const Dictionary),
+        };
+
+        let position = match self.positions.get(&key) {
+            Some(&position) => position,
+            None => {
+                self.fonts.push(describe_font(doc, font));
+                self.positions.insert(key, self.fonts.len() - 1);
+                self.fonts.len() - 1
+            }
+        };
+
+        let pages = &mut self.fonts[position].pages;
+        if pages.last() != Some(&page_number) {
+            pages.push(page_number);
+        }
+
+        for (descendant_id, descendant) in descendant_fonts(doc, font) {
+            self.record(doc, descendant_id, descendant, page_number);
+        }
+    }
+}
+
+/// Monta o `FontInfo` de um dicionário de fonte (sem as páginas)
+fn describe_font(doc: &Document, font: &Dictionary) -> FontInfo {
+    let font_type = font
+        .get(b"Subtype")
+        .and_then(Object::as_name)
+        .map(FontType::from_subtype)
+        .unwrap_or(FontType::Unknown);
+    let name = font
+        .get(b"BaseFont")
+        .and_then(Object::as_name)
+        .map(|name| String::from_utf8_lossy(name).into_owned())
+        .unwrap_or_else(|_| "unnamed".to_string());
+
+    let embedded = match font_type {
+        // Type3 define os glifos no próprio PDF
+        FontType::Type3 => true,
+        // Type0 depende das fontes descendentes
+        FontType::Type0 => {
+            let descendants = descendant_fonts(doc, font);
+            !descendants.is_empty() && descendants.iter().all(|(_, descendant)| has_font_file(doc, descendant))
+        }
+        _ => has_font_file(doc, font),
+    };
+
+    FontInfo {
+        subset: is_subset_name(&name),
+        name,
+        font_type,
+        embedded,
+        pages: Vec::new(),
+    }
+}
+
+/// Fontes CID descendentes de uma fonte Type0
+fn descendant_fonts<'a>(doc: &'a Document, font: &'a Dictionary) -> Vec<(Option<ObjectId>, &'a Dictionary)> {
+    let Ok(descendants) = font.get_deref(b"DescendantFonts", doc).and_then(Object::as_array) else {
+        return Vec::new();
+    };
+
+    descendants
+        .iter()
+        .filter_map(|descendant| match doc.dereference(descendant) {
+            Ok((id, Object::Dictionary(dict))) => Some((id, dict)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// O descritor da fonte aponta para um programa de fonte incorporado?
+fn has_font_file(doc: &Document, font: &Dictionary) -> bool {
+    font.get_deref(b"FontDescriptor", doc)
+        .and_then(Object::as_dict)
+        .map(|descriptor| {
+            [b"FontFile".as_slice(), b"FontFile2", b"FontFile3"]
+                .iter()
+                .any(|key| descriptor.has(key))
+        })
+        .unwrap_or(false)
+}
+
+/// Imagens encontradas até agora; XObjects sem repetição
+#[derive(Default)]
+struct ImageInventory {
+    images: Vec<ImageInfo>,
+    positions: HashMap<ObjectId, usize>,
+}
+
+impl ImageInventory {
+    /// Posição do Image XObject no inventário, criando a entrada no primeiro uso
+    fn entry(&mut self, doc: &Document, id: ObjectId, stream: &Stream) -> usize {
+        if let Some(&position) = self.positions.get(&id) {
+            return position;
+        }
+
+        let mut info = describe_image(doc, &stream.dict, None, stream.content.len() as u64);
+        info.object_id = Some(id.0);
+        self.images.push(info);
+        self.positions.insert(id, self.images.len() - 1);
+        self.images.len() - 1
+    }
+}
+
+/// Percorre o conteúdo de uma página acompanhando a CTM
+struct ImageWalker<'a, 'b> {
+    doc: &'a Document,
+    page_number: u32,
+    user_unit: f64,
+    inventory: &'b mut ImageInventory,
+    /// Form XObjects em execução (evita ciclos)
+    forms: Vec<ObjectId>,
+    /// Conteúdo já decodificado de cada Form usado na página
+    decoded_forms: HashMap<ObjectId, Rc<[u8]>>,
+    /// Execuções restantes do orçamento da página
+    draws_left: usize,
+}
+
+impl<'a> ImageWalker<'a, '_> {
+    fn walk(&mut self, content: &[u8], resources: &'a Dictionary, mut ctm: Matrix) {
+        let mut saved = Vec::new();
+
+        for item in pdf_content::parse(content) {
+            match item {
+                ContentItem::Operation(operation) => match operation.operator.as_str() {
+                    "q" => saved.push(ctm),
+                    "Q" => {
+                        if let Some(previous) = saved.pop() {
+                            ctm = previous;
+                        }
+                    }
+                    "cm" => {
+                        if let Some(matrix) = matrix(&operation.operands) {
+                            ctm = multiply(&matrix, &ctm);
+                        }
+                    }
+                    "Do" => {
+                        if let Some(Ok(name)) = operation.operands.first().map(Object::as_name) {
+                            if !self.spend_draw() {
+                                return;
+                            }
+                            self.draw_xobject(name, resources, ctm);
+                        }
+                    }
+                    _ => {}
+                },
+                ContentItem::InlineImage { dict, data } => {
+                    if !self.spend_draw() {
+                        return;
+                    }
+                    let mut info = describe_image(self.doc, &dict, Some(resources), data.len() as u64);
+                    info.inline = true;
+                    info.placements.push(self.placement(&info, &ctm));
+                    self.inventory.images.push(info);
+                }
+            }
+        }
+    }
+
+    fn draw_xobject(&mut self, name: &[u8], resources: &'a Dictionary, ctm: Matrix) {
+        let doc = self.doc;
+        let Some(xobject) = resources
+            .get_deref(b"XObject", doc)
+            .and_then(Object::as_dict)
+            .and_then(|xobjects| xobjects.get(name))
+            .ok()
+        else {
+            return;
+        };
+        let Ok((Some(id), Object::Stream(stream))) = doc.dereference(xobject) else {
+            return;
+        };
+
+        if is_subtype(&stream.dict, b"Image") {
+            let position = self.inventory.entry(doc, id, stream);
+            let placement = self.placement(&self.inventory.images[position], &ctm);
+            self.inventory.images[position].placements.push(placement);
+        } else if is_subtype(&stream.dict, b"Form") {
+            if self.forms.contains(&id) || self.forms.len() >= MAX_FORM_DEPTH {
+                return;
+            }
+            let content = match self.decoded_forms.get(&id) {
+                Some(content) => Rc::clone(content),
+                None => {
+                    let Ok(decoded) = pdf_filters::decode_stream(doc, stream) else {
+                        return;
+                    };
+                    let content: Rc<[u8]> = decoded.data.into();
+                    self.decoded_forms.insert(id, Rc::clone(&content));
+                    content
+                }
+            };
+            let form_matrix = stream
+                .dict
+                .get(b"Matrix")
+                .and_then(Object::as_array)
+                .ok()
+                .and_then(|operands| matrix(operands))
+                .unwrap_or(IDENTITY);
+            let form_resources = stream
+                .dict
+                .get_deref(b"Resources", doc)
+                .and_then(Object::as_dict)
+                .unwrap_or(resources);
+
+            self.forms.push(id);
+            self.walk(&content, form_resources, multiply(&form_matrix, &ctm));
+            self.forms.pop();
+        }
+    }
+
+    /// Consome uma execução do orçamento da página; `false` quando ele acabou
+    fn spend_draw(&mut self) -> bool {
+        if self.draws_left == 0 {
+            return false;
+        }
+        self.draws_left -= 1;
+        true
+    }
+
+    /// Tamanho desenhado e resolução efetiva da imagem sob a CTM atual
+    ///
+    /// A imagem ocupa o quadrado unitário do espaço do usuário; o tamanho em pontos
+    /// é o comprimento dos vetores transformados (considera rotação e `/UserUnit`).
+    fn placement(&self, info: &ImageInfo, ctm: &Matrix) -> ImagePlacement {
+        let width_pt = ctm[0].hypot(ctm[1]) * self.user_unit;
+        let height_pt = ctm[2].hypot(ctm[3]) * self.user_unit;
+        let dpi = |pixels: u32, points: f64| (pixels > 0 && points > 0.0).then(|| f64::from(pixels) * 72.0 / points);
+
+        ImagePlacement {
+            page: self.page_number,
+            width_pt,
+            height_pt,
+            dpi_x: dpi(info.width, width_pt),
+            dpi_y: dpi(info.height, height_pt),
+        }
+    }
+}
+
+/// Monta o `ImageInfo` de um dicionário de imagem (sem os usos)
+///
+/// `resources` resolve espaços de cor nomeados das imagens inline.
+fn describe_image(doc: &Document, dict: &Dictionary, resources: Option<&Dictionary>, size: u64) -> ImageInfo {
+    let filters = pdf_filters::filter_names(doc, dict).unwrap_or_default();
+    let image_mask = dict.get_deref(b"ImageMask", doc).and_then(Object::as_bool).unwrap_or(false);
+    let dimension = |key: &[u8]| {
+        dict.get_deref(key, doc)
+            .and_then(Object::as_i64)
+            .map(|value| value.clamp(0, i64::from(u32::MAX)) as u32)
+            .unwrap_or(0)
+    };
+
+    // Máscaras de estêncil não têm espaço de cor e usam 1 bit; JPX pode omitir os dois
+    let color_space = match dict.get(b"ColorSpace") {
+        Ok(object) if !image_mask => color_space(doc, object, resources),
+        _ => ColorSpace::Unknown,
+    };
+    let bits_per_component = dict
+        .get_deref(b"BitsPerComponent", doc)
+        .and_then(Object::as_i64)
+        .map(|bits| bits.clamp(0, 32) as u8)
+        .unwrap_or(if image_mask { 1 } else { 8 });
+
+    ImageInfo {
+        format: ImageFormat::from_filters(&filters),
+        width: dimension(b"Width"),
+        height: dimension(b"Height"),
+        color_space,
+        bits_per_component,
+        compressed: !filters.is_empty(),
+        size_bytes: Some(size),
+        object_id: None,
+        inline: false,
+        placements: Vec::new(),
+    }
+}
+
+/// Família do espaço de cor, seguindo nomes definidos em `/Resources /ColorSpace`
+fn color_space(doc: &Document, object: &Object, resources: Option<&Dictionary>) -> ColorSpace {
+    match doc.dereference(object).map(|(_, object)| object) {
+        Ok(Object::Name(name)) => match ColorSpace::from_name(name) {
+            ColorSpace::Unknown => resources
+                .and_then(|resources| resources.get_deref(b"ColorSpace", doc).and_then(Object::as_dict).ok())
+                .and_then(|spaces| spaces.get(name).ok())
+                .map(|named| color_space(doc, named, None))
+                .unwrap_or(ColorSpace::Unknown),
+            known => known,
+        },
+        Ok(Object::Array(items)) => items
+            .first()
+            .and_then(|family| family.as_name().ok())
+            .map(ColorSpace::from_name)
+            .unwrap_or(ColorSpace::Unknown),
+        _ => ColorSpace::Unknown,
+    }
+}
+
+/// Lê os seis números de `cm` ou de `/Matrix`
+pub(crate) fn matrix(operands: &[Object]) -> Option<Matrix> {
+    if operands.len() != 6 {
+        return None;
+    }
+
+    let mut matrix = IDENTITY;
+    for (slot, operand) in matrix.iter_mut().zip(operands) {
+        *slot = f64::from(operand.as_float().ok()?);
+    }
+    Some(matrix)
+}
+
+/// Produto `m × n` (aplica `m` e depois `n`)
+pub(crate) fn multiply(m: &Matrix, n: &Matrix) -> Matrix {
+    [
+        m[0] * n[0] + m[1] * n[2],
+        m[0] * n[1] + m[1] * n[3],
+        m[2] * n[0] + m[3] * n[2],
+        m[2] * n[1] + m[3] * n[3],
+        m[4] * n[0] + m[5] * n[2] + n[4],
+        m[4] * n[1] + m[5] * n[3] + n[5],
+    ]
+}
+
+pub(crate) fn is_subtype(dict: &Dictionary, subtype: &[u8]) -> bool {
+    dict.get(b"Subtype").and_then(Object::as_name).ok() == Some(subtype)
+}
+
+/// Monta o `AnnotationInfo` de um dicionário de anotação
+fn describe_annotation(doc: &Document, annot_id: Option<ObjectId>, annot: &Dictionary, page: u32) -> AnnotationInfo {
+    let annotation_type = annot
+        .get(b"Subtype")
+        .and_then(Object::as_name)
+        .map(AnnotationType::from_subtype)
+        .unwrap_or(AnnotationType::Unknown);
+
+    // `/Rect` pode trazer quaisquer dois cantos opostos; normaliza para (x1, y1, x2, y2)
+    let rectangle = annot
+        .get_deref(b"Rect", doc)
+        .and_then(Object::as_array)
+        .ok()
+        .and_then(|rect| {
+            let numbers = rect.iter().map(|n| n.as_float().ok().map(f64::from)).collect::<Option<Vec<_>>>()?;
+            match numbers[..] {
+                [x1, y1, x2, y2] => Some((x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2))),
+                _ => None,
+            }
+        })
+        .unwrap_or_default();
+
+    AnnotationInfo {
+        annotation_type,
+        page,
+        rectangle,
+        contents: text_string(doc, annot, b"Contents"),
+        author: text_string(doc, annot, b"T"),
+        creation_date: text_string(doc, annot, b"CreationDate"),
+        object_id: annot_id.map(|id| id.0),
+    }
+}
+
+/// Lê uma entrada de texto de um dicionário
+fn text_string(doc: &Document, dict: &Dictionary, key: &[u8]) -> Option<String> {
+    dict.get_deref(key, doc)
+        .and_then(Object::as_str)
+        .ok()
+        .map(pdf_encoding::decode_text_string)
+}
+
+/// Filhos de um nó de `/Outlines`, seguindo `/First` e `/Next`
+fn outline_children(
+    doc: &Document,
+    parent: &Dictionary,
+    level: u32,
+    resolver: &DestinationResolver<'_>,
+    visited: &mut HashSet<ObjectId>,
+) -> Vec<BookmarkInfo> {
+    let mut children = Vec::new();
+    if level as usize > MAX_OUTLINE_DEPTH {
+        return children;
+    }
+
+    let mut next = parent.get(b"First").and_then(Object::as_reference).ok();
+    while let Some(item_id) = next {
+        // Listas circulares (`/Next` apontando para trás) são interrompidas
+        if !visited.insert(item_id) {
+            break;
+        }
+        let Ok(item) = doc.get_dictionary(item_id) else {
+            break;
+        };
+
+        children.push(BookmarkInfo {
+            title: text_string(doc, item, b"Title").unwrap_or_default(),
+            level,
+            page: resolver.outline_page(item).unwrap_or(0),
+            children: outline_children(doc, item, level + 1, resolver, visited),
+        });
+
+        next = item.get(b"Next").and_then(Object::as_reference).ok();
+    }
+
+    children
+}
+
+/// Resolve destinos de bookmarks para números de página
+struct DestinationResolver<'a> {
+    doc: &'a Document,
+    /// Página (1-indexed) de cada objeto de página
+    pages: HashMap<ObjectId, u32>,
+    /// Destinos nomeados de `/Dests` (PDF 1.1) e da árvore `/Names /Dests`
+    named: HashMap<Vec<u8>, &'a Object>,
+}
+
+impl<'a> DestinationResolver<'a> {
+    fn new(doc: &'a Document) -> Self {
+        let pages = doc.get_pages().into_iter().map(|(number, id)| (id, number)).collect();
+        let mut named = HashMap::new();
+
+        if let Ok(catalog) = doc.catalog() {
+            if let Ok(dests) = catalog.get_deref(b"Dests", doc).and_then(Object::as_dict) {
+                for (name, dest) in dests.iter() {
+                    named.insert(name.clone(), dest);
+                }
+            }
+            if let Ok(tree) = catalog
+                .get_deref(b"Names", doc)
+                .and_then(Object::as_dict)
+                .and_then(|names| names.get_deref(b"Dests", doc))
+                .and_then(Object::as_dict)
+            {
+                collect_name_tree(doc, tree, 0, &mut named);
+            }
+        }
+
+        Self { doc, pages, named }
+    }
+
+    /// Página do destino de um item: `/Dest` ou a ação `/GoTo` em `/A`
+    fn outline_page(&self, item: &Dictionary) -> Option<u32> {
+        if let Ok(dest) = item.get(b"Dest") {
+            return self.page(dest, 0);
+        }
+
+        let action = item.get_deref(b"A", self.doc).and_then(Object::as_dict).ok()?;
+        if action.get(b"S").and_then(Object::as_name).ok() != Some(b"GoTo".as_slice()) {
+            return None;
+        }
+        self.page(action.get(b"D").ok()?, 0)
+    }
+
+    fn page(&self, dest: &Object, depth: usize) -> Option<u32> {
+        if depth > 2 {
+            return None;
+        }
+
+        match self.doc.dereference(dest).ok()?.1 {
+            // Destino explícito: [página /XYZ ...]
+            Object::Array(items) => match items.first()? {
+                Object::Reference(page_id) => self.pages.get(page_id).copied(),
+                // Alguns geradores usam o índice da página (0-indexed)
+                Object::Integer(index) => u32::try_from(*index).ok().map(|index| index + 1),
+                _ => None,
+            },
+            // Destino nomeado
+            Object::Name(name) | Object::String(name, _) => self.page(self.named.get(name)?, depth + 1),
+            // Entrada de destino nomeado: << /D [...] >>
+            Object::Dictionary(dict) => self.page(dict.get(b"D").ok()?, depth + 1),
+            _ => None,
+        }
+    }
+}
+
+/// Coleta as entradas de uma árvore de nomes (`/Names` e `/Kids`)
+fn collect_name_tree<'a>(doc: &'a Document, node: &'a Dictionary, depth: usize, entries: &mut HashMap<Vec<u8>, &'a Object>) {
+    if depth > MAX_OUTLINE_DEPTH {
+        return;
+    }
+
+    if let Ok(names) = node.get_deref(b"Names", doc).and_then(Object::as_array) {
+        for pair in names.chunks(2) {
+            if let [key, value] = pair {
+                if let Ok(key) = doc.dereference(key).and_then(|(_, key)| key.as_str()) {
+                    entries.insert(key.to_vec(), value);
+                }
+            }
+        }
+    }
+
+    if let Ok(kids) = node.get_deref(b"Kids", doc).and_then(Object::as_array) {
+        for kid in kids {
+            if let Ok((_, Object::Dictionary(kid))) = doc.dereference(kid) {
+                collect_name_tree(doc, kid, depth + 1, entries);
+            }
+        }
+    }
+}
+
+/// Subconjuntos usam um prefixo de seis letras maiúsculas (ex.: "ABCDEF+Arial")
+fn is_subset_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    bytes.len() > 7 && bytes[6] == b'+' && bytes[..6].iter().all(u8::is_ascii_uppercase)
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::test_support;
+    use lopdf::{dictionary, Stream};
+
+    #[test]
+    fn test_sample_font_is_not_embedded() {
+        let fonts = fonts(&test_support::build_sample_pdf(3));
+        assert_eq!(fonts.len(), 1);
+        assert_eq!(fonts[0].name, "Helvetica");
+        assert_eq!(fonts[0].font_type, FontType::Type1);
+        assert!(!fonts[0].embedded);
+        assert!(!fonts[0].subset);
+        // Os recursos são herdados do nó /Pages
+        assert_eq!(fonts[0].pages, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_type0_descendants_and_form_xobjects() {
+        let mut doc = test_support::build_sample_pdf(2);
+        let second_page = doc.get_pages()[&2];
+
+        let font_file = doc.add_object(Stream::new(dictionary! {}, b"glyphs".to_vec()));
+        let descriptor = doc.add_object(dictionary! {
+            "Type" => "FontDescriptor",
+            "FontName" => "ABCDEF+NotoSans",
+            "FontFile2" => font_file,
+        });
+        let descendant = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "CIDFontType2",
+            "BaseFont" => "ABCDEF+NotoSans",
+            "FontDescriptor" => descriptor,
+        });
+        let type0 = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type0",
+            "BaseFont" => "ABCDEF+NotoSans",
+            "DescendantFonts" => vec![descendant.into()],
+        });
+
+        // A fonte Type0 só aparece dentro de um Form XObject da segunda página
+        let form = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Form",
+                "Resources" => dictionary! { "Font" => dictionary! { "F9" => type0 } },
+            },
+            Vec::new(),
+        ));
+        doc.get_dictionary_mut(second_page).unwrap().set("Resources", dictionary! {
+            "Font" => dictionary! { "F1" => (2, 0) },
+            "XObject" => dictionary! { "X1" => form },
+        });
+
+        let fonts = fonts(&doc);
+        let names: Vec<(&str, FontType)> = fonts.iter().map(|f| (f.name.as_str(), f.font_type)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Helvetica", FontType::Type1),
+                ("ABCDEF+NotoSans", FontType::Type0),
+                ("ABCDEF+NotoSans", FontType::CIDFontType2),
+            ]
+        );
+        assert_eq!(fonts[0].pages, vec![1, 2]);
+        assert!(fonts[1].embedded && fonts[1].subset);
+        assert!(fonts[2].embedded);
+        assert_eq!(fonts[2].pages, vec![2]);
+    }
+
+    /// Substitui o conteúdo e os recursos de uma página
+    fn set_page(doc: &mut Document, page_number: u32, content: &[u8], resources: Dictionary) {
+        let page_id = doc.get_pages()[&page_number];
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content.to_vec()));
+        let page = doc.get_dictionary_mut(page_id).unwrap();
+        page.set("Contents", content_id);
+        page.set("Resources", resources);
+    }
+
+    #[test]
+    fn test_sample_has_no_images() {
+        assert!(images(&test_support::build_sample_pdf(2)).is_empty());
+    }
+
+    #[test]
+    fn test_images_report_format_and_effective_dpi() {
+        let mut doc = test_support::build_sample_pdf(2);
+        let photo = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => 300,
+                "Height" => 150,
+                "ColorSpace" => "DeviceRGB",
+                "BitsPerComponent" => 8,
+                "Filter" => "DCTDecode",
+            },
+            vec![0xFF; 64],
+        ));
+        // Form que desenha a mesma imagem na metade do tamanho
+        let form = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Form",
+                "Matrix" => vec![0.5.into(), 0.into(), 0.into(), 0.5.into(), 0.into(), 0.into()],
+                "Resources" => dictionary! { "XObject" => dictionary! { "Im1" => photo } },
+            },
+            b"/Im1 Do".to_vec(),
+        ));
+
+        set_page(&mut doc, 1, b"q 72 0 0 36 100 100 cm /Im1 Do Q", dictionary! {
+            "XObject" => dictionary! { "Im1" => photo },
+        });
+        set_page(
+            &mut doc,
+            2,
+            b"q 72 0 0 36 0 0 cm /Fm1 Do Q q 10 0 0 10 0 0 cm BI /W 100 /H 100 /CS /G /BPC 8 /F /AHx ID 00FF> EI Q",
+            dictionary! { "XObject" => dictionary! { "Fm1" => form } },
+        );
+
+        let images = images(&doc);
+        assert_eq!(images.len(), 2);
+
+        let xobject = &images[0];
+        assert_eq!(xobject.object_id, Some(photo.0));
+        assert_eq!(xobject.format, ImageFormat::JPEG);
+        assert_eq!((xobject.width, xobject.height), (300, 150));
+        assert_eq!(xobject.color_space, ColorSpace::DeviceRGB);
+        assert_eq!(xobject.size_bytes, Some(64));
+        assert!(xobject.compressed && !xobject.inline);
+
+        let pages: Vec<(u32, Option<f64>)> = xobject.placements.iter().map(|p| (p.page, p.effective_dpi())).collect();
+        assert_eq!(pages, vec![(1, Some(300.0)), (2, Some(600.0))]);
+        assert_eq!(xobject.max_dpi(), Some(600.0));
+
+        let inline = &images[1];
+        assert!(inline.inline);
+        assert_eq!(inline.object_id, None);
+        assert_eq!(inline.format, ImageFormat::Raw);
+        assert_eq!(inline.color_space, ColorSpace::DeviceGray);
+        assert_eq!(inline.placements[0].page, 2);
+        assert_eq!(inline.placements[0].effective_dpi(), Some(720.0));
+    }
+
+    #[test]
+    fn test_images_stop_at_draw_budget_on_form_fan_out() {
+        let mut doc = test_support::build_sample_pdf(1);
+        let image = doc.add_object(Stream::new(
+            dictionary! { "Subtype" => "Image", "Width" => 1, "Height" => 1, "ColorSpace" => "DeviceGray", "BitsPerComponent" => 8 },
+            vec![0],
+        ));
+
+        // Cada nível chama o anterior 8 vezes: 8^15 execuções sem o orçamento
+        let mut level = image;
+        for _ in 0..15 {
+            level = doc.add_object(Stream::new(
+                dictionary! {
+                    "Subtype" => "Form",
+                    "Resources" => dictionary! { "XObject" => dictionary! { "X" => level } },
+                },
+                b"/X Do ".repeat(8),
+            ));
+        }
+        set_page(&mut doc, 1, b"/Fm Do", dictionary! { "XObject" => dictionary! { "Fm" => level } });
+
+        let images = images(&doc);
+        assert_eq!(images.len(), 1);
+        assert!(!images[0].placements.is_empty());
+        assert!(images[0].placements.len() < MAX_DRAWS_PER_PAGE);
+    }
+
+    #[test]
+    fn test_unused_image_resource_is_listed_without_placements() {
+        let mut doc = test_support::build_sample_pdf(1);
+        let mask = doc.add_object(Stream::new(
+            dictionary! {
+                "Subtype" => "Image",
+                "Width" => 8,
+                "Height" => 8,
+                "ImageMask" => true,
+            },
+            vec![0; 8],
+        ));
+        set_page(&mut doc, 1, b"", dictionary! { "XObject" => dictionary! { "M" => mask } });
+
+        let images = images(&doc);
+        assert_eq!(images.len(), 1);
+        assert!(images[0].placements.is_empty());
+        assert_eq!(images[0].bits_per_component, 1);
+        assert_eq!(images[0].color_space, ColorSpace::Unknown);
+        assert!(!images[0].compressed);
+    }
+
+    #[test]
+    fn test_annotations_follow_page_order() {
+        let mut doc = test_support::build_sample_pdf(2);
+        let highlight = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Highlight",
+            "Rect" => vec![200.into(), 700.into(), 100.into(), 680.into()],
+            "Contents" => Object::string_literal("Check this figure"),
+            "T" => Object::string_literal("Reviewer"),
+            "CreationDate" => Object::string_literal("D:20240102030405Z"),
+        });
+        let pages = doc.get_pages();
+        doc.get_dictionary_mut(pages[&1]).unwrap().set("Annots", vec![highlight.into()]);
+        doc.get_dictionary_mut(pages[&2]).unwrap().set(
+            "Annots",
+            vec![Object::Dictionary(dictionary! {
+                "Subtype" => "Link",
+                "Rect" => vec![0.into(), 0.into(), 10.into(), 10.into()],
+            })],
+        );
+
+        let annotations = annotations(&doc);
+        assert_eq!(annotations.len(), 2);
+
+        let first = &annotations[0];
+        assert_eq!(first.annotation_type, AnnotationType::Highlight);
+        assert_eq!(first.page, 1);
+        assert_eq!(first.rectangle, (100.0, 680.0, 200.0, 700.0));
+        assert_eq!(first.contents.as_deref(), Some("Check this figure"));
+        assert_eq!(first.author.as_deref(), Some("Reviewer"));
+        assert_eq!(first.creation_date.as_deref(), Some("D:20240102030405Z"));
+        assert_eq!(first.object_id, Some(highlight.0));
+
+        let second = &annotations[1];
+        assert_eq!(second.annotation_type, AnnotationType::Link);
+        assert_eq!(second.page, 2);
+        assert_eq!(second.object_id, None);
+        assert!(second.contents.is_none());
+    }
+
+    #[test]
+    fn test_bookmarks_resolve_explicit_and_named_destinations() {
+        let mut doc = test_support::build_sample_pdf(3);
+        let pages = doc.get_pages();
+
+        let outlines = doc.new_object_id();
+        let chapter = doc.new_object_id();
+        let section = doc.new_object_id();
+        let appendix = doc.new_object_id();
+
+        doc.objects.insert(chapter, Object::Dictionary(dictionary! {
+            // "Introdução" em PDFDocEncoding
+            "Title" => Object::string_literal(b"Introdu\xE7\xE3o".to_vec()),
+            "Parent" => outlines,
+            "First" => section,
+            "Last" => section,
+            "Next" => appendix,
+            "Dest" => vec![pages[&1].into(), "Fit".into()],
+        }));
+        doc.objects.insert(section, Object::Dictionary(dictionary! {
+            "Title" => Object::String(
+                [&[0xFE, 0xFF][..], &"Seção".encode_utf16().flat_map(u16::to_be_bytes).collect::<Vec<_>>()].concat(),
+                lopdf::StringFormat::Hexadecimal,
+            ),
+            "Parent" => chapter,
+            "A" => dictionary! { "S" => "GoTo", "D" => Object::string_literal("sec") },
+        }));
+        doc.objects.insert(appendix, Object::Dictionary(dictionary! {
+            "Title" => Object::string_literal("Appendix"),
+            "Parent" => outlines,
+            "Prev" => chapter,
+            "Dest" => "appendix",
+        }));
+        doc.objects.insert(outlines, Object::Dictionary(dictionary! {
+            "Type" => "Outlines",
+            "First" => chapter,
+            "Last" => appendix,
+        }));
+
+        let catalog = doc.catalog_mut().unwrap();
+        catalog.set("Outlines", outlines);
+        // Nome PDF 1.1 em /Dests e string na árvore /Names
+        catalog.set("Dests", dictionary! { "appendix" => vec![pages[&3].into(), "Fit".into()] });
+        catalog.set("Names", dictionary! {
+            "Dests" => dictionary! {
+                "Kids" => vec![Object::Dictionary(dictionary! {
+                    "Names" => vec![
+                        Object::string_literal("sec"),
+                        Object::Dictionary(dictionary! { "D" => vec![pages[&2].into(), "Fit".into()] }),
+                    ],
+                })],
+            },
+        });
+
+        let bookmarks = bookmarks(&doc);
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].title, "Introdução");
+        assert_eq!((bookmarks[0].level, bookmarks[0].page), (1, 1));
+        assert_eq!(bookmarks[0].children.len(), 1);
+
+        let child = &bookmarks[0].children[0];
+        assert_eq!(child.title, "Seção");
+        assert_eq!((child.level, child.page), (2, 2));
+
+        assert_eq!(bookmarks[1].title, "Appendix");
+        assert_eq!(bookmarks[1].page, 3);
+    }
+
+    #[test]
+    fn test_bookmarks_stop_on_cycles() {
+        let mut doc = test_support::build_sample_pdf(1);
+        let outlines = doc.new_object_id();
+        let item = doc.new_object_id();
+        doc.objects.insert(item, Object::Dictionary(dictionary! {
+            "Title" => Object::string_literal("Loop"),
+            "Next" => item,
+            "First" => item,
+        }));
+        doc.objects.insert(outlines, Object::Dictionary(dictionary! { "First" => item }));
+        doc.catalog_mut().unwrap().set("Outlines", outlines);
+
+        let bookmarks = bookmarks(&doc);
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].page, 0);
+        assert!(bookmarks[0].children.is_empty());
+    }
+
+    #[test]
+    fn test_pages_resolve_inherited_boxes_and_rotation() {
+        let mut doc = test_support::build_sample_pdf(3);
+        let page_ids = doc.get_pages();
+
+        // Página 2: Letter em paisagem por /Rotate; página 3: CropBox A5 dentro do A4 herdado
+        let second = doc.get_dictionary_mut(page_ids[&2]).unwrap();
+        second.set("MediaBox", vec![0.into(), 0.into(), 612.into(), 792.into()]);
+        second.set("Rotate", -90);
+        doc.get_dictionary_mut(page_ids[&3]).unwrap().set(
+            "CropBox",
+            vec![0.into(), 0.into(), 420.into(), 595.into()],
+        );
+
+        let info = pages(&doc);
+        assert_eq!(info.len(), 3);
+
+        let first = &info[0];
+        assert_eq!(first.page_number, 1);
+        assert_eq!(first.dimensions, (595.0, 842.0));
+        assert_eq!(first.media_box, (0.0, 0.0, 595.0, 842.0));
+        assert_eq!(first.paper_size.as_deref(), Some("A4"));
+        assert_eq!(first.orientation, PageOrientation::Portrait);
+        assert!(first.has_text && !first.has_images && !first.has_annotations);
+        // Página, conteúdo, recursos e fonte
+        assert_eq!(first.object_count, 4);
+
+        let second = &info[1];
+        assert_eq!(second.rotation, 270);
+        assert_eq!(second.paper_size.as_deref(), Some("Letter"));
+        assert_eq!(second.orientation, PageOrientation::Landscape);
+
+        let third = &info[2];
+        assert_eq!(third.crop_box, (0.0, 0.0, 420.0, 595.0));
+        assert_eq!(third.dimensions, (420.0, 595.0));
+        assert_eq!(third.paper_size.as_deref(), Some("A5"));
+    }
+
+    #[test]
+    fn test_pages_detect_images_and_text_in_forms() {
+        let mut doc = test_support::build_sample_pdf(2);
+        let image = doc.add_object(Stream::new(
+            dictionary! { "Subtype" => "Image", "Width" => 1, "Height" => 1, "ColorSpace" => "DeviceGray", "BitsPerComponent" => 8 },
+            vec![0],
+        ));
+        let form = doc.add_object(Stream::new(
+            dictionary! {
+                "Subtype" => "Form",
+                "Resources" => dictionary! { "Font" => dictionary! { "F1" => (2, 0) } },
+            },
+            b"BT /F1 12 Tf (Stamp) Tj ET".to_vec(),
+        ));
+        set_page(&mut doc, 1, b"q 100 0 0 100 0 0 cm /Im1 Do Q", dictionary! {
+            "XObject" => dictionary! { "Im1" => image },
+        });
+        set_page(&mut doc, 2, b"/Fm1 Do", dictionary! { "XObject" => dictionary! { "Fm1" => form } });
+
+        let info = pages(&doc);
+        assert!(info[0].has_images && !info[0].has_text);
+        assert!(!info[1].has_images && info[1].has_text);
+    }
+
+    #[test]
+    fn test_normalize_rotation() {
+        assert_eq!(normalize_rotation(0), 0);
+        assert_eq!(normalize_rotation(450), 90);
+        assert_eq!(normalize_rotation(-90), 270);
+        assert_eq!(normalize_rotation(-180), 180);
+    }
+
+    #[test]
+    fn test_is_subset_name() {
+        assert!(is_subset_name("ABCDEF+Arial"));
+        assert!(!is_subset_name("Arial"));
+        assert!(!is_subset_name("ABCdEF+Arial"));
+        assert!(!is_subset_name("ABCDEF+"));
+    }
+}
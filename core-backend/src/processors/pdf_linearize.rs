@@ -0,0 +1,941 @@
+//! Gravação linearizada ("fast web view") de documentos PDF
+//!
+//! Um arquivo linearizado começa com o dicionário `/Linearized` e com uma
+//! tabela xref só da primeira página, seguidos dos objetos dessa página. O
+//! navegador exibe a primeira página assim que esses bytes chegam e busca o
+//! restante sob demanda (range requests), guiado pela hint stream.
+//!
+//! ## Layout gravado (ISO 32000-1, anexo F):
+//! 1. Cabeçalho
+//! 2. Dicionário de linearização
+//! 3. Xref e trailer da primeira página
+//! 4. Catálogo
+//! 5. Hint stream primária (page offset e shared object hint tables)
+//! 6. Objetos da primeira página
+//! 7. Objetos exclusivos de cada página seguinte, na ordem das páginas
+//! 8. Objetos compartilhados entre páginas
+//! 9. Demais objetos (Info, árvore de páginas, outlines...)
+//! 10. Xref e trailer principais
+//!
+//! Os objetos das partes 7-9 recebem os números 1..m e os da primeira página
+//! os números seguintes, de modo que cada tabela xref tenha uma única subseção.
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use std::collections::{HashMap, HashSet};
+
+use crate::processors::pdf_writer;
+
+/// Largura fixa dos números que só são conhecidos depois do layout
+const OFFSET_WIDTH: usize = 10;
+
+/// Atributos de página herdáveis da árvore de páginas
+const INHERITABLE_KEYS: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+/// Profundidade máxima percorrida na árvore de páginas
+const MAX_PAGE_TREE_DEPTH: usize = 64;
+
+/// Grava o documento em layout linearizado
+///
+/// Documentos criptografados são recusados: renumerar os objetos invalidaria
+/// as chaves derivadas do número de cada objeto.
+pub fn linearize(doc: &Document) -> Result<Vec<u8>, String> {
+    if doc.trailer.get(b"Encrypt").is_ok() {
+        return Err("encrypted documents cannot be linearized".to_string());
+    }
+    let root_id = doc
+        .trailer
+        .get(b"Root")
+        .and_then(Object::as_reference)
+        .map_err(|_| "trailer has no /Root reference".to_string())?;
+
+    let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+    if pages.is_empty() {
+        return Err("document has no pages".to_string());
+    }
+
+    let source = Source::new(doc, &pages);
+    let plan = Plan::new(&source, root_id, &pages);
+    let writer = LinearizedWriter::new(&source, &plan);
+    Ok(writer.write())
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+/// Objetos do documento, com os atributos herdados copiados para cada página
+///
+/// Sem isso os recursos da primeira página ficariam no nó `/Pages`, fora da
+/// seção que o navegador baixa primeiro.
+struct Source<'a> {
+    doc: &'a Document,
+    pages: HashMap<ObjectId, Object>,
+}
+
+impl<'a> Source<'a> {
+    fn new(doc: &'a Document, page_ids: &[ObjectId]) -> Self {
+        let pages = page_ids
+            .iter()
+            .filter_map(|&id| Some((id, Object::Dictionary(expanded_page(doc, id)?))))
+            .collect();
+        Self { doc, pages }
+    }
+
+    fn get(&self, id: ObjectId) -> Option<&Object> {
+        self.pages.get(&id).or_else(|| self.doc.get_object(id).ok())
+    }
+}
+
+/// Dicionário da página com os atributos herdados que ela não declara
+fn expanded_page(doc: &Document, page_id: ObjectId) -> Option<Dictionary> {
+    let mut page = doc.get_dictionary(page_id).ok()?.clone();
+    let mut node = page.get(b"Parent").and_then(Object::as_reference).ok();
+
+    for _ in 0..MAX_PAGE_TREE_DEPTH {
+        let Some(parent) = node.and_then(|id| doc.get_dictionary(id).ok()) else {
+            break;
+        };
+        for key in INHERITABLE_KEYS {
+            if !page.has(key) {
+                if let Ok(value) = parent.get(key) {
+                    page.set(key.to_vec(), value.clone());
+                }
+            }
+        }
+        node = parent.get(b"Parent").and_then(Object::as_reference).ok();
+    }
+
+    Some(page)
+}
+
+/// Distribuição dos objetos entre as partes do arquivo
+struct Plan {
+    root: ObjectId,
+    /// Objetos da primeira página (parte 6), começando pelo objeto da página
+    first_page: Vec<ObjectId>,
+    /// Objetos exclusivos de cada página seguinte (parte 7)
+    private: Vec<Vec<ObjectId>>,
+    /// Objetos usados por mais de uma página, fora da primeira (parte 8)
+    shared: Vec<ObjectId>,
+    /// Demais objetos alcançáveis pelo trailer (parte 9)
+    other: Vec<ObjectId>,
+    /// Objetos de cada página seguinte que estão na primeira página ou na parte 8
+    shared_references: Vec<Vec<ObjectId>>,
+}
+
+impl Plan {
+    fn new(source: &Source, root: ObjectId, pages: &[ObjectId]) -> Self {
+        let closures: Vec<Vec<ObjectId>> = pages
+            .iter()
+            .map(|&page| page_closure(source, page, root))
+            .collect();
+
+        let mut users: HashMap<ObjectId, usize> = HashMap::new();
+        for id in closures.iter().flatten() {
+            *users.entry(*id).or_default() += 1;
+        }
+
+        let first_page = closures[0].clone();
+        let mut assigned: HashSet<ObjectId> = first_page.iter().copied().collect();
+        assigned.insert(root);
+
+        let mut private = Vec::with_capacity(pages.len() - 1);
+        for closure in &closures[1..] {
+            let objects: Vec<ObjectId> = closure
+                .iter()
+                .copied()
+                .filter(|id| users[id] == 1 && !assigned.contains(id))
+                .collect();
+            assigned.extend(objects.iter().copied());
+            private.push(objects);
+        }
+
+        let mut shared = Vec::new();
+        for closure in &closures[1..] {
+            for &id in closure {
+                if assigned.insert(id) {
+                    shared.push(id);
+                }
+            }
+        }
+
+        let shared_set: HashSet<ObjectId> = shared.iter().copied().collect();
+        let first_set: HashSet<ObjectId> = first_page.iter().copied().collect();
+        let shared_references = closures[1..]
+            .iter()
+            .map(|closure| {
+                closure
+                    .iter()
+                    .copied()
+                    .filter(|id| first_set.contains(id) || shared_set.contains(id))
+                    .collect()
+            })
+            .collect();
+
+        let mut other = Vec::new();
+        let mut seen = HashSet::new();
+        for key in [b"Root".as_slice(), b"Info".as_slice()] {
+            if let Ok(&Object::Reference(id)) = source.doc.trailer.get(key) {
+                collect_references(source, id, &mut seen, &mut other, &|_| false);
+            }
+        }
+        other.retain(|id| !assigned.contains(id));
+
+        Self {
+            root,
+            first_page,
+            private,
+            shared,
+            other,
+            shared_references,
+        }
+    }
+
+    /// Objetos numerados de 1 a m, na ordem em que aparecem no arquivo
+    fn remaining(&self) -> impl Iterator<Item = ObjectId> + '_ {
+        self.private
+            .iter()
+            .flatten()
+            .chain(self.shared.iter())
+            .chain(self.other.iter())
+            .copied()
+    }
+}
+
+/// Objetos necessários para exibir uma página, em ordem de descoberta
+///
+/// A busca não atravessa outras páginas, nós da árvore de páginas nem o catálogo,
+/// alcançados por `/Parent` ou por destinos de links.
+fn page_closure(source: &Source, page: ObjectId, root: ObjectId) -> Vec<ObjectId> {
+    let mut objects = Vec::new();
+    let mut seen = HashSet::new();
+    let stop = |id: ObjectId| id != page && (id == root || is_page_tree_node(source.doc, id));
+    collect_references(source, page, &mut seen, &mut objects, &stop);
+    objects
+}
+
+fn is_page_tree_node(doc: &Document, id: ObjectId) -> bool {
+    doc.get_dictionary(id)
+        .and_then(|dict| dict.get(b"Type"))
+        .and_then(Object::as_name)
+        .is_ok_and(|name| name == b"Page" || name == b"Pages")
+}
+
+/// Percorre as referências a partir de `start`, em profundidade
+fn collect_references(
+    source: &Source,
+    start: ObjectId,
+    seen: &mut HashSet<ObjectId>,
+    out: &mut Vec<ObjectId>,
+    stop: &dyn Fn(ObjectId) -> bool,
+) {
+    let mut stack = vec![start];
+    while let Some(id) = stack.pop() {
+        if stop(id) || !seen.insert(id) {
+            continue;
+        }
+        let Some(object) = source.get(id) else {
+            continue;
+        };
+        out.push(id);
+
+        let mut children = Vec::new();
+        references(object, &mut children);
+        // Empilha ao contrário para visitar na ordem em que aparecem
+        stack.extend(children.into_iter().rev());
+    }
+}
+
+fn references(object: &Object, out: &mut Vec<ObjectId>) {
+    match object {
+        Object::Reference(id) => out.push(*id),
+        Object::Array(items) => items.iter().for_each(|item| references(item, out)),
+        Object::Dictionary(dict) => dict.iter().for_each(|(_, value)| references(value, out)),
+        Object::Stream(stream) => {
+            // O tamanho das streams é regravado direto no dicionário
+            for (key, value) in stream.dict.iter() {
+                if key != b"Length" {
+                    references(value, out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Offsets e tamanhos de cada objeto para um tamanho de hint stream
+struct Layout {
+    /// Offset de cada objeto, indexado pelo novo número
+    offsets: Vec<u64>,
+    /// Tamanho de cada objeto em bytes, indexado pelo novo número
+    lengths: Vec<u64>,
+    first_xref: u64,
+    first_page_end: u64,
+    main_xref: u64,
+    file_length: u64,
+}
+
+/// Dados da hint stream primária
+#[derive(Default)]
+struct HintStream {
+    data: Vec<u8>,
+    /// `/S`: offset da shared object hint table dentro de `data`
+    shared_offset: usize,
+}
+
+/// Serializa o documento seguindo o `Plan`
+struct LinearizedWriter<'a> {
+    plan: &'a Plan,
+    version: String,
+    /// Novo número de cada objeto original
+    numbers: HashMap<ObjectId, u32>,
+    /// Quantidade de objetos das partes 7-9 (números 1..=m)
+    remaining_count: u32,
+    linearization_number: u32,
+    hint_number: u32,
+    /// Último número em uso
+    last_number: u32,
+    /// Objetos já serializados, indexados pelo novo número
+    bodies: Vec<Vec<u8>>,
+    /// `/Root`, `/Info` e `/ID` do trailer original, já renumerados
+    trailer_entries: Vec<u8>,
+}
+
+impl<'a> LinearizedWriter<'a> {
+    fn new(source: &Source, plan: &'a Plan) -> Self {
+        let mut numbers = HashMap::new();
+        let mut next = 1u32;
+        for id in plan.remaining() {
+            numbers.insert(id, next);
+            next += 1;
+        }
+        let remaining_count = next - 1;
+
+        let linearization_number = next;
+        numbers.insert(plan.root, next + 1);
+        let hint_number = next + 2;
+        next += 3;
+        for &id in &plan.first_page {
+            numbers.insert(id, next);
+            next += 1;
+        }
+        let last_number = next - 1;
+
+        let mut writer = Self {
+            plan,
+            version: source.doc.version.clone(),
+            numbers,
+            remaining_count,
+            linearization_number,
+            hint_number,
+            last_number,
+            bodies: vec![Vec::new(); last_number as usize + 1],
+            trailer_entries: Vec::new(),
+        };
+
+        let ids: Vec<ObjectId> = plan.remaining().chain([plan.root]).chain(plan.first_page.iter().copied()).collect();
+        for id in ids {
+            let number = writer.numbers[&id];
+            let mut body = format!("{} 0 obj\n", number).into_bytes();
+            match source.get(id) {
+                Some(object) => writer.write_object(&mut body, object),
+                None => body.extend_from_slice(b"null"),
+            }
+            body.extend_from_slice(b"\nendobj\n");
+            writer.bodies[number as usize] = body;
+        }
+
+        let mut entries = Vec::new();
+        for key in [b"Root".as_slice(), b"Info".as_slice(), b"ID".as_slice()] {
+            if let Ok(value) = source.doc.trailer.get(key) {
+                pdf_writer::write_name(&mut entries, key);
+                entries.push(b' ');
+                writer.write_object(&mut entries, value);
+                entries.push(b' ');
+            }
+        }
+        writer.trailer_entries = entries;
+        writer
+    }
+
+    fn write(&self) -> Vec<u8> {
+        // O tamanho da hint stream altera os offsets que ela mesma descreve:
+        // refaz o layout até os dados caberem no espaço reservado. A reserva só
+        // cresce e os dados crescem com o logaritmo dos offsets, então converge.
+        let mut reserved = 0;
+        let (layout, hint) = loop {
+            let layout = self.layout(self.hint_object(&HintStream::default(), reserved).len() as u64);
+            let hint = self.hint_stream(&layout);
+            if hint.data.len() <= reserved {
+                break (layout, hint);
+            }
+            reserved = hint.data.len();
+        };
+
+        let mut out = self.header();
+        out.extend_from_slice(&self.linearization_object(&layout));
+        out.extend_from_slice(&self.first_page_xref(&layout.offsets, layout.main_xref));
+        out.extend_from_slice(self.body(self.plan.root));
+        out.extend_from_slice(&self.hint_object(&hint, reserved));
+        for &id in &self.plan.first_page {
+            out.extend_from_slice(self.body(id));
+        }
+        for id in self.plan.remaining() {
+            out.extend_from_slice(self.body(id));
+        }
+        out.extend_from_slice(&self.main_xref(&layout.offsets, layout.first_xref));
+        debug_assert_eq!(out.len() as u64, layout.file_length);
+        out
+    }
+
+    fn layout(&self, hint_length: u64) -> Layout {
+        let size = self.last_number as usize + 1;
+        let mut offsets = vec![0u64; size];
+        let mut lengths = vec![0u64; size];
+
+        let linearization_offset = self.header().len() as u64;
+        let linearization_length = self.linearization_object(&Layout::empty(size)).len() as u64;
+        let first_xref = linearization_offset + linearization_length;
+        let mut position = first_xref + self.first_page_xref(&vec![0; size], 0).len() as u64;
+
+        let mut place = |number: u32, length: u64, position: &mut u64| {
+            offsets[number as usize] = *position;
+            lengths[number as usize] = length;
+            *position += length;
+        };
+
+        place(self.linearization_number, linearization_length, &mut { linearization_offset });
+        place(self.numbers[&self.plan.root], self.body(self.plan.root).len() as u64, &mut position);
+        place(self.hint_number, hint_length, &mut position);
+        for &id in &self.plan.first_page {
+            place(self.numbers[&id], self.body(id).len() as u64, &mut position);
+        }
+        let first_page_end = position;
+        for id in self.plan.remaining() {
+            place(self.numbers[&id], self.body(id).len() as u64, &mut position);
+        }
+
+        let main_xref = position;
+        let file_length = main_xref + self.main_xref(&vec![0; size], first_xref).len() as u64;
+        Layout {
+            offsets,
+            lengths,
+            first_xref,
+            first_page_end,
+            main_xref,
+            file_length,
+        }
+    }
+
+    fn body(&self, id: ObjectId) -> &[u8] {
+        &self.bodies[self.numbers[&id] as usize]
+    }
+
+    fn header(&self) -> Vec<u8> {
+        let mut header = format!("%PDF-{}\n", self.version).into_bytes();
+        header.extend_from_slice(b"%\xE2\xE3\xCF\xD3\n");
+        header
+    }
+
+    /// Dicionário de linearização, com números de largura fixa
+    fn linearization_object(&self, layout: &Layout) -> Vec<u8> {
+        // /T aponta para o fim de linha que precede a primeira entrada da xref principal
+        let main_first_entry = layout.main_xref + format!("xref\n0 {}", self.remaining_count + 1).len() as u64;
+        let hint = self.hint_number as usize;
+        format!(
+            "{} 0 obj\n<< /Linearized 1 /L {:0w$} /H [ {:0w$} {:0w$} ] /O {} /E {:0w$} /N {} /T {:0w$} >>\nendobj\n",
+            self.linearization_number,
+            layout.file_length,
+            layout.offsets[hint],
+            layout.lengths[hint],
+            self.numbers[&self.plan.first_page[0]],
+            layout.first_page_end,
+            self.plan.private.len() + 1,
+            main_first_entry,
+            w = OFFSET_WIDTH,
+        )
+        .into_bytes()
+    }
+
+    /// Xref dos objetos da primeira página, com `/Prev` para a xref principal
+    fn first_page_xref(&self, offsets: &[u64], main_xref: u64) -> Vec<u8> {
+        let first = self.linearization_number;
+        let mut out = format!("xref\n{} {}\n", first, self.last_number - first + 1).into_bytes();
+        for number in first..=self.last_number {
+            write_xref_entry(&mut out, offsets[number as usize]);
+        }
+        out.extend_from_slice(
+            format!("trailer\n<< /Size {} /Prev {:0w$} ", self.last_number + 1, main_xref, w = OFFSET_WIDTH).as_bytes(),
+        );
+        out.extend_from_slice(&self.trailer_entries);
+        out.extend_from_slice(b">>\nstartxref\n0\n%%EOF\n");
+        out
+    }
+
+    /// Xref principal; o `startxref` final aponta para a xref da primeira página
+    fn main_xref(&self, offsets: &[u64], first_xref: u64) -> Vec<u8> {
+        let mut out = format!("xref\n0 {}\n", self.remaining_count + 1).into_bytes();
+        out.extend_from_slice(b"0000000000 65535 f \n");
+        for number in 1..=self.remaining_count {
+            write_xref_entry(&mut out, offsets[number as usize]);
+        }
+        out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} >>\nstartxref\n{}\n%%EOF\n",
+                self.remaining_count + 1,
+                first_xref
+            )
+            .as_bytes(),
+        );
+        out
+    }
+
+    /// Objeto da hint stream, com `/Length` e `/S` de largura fixa e os dados
+    /// completados com zeros até `reserved`
+    fn hint_object(&self, hint: &HintStream, reserved: usize) -> Vec<u8> {
+        let mut out = format!(
+            "{} 0 obj\n<< /Length {:0w$} /S {:0w$} >>\nstream\n",
+            self.hint_number,
+            reserved,
+            hint.shared_offset,
+            w = OFFSET_WIDTH
+        )
+        .into_bytes();
+        out.extend_from_slice(&hint.data);
+        out.resize(out.len() + reserved.saturating_sub(hint.data.len()), 0);
+        out.extend_from_slice(b"\nendstream\nendobj\n");
+        out
+    }
+
+    /// Page offset hint table seguida da shared object hint table (anexo F.4)
+    fn hint_stream(&self, layout: &Layout) -> HintStream {
+        let mut data = self.page_offset_table(layout);
+        let shared_offset = data.len();
+        data.extend_from_slice(&self.shared_object_table(layout));
+        HintStream { data, shared_offset }
+    }
+
+    fn page_offset_table(&self, layout: &Layout) -> Vec<u8> {
+        let span = |objects: &[ObjectId]| -> (u64, u64) {
+            let numbers = objects.iter().map(|id| self.numbers[id] as usize);
+            let length = numbers.clone().map(|n| layout.lengths[n]).sum();
+            let start = numbers.map(|n| layout.offsets[n]).next().unwrap_or(0);
+            (start, length)
+        };
+
+        let (first_page_offset, _) = span(&self.plan.first_page);
+        let mut object_counts = vec![self.plan.first_page.len() as u64];
+        let mut page_lengths = vec![layout.first_page_end - first_page_offset];
+        for objects in &self.plan.private {
+            object_counts.push(objects.len() as u64);
+            page_lengths.push(span(objects).1);
+        }
+
+        let identifiers = self.shared_identifiers();
+        // A primeira página contém todos os seus objetos e não referencia nenhum compartilhado
+        let mut shared_references: Vec<Vec<u64>> = vec![Vec::new()];
+        shared_references.extend(
+            self.plan
+                .shared_references
+                .iter()
+                .map(|objects| objects.iter().map(|id| identifiers[id]).collect()),
+        );
+
+        let least_objects = object_counts.iter().copied().min().unwrap_or(0);
+        let least_length = page_lengths.iter().copied().min().unwrap_or(0);
+        let object_bits = bit_width(object_counts.iter().map(|n| n - least_objects).max().unwrap_or(0));
+        let length_bits = bit_width(page_lengths.iter().map(|n| n - least_length).max().unwrap_or(0));
+        let count_bits = bit_width(shared_references.iter().map(|refs| refs.len() as u64).max().unwrap_or(0));
+        let identifier_bits = bit_width(identifiers.len().saturating_sub(1) as u64);
+
+        let mut bits = BitWriter::default();
+        bits.write(least_objects, 32);
+        bits.write(first_page_offset, 32);
+        bits.write(u64::from(object_bits), 16);
+        bits.write(least_length, 32);
+        bits.write(u64::from(length_bits), 16);
+        // Offset do conteúdo relativo à página (sempre 0) e tamanho igual ao da página
+        bits.write(0, 32);
+        bits.write(0, 16);
+        bits.write(least_length, 32);
+        bits.write(u64::from(length_bits), 16);
+        bits.write(u64::from(count_bits), 16);
+        bits.write(u64::from(identifier_bits), 16);
+        // Sem posição fracionária dentro de objetos compartilhados
+        bits.write(0, 16);
+        bits.write(1, 16);
+
+        // Cada item é gravado para todas as páginas e começa em um byte novo
+        for count in &object_counts {
+            bits.write(count - least_objects, object_bits);
+        }
+        bits.align();
+        for length in &page_lengths {
+            bits.write(length - least_length, length_bits);
+        }
+        bits.align();
+        for references in &shared_references {
+            bits.write(references.len() as u64, count_bits);
+        }
+        bits.align();
+        for identifier in shared_references.iter().flatten() {
+            bits.write(*identifier, identifier_bits);
+        }
+        bits.align();
+        for length in &page_lengths {
+            bits.write(length - least_length, length_bits);
+        }
+        bits.align();
+        bits.into_bytes()
+    }
+
+    fn shared_object_table(&self, layout: &Layout) -> Vec<u8> {
+        let groups: Vec<u64> = self
+            .plan
+            .first_page
+            .iter()
+            .chain(self.plan.shared.iter())
+            .map(|id| layout.lengths[self.numbers[id] as usize])
+            .collect();
+        let least_length = groups.iter().copied().min().unwrap_or(0);
+        let length_bits = bit_width(groups.iter().map(|n| n - least_length).max().unwrap_or(0));
+
+        let (first_shared_number, first_shared_offset) = self
+            .plan
+            .shared
+            .first()
+            .map(|id| {
+                let number = self.numbers[id];
+                (u64::from(number), layout.offsets[number as usize])
+            })
+            .unwrap_or((0, 0));
+
+        let mut bits = BitWriter::default();
+        bits.write(first_shared_number, 32);
+        bits.write(first_shared_offset, 32);
+        bits.write(self.plan.first_page.len() as u64, 32);
+        bits.write(groups.len() as u64, 32);
+        // Um objeto por grupo
+        bits.write(0, 16);
+        bits.write(least_length, 32);
+        bits.write(u64::from(length_bits), 16);
+
+        for length in &groups {
+            bits.write(length - least_length, length_bits);
+        }
+        bits.align();
+        // Nenhum grupo traz assinatura MD5
+        for _ in &groups {
+            bits.write(0, 1);
+        }
+        bits.align();
+        bits.into_bytes()
+    }
+
+    /// Identificador, na shared object hint table, de cada objeto compartilhável
+    fn shared_identifiers(&self) -> HashMap<ObjectId, u64> {
+        self.plan
+            .first_page
+            .iter()
+            .chain(self.plan.shared.iter())
+            .enumerate()
+            .map(|(index, &id)| (id, index as u64))
+            .collect()
+    }
+
+    /// Serializa um objeto trocando as referências pelos novos números
+    fn write_object(&self, out: &mut Vec<u8>, object: &Object) {
+        pdf_writer::write_object(out, object, &|id| self.numbers.get(&id).map(|&number| (number, 0)));
+    }
+}
+
+impl Layout {
+    /// Layout zerado, usado para medir as partes de tamanho fixo
+    fn empty(size: usize) -> Self {
+        Self {
+            offsets: vec![0; size],
+            lengths: vec![0; size],
+            first_xref: 0,
+            first_page_end: 0,
+            main_xref: 0,
+            file_length: 0,
+        }
+    }
+}
+
+/// Escritor de campos de bits (mais significativo primeiro), como nas hint tables
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    /// Bits já usados no último byte (0 = byte completo)
+    used: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u64, width: u32) {
+        for bit in (0..width).rev() {
+            if self.used == 0 {
+                self.bytes.push(0);
+            }
+            if (value >> bit) & 1 == 1 {
+                *self.bytes.last_mut().unwrap() |= 0x80 >> self.used;
+            }
+            self.used = (self.used + 1) % 8;
+        }
+    }
+
+    /// Completa o byte atual com zeros
+    fn align(&mut self) {
+        self.used = 0;
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Bits necessários para representar `value`
+fn bit_width(value: u64) -> u32 {
+    u64::BITS - value.leading_zeros()
+}
+
+/// Escreve uma entrada de 20 bytes da tabela xref
+fn write_xref_entry(out: &mut Vec<u8>, offset: u64) {
+    out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::pdf_xref;
+    use crate::processors::test_support;
+
+    #[test]
+    fn test_linearized_output_is_detected_and_loads() {
+        let doc = test_support::build_sample_pdf(4);
+        let bytes = linearize(&doc).unwrap();
+
+        let params = pdf_xref::linearization(&bytes).expect("linearization dictionary");
+        assert!(params.matches_length(bytes.len() as u64));
+        assert_eq!(params.page_count, Some(4));
+
+        // Duas seções xref encadeadas por /Prev, todas as entradas válidas
+        let report = pdf_xref::analyze(&bytes);
+        assert!(report.is_valid(), "{:?}", report.problems);
+        assert_eq!(report.sections.len(), 2);
+
+        // /T aponta para o fim de linha antes da entrada do objeto 0
+        let t = params.main_xref_offset.unwrap() as usize;
+        assert_eq!(&bytes[t..t + 21], b"\n0000000000 65535 f \n");
+
+        let reloaded = Document::load_mem(&bytes).unwrap();
+        let pages = reloaded.get_pages();
+        assert_eq!(pages.len(), 4);
+        assert_eq!(u64::from(pages[&1].0), params.first_page_object.unwrap());
+        for (number, &page_id) in &pages {
+            let text = reloaded.extract_text(&[*number]).unwrap();
+            assert_eq!(text, format!("Page {}\n", number));
+            // Os atributos herdados foram copiados para cada página
+            let page = reloaded.get_dictionary(page_id).unwrap();
+            let resources = reloaded.get_dictionary(page.get(b"Resources").unwrap().as_reference().unwrap()).unwrap();
+            let font = reloaded.get_dictionary(
+                resources.get(b"Font").unwrap().as_dict().unwrap().get(b"F1").unwrap().as_reference().unwrap(),
+            ).unwrap();
+            assert_eq!(font.get(b"BaseFont").unwrap().as_name().unwrap(), b"Helvetica");
+            let media_box: Vec<i64> = page.get(b"MediaBox").unwrap().as_array().unwrap()
+                .iter().map(|value| value.as_i64().unwrap()).collect();
+            assert_eq!(media_box, vec![0, 0, 595, 842]);
+        }
+        let info = reloaded.get_dictionary(reloaded.trailer.get(b"Info").unwrap().as_reference().unwrap()).unwrap();
+        assert_eq!(info.get(b"Title").unwrap().as_str().unwrap(), b"Sample");
+        assert_eq!(info.get(b"Producer").unwrap().as_str().unwrap(), b"DocHub tests");
+    }
+
+    #[test]
+    fn test_first_page_section_holds_its_objects() {
+        let doc = test_support::build_sample_pdf(3);
+        let bytes = linearize(&doc).unwrap();
+        let params = pdf_xref::linearization(&bytes).unwrap();
+        let first_page_end = params.first_page_end.unwrap();
+
+        let report = pdf_xref::analyze(&bytes);
+        let first_page_section = &report.sections[0];
+        assert_eq!(first_page_section.entries[0].object_number, params.object_number);
+
+        // Página, conteúdo e fonte da primeira página estão antes de /E
+        let reloaded = Document::load_mem(&bytes).unwrap();
+        let page_id = reloaded.get_pages()[&1];
+        let content_id = reloaded.get_page_contents(page_id)[0];
+        let offset_of = |number: u32| {
+            report.sections.iter()
+                .flat_map(|section| &section.entries)
+                .find_map(|entry| match entry.kind {
+                    pdf_xref::XrefEntryKind::InUse { offset } if entry.object_number == number => Some(offset),
+                    _ => None,
+                })
+                .unwrap()
+        };
+        assert!(offset_of(page_id.0) < first_page_end);
+        assert!(offset_of(content_id.0) < first_page_end);
+
+        let second_page = reloaded.get_pages()[&2];
+        assert!(offset_of(second_page.0) >= first_page_end);
+    }
+
+    /// Leitor de campos de bits, MSB primeiro, como as hint tables
+    struct BitReader<'a> {
+        data: &'a [u8],
+        bit: usize,
+    }
+
+    impl<'a> BitReader<'a> {
+        fn new(data: &'a [u8]) -> Self {
+            Self { data, bit: 0 }
+        }
+
+        fn read(&mut self, width: u64) -> u64 {
+            (0..width).fold(0, |value, _| {
+                let byte = self.data[self.bit / 8];
+                let set = (byte >> (7 - self.bit % 8)) & 1;
+                self.bit += 1;
+                (value << 1) | u64::from(set)
+            })
+        }
+
+        fn align(&mut self) {
+            self.bit = self.bit.div_ceil(8) * 8;
+        }
+    }
+
+    #[test]
+    fn test_hint_stream_describes_pages() {
+        let doc = test_support::build_sample_pdf(3);
+        let bytes = linearize(&doc).unwrap();
+        let params = pdf_xref::linearization(&bytes).unwrap();
+        let first_page_end = params.first_page_end.unwrap();
+        let reloaded = Document::load_mem(&bytes).unwrap();
+        let pages = reloaded.get_pages();
+
+        // Offsets de todos os objetos, em ordem no arquivo
+        let report = pdf_xref::analyze(&bytes);
+        let mut layout: Vec<(u64, u32)> = report.sections.iter()
+            .flat_map(|section| &section.entries)
+            .filter_map(|entry| match entry.kind {
+                pdf_xref::XrefEntryKind::InUse { offset } => Some((offset, entry.object_number)),
+                _ => None,
+            })
+            .collect();
+        layout.sort();
+        let offset_of = |number: u32| layout.iter().find(|(_, n)| *n == number).unwrap().0;
+        let length_of = |number: u32| {
+            let position = layout.iter().position(|(_, n)| *n == number).unwrap();
+            layout[position + 1].0 - layout[position].0
+        };
+
+        let hint = reloaded.objects.values()
+            .find_map(|object| match object {
+                Object::Stream(stream) if stream.dict.has(b"S") => Some(stream),
+                _ => None,
+            })
+            .expect("hint stream");
+        let data = &hint.content;
+
+        // Page offset hint table (anexo F.4.1): cabeçalho de 13 itens
+        let mut page_table = BitReader::new(data);
+        let header: Vec<u64> = [32, 32, 16, 32, 16, 32, 16, 32, 16, 16, 16, 16, 16]
+            .iter()
+            .map(|&width| page_table.read(width))
+            .collect();
+        let [least_objects, first_page_offset, object_bits, least_length, length_bits, _, _, _, _, count_bits, identifier_bits, _, _] =
+            header[..]
+        else {
+            unreachable!()
+        };
+        assert_eq!(first_page_offset, offset_of(pages[&1].0));
+
+        let counts: Vec<u64> = (0..3).map(|_| least_objects + page_table.read(object_bits)).collect();
+        page_table.align();
+        let lengths: Vec<u64> = (0..3).map(|_| least_length + page_table.read(length_bits)).collect();
+        page_table.align();
+        let shared_counts: Vec<u64> = (0..3).map(|_| page_table.read(count_bits)).collect();
+        page_table.align();
+        let identifiers: Vec<u64> = (0..shared_counts.iter().sum()).map(|_| page_table.read(identifier_bits)).collect();
+
+        // Primeira página: todos os objetos entre o objeto da página e /E
+        let first_page: Vec<u32> = layout.iter()
+            .filter(|(offset, _)| (first_page_offset..first_page_end).contains(offset))
+            .map(|(_, number)| *number)
+            .collect();
+        assert_eq!(counts[0], first_page.len() as u64);
+        assert_eq!(lengths[0], first_page_end - first_page_offset);
+
+        // Páginas seguintes: o dicionário e o conteúdo, gravados em sequência
+        for page_number in [2, 3] {
+            let page_id = pages[&page_number];
+            let content_id = reloaded.get_page_contents(page_id)[0];
+            let index = page_number as usize - 1;
+            assert_eq!(counts[index], 2);
+            assert_eq!(offset_of(content_id.0), offset_of(page_id.0) + length_of(page_id.0));
+            assert_eq!(lengths[index], length_of(page_id.0) + length_of(content_id.0));
+        }
+
+        // Páginas 2 e 3 usam os recursos e a fonte da primeira página
+        assert_eq!(shared_counts, vec![0, 2, 2]);
+        let page_two = reloaded.get_dictionary(pages[&2]).unwrap();
+        let resources = page_two.get(b"Resources").unwrap().as_reference().unwrap().0;
+        let font = reloaded.get_dictionary((resources, 0)).unwrap()
+            .get(b"Font").unwrap().as_dict().unwrap()
+            .get(b"F1").unwrap().as_reference().unwrap().0;
+        let referenced: Vec<u32> = identifiers.iter().map(|&id| first_page[id as usize]).collect();
+        for expected in [resources, font] {
+            assert_eq!(referenced.iter().filter(|&&number| number == expected).count(), 2);
+        }
+
+        // Shared object hint table (anexo F.4.2): um grupo por objeto da primeira página
+        let shared_offset = hint.dict.get(b"S").unwrap().as_i64().unwrap() as usize;
+        let mut shared_table = BitReader::new(&data[shared_offset..]);
+        let shared_header: Vec<u64> = [32, 32, 32, 32, 16, 32, 16].iter().map(|&width| shared_table.read(width)).collect();
+        assert_eq!(&shared_header[..5], &[0, 0, first_page.len() as u64, first_page.len() as u64, 0]);
+        let group_lengths: Vec<u64> = (0..first_page.len()).map(|_| shared_header[5] + shared_table.read(shared_header[6])).collect();
+        let expected: Vec<u64> = first_page.iter().map(|&number| length_of(number)).collect();
+        assert_eq!(group_lengths, expected);
+    }
+
+    #[test]
+    fn test_strings_with_carriage_return_bytes_survive() {
+        let mut doc = test_support::build_sample_pdf(2);
+        let info = doc.trailer.get(b"Info").unwrap().as_reference().unwrap();
+        // "Relatório č Ѝ" em UTF-16BE: "č" e "Ѝ" têm 0x0D no byte baixo
+        let title: Vec<u8> = std::iter::once(0xFEFF)
+            .chain("Relatório č Ѝ".encode_utf16())
+            .flat_map(u16::to_be_bytes)
+            .collect();
+        doc.get_dictionary_mut(info).unwrap().set("Title", Object::String(title.clone(), lopdf::StringFormat::Literal));
+        doc.get_dictionary_mut(info).unwrap().set("Subject", Object::string_literal("line 1\r\nline 2"));
+
+        let reloaded = Document::load_mem(&linearize(&doc).unwrap()).unwrap();
+        let info = reloaded.trailer.get(b"Info").and_then(Object::as_reference).unwrap();
+        let info = reloaded.get_dictionary(info).unwrap();
+        assert_eq!(info.get(b"Title").unwrap().as_str().unwrap(), &title[..]);
+        assert_eq!(info.get(b"Subject").unwrap().as_str().unwrap(), b"line 1\r\nline 2");
+    }
+
+    #[test]
+    fn test_rejects_encrypted_document() {
+        let mut doc = test_support::build_sample_pdf(1);
+        doc.trailer.set("Encrypt", lopdf::dictionary! { "Filter" => "Standard" });
+        assert!(linearize(&doc).is_err());
+    }
+
+    #[test]
+    fn test_bit_writer_packs_msb_first() {
+        let mut bits = BitWriter::default();
+        bits.write(0b101, 3);
+        bits.write(0b1, 1);
+        bits.align();
+        bits.write(0xABCD, 16);
+        assert_eq!(bits.into_bytes(), vec![0b1011_0000, 0xAB, 0xCD]);
+        assert_eq!(bit_width(0), 0);
+        assert_eq!(bit_width(255), 8);
+    }
+}
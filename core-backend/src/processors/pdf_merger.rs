@@ -21,9 +21,11 @@ use std::path::{Path, PathBuf};
 use std::time::Instant;
 use tracing::{info, warn, error, instrument};
 
-use crate::utils::error_handling::{Result, AppError, PdfError, ValidationError};
+use crate::utils::error_handling::{Result, AppError, IoError, PdfError, ValidationError};
 use crate::utils::error_handling::validate_not_empty;
 use crate::api::file_handlers::FileHandler;
+use crate::processors::pdf_linearize;
+use crate::processors::runtime::ProcessorRuntime;
 
 /// Configurações para o merge de PDFs
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +38,9 @@ pub struct MergeConfig {
     pub keep_bookmarks: bool,
     /// Nível de compressão (1-9, onde 9 é máxima)
     pub compression_level: u8,
+    /// Gravar linearizado (fast web view), para o navegador exibir a primeira página antes do download terminar
+    #[serde(default)]
+    pub linearize: bool,
 }
 
 impl Default for MergeConfig {
@@ -45,6 +50,7 @@ impl Default for MergeConfig {
             optimize_size: false,
             keep_bookmarks: true,
             compression_level: 6,
+            linearize: false,
         }
     }
 }
@@ -195,41 +201,37 @@ pub struct MergeResult {
 }
 
 /// Processador de merge de PDFs
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PdfMerger {
-    file_handler: FileHandler,
+    runtime: ProcessorRuntime,
 }
 
-impl PdfMerger {
-    /// Cria um novo PdfMerger
-    pub fn new() -> Self {
-        Self {
-            file_handler: FileHandler::new(),
-        }
-    }
-
-    /// Cria um PdfMerger com um FileHandler específico
-    pub fn with_file_handler(file_handler: FileHandler) -> Self {
-        Self { file_handler }
-    }
+processor_constructors!(PdfMerger);
 
+impl PdfMerger {
     /// Merge de múltiplos PDFs em um único documento
     #[instrument(name = "merge_pdfs", skip(self, request), fields(
         file_count = request.files.len(),
         output = %request.output_path.display()
     ))]
     pub async fn merge_pdfs(&self, request: MergeRequest) -> Result<MergeResult> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.merge_pdfs_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `merge_pdfs`, executado no pool de CPU
+    fn merge_pdfs_blocking(&self, request: MergeRequest) -> Result<MergeResult> {
         let start_time = Instant::now();
 
         info!("Starting PDF merge process");
 
         // 1. Validação da request
-        request.validate(&self.file_handler)?;
+        request.validate(self.runtime.file_handler())?;
 
         // 2. Cria diretório de saída se necessário
         if let Some(parent) = request.output_path.parent() {
             if !parent.exists() {
-                self.file_handler.create_dir(parent.to_str().unwrap_or(""))?;
+                self.runtime.file_handler().create_dir(parent.to_str().unwrap_or(""))?;
                 info!("Created output directory: {}", parent.display());
             }
         }
@@ -252,10 +254,10 @@ impl PdfMerger {
         }
 
         // 6. Salva o documento
-        self.save_document(&mut merged_doc, &request.output_path)?;
+        self.save_document(&mut merged_doc, &request.output_path, request.config.linearize)?;
 
         // 7. Valida o arquivo gerado
-        let output_metadata = self.file_handler.validate_file(
+        let output_metadata = self.runtime.file_handler().validate_file(
             request.output_path.to_str().unwrap_or("")
         )?;
 
@@ -435,17 +437,32 @@ impl PdfMerger {
 
     /// Salva o documento no caminho especificado
     #[instrument(name = "save_document", skip(self, doc, output_path))]
-    fn save_document(&self, doc: &mut Document, output_path: &Path) -> Result<()> {
-        info!(path = %output_path.display(), "Saving merged PDF");
-        
-        doc.save(output_path)
-            .map_err(|e| {
-                error!(path = %output_path.display(), error = %e, "Failed to save PDF");
-                AppError::Pdf(PdfError::ProcessingFailed {
-                    reason: format!("Failed to save PDF: {}", e),
-                })
-            })?;
-            
+    fn save_document(&self, doc: &mut Document, output_path: &Path, linearize: bool) -> Result<()> {
+        info!(path = %output_path.display(), linearize, "Saving merged PDF");
+
+        if linearize {
+            let bytes = pdf_linearize::linearize(doc)
+                .map_err(|reason| {
+                    error!(path = %output_path.display(), error = %reason, "Failed to linearize PDF");
+                    AppError::Pdf(PdfError::ProcessingFailed {
+                        reason: format!("Failed to linearize PDF: {}", reason),
+                    })
+                })?;
+            std::fs::write(output_path, bytes)
+                .map_err(|e| {
+                    error!(path = %output_path.display(), error = %e, "Failed to save PDF");
+                    AppError::Io(IoError::WriteFailed { path: output_path.to_path_buf(), source: e })
+                })?;
+        } else {
+            doc.save(output_path)
+                .map_err(|e| {
+                    error!(path = %output_path.display(), error = %e, "Failed to save PDF");
+                    AppError::Pdf(PdfError::ProcessingFailed {
+                        reason: format!("Failed to save PDF: {}", e),
+                    })
+                })?;
+        }
+
         info!(path = %output_path.display(), "PDF saved successfully");
         Ok(())
     }
@@ -470,10 +487,10 @@ impl PdfMerger {
 // ==================== FUNÇÃO DE CONVENIÊNCIA ====================
 
 /// Função de conveniência para merge de PDFs (mantém compatibilidade)
-#[instrument(name = "merge_pdfs", skip(data))]
-pub async fn merge_pdfs(data: Value) -> Result<Value> {
+#[instrument(name = "merge_pdfs", skip(data, runtime))]
+pub async fn merge_pdfs(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
     let request = MergeRequest::from_value(&data)?;
-    let merger = PdfMerger::new();
+    let merger = PdfMerger::with_runtime(runtime);
     let result = merger.merge_pdfs(request).await?;
     
     Ok(serde_json::to_value(result)
@@ -558,16 +575,33 @@ mod tests {
         assert!(!config.optimize_size);
         assert!(config.keep_bookmarks);
         assert_eq!(config.compression_level, 6);
+        assert!(!config.linearize);
+    }
+
+    #[test]
+    fn test_save_document_with_linearize_writes_fast_web_view() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let output = temp_dir.path().join("merged.pdf");
+        let mut doc = crate::processors::test_support::build_sample_pdf(5);
+
+        PdfMerger::new().save_document(&mut doc, &output, true)?;
+
+        let bytes = std::fs::read(&output)?;
+        let linearization = crate::processors::pdf_xref::linearization(&bytes).expect("linearized output");
+        assert!(linearization.matches_length(bytes.len() as u64));
+        assert_eq!(linearization.page_count, Some(5));
+        assert_eq!(Document::load_mem(&bytes).unwrap().get_pages().len(), 5);
+        Ok(())
     }
 
     #[tokio::test]
     async fn test_pdf_merger_creation() {
         let merger = PdfMerger::new();
-        assert!(merger.file_handler.config.max_file_size > 0);
+        assert!(merger.runtime.file_handler().config().max_file_size > 0);
         
         let file_handler = FileHandler::new();
         let merger = PdfMerger::with_file_handler(file_handler);
-        assert!(merger.file_handler.config.max_file_size > 0);
+        assert!(merger.runtime.file_handler().config().max_file_size > 0);
     }
 
     // Nota: Testes de merge real requerem PDFs de teste
@@ -0,0 +1,874 @@
+//! Edição de metadados de PDFs para o DocHub
+//!
+//! Altera os campos do dicionário Info (título, autor, assunto, palavras-chave
+//! e chaves personalizadas) e regrava o XMP junto (`pdf_xmp`), para que os dois
+//! continuem dizendo a mesma coisa. A gravação pode ser uma atualização
+//! incremental (`pdf_writer`), que mantém válidas as assinaturas existentes.
+//!
+//! ## Funcionalidades:
+//! - Patch parcial: campos ausentes ficam como estão, valores vazios removem
+//! - Datas aceitas no formato PDF ou ISO 8601, gravadas no formato PDF
+//! - `/ModDate` atualizado automaticamente (configurável)
+//! - Gravação completa ou incremental
+//! - Aplicação do mesmo patch a muitos arquivos, em paralelo no pool de CPU
+
+// O `AppError` das ações (e das falhas em lote) segue o desenho de `error_handling`
+#![allow(clippy::result_large_err)]
+
+use chrono::Local;
+use lopdf::{Document, Object, StringFormat};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tracing::{info, warn, error, instrument};
+
+use crate::utils::error_handling::{Result, AppError, PdfError};
+use crate::utils::error_handling::validate;
+use crate::api::file_handlers::FileHandler;
+use crate::processors::pdf_encoding;
+use crate::processors::pdf_signatures;
+use crate::processors::pdf_writer;
+use crate::processors::pdf_xmp::{self, DocumentInfo};
+use crate::processors::runtime::ProcessorRuntime;
+
+/// Chaves do Info editadas pelos campos próprios do patch, não por `custom`
+const STANDARD_KEYS: [&str; 9] = [
+    "Title", "Author", "Subject", "Keywords", "Creator", "Producer", "CreationDate", "ModDate", "Trapped",
+];
+
+/// Alterações nos metadados do documento
+///
+/// Campos ausentes (None) ficam como estão; string vazia (ou lista vazia de
+/// palavras-chave) remove o campo.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MetadataPatch {
+    /// Título (`/Title`)
+    pub title: Option<String>,
+    /// Autor (`/Author`)
+    pub author: Option<String>,
+    /// Assunto (`/Subject`)
+    pub subject: Option<String>,
+    /// Palavras-chave (`/Keywords`, gravadas separadas por vírgula)
+    pub keywords: Option<Vec<String>>,
+    /// Software de criação (`/Creator`)
+    pub creator: Option<String>,
+    /// Produtor do PDF (`/Producer`)
+    pub producer: Option<String>,
+    /// Data de criação (formato PDF ou ISO 8601)
+    pub creation_date: Option<String>,
+    /// Data de modificação (formato PDF ou ISO 8601)
+    pub modification_date: Option<String>,
+    /// Chaves personalizadas do Info (ex.: "Department")
+    pub custom: BTreeMap<String, String>,
+}
+
+impl MetadataPatch {
+    /// Valida o patch: precisa alterar algo, com datas e chaves válidas
+    pub fn validate(&self) -> Result<()> {
+        validate(
+            *self != Self::default(),
+            AppError::validation("Metadata patch is empty"),
+        )?;
+
+        for (field, value) in [("creation_date", &self.creation_date), ("modification_date", &self.modification_date)] {
+            if let Some(value) = value.as_deref().filter(|value| !value.trim().is_empty()) {
+                normalize_date(value)
+                    .ok_or_else(|| AppError::validation(format!("Invalid date for '{}': {}", field, value)))?;
+            }
+        }
+
+        for key in self.custom.keys() {
+            validate(
+                !key.trim().is_empty(),
+                AppError::validation("Custom metadata keys cannot be empty"),
+            )?;
+            validate(
+                !STANDARD_KEYS.contains(&key.as_str()),
+                AppError::validation(format!("'{}' is a standard key; use its own field instead of 'custom'", key)),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Aplica o patch aos campos padrão do Info
+    fn apply(&self, info: &mut DocumentInfo) {
+        let set = |target: &mut Option<String>, value: &Option<String>| {
+            if let Some(value) = value {
+                *target = Some(value.trim().to_string()).filter(|value| !value.is_empty());
+            }
+        };
+        set(&mut info.title, &self.title);
+        set(&mut info.author, &self.author);
+        set(&mut info.subject, &self.subject);
+        set(&mut info.creator, &self.creator);
+        set(&mut info.producer, &self.producer);
+
+        if let Some(keywords) = &self.keywords {
+            let keywords: Vec<_> = keywords
+                .iter()
+                .map(|keyword| keyword.trim())
+                .filter(|keyword| !keyword.is_empty())
+                .collect();
+            info.keywords = (!keywords.is_empty()).then(|| keywords.join(", "));
+        }
+
+        // Datas já validadas; gravadas sempre no formato PDF
+        let date = |target: &mut Option<String>, value: &Option<String>| {
+            if let Some(value) = value {
+                *target = normalize_date(value);
+            }
+        };
+        date(&mut info.creation_date, &self.creation_date);
+        date(&mut info.modification_date, &self.modification_date);
+    }
+}
+
+/// Configurações da gravação
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SetMetadataConfig {
+    /// Grava como atualização incremental, preservando as assinaturas existentes
+    pub incremental: bool,
+    /// Atualiza `/ModDate` para agora quando o patch não define a data
+    pub update_modification_date: bool,
+}
+
+impl Default for SetMetadataConfig {
+    fn default() -> Self {
+        Self {
+            incremental: false,
+            update_modification_date: true,
+        }
+    }
+}
+
+/// Request para edição de metadados
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetMetadataRequest {
+    /// Caminho do PDF de origem
+    pub file_path: PathBuf,
+    /// Caminho do PDF com os novos metadados
+    pub output_path: PathBuf,
+    /// Alterações a aplicar
+    pub patch: MetadataPatch,
+    /// Configurações da gravação
+    #[serde(default)]
+    pub config: SetMetadataConfig,
+}
+
+impl SetMetadataRequest {
+    /// Cria um SetMetadataRequest a partir de JSON
+    pub fn from_value(data: &Value) -> Result<Self> {
+        let file_path = data["file"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'file' field"))?;
+
+        let output_path = data["output"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'output' field"))?;
+
+        Ok(Self {
+            file_path,
+            output_path,
+            patch: parse_patch(data)?,
+            config: parse_config(data)?,
+        })
+    }
+
+    /// Valida a request
+    pub fn validate(&self, file_handler: &FileHandler) -> Result<()> {
+        let metadata = file_handler.validate_file(self.file_path.to_str().unwrap_or(""))?;
+        validate(
+            self.output_path != self.file_path,
+            AppError::validation("Output path must differ from the input file"),
+        )?;
+        self.patch.validate()?;
+
+        info!(
+            path = %self.file_path.display(),
+            size = metadata.len(),
+            "Input file validated for set_metadata"
+        );
+
+        Ok(())
+    }
+}
+
+/// Request para aplicar o mesmo patch a vários PDFs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkSetMetadataRequest {
+    /// PDFs de origem
+    pub files: Vec<PathBuf>,
+    /// Diretório dos PDFs gravados (mesmo nome de arquivo da origem)
+    pub output_dir: PathBuf,
+    /// Alterações a aplicar em todos os arquivos
+    pub patch: MetadataPatch,
+    /// Configurações da gravação
+    #[serde(default)]
+    pub config: SetMetadataConfig,
+}
+
+impl BulkSetMetadataRequest {
+    /// Cria um BulkSetMetadataRequest a partir de JSON
+    pub fn from_value(data: &Value) -> Result<Self> {
+        let files = data["files"]
+            .as_array()
+            .ok_or_else(|| AppError::validation("Missing or invalid 'files' field"))?
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map(PathBuf::from)
+                    .ok_or_else(|| AppError::validation("Invalid file path in 'files' array"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let output_dir = data["output_dir"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'output_dir' field"))?;
+
+        Ok(Self {
+            files,
+            output_dir,
+            patch: parse_patch(data)?,
+            config: parse_config(data)?,
+        })
+    }
+
+    /// Valida a request (os arquivos são validados um a um no processamento)
+    pub fn validate(&self) -> Result<()> {
+        validate(
+            !self.files.is_empty(),
+            AppError::validation("File list cannot be empty"),
+        )?;
+        self.patch.validate()
+    }
+}
+
+/// Resultado da edição de metadados de um PDF
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetMetadataResult {
+    /// Caminho do PDF de origem
+    pub file_path: PathBuf,
+    /// Caminho do PDF gravado
+    pub output_path: PathBuf,
+    /// Chaves do Info alteradas
+    pub changed: Vec<String>,
+    /// Campos padrão do Info após a edição
+    pub metadata: DocumentInfo,
+    /// Chaves personalizadas do Info após a edição
+    pub custom: BTreeMap<String, String>,
+    /// Gravado como atualização incremental?
+    pub incremental: bool,
+    /// Assinaturas invalidadas pela regravação completa do arquivo
+    pub invalidated_signatures: usize,
+    /// Tamanho do PDF gravado (em bytes)
+    pub file_size: u64,
+    /// Tempo total de processamento (em milissegundos)
+    pub processing_time_ms: u128,
+}
+
+/// Falha de um arquivo na edição em lote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkFailure {
+    /// Caminho do PDF de origem
+    pub file_path: PathBuf,
+    /// Motivo da falha
+    pub error: String,
+}
+
+/// Resultado da edição de metadados em lote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkSetMetadataResult {
+    /// Diretório dos PDFs gravados
+    pub output_dir: PathBuf,
+    /// Arquivos editados, na ordem da requisição
+    pub succeeded: Vec<SetMetadataResult>,
+    /// Arquivos que não puderam ser editados
+    pub failed: Vec<BulkFailure>,
+    /// Tempo total de processamento (em milissegundos)
+    pub processing_time_ms: u128,
+}
+
+/// Processador de edição de metadados
+#[derive(Debug, Clone)]
+pub struct PdfMetadataEditor {
+    runtime: ProcessorRuntime,
+}
+
+processor_constructors!(PdfMetadataEditor);
+
+impl PdfMetadataEditor {
+    /// Altera os metadados de um PDF
+    #[instrument(name = "set_metadata", skip(self, request), fields(
+        input_file = %request.file_path.display(),
+        output_file = %request.output_path.display()
+    ))]
+    pub async fn set_metadata(&self, request: SetMetadataRequest) -> Result<SetMetadataResult> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.set_metadata_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `set_metadata`, executado no pool de CPU
+    fn set_metadata_blocking(&self, request: SetMetadataRequest) -> Result<SetMetadataResult> {
+        request.validate(self.runtime.file_handler())?;
+        self.edit_file(&request.file_path, &request.output_path, &request.patch, &request.config)
+    }
+
+    /// Aplica o mesmo patch a vários PDFs
+    ///
+    /// Uma falha não interrompe o lote: o arquivo vai para `failed` e os
+    /// demais seguem.
+    #[instrument(name = "set_metadata_bulk", skip(self, request), fields(
+        file_count = request.files.len(),
+        output_dir = %request.output_dir.display()
+    ))]
+    pub async fn set_metadata_bulk(&self, request: BulkSetMetadataRequest) -> Result<BulkSetMetadataResult> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.set_metadata_bulk_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `set_metadata_bulk`, executado no pool de CPU
+    fn set_metadata_bulk_blocking(&self, request: BulkSetMetadataRequest) -> Result<BulkSetMetadataResult> {
+        let start_time = Instant::now();
+
+        request.validate()?;
+        self.runtime.file_handler().create_dir(request.output_dir.to_str().unwrap_or(""))?;
+
+        // Arquivos de mesmo nome vindos de pastas diferentes não se sobrescrevem
+        let mut used = HashSet::new();
+        let jobs: Vec<Result<PathBuf>> = request
+            .files
+            .iter()
+            .map(|file_path| {
+                let name = file_path
+                    .file_name()
+                    .ok_or_else(|| AppError::validation("Invalid file path"))?;
+                validate(
+                    used.insert(name.to_string_lossy().to_lowercase()),
+                    AppError::validation("Another file in the batch has the same name"),
+                )?;
+                Ok(request.output_dir.join(name))
+            })
+            .collect();
+
+        let pool = self.runtime.thread_pool()?;
+        info!(threads = pool.current_num_threads(), files = request.files.len(), "Editing metadata in parallel");
+
+        let outcomes: Vec<_> = pool.install(|| {
+            request
+                .files
+                .par_iter()
+                .zip(jobs)
+                .map(|(file_path, output_path)| {
+                    let output_path = output_path?;
+                    self.runtime.file_handler().validate_file(file_path.to_str().unwrap_or(""))?;
+                    validate(
+                        &output_path != file_path,
+                        AppError::validation("Output path must differ from the input file"),
+                    )?;
+                    self.edit_file(file_path, &output_path, &request.patch, &request.config)
+                })
+                .collect()
+        });
+
+        let mut succeeded = Vec::new();
+        let mut failed = Vec::new();
+        for (file_path, outcome) in request.files.iter().zip(outcomes) {
+            match outcome {
+                Ok(result) => succeeded.push(result),
+                Err(e) => {
+                    warn!(file = %file_path.display(), error = %e, "Metadata edit failed");
+                    failed.push(BulkFailure { file_path: file_path.clone(), error: e.to_string() });
+                }
+            }
+        }
+
+        info!(
+            succeeded = succeeded.len(),
+            failed = failed.len(),
+            processing_time_ms = start_time.elapsed().as_millis(),
+            "Bulk metadata edit completed"
+        );
+
+        Ok(BulkSetMetadataResult {
+            output_dir: request.output_dir,
+            succeeded,
+            failed,
+            processing_time_ms: start_time.elapsed().as_millis(),
+        })
+    }
+
+    // ==================== MÉTODOS PRIVADOS ====================
+
+    /// Carrega um documento PDF já lido em memória
+    fn load_document(&self, bytes: &[u8], path: &Path) -> Result<Document> {
+        info!(path = %path.display(), "Loading PDF document");
+
+        Document::load_mem(bytes)
+            .map_err(|e| {
+                error!(path = %path.display(), error = %e, "Failed to load PDF");
+                AppError::Pdf(PdfError::CorruptedPdf {
+                    path: path.to_path_buf(),
+                })
+            })
+    }
+
+    /// Aplica o patch a um arquivo e grava o resultado
+    fn edit_file(
+        &self,
+        file_path: &Path,
+        output_path: &Path,
+        patch: &MetadataPatch,
+        config: &SetMetadataConfig,
+    ) -> Result<SetMetadataResult> {
+        let start_time = Instant::now();
+
+        let source = std::fs::read(file_path)
+            .map_err(|e| AppError::from_io_error("reading PDF", file_path.to_path_buf(), e))?;
+        let mut doc = self.load_document(&source, file_path)?;
+        validate(
+            doc.trailer.get(b"Encrypt").is_err(),
+            AppError::validation("Encrypted documents cannot be edited"),
+        )?;
+
+        // 1. Campos padrão: Info e XMP juntos
+        let before = DocumentInfo::read(&doc);
+        let custom_before = custom_entries(&doc);
+        let mut info = before.clone();
+        patch.apply(&mut info);
+        if patch.modification_date.is_none() && config.update_modification_date {
+            info.modification_date = Some(pdf_encoding::format_pdf_date(&Local::now()));
+        }
+        let changed_objects = pdf_xmp::write(&mut doc, &info)
+            .map_err(|reason| AppError::Pdf(PdfError::ProcessingFailed { reason }))?;
+
+        // 2. Chaves personalizadas, só no Info
+        let info_id = doc
+            .trailer
+            .get(b"Info")
+            .and_then(Object::as_reference)
+            .map_err(|e| AppError::Pdf(PdfError::ProcessingFailed { reason: e.to_string() }))?;
+        let info_dict = doc
+            .get_dictionary_mut(info_id)
+            .map_err(|e| AppError::Pdf(PdfError::ProcessingFailed { reason: e.to_string() }))?;
+        for (key, value) in &patch.custom {
+            match value.trim() {
+                "" => {
+                    info_dict.remove(key.as_bytes());
+                }
+                value => info_dict.set(
+                    key.as_bytes().to_vec(),
+                    Object::String(pdf_encoding::encode_text_string(value), StringFormat::Literal),
+                ),
+            }
+        }
+
+        // 3. Grava: incremental preserva as assinaturas; a regravação completa as invalida
+        let signatures = pdf_signatures::verify(&doc, &source).len();
+        let bytes = if config.incremental {
+            pdf_writer::incremental_update(&source, &doc, &changed_objects)
+                .map_err(|reason| AppError::Pdf(PdfError::ProcessingFailed { reason }))?
+        } else {
+            if signatures > 0 {
+                warn!(file = %file_path.display(), signatures, "Rewriting a signed PDF invalidates its signatures");
+            }
+            let mut bytes = Vec::new();
+            doc.save_to(&mut bytes)
+                .map_err(|e| AppError::Pdf(PdfError::ProcessingFailed {
+                    reason: format!("Failed to save PDF: {}", e),
+                }))?;
+            bytes
+        };
+        self.runtime.file_handler().write_file(output_path.to_str().unwrap_or(""), &bytes)?;
+
+        let custom = custom_entries(&doc);
+        let result = SetMetadataResult {
+            file_path: file_path.to_path_buf(),
+            output_path: output_path.to_path_buf(),
+            changed: changed_keys(&before, &info, &custom_before, &custom),
+            metadata: info,
+            custom,
+            incremental: config.incremental,
+            invalidated_signatures: if config.incremental { 0 } else { signatures },
+            file_size: bytes.len() as u64,
+            processing_time_ms: start_time.elapsed().as_millis(),
+        };
+
+        info!(
+            output = %result.output_path.display(),
+            changed = result.changed.len(),
+            incremental = result.incremental,
+            processing_time_ms = result.processing_time_ms,
+            "PDF metadata updated"
+        );
+
+        Ok(result)
+    }
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+/// Converte uma data PDF ou ISO 8601 para o formato PDF (None = vazia ou inválida)
+fn normalize_date(value: &str) -> Option<String> {
+    let value = value.trim();
+    pdf_encoding::parse_pdf_date(value)
+        .or_else(|| pdf_xmp::parse_xmp_date(value))
+        .map(|date| pdf_encoding::format_pdf_date(&date))
+}
+
+/// Chaves do Info fora das padrão, com valor de texto
+fn custom_entries(doc: &Document) -> BTreeMap<String, String> {
+    let Some(info) = doc
+        .trailer
+        .get(b"Info")
+        .ok()
+        .and_then(|info| doc.dereference(info).ok())
+        .and_then(|(_, info)| info.as_dict().ok())
+    else {
+        return BTreeMap::new();
+    };
+
+    info.iter()
+        .filter_map(|(key, value)| {
+            let key = String::from_utf8_lossy(key).into_owned();
+            if STANDARD_KEYS.contains(&key.as_str()) {
+                return None;
+            }
+            let text = value.as_str().ok().map(pdf_encoding::decode_text_string)?;
+            Some((key, text))
+        })
+        .collect()
+}
+
+/// Chaves do Info cujo valor mudou
+fn changed_keys(
+    before: &DocumentInfo,
+    after: &DocumentInfo,
+    custom_before: &BTreeMap<String, String>,
+    custom_after: &BTreeMap<String, String>,
+) -> Vec<String> {
+    let fields = [
+        ("Title", &before.title, &after.title),
+        ("Author", &before.author, &after.author),
+        ("Subject", &before.subject, &after.subject),
+        ("Keywords", &before.keywords, &after.keywords),
+        ("Creator", &before.creator, &after.creator),
+        ("Producer", &before.producer, &after.producer),
+        ("CreationDate", &before.creation_date, &after.creation_date),
+        ("ModDate", &before.modification_date, &after.modification_date),
+    ];
+
+    let mut changed: Vec<String> = fields
+        .into_iter()
+        .filter(|(_, before, after)| before != after)
+        .map(|(key, _, _)| key.to_string())
+        .collect();
+
+    let keys: HashSet<&String> = custom_before.keys().chain(custom_after.keys()).collect();
+    let mut custom: Vec<String> = keys
+        .into_iter()
+        .filter(|key| custom_before.get(*key) != custom_after.get(*key))
+        .cloned()
+        .collect();
+    custom.sort();
+    changed.extend(custom);
+    changed
+}
+
+/// Lê o patch do campo `metadata`
+fn parse_patch(data: &Value) -> Result<MetadataPatch> {
+    let patch = data
+        .get("metadata")
+        .ok_or_else(|| AppError::validation("Missing 'metadata' field"))?;
+    serde_json::from_value(patch.clone())
+        .map_err(|e| AppError::validation(format!("Invalid metadata: {}", e)))
+}
+
+/// Lê as configurações opcionais do campo `config`
+fn parse_config(data: &Value) -> Result<SetMetadataConfig> {
+    if let Some(config_val) = data.get("config") {
+        serde_json::from_value(config_val.clone())
+            .map_err(|e| AppError::validation(format!("Invalid config: {}", e)))
+    } else {
+        Ok(SetMetadataConfig::default())
+    }
+}
+
+// ==================== FUNÇÕES DE CONVENIÊNCIA ====================
+
+/// Função de conveniência para edição de metadados
+#[instrument(name = "set_metadata", skip(data, runtime))]
+pub async fn set_metadata(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
+    let request = SetMetadataRequest::from_value(&data)?;
+    let editor = PdfMetadataEditor::with_runtime(runtime);
+    let result = editor.set_metadata(request).await?;
+
+    serde_json::to_value(result)
+        .map_err(|e| AppError::serialization(format!("Failed to serialize result: {}", e)))
+}
+
+/// Função de conveniência para edição de metadados em lote
+#[instrument(name = "set_metadata_bulk", skip(data, runtime))]
+pub async fn set_metadata_bulk(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
+    let request = BulkSetMetadataRequest::from_value(&data)?;
+    let editor = PdfMetadataEditor::with_runtime(runtime);
+    let result = editor.set_metadata_bulk(request).await?;
+
+    serde_json::to_value(result)
+        .map_err(|e| AppError::serialization(format!("Failed to serialize result: {}", e)))
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::pdf_revisions;
+    use crate::processors::test_support;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_patch_keeps_absent_fields_and_normalizes_dates() {
+        let mut info = DocumentInfo {
+            title: Some("Sample".to_string()),
+            producer: Some("DocHub tests".to_string()),
+            ..Default::default()
+        };
+        let patch = MetadataPatch {
+            author: Some("  Setor de Arquivo ".to_string()),
+            producer: Some(String::new()),
+            keywords: Some(vec!["contrato".to_string(), " ".to_string(), "2024".to_string()]),
+            creation_date: Some("2024-03-01T10:30:00-03:00".to_string()),
+            ..Default::default()
+        };
+        patch.validate().unwrap();
+        patch.apply(&mut info);
+
+        assert_eq!(info.title.as_deref(), Some("Sample"));
+        assert_eq!(info.author.as_deref(), Some("Setor de Arquivo"));
+        assert_eq!(info.producer, None);
+        assert_eq!(info.keywords.as_deref(), Some("contrato, 2024"));
+        assert_eq!(info.creation_date.as_deref(), Some("D:20240301103000-03'00'"));
+    }
+
+    #[test]
+    fn test_patch_validation() {
+        assert!(MetadataPatch::default().validate().is_err());
+
+        let bad_date = MetadataPatch { creation_date: Some("yesterday".to_string()), ..Default::default() };
+        assert!(bad_date.validate().is_err());
+
+        let mut custom = MetadataPatch::default();
+        custom.custom.insert("Title".to_string(), "x".to_string());
+        assert!(custom.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_set_metadata_updates_info_xmp_and_custom_keys() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let input = test_support::write_sample_pdf(temp_dir.path(), "input.pdf", 1);
+        let output = temp_dir.path().join("output.pdf");
+
+        let result = PdfMetadataEditor::new()
+            .set_metadata(SetMetadataRequest::from_value(&json!({
+                "file": input,
+                "output": output,
+                "metadata": {
+                    "author": "Records",
+                    "keywords": ["archive", "2024"],
+                    "custom": { "Department": "Legal" },
+                },
+            }))?)
+            .await?;
+        assert_eq!(result.changed, vec!["Author", "Keywords", "ModDate", "Department"]);
+        assert!(!result.incremental);
+
+        let doc = Document::load(&output).unwrap();
+        let info = DocumentInfo::read(&doc);
+        let modification_date = info.modification_date.clone().unwrap();
+        assert_eq!(info, DocumentInfo {
+            title: Some("Sample".to_string()),
+            author: Some("Records".to_string()),
+            keywords: Some("archive, 2024".to_string()),
+            producer: Some("DocHub tests".to_string()),
+            modification_date: Some(modification_date.clone()),
+            ..Default::default()
+        });
+        assert_eq!(result.metadata, info);
+
+        // Campos ASCII continuam em PDFDocEncoding; a chave própria fica no Info
+        let info_dict = doc.get_dictionary(doc.trailer.get(b"Info").unwrap().as_reference().unwrap()).unwrap();
+        let raw: Vec<(&[u8], &[u8])> = info_dict.iter().map(|(key, value)| (key.as_slice(), value.as_str().unwrap())).collect();
+        assert_eq!(raw, vec![
+            (&b"Title"[..], &b"Sample"[..]),
+            (b"Author", b"Records"),
+            (b"Keywords", b"archive, 2024"),
+            (b"Producer", b"DocHub tests"),
+            (b"ModDate", modification_date.as_bytes()),
+            (b"Department", b"Legal"),
+        ]);
+        assert_eq!(custom_entries(&doc), BTreeMap::from([("Department".to_string(), "Legal".to_string())]));
+        assert_eq!(result.custom, custom_entries(&doc));
+
+        let xmp = pdf_xmp::read(&doc).unwrap();
+        let modified = pdf_encoding::parse_pdf_date(&modification_date).unwrap();
+        assert_eq!(xmp, pdf_xmp::XmpMetadata {
+            title: Some("Sample".to_string()),
+            creators: vec!["Records".to_string()],
+            subjects: vec!["archive".to_string(), "2024".to_string()],
+            modify_date: Some(modified.to_rfc3339()),
+            metadata_date: Some(modified.to_rfc3339()),
+            producer: Some("DocHub tests".to_string()),
+            keywords: Some("archive, 2024".to_string()),
+            ..Default::default()
+        });
+        assert!(pdf_xmp::compare(&info, &xmp).is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_non_latin_metadata_survives_reload_in_both_modes() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let input = test_support::write_sample_pdf(temp_dir.path(), "input.pdf", 1);
+        let editor = PdfMetadataEditor::new();
+
+        // "č", "Ѝ" e "ō" têm 0x0D no byte baixo em UTF-16BE
+        let title = "Relatório č Ѝ";
+        let author = "Ѝван Kōno";
+        for incremental in [false, true] {
+            let output = temp_dir.path().join(format!("output-{}.pdf", incremental));
+            editor
+                .set_metadata(SetMetadataRequest::from_value(&json!({
+                    "file": input,
+                    "output": output,
+                    "metadata": { "title": title, "author": author, "subject": "Řešení" },
+                    "config": { "incremental": incremental },
+                }))?)
+                .await?;
+
+            let doc = Document::load(&output).unwrap();
+            let info = DocumentInfo::read(&doc);
+            assert_eq!(info.title.as_deref(), Some(title));
+            assert_eq!(info.author.as_deref(), Some(author));
+            assert_eq!(info.subject.as_deref(), Some("Řešení"));
+
+            // Fora do PDFDocEncoding: UTF-16BE com BOM no dicionário Info
+            let info_dict = doc.get_dictionary(doc.trailer.get(b"Info").unwrap().as_reference().unwrap()).unwrap();
+            let raw_title = info_dict.get(b"Title").unwrap().as_str().unwrap();
+            let utf16: Vec<u8> = title.encode_utf16().flat_map(u16::to_be_bytes).collect();
+            assert_eq!(raw_title, [&b"\xFE\xFF"[..], &utf16].concat());
+
+            let xmp = pdf_xmp::read(&doc).unwrap();
+            assert_eq!(xmp.title.as_deref(), Some(title));
+            assert_eq!(xmp.creators, vec![author]);
+            assert_eq!(xmp.description.as_deref(), Some("Řešení"));
+            assert_eq!(xmp.producer.as_deref(), Some("DocHub tests"));
+            assert!(pdf_xmp::compare(&info, &xmp).is_empty(), "{:?}", pdf_xmp::compare(&info, &xmp));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_incremental_edit_preserves_signature() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let input = temp_dir.path().join("signed.pdf");
+        std::fs::write(&input, test_support::sign_sample_pdf(&test_support::build_sample_pdf(1)))?;
+        let editor = PdfMetadataEditor::new();
+
+        let incremental = temp_dir.path().join("incremental.pdf");
+        let result = editor
+            .set_metadata(SetMetadataRequest::from_value(&json!({
+                "file": input,
+                "output": incremental,
+                "metadata": { "title": "Assinado" },
+                "config": { "incremental": true },
+            }))?)
+            .await?;
+        assert_eq!(result.invalidated_signatures, 0);
+
+        let source = std::fs::read(&input)?;
+        let bytes = std::fs::read(&incremental)?;
+        assert!(bytes.starts_with(&source));
+        let doc = Document::load_mem(&bytes).unwrap();
+        assert_eq!(DocumentInfo::read(&doc).title.as_deref(), Some("Assinado"));
+        assert_eq!(pdf_xmp::read(&doc).unwrap().title.as_deref(), Some("Assinado"));
+        let signatures = pdf_signatures::verify(&doc, &bytes);
+        assert_eq!(signatures.len(), 1);
+        assert!(signatures[0].is_intact());
+        assert_eq!(signatures[0].bytes_after_signature, (bytes.len() - source.len()) as u64);
+
+        // A atualização só regrava o Info e o catálogo e acrescenta o XMP
+        let info_id = doc.trailer.get(b"Info").unwrap().as_reference().unwrap().0;
+        let root_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap().0;
+        let metadata_id = doc.catalog().unwrap().get(b"Metadata").unwrap().as_reference().unwrap().0;
+        let history = pdf_revisions::history(&bytes);
+        assert_eq!(history.revisions.len(), 2);
+        // O original termina em `%%EOF` sem fim de linha; o da atualização conta para ele
+        assert!(source.ends_with(b"%%EOF"));
+        assert_eq!(bytes[source.len()], b'\n');
+        assert_eq!(history.revisions[0].end_offset, source.len() as u64 + 1);
+        let update = &history.revisions[1];
+        assert_eq!(update.added_objects, vec![metadata_id]);
+        let mut modified = vec![info_id, root_id];
+        modified.sort();
+        assert_eq!(update.modified_objects, modified);
+        assert!(update.deleted_objects.is_empty());
+
+        let rewritten = editor
+            .set_metadata(SetMetadataRequest::from_value(&json!({
+                "file": input,
+                "output": temp_dir.path().join("rewritten.pdf"),
+                "metadata": { "title": "Assinado" },
+            }))?)
+            .await?;
+        assert_eq!(rewritten.invalidated_signatures, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bulk_applies_patch_and_reports_failures() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let first = test_support::write_sample_pdf(temp_dir.path(), "a.pdf", 1);
+        let second = test_support::write_sample_pdf(temp_dir.path(), "b.pdf", 2);
+        let broken = temp_dir.path().join("broken.pdf");
+        std::fs::write(&broken, b"%PDF-1.4 not really")?;
+        let output_dir = temp_dir.path().join("out");
+
+        let result = PdfMetadataEditor::new()
+            .set_metadata_bulk(BulkSetMetadataRequest::from_value(&json!({
+                "files": [first, broken, second],
+                "output_dir": output_dir,
+                "metadata": { "author": "Records", "keywords": ["archive"] },
+            }))?)
+            .await?;
+
+        let outputs: Vec<_> = result.succeeded.iter().map(|done| (done.file_path.clone(), done.output_path.clone())).collect();
+        assert_eq!(outputs, vec![
+            (first, output_dir.join("a.pdf")),
+            (second, output_dir.join("b.pdf")),
+        ]);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].file_path, broken);
+        for (name, pages) in [("a.pdf", 1), ("b.pdf", 2)] {
+            let doc = Document::load(output_dir.join(name)).unwrap();
+            assert_eq!(doc.get_pages().len(), pages);
+            let info = DocumentInfo::read(&doc);
+            assert_eq!(
+                (info.title.as_deref(), info.author.as_deref(), info.keywords.as_deref()),
+                (Some("Sample"), Some("Records"), Some("archive"))
+            );
+            let xmp = pdf_xmp::read(&doc).unwrap();
+            assert_eq!((xmp.creators, xmp.subjects), (vec!["Records".to_string()], vec!["archive".to_string()]));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,604 @@
+//! Verificação de conformidade PDF/A (ISO 19005)
+//!
+//! Confere os requisitos de PDF/A-1b, PDF/A-2b e PDF/A-3b que mais reprovam
+//! arquivos na prática (por exemplo, no peticionamento eletrônico do PJe),
+//! indicando a cláusula da norma violada em cada caso.
+//!
+//! ## Verificações:
+//! - Todas as fontes incorporadas
+//! - Ausência de criptografia
+//! - Ausência de ações JavaScript e Launch
+//! - Pacote XMP com o esquema de identificação `pdfaid`
+//! - Dicionário Info consistente com o XMP (`pdf_xmp`)
+//! - OutputIntent `GTS_PDFA1` com perfil ICC
+//! - Ausência de transparência (apenas PDF/A-1)
+//! - Ausência de compressão LZW
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::processors::pdf_filters;
+use crate::processors::pdf_xmp::{self, DocumentInfo};
+
+/// Namespace do esquema de identificação PDF/A no XMP
+const PDFAID_NAMESPACE: &str = "http://www.aiim.org/pdfa/ns/id/";
+
+/// Níveis de conformidade PDF/A suportados
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PdfaLevel {
+    /// ISO 19005-1, nível B
+    Pdfa1b,
+    /// ISO 19005-2, nível B (padrão quando o documento não declara nível)
+    #[default]
+    Pdfa2b,
+    /// ISO 19005-3, nível B
+    Pdfa3b,
+}
+
+impl PdfaLevel {
+    /// Parte da norma ISO 19005 correspondente
+    pub fn part(&self) -> u8 {
+        match self {
+            Self::Pdfa1b => 1,
+            Self::Pdfa2b => 2,
+            Self::Pdfa3b => 3,
+        }
+    }
+
+    /// Nível correspondente a uma parte declarada no XMP
+    pub fn from_part(part: u8) -> Option<Self> {
+        match part {
+            1 => Some(Self::Pdfa1b),
+            2 => Some(Self::Pdfa2b),
+            3 => Some(Self::Pdfa3b),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PdfaLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PDF/A-{}b", self.part())
+    }
+}
+
+/// Requisitos verificados
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PdfaRule {
+    /// O trailer não pode conter `/Encrypt`
+    NoEncryption,
+    /// Filtro LZWDecode não é permitido
+    NoLzw,
+    /// Deve existir um OutputIntent PDF/A com perfil ICC
+    OutputIntent,
+    /// Todas as fontes devem estar incorporadas
+    EmbeddedFonts,
+    /// Transparência não é permitida (PDF/A-1)
+    NoTransparency,
+    /// Ações JavaScript e Launch não são permitidas
+    NoForbiddenActions,
+    /// Metadados XMP devem identificar o nível PDF/A
+    XmpIdentification,
+    /// O dicionário Info deve ter equivalente idêntico no XMP
+    MetadataConsistency,
+}
+
+impl PdfaRule {
+    /// Cláusula da norma para o nível informado
+    pub fn clause(&self, level: PdfaLevel) -> &'static str {
+        let part_one = level == PdfaLevel::Pdfa1b;
+        match self {
+            Self::NoEncryption => "6.1.3",
+            Self::NoLzw => if part_one { "6.1.10" } else { "6.1.7.2" },
+            Self::OutputIntent => if part_one { "6.2.2" } else { "6.2.3" },
+            Self::EmbeddedFonts => if part_one { "6.3.4" } else { "6.2.11.4.1" },
+            Self::NoTransparency => "6.4",
+            Self::NoForbiddenActions => "6.6.1",
+            Self::XmpIdentification => if part_one { "6.7.11" } else { "6.6.4" },
+            Self::MetadataConsistency => if part_one { "6.7.3" } else { "6.6.3" },
+        }
+    }
+}
+
+/// Uma violação de requisito PDF/A
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfaViolation {
+    /// Requisito violado
+    pub rule: PdfaRule,
+    /// Cláusula da norma (ex.: "ISO 19005-2 6.2.11.4.1")
+    pub clause: String,
+    /// Descrição do problema
+    pub description: String,
+    /// Objeto onde o problema foi encontrado
+    pub object_id: Option<ObjectId>,
+}
+
+/// Resultado da verificação PDF/A
+#[derive(Debug, Clone)]
+pub struct PdfaReport {
+    /// Nível usado na verificação
+    pub level: PdfaLevel,
+    /// Identificação declarada no XMP (ex.: "2B")
+    pub declared: Option<String>,
+    /// Violações encontradas
+    pub violations: Vec<PdfaViolation>,
+}
+
+impl PdfaReport {
+    /// O documento atende a todos os requisitos verificados?
+    pub fn is_compliant(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Verifica o documento contra um nível PDF/A
+///
+/// Sem `target`, usa o nível declarado no XMP do próprio documento e, na
+/// falta dele, PDF/A-2b.
+pub fn check(doc: &Document, target: Option<PdfaLevel>) -> PdfaReport {
+    let catalog = doc.catalog().ok();
+    let identification = catalog.and_then(|catalog| read_identification(doc, catalog));
+    let declared_level = identification
+        .as_ref()
+        .and_then(|(part, _)| part.parse().ok())
+        .and_then(PdfaLevel::from_part);
+    let level = target.or(declared_level).unwrap_or_default();
+
+    let mut checker = Checker {
+        doc,
+        level,
+        violations: Vec::new(),
+    };
+
+    checker.check_encryption();
+    checker.check_identification(identification.as_ref());
+    checker.check_metadata_consistency();
+    if let Some(catalog) = catalog {
+        checker.check_output_intent(catalog);
+        checker.check_javascript_names(catalog);
+    }
+    checker.check_objects();
+
+    PdfaReport {
+        level,
+        declared: identification.map(|(part, conformance)| format!("{}{}", part, conformance)),
+        violations: checker.violations,
+    }
+}
+
+// ==================== MÉTODOS PRIVADOS ====================
+
+/// Acumula as violações encontradas durante a verificação
+struct Checker<'a> {
+    doc: &'a Document,
+    level: PdfaLevel,
+    violations: Vec<PdfaViolation>,
+}
+
+impl Checker<'_> {
+    fn report(&mut self, rule: PdfaRule, description: String, object_id: Option<ObjectId>) {
+        self.violations.push(PdfaViolation {
+            rule,
+            clause: format!("ISO 19005-{} {}", self.level.part(), rule.clause(self.level)),
+            description,
+            object_id,
+        });
+    }
+
+    fn check_encryption(&mut self) {
+        if self.doc.trailer.get(b"Encrypt").is_ok() {
+            self.report(PdfaRule::NoEncryption, "Document is encrypted".to_string(), None);
+        }
+    }
+
+    fn check_identification(&mut self, identification: Option<&(String, String)>) {
+        match identification {
+            None => self.report(
+                PdfaRule::XmpIdentification,
+                "XMP metadata has no pdfaid:part and pdfaid:conformance identification".to_string(),
+                None,
+            ),
+            Some((part, conformance)) if part != &self.level.part().to_string() => self.report(
+                PdfaRule::XmpIdentification,
+                format!("Document identifies itself as PDF/A-{}{}, not {}", part, conformance, self.level),
+                None,
+            ),
+            Some(_) => {}
+        }
+    }
+
+    fn check_metadata_consistency(&mut self) {
+        // Sem XMP, a falta de identificação já é reportada
+        let Some(xmp) = pdf_xmp::read(self.doc) else {
+            return;
+        };
+
+        for mismatch in pdf_xmp::compare(&DocumentInfo::read(self.doc), &xmp) {
+            let description = match mismatch.xmp_value {
+                Some(xmp_value) => format!(
+                    "Info /{} \"{}\" differs from XMP {} \"{}\"",
+                    mismatch.info_key, mismatch.info_value, mismatch.xmp_property, xmp_value
+                ),
+                None => format!(
+                    "Info /{} has no {} equivalent in the XMP metadata",
+                    mismatch.info_key, mismatch.xmp_property
+                ),
+            };
+            self.report(PdfaRule::MetadataConsistency, description, None);
+        }
+    }
+
+    fn check_output_intent(&mut self, catalog: &Dictionary) {
+        let intents = match catalog.get_deref(b"OutputIntents", self.doc).and_then(Object::as_array) {
+            Ok(intents) => intents,
+            Err(_) => {
+                self.report(PdfaRule::OutputIntent, "Document has no OutputIntents".to_string(), None);
+                return;
+            }
+        };
+
+        let pdfa_intent = intents
+            .iter()
+            .filter_map(|intent| self.doc.dereference(intent).ok())
+            .filter_map(|(id, intent)| intent.as_dict().ok().map(|dict| (id, dict)))
+            .find(|(_, intent)| intent.get(b"S").and_then(Object::as_name).ok() == Some(b"GTS_PDFA1".as_slice()));
+
+        match pdfa_intent {
+            None => self.report(
+                PdfaRule::OutputIntent,
+                "Document has no GTS_PDFA1 OutputIntent".to_string(),
+                None,
+            ),
+            Some((id, intent)) => {
+                let has_profile = intent
+                    .get_deref(b"DestOutputProfile", self.doc)
+                    .and_then(Object::as_stream)
+                    .is_ok();
+                if !has_profile {
+                    self.report(
+                        PdfaRule::OutputIntent,
+                        "OutputIntent has no embedded ICC profile (DestOutputProfile)".to_string(),
+                        id,
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_javascript_names(&mut self, catalog: &Dictionary) {
+        let has_javascript = catalog
+            .get_deref(b"Names", self.doc)
+            .and_then(Object::as_dict)
+            .map(|names| names.has(b"JavaScript"))
+            .unwrap_or(false);
+        if has_javascript {
+            self.report(
+                PdfaRule::NoForbiddenActions,
+                "Document name dictionary contains JavaScript".to_string(),
+                None,
+            );
+        }
+    }
+
+    /// Percorre todos os dicionários do documento, inclusive os aninhados
+    fn check_objects(&mut self) {
+        for (&object_id, object) in &self.doc.objects {
+            let mut pending = vec![object];
+
+            while let Some(current) = pending.pop() {
+                let dict = match current {
+                    Object::Array(items) => {
+                        pending.extend(items.iter().rev());
+                        continue;
+                    }
+                    Object::Dictionary(dict) => dict,
+                    Object::Stream(stream) => {
+                        self.check_stream_filters(object_id, &stream.dict);
+                        &stream.dict
+                    }
+                    _ => continue,
+                };
+
+                self.check_dictionary(object_id, dict);
+                pending.extend(dict.iter().map(|(_, value)| value).rev());
+            }
+        }
+    }
+
+    fn check_dictionary(&mut self, object_id: ObjectId, dict: &Dictionary) {
+        if dict.type_is(b"Font") {
+            self.check_font(object_id, dict);
+        }
+
+        if let Ok(action) = dict.get(b"S").and_then(Object::as_name) {
+            if action == b"JavaScript" || action == b"Launch" {
+                self.report(
+                    PdfaRule::NoForbiddenActions,
+                    format!("{} action is not allowed", String::from_utf8_lossy(action)),
+                    Some(object_id),
+                );
+            }
+        }
+
+        if self.level == PdfaLevel::Pdfa1b {
+            self.check_transparency(object_id, dict);
+        }
+    }
+
+    fn check_font(&mut self, object_id: ObjectId, font: &Dictionary) {
+        let subtype = font.get(b"Subtype").and_then(Object::as_name).unwrap_or_default();
+
+        // Type3 desenha os glifos no conteúdo; Type0 é verificada pelas descendentes
+        if subtype == b"Type3" || subtype == b"Type0" {
+            return;
+        }
+
+        let embedded = font
+            .get_deref(b"FontDescriptor", self.doc)
+            .and_then(Object::as_dict)
+            .map(|descriptor| {
+                [b"FontFile".as_slice(), b"FontFile2", b"FontFile3"]
+                    .iter()
+                    .any(|key| descriptor.has(key))
+            })
+            .unwrap_or(false);
+
+        if !embedded {
+            let name = font
+                .get(b"BaseFont")
+                .and_then(Object::as_name)
+                .map(|name| String::from_utf8_lossy(name).into_owned())
+                .unwrap_or_else(|_| "unnamed".to_string());
+            self.report(
+                PdfaRule::EmbeddedFonts,
+                format!("Font {} is not embedded", name),
+                Some(object_id),
+            );
+        }
+    }
+
+    fn check_transparency(&mut self, object_id: ObjectId, dict: &Dictionary) {
+        if let Ok(mask) = dict.get(b"SMask") {
+            if mask.as_name().ok() != Some(b"None".as_slice()) {
+                self.report(
+                    PdfaRule::NoTransparency,
+                    "Soft mask (SMask) is not allowed".to_string(),
+                    Some(object_id),
+                );
+            }
+        }
+
+        for key in [b"CA".as_slice(), b"ca"] {
+            if let Ok(alpha) = dict.get(key).and_then(Object::as_float) {
+                if alpha != 1.0 {
+                    self.report(
+                        PdfaRule::NoTransparency,
+                        format!("Constant alpha /{} {} is not allowed", String::from_utf8_lossy(key), alpha),
+                        Some(object_id),
+                    );
+                }
+            }
+        }
+
+        if let Ok(blend) = dict.get(b"BM").and_then(Object::as_name) {
+            if blend != b"Normal" && blend != b"Compatible" {
+                self.report(
+                    PdfaRule::NoTransparency,
+                    format!("Blend mode /{} is not allowed", String::from_utf8_lossy(blend)),
+                    Some(object_id),
+                );
+            }
+        }
+
+        if dict.type_is(b"Group") && dict.get(b"S").and_then(Object::as_name).ok() == Some(b"Transparency".as_slice()) {
+            self.report(
+                PdfaRule::NoTransparency,
+                "Transparency group is not allowed".to_string(),
+                Some(object_id),
+            );
+        }
+    }
+
+    fn check_stream_filters(&mut self, object_id: ObjectId, dict: &Dictionary) {
+        let uses_lzw = match dict.get(b"Filter") {
+            Ok(Object::Name(name)) => is_lzw(name),
+            Ok(Object::Array(filters)) => filters
+                .iter()
+                .any(|filter| filter.as_name().map(is_lzw).unwrap_or(false)),
+            _ => false,
+        };
+
+        if uses_lzw {
+            self.report(
+                PdfaRule::NoLzw,
+                "Stream uses LZWDecode compression".to_string(),
+                Some(object_id),
+            );
+        }
+    }
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+fn is_lzw(filter: &[u8]) -> bool {
+    filter == b"LZWDecode" || filter == b"LZW"
+}
+
+/// Lê `pdfaid:part` e `pdfaid:conformance` do XMP do catálogo
+fn read_identification(doc: &Document, catalog: &Dictionary) -> Option<(String, String)> {
+    let stream = catalog.get_deref(b"Metadata", doc).and_then(Object::as_stream).ok()?;
+    let decoded = pdf_filters::decode_stream(doc, stream).ok()?;
+    let xmp = String::from_utf8_lossy(&decoded.data);
+
+    if !xmp.contains(PDFAID_NAMESPACE) {
+        return None;
+    }
+
+    let part = xmp_property(&xmp, "pdfaid:part")?;
+    let conformance = xmp_property(&xmp, "pdfaid:conformance").unwrap_or_default();
+    Some((part, conformance.to_uppercase()))
+}
+
+/// Valor de uma propriedade XMP simples, na forma de atributo ou de elemento
+fn xmp_property(xmp: &str, name: &str) -> Option<String> {
+    let mut rest = xmp;
+
+    while let Some(position) = rest.find(name) {
+        let after = &rest[position + name.len()..];
+
+        if let Some(value) = after.trim_start().strip_prefix('=') {
+            let value = value.trim_start();
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let end = value[1..].find(quote)?;
+            return Some(value[1..1 + end].trim().to_string());
+        }
+
+        if let Some(value) = after.strip_prefix('>') {
+            let end = value.find('<')?;
+            return Some(value[..end].trim().to_string());
+        }
+
+        rest = after;
+    }
+
+    None
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::test_support;
+    use lopdf::{dictionary, Stream};
+
+    fn xmp_packet(part: u8) -> Vec<u8> {
+        format!(
+            "<?xpacket begin=\"\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\
+             <x:xmpmeta xmlns:x=\"adobe:ns:meta/\"><rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\
+             <rdf:Description rdf:about=\"\" xmlns:pdfaid=\"{}\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+             xmlns:pdf=\"http://ns.adobe.com/pdf/1.3/\" pdf:Producer=\"DocHub tests\">\
+             <pdfaid:part>{}</pdfaid:part><pdfaid:conformance>B</pdfaid:conformance>\
+             <dc:title><rdf:Alt><rdf:li xml:lang=\"x-default\">Sample</rdf:li></rdf:Alt></dc:title>\
+             </rdf:Description></rdf:RDF></x:xmpmeta><?xpacket end=\"w\"?>",
+            PDFAID_NAMESPACE, part
+        )
+        .into_bytes()
+    }
+
+    /// Documento de exemplo com XMP, OutputIntent e fonte incorporada
+    fn compliant_document(part: u8) -> Document {
+        let mut doc = test_support::build_sample_pdf(1);
+
+        let font_file = doc.add_object(Stream::new(dictionary! {}, b"font program".to_vec()));
+        let descriptor = doc.add_object(dictionary! {
+            "Type" => "FontDescriptor",
+            "FontName" => "Helvetica",
+            "FontFile2" => font_file,
+        });
+        doc.get_dictionary_mut((2, 0)).unwrap().set("FontDescriptor", descriptor);
+
+        let metadata = doc.add_object(Stream::new(
+            dictionary! { "Type" => "Metadata", "Subtype" => "XML" },
+            xmp_packet(part),
+        ));
+        let profile = doc.add_object(Stream::new(dictionary! { "N" => 3 }, b"icc profile".to_vec()));
+        let intent = dictionary! {
+            "Type" => "OutputIntent",
+            "S" => "GTS_PDFA1",
+            "OutputConditionIdentifier" => Object::string_literal("sRGB"),
+            "DestOutputProfile" => profile,
+        };
+
+        let catalog = doc.catalog_mut().unwrap();
+        catalog.set("Metadata", metadata);
+        catalog.set("OutputIntents", vec![Object::Dictionary(intent)]);
+        doc
+    }
+
+    fn rules(report: &PdfaReport) -> Vec<PdfaRule> {
+        report.violations.iter().map(|v| v.rule).collect()
+    }
+
+    #[test]
+    fn test_compliant_document_passes() {
+        let report = check(&compliant_document(2), None);
+        assert_eq!(report.level, PdfaLevel::Pdfa2b);
+        assert_eq!(report.declared.as_deref(), Some("2B"));
+        assert!(report.is_compliant(), "{:?}", report.violations);
+    }
+
+    #[test]
+    fn test_declared_level_is_used_by_default() {
+        let report = check(&compliant_document(1), None);
+        assert_eq!(report.level, PdfaLevel::Pdfa1b);
+        assert!(report.is_compliant(), "{:?}", report.violations);
+    }
+
+    #[test]
+    fn test_plain_document_reports_each_requirement() {
+        let report = check(&test_support::build_sample_pdf(1), None);
+        let found = rules(&report);
+        assert!(found.contains(&PdfaRule::XmpIdentification));
+        assert!(found.contains(&PdfaRule::OutputIntent));
+        assert!(found.contains(&PdfaRule::EmbeddedFonts));
+
+        let font = report.violations.iter().find(|v| v.rule == PdfaRule::EmbeddedFonts).unwrap();
+        assert_eq!(font.clause, "ISO 19005-2 6.2.11.4.1");
+        assert_eq!(font.object_id, Some((2, 0)));
+        assert!(font.description.contains("Helvetica"));
+    }
+
+    #[test]
+    fn test_forbidden_actions_and_lzw() {
+        let mut doc = compliant_document(2);
+        doc.add_object(dictionary! { "S" => "JavaScript", "JS" => Object::string_literal("app.alert(1)") });
+        doc.add_object(Stream::new(dictionary! { "Filter" => vec!["LZWDecode".into()] }, vec![0x80]));
+
+        let found = rules(&check(&doc, None));
+        assert!(found.contains(&PdfaRule::NoForbiddenActions));
+        assert!(found.contains(&PdfaRule::NoLzw));
+    }
+
+    #[test]
+    fn test_transparency_only_forbidden_in_part_one() {
+        let mut doc = compliant_document(2);
+        doc.add_object(dictionary! { "Type" => "ExtGState", "ca" => 0.5 });
+
+        assert!(check(&doc, None).is_compliant());
+
+        let report = check(&doc, Some(PdfaLevel::Pdfa1b));
+        let found = rules(&report);
+        assert!(found.contains(&PdfaRule::NoTransparency));
+        // O documento declara PDF/A-2, não PDF/A-1
+        assert!(found.contains(&PdfaRule::XmpIdentification));
+    }
+
+    #[test]
+    fn test_info_must_match_xmp() {
+        let mut doc = compliant_document(2);
+        let info_id = doc.trailer.get(b"Info").unwrap().as_reference().unwrap();
+        let info = doc.get_dictionary_mut(info_id).unwrap();
+        info.set("Title", Object::string_literal("Renamed"));
+        info.set("Author", Object::string_literal("Ana"));
+
+        let report = check(&doc, None);
+        let consistency: Vec<_> = report
+            .violations
+            .iter()
+            .filter(|v| v.rule == PdfaRule::MetadataConsistency)
+            .collect();
+        assert_eq!(consistency.len(), 2);
+        assert_eq!(consistency[0].clause, "ISO 19005-2 6.6.3");
+        assert!(consistency[0].description.contains("dc:title \"Sample\""));
+        assert!(consistency[1].description.contains("dc:creator"));
+    }
+
+    #[test]
+    fn test_xmp_property_attribute_form() {
+        let xmp = r#"<rdf:Description pdfaid:part="3" pdfaid:conformance='b'/>"#;
+        assert_eq!(xmp_property(xmp, "pdfaid:part").as_deref(), Some("3"));
+        assert_eq!(xmp_property(xmp, "pdfaid:conformance").as_deref(), Some("b"));
+    }
+}
@@ -0,0 +1,418 @@
+//! Verificação de acessibilidade PDF/UA (ISO 14289-1)
+//!
+//! Confere, sem depender de serviços externos, os requisitos de PDF/UA que
+//! podem ser verificados diretamente na estrutura do documento, listando as
+//! falhas concretas de cada página.
+//!
+//! ## Verificações:
+//! - Documento marcado (`/StructTreeRoot` e `/MarkInfo /Marked true`)
+//! - Idioma do documento (`/Lang`)
+//! - Título exibido pelo leitor (`/ViewerPreferences /DisplayDocTitle true`)
+//! - Texto alternativo em elementos `Figure`
+//! - Anotações incluídas na árvore de estrutura
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Limite de redirecionamentos seguidos no `/RoleMap`
+const MAX_ROLE_MAP_DEPTH: usize = 16;
+
+/// Requisitos de acessibilidade verificados
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PdfuaRule {
+    /// O catálogo deve ter `/StructTreeRoot`
+    StructTreeRoot,
+    /// O catálogo deve declarar `/MarkInfo /Marked true`
+    MarkedContent,
+    /// O idioma do documento deve estar em `/Lang`
+    DocumentLanguage,
+    /// O leitor deve exibir o título do documento
+    DisplayDocTitle,
+    /// Elementos `Figure` precisam de texto alternativo
+    FigureAltText,
+    /// Anotações precisam estar na árvore de estrutura
+    TaggedAnnotation,
+}
+
+impl PdfuaRule {
+    /// Cláusula da ISO 14289-1
+    pub fn clause(&self) -> &'static str {
+        match self {
+            Self::StructTreeRoot | Self::MarkedContent | Self::DisplayDocTitle => "7.1",
+            Self::DocumentLanguage => "7.2",
+            Self::FigureAltText => "7.3",
+            Self::TaggedAnnotation => "7.18.1",
+        }
+    }
+}
+
+/// Uma falha de acessibilidade
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PdfuaFailure {
+    /// Requisito violado
+    pub rule: PdfuaRule,
+    /// Cláusula da norma (ex.: "ISO 14289-1 7.3")
+    pub clause: String,
+    /// Descrição do problema
+    pub description: String,
+    /// Objeto onde o problema foi encontrado
+    pub object_id: Option<ObjectId>,
+}
+
+/// Falhas encontradas em uma página
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageFailures {
+    /// Número da página (começando em 1)
+    pub page_number: u32,
+    /// Falhas da página
+    pub failures: Vec<PdfuaFailure>,
+}
+
+/// Resultado da verificação PDF/UA
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PdfuaReport {
+    /// Falhas que afetam o documento como um todo
+    pub document_failures: Vec<PdfuaFailure>,
+    /// Falhas agrupadas por página, em ordem
+    pub pages: Vec<PageFailures>,
+}
+
+impl PdfuaReport {
+    /// O documento atende a todos os requisitos verificados?
+    pub fn is_compliant(&self) -> bool {
+        self.failure_count() == 0
+    }
+
+    /// Total de falhas encontradas
+    pub fn failure_count(&self) -> usize {
+        self.document_failures.len() + self.pages.iter().map(|page| page.failures.len()).sum::<usize>()
+    }
+}
+
+/// Verifica os requisitos de acessibilidade do documento
+pub fn check(doc: &Document) -> PdfuaReport {
+    let page_numbers: HashMap<ObjectId, u32> = doc
+        .get_pages()
+        .into_iter()
+        .map(|(number, id)| (id, number))
+        .collect();
+
+    let mut document_failures = Vec::new();
+    let mut page_failures: BTreeMap<u32, Vec<PdfuaFailure>> = BTreeMap::new();
+
+    match doc.catalog() {
+        Ok(catalog) => {
+            check_catalog(doc, catalog, &mut document_failures);
+            if let Ok(root) = catalog.get_deref(b"StructTreeRoot", doc).and_then(Object::as_dict) {
+                check_figures(doc, root, &page_numbers, &mut document_failures, &mut page_failures);
+            }
+        }
+        Err(_) => document_failures.push(failure(
+            PdfuaRule::StructTreeRoot,
+            "Document has no catalog".to_string(),
+            None,
+        )),
+    }
+
+    check_annotations(doc, &mut page_failures);
+
+    PdfuaReport {
+        document_failures,
+        pages: page_failures
+            .into_iter()
+            .map(|(page_number, failures)| PageFailures { page_number, failures })
+            .collect(),
+    }
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+fn failure(rule: PdfuaRule, description: String, object_id: Option<ObjectId>) -> PdfuaFailure {
+    PdfuaFailure {
+        rule,
+        clause: format!("ISO 14289-1 {}", rule.clause()),
+        description,
+        object_id,
+    }
+}
+
+/// Requisitos declarados no catálogo
+fn check_catalog(doc: &Document, catalog: &Dictionary, failures: &mut Vec<PdfuaFailure>) {
+    if catalog.get_deref(b"StructTreeRoot", doc).and_then(Object::as_dict).is_err() {
+        failures.push(failure(
+            PdfuaRule::StructTreeRoot,
+            "Document is not tagged (no /StructTreeRoot)".to_string(),
+            None,
+        ));
+    }
+
+    let marked = catalog
+        .get_deref(b"MarkInfo", doc)
+        .and_then(Object::as_dict)
+        .and_then(|info| info.get(b"Marked"))
+        .and_then(Object::as_bool)
+        .unwrap_or(false);
+    if !marked {
+        failures.push(failure(
+            PdfuaRule::MarkedContent,
+            "Catalog does not declare /MarkInfo /Marked true".to_string(),
+            None,
+        ));
+    }
+
+    let has_language = catalog
+        .get_deref(b"Lang", doc)
+        .and_then(Object::as_str)
+        .map(|lang| !lang.is_empty())
+        .unwrap_or(false);
+    if !has_language {
+        failures.push(failure(
+            PdfuaRule::DocumentLanguage,
+            "Document language (/Lang) is not set".to_string(),
+            None,
+        ));
+    }
+
+    let displays_title = catalog
+        .get_deref(b"ViewerPreferences", doc)
+        .and_then(Object::as_dict)
+        .and_then(|preferences| preferences.get(b"DisplayDocTitle"))
+        .and_then(Object::as_bool)
+        .unwrap_or(false);
+    if !displays_title {
+        failures.push(failure(
+            PdfuaRule::DisplayDocTitle,
+            "Viewer preferences do not set /DisplayDocTitle true".to_string(),
+            None,
+        ));
+    }
+}
+
+/// Percorre a árvore de estrutura procurando `Figure` sem texto alternativo
+fn check_figures(
+    doc: &Document,
+    root: &Dictionary,
+    page_numbers: &HashMap<ObjectId, u32>,
+    document_failures: &mut Vec<PdfuaFailure>,
+    page_failures: &mut BTreeMap<u32, Vec<PdfuaFailure>>,
+) {
+    let role_map = root.get_deref(b"RoleMap", doc).and_then(Object::as_dict).ok();
+    let mut visited = HashSet::new();
+    // (objeto, id do objeto indireto, página herdada do elemento pai)
+    let mut pending: Vec<(&Object, Option<ObjectId>, Option<ObjectId>)> = root
+        .get(b"K")
+        .map(|kids| vec![(kids, None, None)])
+        .unwrap_or_default();
+
+    while let Some((object, owner, inherited_page)) = pending.pop() {
+        let (object, owner) = match object {
+            Object::Reference(id) => {
+                if !visited.insert(*id) {
+                    continue;
+                }
+                match doc.get_object(*id) {
+                    Ok(target) => (target, Some(*id)),
+                    Err(_) => continue,
+                }
+            }
+            _ => (object, owner),
+        };
+
+        let element = match object {
+            Object::Array(items) => {
+                pending.extend(items.iter().rev().map(|item| (item, owner, inherited_page)));
+                continue;
+            }
+            Object::Dictionary(dict) => dict,
+            _ => continue,
+        };
+
+        // Referências a conteúdo marcado e a objetos não são elementos de estrutura
+        if element.type_is(b"MCR") || element.type_is(b"OBJR") {
+            continue;
+        }
+
+        let page = element
+            .get(b"Pg")
+            .and_then(Object::as_reference)
+            .ok()
+            .or(inherited_page);
+
+        if let Ok(structure_type) = element.get(b"S").and_then(Object::as_name) {
+            if standard_type(structure_type, role_map) == b"Figure" && !has_alternate_text(element) {
+                let item = failure(
+                    PdfuaRule::FigureAltText,
+                    "Figure has no alternate text (/Alt)".to_string(),
+                    owner,
+                );
+                match page.and_then(|id| page_numbers.get(&id)) {
+                    Some(&number) => page_failures.entry(number).or_default().push(item),
+                    None => document_failures.push(item),
+                }
+            }
+        }
+
+        if let Ok(kids) = element.get(b"K") {
+            pending.push((kids, owner, page));
+        }
+    }
+}
+
+/// Resolve o tipo de estrutura através do `/RoleMap`
+fn standard_type<'a>(mut structure_type: &'a [u8], role_map: Option<&'a Dictionary>) -> &'a [u8] {
+    let Some(role_map) = role_map else {
+        return structure_type;
+    };
+
+    for _ in 0..MAX_ROLE_MAP_DEPTH {
+        match role_map.get(structure_type).and_then(Object::as_name) {
+            Ok(mapped) if mapped != structure_type => structure_type = mapped,
+            _ => break,
+        }
+    }
+
+    structure_type
+}
+
+fn has_alternate_text(element: &Dictionary) -> bool {
+    [b"Alt".as_slice(), b"ActualText"].iter().any(|key| {
+        element
+            .get(key)
+            .and_then(Object::as_str)
+            .map(|text| !text.is_empty())
+            .unwrap_or(false)
+    })
+}
+
+/// Toda anotação (exceto Popup) precisa de `/StructParent`
+fn check_annotations(doc: &Document, page_failures: &mut BTreeMap<u32, Vec<PdfuaFailure>>) {
+    for (page_number, page_id) in doc.get_pages() {
+        let Ok(page) = doc.get_dictionary(page_id) else {
+            continue;
+        };
+        let Ok(annotations) = page.get_deref(b"Annots", doc).and_then(Object::as_array) else {
+            continue;
+        };
+
+        for annotation in annotations {
+            let Ok((annotation_id, Object::Dictionary(annotation))) = doc.dereference(annotation) else {
+                continue;
+            };
+
+            let subtype = annotation.get(b"Subtype").and_then(Object::as_name).unwrap_or_default();
+            if subtype == b"Popup" || annotation.has(b"StructParent") {
+                continue;
+            }
+
+            page_failures.entry(page_number).or_default().push(failure(
+                PdfuaRule::TaggedAnnotation,
+                format!("{} annotation is not tagged (no /StructParent)", String::from_utf8_lossy(subtype)),
+                annotation_id,
+            ));
+        }
+    }
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::test_support;
+    use lopdf::dictionary;
+
+    /// Documento de exemplo marcado, com idioma e título exibido
+    fn tagged_document() -> Document {
+        let mut doc = test_support::build_sample_pdf(2);
+        let root = doc.add_object(dictionary! {
+            "Type" => "StructTreeRoot",
+            "K" => Object::Array(vec![]),
+        });
+
+        let catalog = doc.catalog_mut().unwrap();
+        catalog.set("StructTreeRoot", root);
+        catalog.set("MarkInfo", dictionary! { "Marked" => true });
+        catalog.set("Lang", Object::string_literal("pt-BR"));
+        catalog.set("ViewerPreferences", dictionary! { "DisplayDocTitle" => true });
+        doc
+    }
+
+    fn page_id(doc: &Document, number: u32) -> ObjectId {
+        doc.get_pages()[&number]
+    }
+
+    #[test]
+    fn test_untagged_document_reports_catalog_requirements() {
+        let report = check(&test_support::build_sample_pdf(1));
+        let rules: Vec<PdfuaRule> = report.document_failures.iter().map(|f| f.rule).collect();
+        assert_eq!(
+            rules,
+            vec![
+                PdfuaRule::StructTreeRoot,
+                PdfuaRule::MarkedContent,
+                PdfuaRule::DocumentLanguage,
+                PdfuaRule::DisplayDocTitle,
+            ]
+        );
+        assert!(!report.is_compliant());
+    }
+
+    #[test]
+    fn test_tagged_document_passes() {
+        let report = check(&tagged_document());
+        assert!(report.is_compliant(), "{:?}", report);
+    }
+
+    #[test]
+    fn test_figure_without_alt_is_reported_on_its_page() {
+        let mut doc = tagged_document();
+        let second_page = page_id(&doc, 2);
+
+        let figure = doc.add_object(dictionary! { "Type" => "StructElem", "S" => "Image", "Pg" => second_page });
+        let described = doc.add_object(dictionary! {
+            "Type" => "StructElem",
+            "S" => "Figure",
+            "Pg" => second_page,
+            "Alt" => Object::string_literal("Company logo"),
+        });
+        let section = doc.add_object(dictionary! {
+            "Type" => "StructElem",
+            "S" => "Sect",
+            "K" => vec![figure.into(), described.into()],
+        });
+
+        let root_id = doc.catalog().unwrap().get(b"StructTreeRoot").unwrap().as_reference().unwrap();
+        let root = doc.get_dictionary_mut(root_id).unwrap();
+        root.set("K", vec![section.into()]);
+        root.set("RoleMap", dictionary! { "Image" => "Figure" });
+
+        let report = check(&doc);
+        assert!(report.document_failures.is_empty());
+        assert_eq!(report.pages.len(), 1);
+        assert_eq!(report.pages[0].page_number, 2);
+        assert_eq!(report.pages[0].failures[0].rule, PdfuaRule::FigureAltText);
+        assert_eq!(report.pages[0].failures[0].object_id, Some(figure));
+    }
+
+    #[test]
+    fn test_untagged_annotations_are_reported_per_page() {
+        let mut doc = tagged_document();
+        let first_page = page_id(&doc, 1);
+
+        let link = doc.add_object(dictionary! { "Type" => "Annot", "Subtype" => "Link" });
+        let tagged = doc.add_object(dictionary! { "Type" => "Annot", "Subtype" => "Link", "StructParent" => 0 });
+        let popup = doc.add_object(dictionary! { "Type" => "Annot", "Subtype" => "Popup" });
+        doc.get_dictionary_mut(first_page)
+            .unwrap()
+            .set("Annots", vec![link.into(), tagged.into(), popup.into()]);
+
+        let report = check(&doc);
+        assert_eq!(report.failure_count(), 1);
+        assert_eq!(report.pages[0].page_number, 1);
+        let failure = &report.pages[0].failures[0];
+        assert_eq!(failure.rule, PdfuaRule::TaggedAnnotation);
+        assert_eq!(failure.clause, "ISO 14289-1 7.18.1");
+        assert_eq!(failure.object_id, Some(link));
+    }
+}
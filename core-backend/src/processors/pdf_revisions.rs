@@ -0,0 +1,471 @@
+//! Histórico de revisões (atualizações incrementais) de um PDF
+//!
+//! Cada atualização incremental acrescenta ao final do arquivo os objetos
+//! alterados, uma nova seção xref e um novo `%%EOF`. As versões anteriores
+//! dos objetos continuam no arquivo: um texto "apagado" por uma edição
+//! posterior ainda pode ser lido na revisão em que foi gravado.
+//!
+//! ## Funcionalidades:
+//! - Localização do fim de cada revisão (`startxref` seguido de `%%EOF`)
+//! - Objetos adicionados, regravados e liberados por revisão
+//! - Bytes de cada revisão, que formam um PDF completo daquela versão
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+
+use crate::processors::pdf_xref::{self, XrefEntryKind};
+
+/// Uma revisão do arquivo
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Revision {
+    /// Número da revisão (1 = documento original)
+    pub number: usize,
+    /// Fim da revisão: os bytes até aqui formam o PDF desta versão
+    pub end_offset: u64,
+    /// Bytes acrescentados por esta revisão
+    pub added_bytes: u64,
+    /// Offset da seção xref declarado no `startxref` da revisão
+    pub startxref: u64,
+    /// Objetos que aparecem pela primeira vez
+    pub added_objects: Vec<u32>,
+    /// Objetos existentes regravados nesta revisão
+    pub modified_objects: Vec<u32>,
+    /// Objetos existentes liberados (entradas `f`) nesta revisão
+    pub deleted_objects: Vec<u32>,
+}
+
+/// Histórico completo de revisões
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevisionHistory {
+    /// Revisões, da original para a mais recente
+    pub revisions: Vec<Revision>,
+    /// Bytes depois do último `%%EOF` (atualização truncada ou lixo)
+    pub trailing_bytes: u64,
+}
+
+impl RevisionHistory {
+    /// O arquivo recebeu atualizações incrementais?
+    pub fn has_incremental_updates(&self) -> bool {
+        self.revisions.len() > 1
+    }
+
+    /// Objetos alterados ou removidos depois da revisão original
+    ///
+    /// As versões anteriores desses objetos continuam recuperáveis no arquivo.
+    pub fn superseded_objects(&self) -> Vec<u32> {
+        let superseded: BTreeSet<u32> = self
+            .revisions
+            .iter()
+            .skip(1)
+            .flat_map(|revision| revision.modified_objects.iter().chain(&revision.deleted_objects))
+            .copied()
+            .collect();
+        superseded.into_iter().collect()
+    }
+}
+
+/// Separa as revisões de um arquivo PDF
+///
+/// As seções xref são lidas uma única vez, seguindo a cadeia `/Prev` a partir
+/// do `startxref` de cada revisão (em geral a da última já cobre o arquivo
+/// todo). Cada seção pertence à primeira revisão que termina depois dela, e
+/// suas entradas são comparadas com os objetos vivos até a revisão anterior.
+pub fn history(bytes: &[u8]) -> RevisionHistory {
+    let ends = revision_ends(bytes);
+
+    // Uma cadeia `/Prev` quebrada deixa revisões antigas fora da cadeia final;
+    // elas são lidas a partir do próprio `startxref`, sem repetir seções
+    let mut visited = HashSet::new();
+    let mut sections = Vec::new();
+    for &(startxref, _) in ends.iter().rev() {
+        if !visited.contains(&startxref) {
+            sections.extend(pdf_xref::section_chain(bytes, startxref, &mut visited).0);
+        }
+    }
+    sections.sort_by_key(|section| section.offset);
+
+    let mut history = RevisionHistory::default();
+    let mut live_objects: HashSet<u32> = HashSet::new();
+    let mut pending = sections.iter().peekable();
+    let mut previous_end = 0u64;
+
+    for (startxref, end) in ends {
+        let end = end as u64;
+        let mut added = BTreeSet::new();
+        let mut modified = BTreeSet::new();
+        let mut deleted = BTreeSet::new();
+        let mut has_sections = false;
+
+        while let Some(section) = pending.next_if(|section| section.offset < end) {
+            has_sections = true;
+            for entry in &section.entries {
+                if entry.object_number == 0 {
+                    continue;
+                }
+                let existed = live_objects.contains(&entry.object_number);
+                match entry.kind {
+                    XrefEntryKind::Free if existed => {
+                        deleted.insert(entry.object_number);
+                    }
+                    XrefEntryKind::Free => {}
+                    _ if existed => {
+                        modified.insert(entry.object_number);
+                    }
+                    _ => {
+                        added.insert(entry.object_number);
+                    }
+                }
+            }
+        }
+        if !has_sections {
+            continue;
+        }
+
+        // Uma seção híbrida pode repetir objetos da tabela clássica
+        modified.retain(|number| !deleted.contains(number));
+        live_objects.extend(added.iter().chain(&modified));
+        for number in &deleted {
+            live_objects.remove(number);
+        }
+
+        history.revisions.push(Revision {
+            number: history.revisions.len() + 1,
+            end_offset: end,
+            added_bytes: end - previous_end,
+            startxref,
+            added_objects: added.into_iter().collect(),
+            modified_objects: modified.into_iter().collect(),
+            deleted_objects: deleted.into_iter().collect(),
+        });
+        previous_end = end;
+    }
+
+    history.trailing_bytes = bytes.len() as u64 - previous_end;
+    history
+}
+
+/// Bytes de uma revisão: um PDF completo, como era naquela versão
+pub fn revision_bytes<'a>(bytes: &'a [u8], revision: &Revision) -> &'a [u8] {
+    let end = usize::try_from(revision.end_offset).unwrap_or(usize::MAX).min(bytes.len());
+    &bytes[..end]
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+/// Posições de `startxref N %%EOF`, com o offset declarado e o fim do marcador
+///
+/// `startxref 0` é ignorado: é o marcador da xref da primeira página em
+/// arquivos linearizados, não o fim de uma revisão.
+fn revision_ends(bytes: &[u8]) -> Vec<(u64, usize)> {
+    let keyword = b"startxref";
+    let mut ends = Vec::new();
+    let mut search = 0;
+
+    while let Some(found) = bytes[search..].windows(keyword.len()).position(|w| w == keyword) {
+        let start = search + found;
+        search = start + keyword.len();
+
+        let mut pos = skip_whitespace(bytes, search);
+        let digits = bytes[pos..].iter().take_while(|b| b.is_ascii_digit()).count();
+        let Some(offset) = std::str::from_utf8(&bytes[pos..pos + digits])
+            .ok()
+            .and_then(|text| text.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        pos = skip_whitespace(bytes, pos + digits);
+
+        if !bytes[pos..].starts_with(b"%%EOF") || offset == 0 {
+            continue;
+        }
+        pos += b"%%EOF".len();
+
+        // O fim de linha depois de `%%EOF` pertence à revisão
+        if bytes.get(pos) == Some(&b'\r') {
+            pos += 1;
+        }
+        if bytes.get(pos) == Some(&b'\n') {
+            pos += 1;
+        }
+        ends.push((offset, pos));
+        search = pos;
+    }
+
+    ends
+}
+
+fn skip_whitespace(bytes: &[u8], mut pos: usize) -> usize {
+    while bytes.get(pos).is_some_and(|b| matches!(b, b' ' | b'\t' | b'\n' | b'\r' | b'\x0c' | b'\0')) {
+        pos += 1;
+    }
+    pos
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::test_support;
+    use lopdf::Document;
+
+    /// Formulário gravado pelo Acrobat só com xref streams (Flate + PNG Up):
+    /// o original guarda o campo numa ObjStm, a 1ª atualização preenche o
+    /// campo, a 2ª remove a nota adesiva (objeto 11) e sobra lixo depois do
+    /// último `%%EOF`
+    const ACROBAT_FORM_UPDATES: &[u8] = b"%PDF-1.6\r\
+    %\xE2\xE3\xCF\xD3\r\
+    \n\
+    1 0 obj\r\
+    <</AcroForm 8 0 R/Pages 2 0 R/Type/Catalog>>\r\
+    endobj\r\
+    2 0 obj\r\
+    <</Count 1/Kids[3 0 R]/Type/Pages>>\r\
+    endobj\r\
+    3 0 obj\r\
+    <</Annots[9 0 R 11 0 R]/Contents 4 0 R/MediaBox[0 0 612 792]/Parent 2 0 R/Resources<</Font<</Helv 5 0 R>>>>/Type/Page>>\r\
+    endobj\r\
+    4 0 obj\r\
+    <</Length 38>>stream\r\
+    \n\
+    BT /Helv 12 Tf 72 720 Td (Nome:) Tj ET\r\
+    \n\
+    endstream\r\
+    endobj\r\
+    5 0 obj\r\
+    <</BaseFont/Helvetica/Encoding/WinAnsiEncoding/Subtype/Type1/Type/Font>>\r\
+    endobj\r\
+    6 0 obj\r\
+    <</CreationDate(D:20230914091200-03'00')/Creator(Adobe Acrobat Pro 23.6)/Producer(Adobe Acrobat Pro 23.6)>>\r\
+    endobj\r\
+    7 0 obj\r\
+    <</First 9/Length 172/N 2/Type/ObjStm>>stream\r\
+    \n\
+    8 0 9 65 <</DA(/Helv 0 Tf 0 g )/DR<</Font<</Helv 5 0 R>>>>/Fields[9 0 R]>><</DA(/Helv 12 Tf 0 g)/F 4/FT/Tx/P 3 0 R/Rect[120 712 400 732]/Subtype/Widget/T(nome)/Type/Annot>>\r\
+    \n\
+    endstream\r\
+    endobj\r\
+    11 0 obj\r\
+    <</Contents(Conferir CPF)/F 4/P 3 0 R/Rect[420 700 440 720]/Subtype/Text/Type/Annot>>\r\
+    endobj\r\
+    10 0 obj\r\
+    <</DecodeParms<</Columns 5/Predictor 12>>/Filter/FlateDecode/ID[<7F3A1C2B9E0D4F5A8B6C1D2E3F405162><7F3A1C2B9E0D4F5A8B6C1D2E3F405162>]/Index[0 12]/Info 6 0 R/Length 57/Root 1 0 R/Size 12/Type/XRef/W[1 3 1]>>stream\r\
+    \n\
+    x\xDAcb\x00\x82\xFFL\x8C\x0C\x0C\x02\x8CL@\xA6\r\
+    \x03\x884\x06\x91\x8C\xED`v8\x98\x8C\x00\x8BT3\x00U\xFE\xBB\x0E\x16a`d\xFA\xCF\xC0\xDC\xFA\x1F\xC4\x9E\xC5\x00\x00\xDB0\x08C\r\
+    \n\
+    endstream\r\
+    endobj\r\
+    startxref\r\
+    \n\
+    908\r\
+    \n\
+    %%EOF\r\
+    \n\
+    9 0 obj\r\
+    <</AP<</N 12 0 R>>/DA(/Helv 12 Tf 0 g)/F 4/FT/Tx/P 3 0 R/Rect[120 712 400 732]/Subtype/Widget/T(nome)/Type/Annot/V(Jo\\343o Pereira)>>\r\
+    endobj\r\
+    12 0 obj\r\
+    <</BBox[0 0 280 20]/Length 61/Resources<</Font<</Helv 5 0 R>>>>/Subtype/Form/Type/XObject>>stream\r\
+    \n\
+    /Tx BMC q BT /Helv 12 Tf 2 4 Td (Jo\\343o Pereira) Tj ET Q EMC\r\
+    \n\
+    endstream\r\
+    endobj\r\
+    6 0 obj\r\
+    <</CreationDate(D:20230914091200-03'00')/Creator(Adobe Acrobat Pro 23.6)/ModDate(D:20230915143010-03'00')/Producer(Adobe Acrobat Pro 23.6)>>\r\
+    endobj\r\
+    13 0 obj\r\
+    <</DecodeParms<</Columns 5/Predictor 12>>/Filter/FlateDecode/ID[<7F3A1C2B9E0D4F5A8B6C1D2E3F405162><7F3A1C2B9E0D4F5A8B6C1D2E3F405162>]/Index[6 1 9 1 12 2]/Info 6 0 R/Length 26/Prev 908/Root 1 0 R/Size 14/Type/XRef/W[1 3 1]>>stream\r\
+    \n\
+    x\xDAcbd`\x93g`b`\xF8\xB7\x1ED2N\x05\x93\x11\x0C\x00!i\x02\xCB\r\
+    \n\
+    endstream\r\
+    endobj\r\
+    startxref\r\
+    \n\
+    1723\r\
+    \n\
+    %%EOF\r\
+    \n\
+    3 0 obj\r\
+    <</Annots[9 0 R]/Contents 4 0 R/MediaBox[0 0 612 792]/Parent 2 0 R/Resources<</Font<</Helv 5 0 R>>>>/Type/Page>>\r\
+    endobj\r\
+    14 0 obj\r\
+    <</DecodeParms<</Columns 5/Predictor 12>>/Filter/FlateDecode/ID[<7F3A1C2B9E0D4F5A8B6C1D2E3F405162><7F3A1C2B9E0D4F5A8B6C1D2E3F405162>]/Index[0 1 3 1 11 1 14 1]/Info 6 0 R/Length 31/Prev 1723/Root 1 0 R/Size 15/Type/XRef/W[1 3 1]>>stream\r\
+    \n\
+    x\xDAcb``\xE0\xFE\xCF\xC4\xC8\xC0\xFE\x94\x91\xE9?\xC3O\x01F \x9B\xA3\xE0?\x007\x9F\x05\x82\r\
+    \n\
+    endstream\r\
+    endobj\r\
+    startxref\r\
+    \n\
+    2160\r\
+    \n\
+    %%EOF\r\
+    \n\
+    \x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00";
+
+    fn sample_bytes() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        test_support::build_sample_pdf(2).save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_single_revision() {
+        let bytes = sample_bytes();
+        let history = history(&bytes);
+
+        assert_eq!(history.revisions.len(), 1);
+        assert!(!history.has_incremental_updates());
+        assert_eq!(history.revisions[0].end_offset, bytes.len() as u64);
+        assert_eq!(history.revisions[0].startxref, pdf_xref::analyze(&bytes).startxref.unwrap());
+        assert_eq!(history.revisions[0].added_objects, (1..=9).collect::<Vec<u32>>());
+        assert!(history.revisions[0].modified_objects.is_empty());
+        assert!(history.superseded_objects().is_empty());
+    }
+
+    #[test]
+    fn test_incremental_update_reports_changed_objects() {
+        let original = sample_bytes();
+        let doc = Document::load_mem(&original).unwrap();
+        let info = doc.trailer.get(b"Info").unwrap().as_reference().unwrap().0;
+        let font = doc.objects.iter()
+            .find(|(_, object)| object.as_dict().is_ok_and(|d| d.has(b"BaseFont")))
+            .map(|(id, _)| id.0)
+            .unwrap();
+        let added = doc.max_id + 1;
+
+        let updated = test_support::append_incremental_update(&original, info, font, added);
+        let history = history(&updated);
+
+        assert_eq!(history.revisions.len(), 2);
+        assert!(history.has_incremental_updates());
+        assert_eq!(history.trailing_bytes, 0);
+
+        let first = &history.revisions[0];
+        assert_eq!(first.end_offset, original.len() as u64);
+        assert_eq!(revision_bytes(&updated, first), &original[..]);
+        assert_eq!(first.added_objects, (1..added).collect::<Vec<u32>>());
+        assert!(first.modified_objects.is_empty() && first.deleted_objects.is_empty());
+
+        let second = &history.revisions[1];
+        assert_eq!(second.modified_objects, vec![info]);
+        assert_eq!(second.deleted_objects, vec![font]);
+        assert_eq!(second.added_objects, vec![added]);
+        assert_eq!(second.added_bytes, (updated.len() - original.len()) as u64);
+        assert_eq!(history.superseded_objects(), {
+            let mut expected = vec![info, font];
+            expected.sort();
+            expected
+        });
+
+        // A revisão original continua um PDF válido, com o título antigo
+        let old = Document::load_mem(revision_bytes(&updated, first)).unwrap();
+        let title = old.get_dictionary((info, 0)).unwrap().get(b"Title").unwrap().as_str().unwrap().to_vec();
+        assert_eq!(title, b"Sample");
+    }
+
+    #[test]
+    fn test_revision_outside_the_prev_chain() {
+        let original = sample_bytes();
+        let doc = Document::load_mem(&original).unwrap();
+        let info = doc.trailer.get(b"Info").unwrap().as_reference().unwrap().0;
+        let root = doc.trailer.get(b"Root").unwrap().as_reference().unwrap().0;
+
+        // A atualização esquece o `/Prev`: a revisão original sai da cadeia final
+        let mut bytes = original.clone();
+        let info_offset = bytes.len();
+        bytes.extend_from_slice(format!("{} 0 obj\n<</Title (Revised)>>\nendobj\n", info).as_bytes());
+        let xref_offset = bytes.len();
+        bytes.extend_from_slice(
+            format!(
+                "xref\n{} 1\n{:010} 00000 n \ntrailer\n<</Size {} /Root {} 0 R /Info {} 0 R>>\nstartxref\n{}\n%%EOF\n",
+                info, info_offset, doc.max_id + 1, root, info, xref_offset
+            )
+            .as_bytes(),
+        );
+
+        let history = history(&bytes);
+        assert_eq!(history.revisions.len(), 2);
+        assert_eq!(history.revisions[0].end_offset, original.len() as u64);
+        assert_eq!(history.revisions[0].added_objects, (1..=doc.max_id).collect::<Vec<u32>>());
+        assert_eq!(history.revisions[1].startxref, xref_offset as u64);
+        assert_eq!(history.revisions[1].modified_objects, vec![info]);
+        assert!(history.revisions[1].added_objects.is_empty());
+    }
+
+    #[test]
+    fn test_linearized_file_is_one_revision() {
+        let bytes = crate::processors::pdf_linearize::linearize(&test_support::build_sample_pdf(3)).unwrap();
+        let history = history(&bytes);
+
+        assert_eq!(history.revisions.len(), 1);
+        assert_eq!(history.revisions[0].end_offset, bytes.len() as u64);
+        assert_eq!(history.revisions[0].startxref, pdf_xref::analyze(&bytes).startxref.unwrap());
+        let objects = Document::load_mem(&bytes).unwrap().max_id;
+        assert_eq!(history.revisions[0].added_objects, (1..=objects).collect::<Vec<u32>>());
+        assert_eq!(history.trailing_bytes, 0);
+    }
+
+    #[test]
+    fn test_trailing_bytes_after_last_eof() {
+        let mut bytes = sample_bytes();
+        let length = bytes.len() as u64;
+        bytes.extend_from_slice(b"garbage");
+        let history = history(&bytes);
+        assert_eq!(history.trailing_bytes, 7);
+        assert_eq!(history.revisions[0].end_offset, length);
+    }
+
+    #[test]
+    fn test_acrobat_form_with_xref_stream_updates() {
+        let bytes = ACROBAT_FORM_UPDATES;
+        let history = history(bytes);
+
+        let summary: Vec<(u64, u64, u64, Vec<u32>, Vec<u32>, Vec<u32>)> = history.revisions.iter()
+            .map(|revision| (
+                revision.end_offset,
+                revision.added_bytes,
+                revision.startxref,
+                revision.added_objects.clone(),
+                revision.modified_objects.clone(),
+                revision.deleted_objects.clone(),
+            ))
+            .collect();
+        assert_eq!(summary, vec![
+            (1230, 1230, 908, (1..=11).collect(), vec![], vec![]),
+            (2032, 802, 1723, vec![12, 13], vec![6, 9], vec![]),
+            (2480, 448, 2160, vec![14], vec![3], vec![11]),
+        ]);
+        assert_eq!(history.trailing_bytes, 16);
+        assert_eq!(history.superseded_objects(), vec![3, 6, 9, 11]);
+
+        // Cada revisão termina no fim de linha depois do seu `%%EOF`
+        for revision in &history.revisions {
+            assert!(revision_bytes(bytes, revision).ends_with(b"%%EOF\r\n"));
+        }
+
+        // O campo estava vazio no original e preenchido a partir da 2ª revisão
+        let field_value = |revision: &Revision| {
+            let doc = Document::load_mem(revision_bytes(bytes, revision)).unwrap();
+            let field = doc.get_dictionary((9, 0)).unwrap();
+            field.get(b"V").ok().map(|value| value.as_str().unwrap().to_vec())
+        };
+        assert_eq!(field_value(&history.revisions[0]), None);
+        assert_eq!(field_value(&history.revisions[1]), Some(b"Jo\xE3o Pereira".to_vec()));
+
+        // A nota adesiva só sai da página na última revisão
+        let annotations = |revision: &Revision| {
+            let doc = Document::load_mem(revision_bytes(bytes, revision)).unwrap();
+            let page = doc.get_dictionary((3, 0)).unwrap();
+            page.get(b"Annots").unwrap().as_array().unwrap()
+                .iter()
+                .map(|annotation| annotation.as_reference().unwrap().0)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(annotations(&history.revisions[1]), vec![9, 11]);
+        assert_eq!(annotations(&history.revisions[2]), vec![9]);
+    }
+}
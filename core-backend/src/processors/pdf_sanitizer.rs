@@ -0,0 +1,846 @@
+//! Sanitização de PDFs ("content disarm") para o DocHub
+//!
+//! Gera uma cópia limpa de um PDF, sem o conteúdo ativo detectado por
+//! `pdf_security`, antes que o arquivo entre no repositório de documentos.
+//!
+//! ## Remove:
+//! - JavaScript (ações, chaves `/JS` e a árvore de nomes `/JavaScript`)
+//! - Gatilhos automáticos (`/OpenAction` e `/AA`)
+//! - Ações `/Launch` e `/URI` (configurável)
+//! - Arquivos incorporados
+//! - Formulários XFA
+//! - Metadados do documento (opcional)
+//! - Histórico de atualizações incrementais
+//!
+//! A cópia é sempre regravada do zero, em uma única revisão: revisões
+//! anteriores nunca sobrevivem, e a resposta informa quantas foram descartadas.
+
+// Como os demais processadores, falha com `AppError` (grande por projeto, ver `error_handling`)
+#![allow(clippy::result_large_err)]
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tracing::{info, error, instrument};
+
+use crate::utils::error_handling::{Result, AppError, PdfError};
+use crate::utils::error_handling::validate;
+use crate::api::file_handlers::FileHandler;
+use crate::processors::pdf_encoding;
+use crate::processors::pdf_revisions;
+use crate::processors::runtime::ProcessorRuntime;
+
+/// Configurações da sanitização
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SanitizeConfig {
+    /// Remover JavaScript
+    pub remove_javascript: bool,
+    /// Remover `/OpenAction` e ações adicionais (`/AA`)
+    pub remove_auto_actions: bool,
+    /// Remover ações `/Launch`
+    pub remove_launch_actions: bool,
+    /// Remover ações `/URI` (links externos)
+    pub remove_uri_actions: bool,
+    /// Remover arquivos incorporados
+    pub remove_embedded_files: bool,
+    /// Remover formulários XFA
+    pub remove_xfa: bool,
+    /// Remover metadados (dicionário Info e XMP do catálogo)
+    pub remove_metadata: bool,
+    /// Remover objetos órfãos deixados por revisões anteriores
+    pub drop_history: bool,
+    /// Permite gravar a cópia limpa sobre o próprio arquivo de origem
+    pub overwrite_input: bool,
+}
+
+impl Default for SanitizeConfig {
+    fn default() -> Self {
+        Self {
+            remove_javascript: true,
+            remove_auto_actions: true,
+            remove_launch_actions: true,
+            remove_uri_actions: false,
+            remove_embedded_files: true,
+            remove_xfa: true,
+            remove_metadata: false,
+            drop_history: true,
+            overwrite_input: false,
+        }
+    }
+}
+
+/// Request para sanitização de PDF
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SanitizeRequest {
+    /// Caminho do PDF de origem
+    pub file_path: PathBuf,
+    /// Caminho da cópia sanitizada
+    pub output_path: PathBuf,
+    /// Configurações da sanitização
+    #[serde(default)]
+    pub config: SanitizeConfig,
+}
+
+impl SanitizeRequest {
+    /// Cria um SanitizeRequest a partir de JSON
+    pub fn from_value(data: &Value) -> Result<Self> {
+        let file_path = data["file"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'file' field"))?;
+
+        let output_path = data["output"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'output' field"))?;
+
+        // Configurações opcionais
+        let config = if let Some(config_val) = data.get("config") {
+            serde_json::from_value(config_val.clone())
+                .map_err(|e| AppError::validation(format!("Invalid config: {}", e)))?
+        } else {
+            SanitizeConfig::default()
+        };
+
+        Ok(Self {
+            file_path,
+            output_path,
+            config,
+        })
+    }
+
+    /// Valida a request
+    pub fn validate(&self, file_handler: &FileHandler) -> Result<()> {
+        let metadata = file_handler.validate_file(self.file_path.to_str().unwrap_or(""))?;
+
+        // A origem só é substituída quando o chamador pede explicitamente
+        validate(
+            self.config.overwrite_input || !self.writes_over_input(),
+            AppError::validation(
+                "Output path must differ from the input file unless 'overwrite_input' is set",
+            ),
+        )?;
+
+        info!(
+            path = %self.file_path.display(),
+            size = metadata.len(),
+            "Input file validated for sanitize"
+        );
+
+        Ok(())
+    }
+
+    /// Indica se `output_path` aponta para o mesmo arquivo que `file_path`
+    fn writes_over_input(&self) -> bool {
+        match (self.file_path.canonicalize(), self.output_path.canonicalize()) {
+            (Ok(input), Ok(output)) => input == output,
+            _ => self.output_path == self.file_path,
+        }
+    }
+}
+
+/// Tipos de conteúdo removido
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SanitizedContent {
+    /// Ação ou código JavaScript
+    JavaScript,
+    /// Ação executada ao abrir o documento
+    OpenAction,
+    /// Ações adicionais (`/AA`)
+    AdditionalActions,
+    /// Ação `/Launch`
+    Launch,
+    /// Ação `/URI`
+    Uri,
+    /// Arquivo incorporado
+    EmbeddedFile,
+    /// Formulário XFA
+    Xfa,
+    /// Metadados do documento
+    Metadata,
+    /// Revisões anteriores e objetos órfãos
+    History,
+}
+
+/// Um item removido da cópia sanitizada
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RemovedItem {
+    /// Tipo de conteúdo removido
+    pub kind: SanitizedContent,
+    /// Localização no documento de origem (ex.: "object 1, /OpenAction")
+    pub location: String,
+    /// Descrição do que foi removido
+    pub description: String,
+}
+
+/// Resultado da sanitização
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SanitizeResult {
+    /// Caminho da cópia sanitizada
+    pub output_path: PathBuf,
+    /// Tudo o que foi removido, na ordem dos objetos
+    pub removed: Vec<RemovedItem>,
+    /// Revisões do arquivo de origem descartadas na regravação
+    pub revisions_discarded: usize,
+    /// Objetos órfãos removidos (com `drop_history`)
+    pub objects_pruned: usize,
+    /// Tamanho da cópia sanitizada (em bytes)
+    pub file_size: u64,
+    /// Tempo total de processamento (em milissegundos)
+    pub processing_time_ms: u128,
+}
+
+/// Processador de sanitização de PDFs
+#[derive(Debug, Clone)]
+pub struct PdfSanitizer {
+    runtime: ProcessorRuntime,
+}
+
+processor_constructors!(PdfSanitizer);
+
+impl PdfSanitizer {
+    /// Grava uma cópia do PDF sem o conteúdo ativo configurado
+    #[instrument(name = "sanitize_pdf", skip(self, request), fields(
+        input_file = %request.file_path.display(),
+        output_file = %request.output_path.display()
+    ))]
+    pub async fn sanitize_pdf(&self, request: SanitizeRequest) -> Result<SanitizeResult> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.sanitize_pdf_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `sanitize_pdf`, executado no pool de CPU
+    fn sanitize_pdf_blocking(&self, request: SanitizeRequest) -> Result<SanitizeResult> {
+        let start_time = Instant::now();
+
+        info!("Starting PDF sanitize process");
+
+        // 1. Valida a request
+        request.validate(self.runtime.file_handler())?;
+
+        // 2. Carrega o documento (os bytes brutos revelam as revisões)
+        let source = std::fs::read(&request.file_path)
+            .map_err(|e| AppError::from_io_error("reading PDF", request.file_path.clone(), e))?;
+        let mut doc = self.load_document(&source, &request.file_path)?;
+
+        validate(
+            doc.trailer.get(b"Encrypt").is_err(),
+            AppError::validation("Encrypted documents cannot be sanitized"),
+        )?;
+
+        // 3. Remove o conteúdo ativo
+        let mut removed = sanitize_document(&mut doc, &request.config);
+
+        // 4. Histórico: a regravação mantém apenas a revisão atual
+        let revisions = pdf_revisions::history(&source).revisions.len();
+        let revisions_discarded = revisions.saturating_sub(1);
+        if revisions_discarded > 0 {
+            removed.push(RemovedItem {
+                kind: SanitizedContent::History,
+                location: "trailer".to_string(),
+                description: format!("Discarded {} earlier incremental revisions", revisions_discarded),
+            });
+        }
+
+        let objects_pruned = if request.config.drop_history {
+            let pruned = doc.prune_objects();
+            if !pruned.is_empty() {
+                removed.push(RemovedItem {
+                    kind: SanitizedContent::History,
+                    location: "document".to_string(),
+                    description: format!("Removed {} unreferenced objects", pruned.len()),
+                });
+            }
+            pruned.len()
+        } else {
+            0
+        };
+
+        // 5. Grava a cópia limpa
+        let file_size = self.save_document(&mut doc, &request)?;
+
+        let result = SanitizeResult {
+            output_path: request.output_path.clone(),
+            removed,
+            revisions_discarded,
+            objects_pruned,
+            file_size,
+            processing_time_ms: start_time.elapsed().as_millis(),
+        };
+
+        info!(
+            output = %result.output_path.display(),
+            removed = result.removed.len(),
+            processing_time_ms = result.processing_time_ms,
+            "PDF sanitize completed"
+        );
+
+        Ok(result)
+    }
+
+    // ==================== MÉTODOS PRIVADOS ====================
+
+    /// Carrega um documento PDF já lido em memória
+    fn load_document(&self, bytes: &[u8], path: &Path) -> Result<Document> {
+        info!(path = %path.display(), "Loading PDF document");
+
+        Document::load_mem(bytes)
+            .map_err(|e| {
+                error!(path = %path.display(), error = %e, "Failed to load PDF");
+                AppError::Pdf(PdfError::CorruptedPdf {
+                    path: path.to_path_buf(),
+                })
+            })
+    }
+
+    /// Salva a cópia limpa e retorna seu tamanho em bytes
+    ///
+    /// Um arquivo existente só é substituído quando é a própria origem e a
+    /// request habilitou `overwrite_input`.
+    #[instrument(name = "save_document", skip(self, doc, request))]
+    fn save_document(&self, doc: &mut Document, request: &SanitizeRequest) -> Result<u64> {
+        let output_path = &request.output_path;
+        info!(path = %output_path.display(), "Saving sanitized PDF");
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes)
+            .map_err(|e| {
+                error!(path = %output_path.display(), error = %e, "Failed to save PDF");
+                AppError::Pdf(PdfError::ProcessingFailed {
+                    reason: format!("Failed to save PDF: {}", e),
+                })
+            })?;
+
+        let file_handler = self.runtime.file_handler();
+        let path = output_path.to_str().unwrap_or("");
+        if request.config.overwrite_input && request.writes_over_input() {
+            file_handler.write_file(path, &bytes)?;
+        } else {
+            file_handler.write_new_file(path, &bytes)?;
+        }
+
+        info!(path = %output_path.display(), "Sanitized PDF saved successfully");
+        Ok(bytes.len() as u64)
+    }
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+/// Remove do documento o conteúdo ativo configurado e lista o que foi removido
+fn sanitize_document(doc: &mut Document, config: &SanitizeConfig) -> Vec<RemovedItem> {
+    let mut cleaner = Cleaner {
+        config,
+        object_id: (0, 0),
+        removed: Vec::new(),
+        embedded_streams: HashSet::new(),
+    };
+
+    if config.remove_metadata {
+        cleaner.remove_document_metadata(doc);
+    }
+
+    let ids: Vec<ObjectId> = doc.objects.keys().copied().collect();
+    for id in &ids {
+        cleaner.object_id = *id;
+        let Some(object) = doc.objects.get_mut(id) else {
+            continue;
+        };
+
+        // Ações proibidas guardadas como objetos indiretos viram null
+        if let Some(kind) = cleaner.forbidden_action(object) {
+            cleaner.record(kind, "", action_description(kind));
+            *object = Object::Null;
+            continue;
+        }
+
+        cleaner.clean_value(object, "");
+    }
+
+    // Dados de arquivos incorporados, inclusive os que não têm especificação
+    if config.remove_embedded_files {
+        for id in &ids {
+            let Some(object) = doc.objects.get_mut(id) else {
+                continue;
+            };
+            let is_embedded_file = matches!(object, Object::Stream(stream) if stream.dict.type_is(b"EmbeddedFile"));
+            if !is_embedded_file {
+                continue;
+            }
+
+            *object = Object::Null;
+            if !cleaner.embedded_streams.contains(id) {
+                cleaner.object_id = *id;
+                cleaner.record(SanitizedContent::EmbeddedFile, "", "Embedded file data".to_string());
+            }
+        }
+    }
+
+    cleaner.removed
+}
+
+/// Percorre os objetos removendo o conteúdo ativo
+struct Cleaner<'a> {
+    config: &'a SanitizeConfig,
+    /// Objeto sendo limpo
+    object_id: ObjectId,
+    removed: Vec<RemovedItem>,
+    /// Streams de dados referenciadas pelas especificações de arquivo removidas
+    embedded_streams: HashSet<ObjectId>,
+}
+
+impl Cleaner<'_> {
+    fn record(&mut self, kind: SanitizedContent, path: &str, description: String) {
+        let location = if path.is_empty() {
+            format!("object {}", self.object_id.0)
+        } else {
+            format!("object {}, {}", self.object_id.0, path)
+        };
+
+        self.removed.push(RemovedItem { kind, location, description });
+    }
+
+    /// Tipo de ação proibida pela configuração, se o valor for uma
+    fn forbidden_action(&self, value: &Object) -> Option<SanitizedContent> {
+        let dict = match value {
+            Object::Dictionary(dict) => dict,
+            _ => return None,
+        };
+        let action = dict.get(b"S").and_then(Object::as_name).unwrap_or_default();
+
+        if self.config.remove_javascript && (action == b"JavaScript" || dict.has(b"JS")) {
+            Some(SanitizedContent::JavaScript)
+        } else if self.config.remove_launch_actions && action == b"Launch" {
+            Some(SanitizedContent::Launch)
+        } else if self.config.remove_uri_actions && action == b"URI" {
+            Some(SanitizedContent::Uri)
+        } else {
+            None
+        }
+    }
+
+    fn clean_value(&mut self, value: &mut Object, path: &str) {
+        match value {
+            Object::Array(items) => {
+                for (index, item) in items.iter_mut().enumerate() {
+                    let item_path = format!("{}[{}]", path, index);
+                    if let Some(kind) = self.forbidden_action(item) {
+                        self.record(kind, &item_path, action_description(kind));
+                        *item = Object::Null;
+                    } else {
+                        self.clean_value(item, &item_path);
+                    }
+                }
+            }
+            Object::Dictionary(dict) => self.clean_dictionary(dict, path),
+            Object::Stream(stream) => self.clean_dictionary(&mut stream.dict, path),
+            _ => {}
+        }
+    }
+
+    fn clean_dictionary(&mut self, dict: &mut Dictionary, path: &str) {
+        if self.config.remove_auto_actions {
+            // Um destino simples (array) apenas posiciona a página inicial
+            if dict.get(b"OpenAction").is_ok_and(|action| !matches!(action, Object::Array(_))) {
+                dict.remove(b"OpenAction");
+                self.record(
+                    SanitizedContent::OpenAction,
+                    &format!("{}/OpenAction", path),
+                    "Action run when the document is opened".to_string(),
+                );
+            }
+            if dict.remove(b"AA").is_some() {
+                self.record(
+                    SanitizedContent::AdditionalActions,
+                    &format!("{}/AA", path),
+                    "Actions triggered by viewer events".to_string(),
+                );
+            }
+        }
+
+        // Árvore de nomes do catálogo (/Names /JavaScript)
+        if self.config.remove_javascript && !dict.has(b"S") && dict.remove(b"JavaScript").is_some() {
+            self.record(
+                SanitizedContent::JavaScript,
+                &format!("{}/JavaScript", path),
+                "Document-level JavaScript".to_string(),
+            );
+        }
+
+        if self.config.remove_embedded_files {
+            if dict.remove(b"EmbeddedFiles").is_some() {
+                self.record(
+                    SanitizedContent::EmbeddedFile,
+                    &format!("{}/EmbeddedFiles", path),
+                    "Embedded files name tree".to_string(),
+                );
+            }
+            if let Some(embedded) = dict.remove(b"EF") {
+                if let Object::Dictionary(streams) = &embedded {
+                    self.embedded_streams
+                        .extend(streams.iter().filter_map(|(_, stream)| stream.as_reference().ok()));
+                }
+                let name = [b"UF".as_slice(), b"F"]
+                    .iter()
+                    .find_map(|key| dict.get(key).and_then(Object::as_str).ok())
+                    .map(|name| format!(" \"{}\"", pdf_encoding::decode_text_string(name)))
+                    .unwrap_or_default();
+                self.record(SanitizedContent::EmbeddedFile, path, format!("Embedded file{}", name));
+            }
+        }
+
+        if self.config.remove_xfa && dict.remove(b"XFA").is_some() {
+            dict.remove(b"NeedsRendering");
+            self.record(SanitizedContent::Xfa, &format!("{}/XFA", path), "XFA form".to_string());
+        }
+
+        let keys: Vec<Vec<u8>> = dict.iter().map(|(key, _)| key.clone()).collect();
+        for key in keys {
+            let child_path = format!("{}/{}", path, String::from_utf8_lossy(&key));
+            let Ok(value) = dict.get_mut(&key) else {
+                continue;
+            };
+
+            if let Some(kind) = self.forbidden_action(value) {
+                self.record(kind, &child_path, action_description(kind));
+                dict.remove(&key);
+            } else {
+                self.clean_value(value, &child_path);
+            }
+        }
+    }
+
+    /// Remove o dicionário Info e o XMP do catálogo, com os objetos que os contêm
+    fn remove_document_metadata(&mut self, doc: &mut Document) {
+        if let Some(info) = doc.trailer.remove(b"Info") {
+            if let Ok(id) = info.as_reference() {
+                doc.objects.insert(id, Object::Null);
+            }
+            self.removed.push(RemovedItem {
+                kind: SanitizedContent::Metadata,
+                location: "trailer, /Info".to_string(),
+                description: "Document information dictionary".to_string(),
+            });
+        }
+
+        let Ok(catalog_id) = doc.trailer.get(b"Root").and_then(Object::as_reference) else {
+            return;
+        };
+        let Some(metadata) = doc.get_dictionary_mut(catalog_id).ok().and_then(|catalog| catalog.remove(b"Metadata")) else {
+            return;
+        };
+
+        if let Ok(id) = metadata.as_reference() {
+            doc.objects.insert(id, Object::Null);
+        }
+        self.object_id = catalog_id;
+        self.record(SanitizedContent::Metadata, "/Metadata", "XMP metadata".to_string());
+    }
+}
+
+fn action_description(kind: SanitizedContent) -> String {
+    match kind {
+        SanitizedContent::JavaScript => "JavaScript action",
+        SanitizedContent::Launch => "Launch action",
+        SanitizedContent::Uri => "URI action",
+        _ => "Action",
+    }
+    .to_string()
+}
+
+// ==================== FUNÇÕES DE CONVENIÊNCIA ====================
+
+/// Função de conveniência para sanitização de PDF
+#[instrument(name = "sanitize_pdf", skip(data, runtime))]
+pub async fn sanitize_pdf(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
+    let request = SanitizeRequest::from_value(&data)?;
+    let sanitizer = PdfSanitizer::with_runtime(runtime);
+    let result = sanitizer.sanitize_pdf(request).await?;
+
+    serde_json::to_value(result)
+        .map_err(|e| AppError::serialization(format!("Failed to serialize result: {}", e)))
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::pdf_security::{self, ActiveContentKind};
+    use crate::processors::pdf_xref;
+    use crate::utils::error_handling::IoError;
+    use crate::processors::test_support;
+    use lopdf::{dictionary, Stream};
+    use tempfile::TempDir;
+
+    /// Documento com JavaScript, gatilhos, links, arquivo incorporado e XFA
+    fn active_document() -> Document {
+        let mut doc = test_support::build_sample_pdf(1);
+        let page_id = doc.get_pages()[&1];
+
+        let script = doc.add_object(dictionary! { "S" => "JavaScript", "JS" => Object::string_literal("app.alert(1)") });
+        let data = doc.add_object(Stream::new(dictionary! { "Type" => "EmbeddedFile" }, b"MZ".to_vec()));
+        let spec = doc.add_object(dictionary! {
+            "Type" => "Filespec",
+            "F" => Object::string_literal("tool.exe"),
+            "EF" => dictionary! { "F" => data },
+        });
+        let link = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "A" => dictionary! { "S" => "URI", "URI" => Object::string_literal("https://example.com") },
+        });
+        let launch = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "A" => dictionary! { "S" => "Launch", "F" => Object::string_literal("cmd.exe") },
+        });
+        doc.get_dictionary_mut(page_id).unwrap().set("Annots", vec![link.into(), launch.into()]);
+        doc.get_dictionary_mut(page_id).unwrap().set("AA", dictionary! { "O" => script });
+
+        let catalog = doc.catalog_mut().unwrap();
+        catalog.set("OpenAction", script);
+        catalog.set("Names", dictionary! {
+            "EmbeddedFiles" => dictionary! { "Names" => vec![Object::string_literal("tool.exe"), spec.into()] },
+        });
+        catalog.set("AcroForm", dictionary! { "Fields" => Object::Array(vec![]), "XFA" => Object::string_literal("<xdp/>") });
+        doc
+    }
+
+    fn sanitize(doc: &mut Document, config: SanitizeConfig) -> (TempDir, SanitizeResult) {
+        let temp_dir = TempDir::new().unwrap();
+        let input = temp_dir.path().join("input.pdf");
+        doc.save(&input).unwrap();
+
+        let request = SanitizeRequest {
+            file_path: input,
+            output_path: temp_dir.path().join("clean.pdf"),
+            config,
+        };
+        let result = PdfSanitizer::new().sanitize_pdf_blocking(request).unwrap();
+        (temp_dir, result)
+    }
+
+    fn kinds(result: &SanitizeResult) -> Vec<SanitizedContent> {
+        result.removed.iter().map(|item| item.kind).collect()
+    }
+
+    #[test]
+    fn test_sanitize_request_from_value() -> Result<()> {
+        let data = serde_json::json!({
+            "file": "in.pdf",
+            "output": "out.pdf",
+            "config": { "remove_uri_actions": true }
+        });
+
+        let request = SanitizeRequest::from_value(&data)?;
+        assert_eq!(request.file_path, PathBuf::from("in.pdf"));
+        assert!(request.config.remove_uri_actions);
+        assert!(request.config.remove_javascript);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sanitize_removes_active_content() {
+        let (_temp_dir, result) = sanitize(&mut active_document(), SanitizeConfig::default());
+
+        let removed = kinds(&result);
+        for kind in [
+            SanitizedContent::JavaScript,
+            SanitizedContent::OpenAction,
+            SanitizedContent::AdditionalActions,
+            SanitizedContent::Launch,
+            SanitizedContent::EmbeddedFile,
+            SanitizedContent::Xfa,
+        ] {
+            assert!(removed.contains(&kind), "{:?} not removed: {:?}", kind, result.removed);
+        }
+        // Links externos são mantidos por padrão
+        assert!(!removed.contains(&SanitizedContent::Uri));
+
+        let clean = Document::load(&result.output_path).unwrap();
+        let report = pdf_security::scan(&clean);
+        let remaining: Vec<ActiveContentKind> = report.findings.iter().map(|f| f.kind).collect();
+        assert_eq!(remaining, vec![ActiveContentKind::Uri]);
+        assert_eq!(clean.get_pages().len(), 1);
+
+        // Catálogo sem gatilho, arquivos incorporados nem XFA; o formulário AcroForm fica
+        let catalog = clean.catalog().unwrap();
+        let keys: Vec<&[u8]> = catalog.iter().map(|(key, _)| key.as_slice()).collect();
+        assert_eq!(keys, vec![&b"Type"[..], b"Pages", b"Names", b"AcroForm"]);
+        assert!(catalog.get(b"Names").unwrap().as_dict().unwrap().is_empty());
+        let acroform = catalog.get(b"AcroForm").unwrap().as_dict().unwrap();
+        assert!(acroform.get(b"XFA").is_err());
+        assert!(acroform.get(b"Fields").unwrap().as_array().unwrap().is_empty());
+
+        // Página: o link externo fica intacto, o /Launch perde a ação e o /AA some
+        let page = clean.get_dictionary(clean.get_pages()[&1]).unwrap();
+        assert!(page.get(b"AA").is_err());
+        let annots: Vec<&Dictionary> = page.get(b"Annots").unwrap().as_array().unwrap().iter()
+            .map(|annot| clean.get_dictionary(annot.as_reference().unwrap()).unwrap())
+            .collect();
+        let uri = annots[0].get(b"A").unwrap().as_dict().unwrap().get(b"URI").unwrap();
+        assert_eq!(uri.as_str().unwrap(), b"https://example.com");
+        assert!(annots[1].get(b"A").is_err());
+        assert_eq!(crate::processors::pdf_text::page_text(&clean, clean.get_pages()[&1], false), "Page 1");
+
+        // O script, o arquivo incorporado e sua especificação não vão para a cópia
+        let bytes = std::fs::read(&result.output_path).unwrap();
+        for removed in [&b"app.alert"[..], b"tool.exe", b"cmd.exe"] {
+            assert!(!bytes.windows(removed.len()).any(|w| w == removed), "{}", String::from_utf8_lossy(removed));
+        }
+        assert_eq!(result.objects_pruned, 3);
+        assert_eq!(result.file_size, bytes.len() as u64);
+    }
+
+    #[test]
+    fn test_sanitize_lists_each_removal_with_location() {
+        let (_temp_dir, result) = sanitize(&mut active_document(), SanitizeConfig::default());
+
+        // Objetos de `active_document`: 5 página, 6 catálogo, 8 script, 10 especificação, 12 /Launch
+        let removed: Vec<(SanitizedContent, &str, &str)> = result.removed.iter()
+            .map(|i| (i.kind, i.location.as_str(), i.description.as_str()))
+            .collect();
+        assert_eq!(removed, vec![
+            (SanitizedContent::AdditionalActions, "object 5, /AA", "Actions triggered by viewer events"),
+            (SanitizedContent::OpenAction, "object 6, /OpenAction", "Action run when the document is opened"),
+            (SanitizedContent::EmbeddedFile, "object 6, /Names/EmbeddedFiles", "Embedded files name tree"),
+            (SanitizedContent::Xfa, "object 6, /AcroForm/XFA", "XFA form"),
+            (SanitizedContent::JavaScript, "object 8", "JavaScript action"),
+            (SanitizedContent::EmbeddedFile, "object 10", "Embedded file \"tool.exe\""),
+            (SanitizedContent::Launch, "object 12, /A", "Launch action"),
+            (SanitizedContent::History, "document", "Removed 3 unreferenced objects"),
+        ]);
+    }
+
+    #[test]
+    fn test_sanitize_uri_and_metadata_on_request() {
+        let config = SanitizeConfig {
+            remove_uri_actions: true,
+            remove_metadata: true,
+            ..SanitizeConfig::default()
+        };
+        let (_temp_dir, result) = sanitize(&mut active_document(), config);
+
+        let uri = result.removed.iter().find(|i| i.kind == SanitizedContent::Uri).unwrap();
+        assert_eq!(uri.location, "object 11, /A");
+        let metadata = result.removed.iter().find(|i| i.kind == SanitizedContent::Metadata).unwrap();
+        assert_eq!(metadata.location, "trailer, /Info");
+
+        let clean = Document::load(&result.output_path).unwrap();
+        assert!(clean.trailer.get(b"Info").is_err());
+        assert!(pdf_security::scan(&clean).findings.is_empty());
+
+        let page = clean.get_dictionary(clean.get_pages()[&1]).unwrap();
+        let link = page.get(b"Annots").unwrap().as_array().unwrap()[0].as_reference().unwrap();
+        assert!(clean.get_dictionary(link).unwrap().get(b"A").is_err());
+        let bytes = std::fs::read(&result.output_path).unwrap();
+        assert!(!bytes.windows(11).any(|w| w == b"example.com"));
+        assert!(!bytes.windows(12).any(|w| w == b"DocHub tests"));
+    }
+
+    #[test]
+    fn test_sanitize_discards_incremental_revisions() {
+        let temp_dir = TempDir::new().unwrap();
+        let input = temp_dir.path().join("input.pdf");
+        test_support::build_sample_pdf(1).save(&input).unwrap();
+
+        // Anexa uma revisão incremental que substitui o dicionário Info
+        let mut bytes = std::fs::read(&input).unwrap();
+        let previous = pdf_xref::analyze(&bytes).startxref.unwrap();
+        let info_offset = bytes.len();
+        bytes.extend_from_slice(b"7 0 obj\n<</Title (Revised)>>\nendobj\n");
+        let xref_offset = bytes.len();
+        bytes.extend_from_slice(
+            format!(
+                "xref\n7 1\n{:010} 00000 n \ntrailer\n<</Size 8 /Root 6 0 R /Info 7 0 R /Prev {}>>\nstartxref\n{}\n%%EOF\n",
+                info_offset, previous, xref_offset
+            )
+            .as_bytes(),
+        );
+        std::fs::write(&input, &bytes).unwrap();
+
+        let request = SanitizeRequest {
+            file_path: input,
+            output_path: temp_dir.path().join("clean.pdf"),
+            config: SanitizeConfig::default(),
+        };
+        let result = PdfSanitizer::new().sanitize_pdf_blocking(request).unwrap();
+        assert_eq!(result.revisions_discarded, 1);
+        let history = result.removed.iter().find(|i| i.location == "trailer").unwrap();
+        assert_eq!(history.kind, SanitizedContent::History);
+        assert_eq!(history.description, "Discarded 1 earlier incremental revisions");
+
+        // A cópia tem uma única seção xref, só com a versão atual do Info
+        let clean = std::fs::read(&result.output_path).unwrap();
+        assert_eq!(pdf_xref::analyze(&clean).sections.len(), 1);
+        assert!(!clean.windows(8).any(|w| w == b"(Sample)"));
+        let doc = Document::load_mem(&clean).unwrap();
+        let info = doc.trailer.get(b"Info").unwrap().as_reference().unwrap();
+        assert_eq!(doc.get_dictionary(info).unwrap().get(b"Title").unwrap().as_str().unwrap(), b"Revised");
+    }
+
+    #[test]
+    fn test_sanitize_rejects_same_output_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let input = test_support::write_sample_pdf(temp_dir.path(), "input.pdf", 1);
+
+        let request = SanitizeRequest {
+            file_path: input.clone(),
+            output_path: input,
+            config: SanitizeConfig::default(),
+        };
+        assert!(PdfSanitizer::new().sanitize_pdf_blocking(request).is_err());
+
+        // Um caminho equivalente, escrito de outra forma, também é recusado
+        let request = SanitizeRequest {
+            file_path: temp_dir.path().join("input.pdf"),
+            output_path: temp_dir.path().join(".").join("input.pdf"),
+            config: SanitizeConfig::default(),
+        };
+        assert!(PdfSanitizer::new().sanitize_pdf_blocking(request).is_err());
+    }
+
+    #[test]
+    fn test_sanitize_overwrites_input_only_on_request() {
+        let temp_dir = TempDir::new().unwrap();
+        let input = temp_dir.path().join("input.pdf");
+        active_document().save(&input).unwrap();
+
+        let request = SanitizeRequest {
+            file_path: input.clone(),
+            output_path: input.clone(),
+            config: SanitizeConfig {
+                overwrite_input: true,
+                ..SanitizeConfig::default()
+            },
+        };
+        let result = PdfSanitizer::new().sanitize_pdf_blocking(request).unwrap();
+        assert_eq!(result.file_size, std::fs::metadata(&input).unwrap().len());
+
+        let clean = Document::load(&input).unwrap();
+        let remaining: Vec<ActiveContentKind> =
+            pdf_security::scan(&clean).findings.iter().map(|f| f.kind).collect();
+        assert_eq!(remaining, vec![ActiveContentKind::Uri]);
+    }
+
+    #[test]
+    fn test_sanitize_keeps_existing_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let input = test_support::write_sample_pdf(temp_dir.path(), "input.pdf", 1);
+        let output = temp_dir.path().join("clean.pdf");
+        std::fs::write(&output, "keep").unwrap();
+
+        let request = SanitizeRequest {
+            file_path: input,
+            output_path: output.clone(),
+            config: SanitizeConfig::default(),
+        };
+        let result = PdfSanitizer::new().sanitize_pdf_blocking(request);
+        assert!(matches!(result, Err(AppError::Io(IoError::WriteFailed { .. }))));
+        assert_eq!(std::fs::read(&output).unwrap(), b"keep");
+    }
+}
@@ -0,0 +1,381 @@
+//! Análise de conteúdo ativo em PDFs
+//!
+//! Localiza tudo o que pode executar código, abrir programas, enviar dados ou
+//! carregar arquivos a partir de um PDF, para que o usuário saiba se é seguro
+//! abrir um documento recebido de terceiros.
+//!
+//! ## Detecta:
+//! - Ações JavaScript (`/S /JavaScript` e chaves `/JS`)
+//! - `/OpenAction` e ações adicionais (`/AA`)
+//! - Ações `/Launch`, `/SubmitForm`, `/ImportData` e `/URI`
+//! - Arquivos incorporados, inclusive executáveis com extensão disfarçada
+//! - Conteúdo RichMedia (Flash, vídeo, 3D)
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+
+use crate::processors::pdf_attachments;
+use crate::processors::pdf_encoding;
+
+/// Extensões de arquivos incorporados tratadas como executáveis
+const EXECUTABLE_EXTENSIONS: &[&str] = &[
+    "exe", "dll", "scr", "com", "bat", "cmd", "ps1", "vbs", "js", "jar", "msi", "sh", "app", "lnk", "hta",
+];
+
+/// Nível de risco de um conteúdo ativo
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RiskLevel {
+    /// Comportamento comum, normalmente inofensivo
+    Low,
+    /// Pode agir sem interação do usuário ou enviar dados
+    Medium,
+    /// Pode executar código dentro do leitor
+    High,
+    /// Pode executar programas no sistema
+    Critical,
+}
+
+/// Tipos de conteúdo ativo detectados
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ActiveContentKind {
+    /// Ação ou código JavaScript
+    JavaScript,
+    /// Ação executada ao abrir o documento
+    OpenAction,
+    /// Ações adicionais disparadas por eventos (`/AA`)
+    AdditionalActions,
+    /// Execução de aplicativo externo
+    Launch,
+    /// Envio de dados de formulário
+    SubmitForm,
+    /// Importação de dados externos
+    ImportData,
+    /// Abertura de endereço externo
+    Uri,
+    /// Arquivo incorporado
+    EmbeddedFile,
+    /// Conteúdo RichMedia
+    RichMedia,
+}
+
+/// Um conteúdo ativo encontrado no documento
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SecurityFinding {
+    /// Tipo de conteúdo
+    pub kind: ActiveContentKind,
+    /// Nível de risco
+    pub risk: RiskLevel,
+    /// Descrição do que foi encontrado
+    pub description: String,
+    /// Localização (ex.: "object 1, /OpenAction")
+    pub location: String,
+    /// Objeto que contém o conteúdo
+    pub object_id: ObjectId,
+}
+
+/// Resultado da análise de segurança
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SecurityReport {
+    /// Conteúdos ativos encontrados, na ordem dos objetos
+    pub findings: Vec<SecurityFinding>,
+}
+
+impl SecurityReport {
+    /// Maior risco encontrado
+    pub fn highest_risk(&self) -> Option<RiskLevel> {
+        self.findings.iter().map(|finding| finding.risk).max()
+    }
+
+    /// O documento contém JavaScript?
+    pub fn has_javascript(&self) -> bool {
+        self.contains(ActiveContentKind::JavaScript)
+    }
+
+    /// Algum conteúdo do tipo informado foi encontrado?
+    pub fn contains(&self, kind: ActiveContentKind) -> bool {
+        self.findings.iter().any(|finding| finding.kind == kind)
+    }
+
+    /// Nenhum conteúdo acima de risco baixo foi encontrado?
+    pub fn is_safe(&self) -> bool {
+        self.highest_risk().is_none_or(|risk| risk <= RiskLevel::Low)
+    }
+}
+
+/// Procura conteúdo ativo em todos os objetos do documento
+pub fn scan(doc: &Document) -> SecurityReport {
+    let mut findings = Vec::new();
+
+    for (&object_id, object) in &doc.objects {
+        let mut pending: Vec<(&Object, String)> = vec![(object, String::new())];
+
+        while let Some((current, path)) = pending.pop() {
+            let dict = match current {
+                Object::Array(items) => {
+                    for (index, item) in items.iter().enumerate().rev() {
+                        pending.push((item, format!("{}[{}]", path, index)));
+                    }
+                    continue;
+                }
+                Object::Dictionary(dict) => dict,
+                Object::Stream(stream) => &stream.dict,
+                _ => continue,
+            };
+
+            inspect_dictionary(doc, object_id, &path, dict, &mut findings);
+
+            for (key, value) in dict.iter().rev() {
+                pending.push((value, format!("{}/{}", path, String::from_utf8_lossy(key))));
+            }
+        }
+    }
+
+    SecurityReport { findings }
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+/// Verifica um único dicionário (sem descer nos valores)
+fn inspect_dictionary(
+    doc: &Document,
+    object_id: ObjectId,
+    path: &str,
+    dict: &Dictionary,
+    findings: &mut Vec<SecurityFinding>,
+) {
+    let mut report = |kind, risk, description: String, suffix: &str| {
+        findings.push(SecurityFinding {
+            kind,
+            risk,
+            description,
+            location: location(object_id, &format!("{}{}", path, suffix)),
+            object_id,
+        });
+    };
+
+    let action = dict.get(b"S").and_then(Object::as_name).unwrap_or_default();
+
+    if action == b"JavaScript" || dict.has(b"JS") {
+        report(
+            ActiveContentKind::JavaScript,
+            RiskLevel::High,
+            "JavaScript code runs when this action is triggered".to_string(),
+            "",
+        );
+    }
+
+    match action {
+        b"Launch" => report(
+            ActiveContentKind::Launch,
+            RiskLevel::Critical,
+            format!("Launch action opens an external application{}", describe_target(doc, dict, b"F")),
+            "",
+        ),
+        b"SubmitForm" => report(
+            ActiveContentKind::SubmitForm,
+            RiskLevel::Medium,
+            format!("Form data is submitted to an external address{}", describe_target(doc, dict, b"F")),
+            "",
+        ),
+        b"ImportData" => report(
+            ActiveContentKind::ImportData,
+            RiskLevel::Medium,
+            format!("Form data is imported from an external file{}", describe_target(doc, dict, b"F")),
+            "",
+        ),
+        b"URI" => report(
+            ActiveContentKind::Uri,
+            RiskLevel::Low,
+            format!("Link opens an external address{}", describe_target(doc, dict, b"URI")),
+            "",
+        ),
+        _ => {}
+    }
+
+    if let Ok(open_action) = dict.get(b"OpenAction") {
+        // Um destino simples apenas posiciona a página inicial
+        let (risk, description) = match doc.dereference(open_action).map(|(_, target)| target) {
+            Ok(Object::Dictionary(target)) if !target.get(b"S").and_then(Object::as_name).is_ok_and(|s| s == b"GoTo") => (
+                RiskLevel::Medium,
+                "An action runs automatically when the document is opened".to_string(),
+            ),
+            _ => (RiskLevel::Low, "Document opens at a specific destination".to_string()),
+        };
+        report(ActiveContentKind::OpenAction, risk, description, "/OpenAction");
+    }
+
+    if dict.has(b"AA") {
+        report(
+            ActiveContentKind::AdditionalActions,
+            RiskLevel::Medium,
+            "Actions run automatically on viewer events (/AA)".to_string(),
+            "/AA",
+        );
+    }
+
+    // Especificação de arquivo com o conteúdo incorporado em /EF
+    if dict.has(b"EF") {
+        let name = pdf_attachments::file_name(doc, dict);
+        let executable_name = name
+            .as_deref()
+            .and_then(|name| name.rsplit_once('.'))
+            .is_some_and(|(_, extension)| EXECUTABLE_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str()));
+        let data = pdf_attachments::file_data(doc, dict);
+        let executable_content = data.as_deref().is_some_and(pdf_attachments::is_executable_content);
+        let label = name.map(|name| format!(" \"{}\"", name)).unwrap_or_default();
+        let size = data.map(|data| format!(" ({} bytes)", data.len())).unwrap_or_default();
+
+        if executable_name {
+            report(
+                ActiveContentKind::EmbeddedFile,
+                RiskLevel::High,
+                format!("Embedded executable file{}{}", label, size),
+                "",
+            );
+        } else if executable_content {
+            // Executável com extensão inofensiva: disfarce típico de malware
+            report(
+                ActiveContentKind::EmbeddedFile,
+                RiskLevel::High,
+                format!("Embedded file{}{} contains executable code", label, size),
+                "",
+            );
+        } else {
+            report(
+                ActiveContentKind::EmbeddedFile,
+                RiskLevel::Medium,
+                format!("Embedded file{}{}", label, size),
+                "",
+            );
+        }
+    }
+
+    if dict.get(b"Subtype").and_then(Object::as_name).is_ok_and(|subtype| subtype == b"RichMedia") {
+        report(
+            ActiveContentKind::RichMedia,
+            RiskLevel::High,
+            "RichMedia content (Flash, video or 3D) can run embedded code".to_string(),
+            "",
+        );
+    }
+}
+
+/// Localização no formato usado pelas demais análises
+fn location(object_id: ObjectId, path: &str) -> String {
+    if path.is_empty() {
+        format!("object {}", object_id.0)
+    } else {
+        format!("object {}, {}", object_id.0, path)
+    }
+}
+
+/// Descreve o destino de uma ação (` to "..."`), se houver
+fn describe_target(doc: &Document, dict: &Dictionary, key: &[u8]) -> String {
+    let target = match dict.get_deref(key, doc) {
+        Ok(Object::String(bytes, _)) => Some(pdf_encoding::decode_text_string(bytes)),
+        Ok(Object::Dictionary(spec)) => pdf_attachments::file_name(doc, spec),
+        _ => None,
+    };
+
+    target.map(|target| format!(" (\"{}\")", target)).unwrap_or_default()
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::test_support;
+    use lopdf::{dictionary, Stream};
+
+    #[test]
+    fn test_sample_document_is_safe() {
+        let report = scan(&test_support::build_sample_pdf(2));
+        assert!(report.findings.is_empty());
+        assert!(report.is_safe());
+        assert!(!report.has_javascript());
+    }
+
+    #[test]
+    fn test_open_action_javascript() {
+        let mut doc = test_support::build_sample_pdf(1);
+        let action = dictionary! { "S" => "JavaScript", "JS" => Object::string_literal("app.alert('hi')") };
+        let catalog_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        doc.get_dictionary_mut(catalog_id).unwrap().set("OpenAction", action);
+
+        let report = scan(&doc);
+        assert!(report.has_javascript());
+        assert_eq!(report.highest_risk(), Some(RiskLevel::High));
+
+        let open = report.findings.iter().find(|f| f.kind == ActiveContentKind::OpenAction).unwrap();
+        assert_eq!(open.risk, RiskLevel::Medium);
+        assert_eq!(open.location, format!("object {}, /OpenAction", catalog_id.0));
+
+        let script = report.findings.iter().find(|f| f.kind == ActiveContentKind::JavaScript).unwrap();
+        assert_eq!(script.location, format!("object {}, /OpenAction", catalog_id.0));
+    }
+
+    #[test]
+    fn test_actions_carry_risk_levels() {
+        let mut doc = test_support::build_sample_pdf(1);
+        doc.add_object(dictionary! { "S" => "Launch", "F" => Object::string_literal("cmd.exe") });
+        doc.add_object(dictionary! { "S" => "SubmitForm", "F" => Object::string_literal("https://example.com") });
+        doc.add_object(dictionary! { "S" => "URI", "URI" => Object::string_literal("https://example.com") });
+        doc.add_object(dictionary! { "Type" => "Annot", "Subtype" => "RichMedia" });
+
+        let report = scan(&doc);
+        let risk_of = |kind| report.findings.iter().find(|f| f.kind == kind).map(|f| f.risk);
+        assert_eq!(risk_of(ActiveContentKind::Launch), Some(RiskLevel::Critical));
+        assert_eq!(risk_of(ActiveContentKind::SubmitForm), Some(RiskLevel::Medium));
+        assert_eq!(risk_of(ActiveContentKind::Uri), Some(RiskLevel::Low));
+        assert_eq!(risk_of(ActiveContentKind::RichMedia), Some(RiskLevel::High));
+        assert!(report.findings[0].description.contains("cmd.exe"));
+        assert!(!report.is_safe());
+    }
+
+    #[test]
+    fn test_embedded_executable_is_high_risk() {
+        let mut doc = test_support::build_sample_pdf(1);
+        let data = doc.add_object(Stream::new(dictionary! { "Type" => "EmbeddedFile" }, b"MZ".to_vec()));
+        let spec = doc.add_object(dictionary! {
+            "Type" => "Filespec",
+            "F" => Object::string_literal("invoice.EXE"),
+            "EF" => dictionary! { "F" => data },
+        });
+
+        let report = scan(&doc);
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].kind, ActiveContentKind::EmbeddedFile);
+        assert_eq!(report.findings[0].risk, RiskLevel::High);
+        assert_eq!(report.findings[0].object_id, spec);
+    }
+
+    #[test]
+    fn test_disguised_executable_is_high_risk() {
+        let mut doc = test_support::build_sample_pdf(1);
+        let data = doc.add_object(Stream::new(dictionary! { "Type" => "EmbeddedFile" }, b"MZ\x90\x00".to_vec()));
+        doc.add_object(dictionary! {
+            "Type" => "Filespec",
+            "F" => Object::string_literal("invoice.pdf"),
+            "EF" => dictionary! { "F" => data },
+        });
+
+        let report = scan(&doc);
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].risk, RiskLevel::High);
+        assert_eq!(report.findings[0].description, "Embedded file \"invoice.pdf\" (4 bytes) contains executable code");
+    }
+
+    #[test]
+    fn test_goto_open_action_is_low_risk() {
+        let mut doc = test_support::build_sample_pdf(1);
+        let page = doc.get_pages()[&1];
+        let catalog = doc.catalog_mut().unwrap();
+        catalog.set("OpenAction", vec![page.into(), "Fit".into()]);
+
+        let report = scan(&doc);
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].risk, RiskLevel::Low);
+        assert!(report.is_safe());
+    }
+}
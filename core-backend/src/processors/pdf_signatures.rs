@@ -0,0 +1,783 @@
+//! Detecção e verificação offline de assinaturas digitais
+//!
+//! Uma assinatura PDF cobre os bytes indicados em `/ByteRange`: o arquivo
+//! inteiro, exceto a string hexadecimal `/Contents` que guarda a mensagem
+//! PKCS#7/CMS. A verificação refaz o hash desses bytes e o compara com o
+//! digest assinado, usando apenas os dados do próprio arquivo (sem OCSP/CRL).
+//!
+//! ## Funcionalidades:
+//! - Campos de assinatura (`/FT /Sig`) do `/AcroForm` e dicionários `/Sig` soltos
+//! - Signatário, data, motivo, local e `/SubFilter` de cada assinatura
+//! - Cobertura do `/ByteRange` e bytes acrescentados depois da assinatura
+//! - Digest recalculado e comparado com o `messageDigest` do CMS (ou com o
+//!   conteúdo encapsulado em `adbe.pkcs7.sha1` e o carimbo de tempo RFC 3161)
+//! - Verificação da assinatura RSA sobre os atributos assinados, com o
+//!   certificado incluído na própria mensagem
+
+use cms::content_info::ContentInfo;
+use cms::signed_data::{CertificateSet, SignedData, SignerIdentifier, SignerInfo};
+use der::asn1::{Any, ObjectIdentifier, OctetString};
+use der::{Decode, Encode, Sequence, SliceReader, Tag, Tagged};
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use rsa::pkcs8::DecodePublicKey;
+use rsa::{Pkcs1v15Sign, RsaPublicKey};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::collections::HashSet;
+use x509_cert::spki::AlgorithmIdentifierOwned;
+use x509_cert::time::Time;
+use x509_cert::Certificate;
+
+use crate::processors::pdf_encoding;
+use crate::processors::pdf_revisions;
+
+/// Limite de níveis percorridos na árvore de campos do `/AcroForm`
+const MAX_FIELD_DEPTH: usize = 32;
+
+const OID_SIGNED_DATA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.7.2");
+const OID_MESSAGE_DIGEST: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.4");
+const OID_SIGNING_TIME: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.5");
+const OID_TST_INFO: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.1.4");
+const OID_COMMON_NAME: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.4.3");
+const OID_SUBJECT_KEY_IDENTIFIER: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.29.14");
+
+const OID_SHA1: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.14.3.2.26");
+const OID_SHA256: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.1");
+const OID_SHA384: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.2");
+const OID_SHA512: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.3");
+
+/// `rsaEncryption` e `sha*WithRSAEncryption`: todas usam PKCS#1 v1.5
+const RSA_PKCS1_OIDS: [ObjectIdentifier; 5] = [
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1"),
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.5"),
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.11"),
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.12"),
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.13"),
+];
+
+/// Resultado da verificação dos bytes assinados
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignatureStatus {
+    /// Digest e assinatura conferem com os bytes cobertos
+    Intact,
+    /// O `/ByteRange`, o digest ou a assinatura não conferem
+    Altered,
+    /// Formato ou algoritmo não suportado: não foi possível verificar
+    NotVerifiable,
+}
+
+/// Uma assinatura digital encontrada no documento
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureInfo {
+    /// Nome completo do campo de assinatura (None = dicionário `/Sig` sem campo)
+    pub field_name: Option<String>,
+    /// Objeto do dicionário `/Sig`, quando indireto
+    pub object_number: Option<u32>,
+    /// Signatário: `/Name` ou, na falta dele, o CN do certificado
+    pub signer_name: Option<String>,
+    /// Titular do certificado do signatário
+    pub certificate_subject: Option<String>,
+    /// Emissor do certificado do signatário
+    pub certificate_issuer: Option<String>,
+    /// Data da assinatura: `/M` ou, na falta dele, o atributo `signingTime`
+    pub signing_time: Option<String>,
+    /// Motivo (`/Reason`)
+    pub reason: Option<String>,
+    /// Local (`/Location`)
+    pub location: Option<String>,
+    /// Contato do signatário (`/ContactInfo`)
+    pub contact_info: Option<String>,
+    /// Formato da assinatura (ex.: "adbe.pkcs7.detached", "ETSI.CAdES.detached")
+    pub sub_filter: Option<String>,
+    /// `/ByteRange` declarado: pares (offset, tamanho)
+    pub byte_range: Vec<u64>,
+    /// Os bytes excluídos são exatamente a string `/Contents`
+    pub byte_range_valid: bool,
+    /// O `/ByteRange` cobre o arquivo inteiro, exceto a assinatura
+    pub covers_whole_file: bool,
+    /// Bytes acrescentados ao arquivo depois do fim da área assinada
+    pub bytes_after_signature: u64,
+    /// Revisão do arquivo que a assinatura cobre (ver `pdf_revisions`)
+    pub signed_revision: Option<usize>,
+    /// Algoritmo de hash usado no digest (ex.: "SHA-256")
+    pub digest_algorithm: Option<String>,
+    /// O digest recalculado confere com o assinado? (None = não verificável)
+    pub digest_matches: Option<bool>,
+    /// A assinatura confere com o certificado incluído? (None = algoritmo não suportado)
+    pub signature_valid: Option<bool>,
+    /// Problemas que impediram parte da verificação
+    pub problems: Vec<String>,
+}
+
+impl SignatureInfo {
+    /// O arquivo recebeu bytes depois desta assinatura?
+    pub fn modified_after_signing(&self) -> bool {
+        self.bytes_after_signature > 0
+    }
+
+    /// Estado da verificação dos bytes assinados
+    ///
+    /// Não diz nada sobre alterações posteriores (`modified_after_signing`)
+    /// nem sobre a confiança no certificado.
+    pub fn status(&self) -> SignatureStatus {
+        if !self.byte_range_valid || self.digest_matches == Some(false) || self.signature_valid == Some(false) {
+            SignatureStatus::Altered
+        } else if self.digest_matches.is_none() || self.signature_valid.is_none() {
+            SignatureStatus::NotVerifiable
+        } else {
+            SignatureStatus::Intact
+        }
+    }
+
+    /// Os bytes assinados foram verificados e estão íntegros?
+    pub fn is_intact(&self) -> bool {
+        self.status() == SignatureStatus::Intact
+    }
+}
+
+/// Encontra e verifica as assinaturas digitais de um documento
+///
+/// `source` são os bytes do arquivo de onde `doc` foi carregado.
+pub fn verify(doc: &Document, source: &[u8]) -> Vec<SignatureInfo> {
+    let history = pdf_revisions::history(source);
+    let mut seen = HashSet::new();
+    let mut signatures = Vec::new();
+
+    for (field_name, id, dict) in signature_dictionaries(doc) {
+        if let Some(id) = id {
+            if !seen.insert(id) {
+                continue;
+            }
+        }
+        let mut info = describe(doc, field_name, id, dict);
+        check_integrity(doc, source, dict, &mut info);
+        let signed_end = info.byte_range.chunks(2).map(|pair| pair.iter().sum::<u64>()).max();
+        info.signed_revision = signed_end.and_then(|end| {
+            history.revisions.iter().find(|revision| revision.end_offset == end).map(|revision| revision.number)
+        });
+        signatures.push(info);
+    }
+
+    signatures
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+/// Dicionários de assinatura: valores de campos `/FT /Sig` e objetos `/Type /Sig` soltos
+fn signature_dictionaries(doc: &Document) -> Vec<(Option<String>, Option<ObjectId>, &Dictionary)> {
+    let mut found = Vec::new();
+
+    let fields = doc
+        .catalog()
+        .ok()
+        .and_then(|catalog| catalog.get(b"AcroForm").ok())
+        .and_then(|acro_form| resolve(doc, acro_form).as_dict().ok())
+        .and_then(|acro_form| acro_form.get(b"Fields").ok())
+        .and_then(|fields| resolve(doc, fields).as_array().ok());
+    for field in fields.into_iter().flatten() {
+        collect_fields(doc, field, None, None, 0, &mut found);
+    }
+
+    // Assinaturas de documento sem campo no formulário (ex.: carimbos de tempo órfãos)
+    let fielded: HashSet<ObjectId> = found.iter().filter_map(|(_, id, _)| *id).collect();
+    for (id, object) in &doc.objects {
+        let Ok(dict) = object.as_dict() else { continue };
+        let is_signature = dict
+            .get(b"Type")
+            .and_then(Object::as_name)
+            .is_ok_and(|name| name == b"Sig" || name == b"DocTimeStamp");
+        if is_signature && dict.has(b"ByteRange") && !fielded.contains(id) {
+            found.push((None, Some(*id), dict));
+        }
+    }
+
+    found
+}
+
+/// Percorre um campo e seus filhos, herdando `/FT` e montando o nome completo
+fn collect_fields<'a>(
+    doc: &'a Document,
+    field: &'a Object,
+    parent_name: Option<&str>,
+    inherited_type: Option<&'a [u8]>,
+    depth: usize,
+    found: &mut Vec<(Option<String>, Option<ObjectId>, &'a Dictionary)>,
+) {
+    if depth > MAX_FIELD_DEPTH {
+        return;
+    }
+    let Ok(dict) = resolve(doc, field).as_dict() else { return };
+
+    let partial = dict.get(b"T").ok().and_then(|t| t.as_str().ok()).map(pdf_encoding::decode_text_string);
+    let name = match (parent_name, partial) {
+        (Some(parent), Some(partial)) => Some(format!("{}.{}", parent, partial)),
+        (None, Some(partial)) => Some(partial),
+        (parent, None) => parent.map(str::to_string),
+    };
+    let field_type = dict.get(b"FT").and_then(Object::as_name).ok().or(inherited_type);
+
+    if field_type == Some(b"Sig".as_slice()) {
+        if let Ok(value) = dict.get(b"V") {
+            let id = value.as_reference().ok();
+            if let Ok(signature) = resolve(doc, value).as_dict() {
+                found.push((name.clone(), id, signature));
+            }
+        }
+    }
+
+    let kids = dict.get(b"Kids").ok().and_then(|kids| resolve(doc, kids).as_array().ok());
+    for kid in kids.into_iter().flatten() {
+        collect_fields(doc, kid, name.as_deref(), field_type, depth + 1, found);
+    }
+}
+
+/// Campos descritivos do dicionário `/Sig`
+fn describe(doc: &Document, field_name: Option<String>, id: Option<ObjectId>, dict: &Dictionary) -> SignatureInfo {
+    let byte_range = dict
+        .get(b"ByteRange")
+        .ok()
+        .and_then(|range| resolve(doc, range).as_array().ok())
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_i64().ok())
+                .map(|value| u64::try_from(value).unwrap_or(0))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    SignatureInfo {
+        field_name,
+        object_number: id.map(|id| id.0),
+        signer_name: text_string(doc, dict, b"Name"),
+        signing_time: text_string(doc, dict, b"M"),
+        reason: text_string(doc, dict, b"Reason"),
+        location: text_string(doc, dict, b"Location"),
+        contact_info: text_string(doc, dict, b"ContactInfo"),
+        sub_filter: dict
+            .get(b"SubFilter")
+            .and_then(Object::as_name)
+            .ok()
+            .map(|name| String::from_utf8_lossy(name).into_owned()),
+        byte_range,
+        ..SignatureInfo::default()
+    }
+}
+
+/// Confere o `/ByteRange`, recalcula o digest e verifica a mensagem CMS
+fn check_integrity(doc: &Document, source: &[u8], dict: &Dictionary, info: &mut SignatureInfo) {
+    let file_length = source.len() as u64;
+    let Some(ranges) = signed_ranges(&info.byte_range, file_length) else {
+        info.problems.push(format!("Invalid /ByteRange {:?}", info.byte_range));
+        return;
+    };
+
+    // Entre os dois intervalos deve estar só a string hexadecimal `/Contents`
+    info.byte_range_valid = ranges[0].0 == 0
+        && ranges.len() == 2
+        && is_hex_string(&source[(ranges[0].0 + ranges[0].1) as usize..ranges[1].0 as usize]);
+    if !info.byte_range_valid {
+        info.problems.push("/ByteRange does not exclude exactly the /Contents string".to_string());
+    }
+    let signed_end = ranges.iter().map(|(offset, length)| offset + length).max().unwrap_or(0);
+    info.bytes_after_signature = file_length - signed_end;
+    info.covers_whole_file = info.byte_range_valid && info.bytes_after_signature == 0;
+
+    let Some(contents) = dict.get(b"Contents").ok().and_then(|c| resolve(doc, c).as_str().ok()) else {
+        info.problems.push("Signature has no /Contents".to_string());
+        return;
+    };
+    let parts: Vec<&[u8]> = ranges
+        .iter()
+        .map(|&(offset, length)| &source[offset as usize..(offset + length) as usize])
+        .collect();
+
+    if let Err(problem) = verify_cms(contents, &parts, info) {
+        info.problems.push(problem);
+    }
+}
+
+/// Intervalos `(offset, tamanho)` do `/ByteRange`, se estiverem dentro do arquivo e em ordem
+fn signed_ranges(byte_range: &[u64], file_length: u64) -> Option<Vec<(u64, u64)>> {
+    if byte_range.is_empty() || !byte_range.len().is_multiple_of(2) {
+        return None;
+    }
+    let ranges: Vec<(u64, u64)> = byte_range.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+    let mut previous_end = 0;
+    for &(offset, length) in &ranges {
+        let end = offset.checked_add(length)?;
+        if offset < previous_end || end > file_length {
+            return None;
+        }
+        previous_end = end;
+    }
+    Some(ranges)
+}
+
+/// `<` dígitos hexadecimais (e espaços) `>`
+fn is_hex_string(gap: &[u8]) -> bool {
+    gap.len() >= 2
+        && gap[0] == b'<'
+        && gap[gap.len() - 1] == b'>'
+        && gap[1..gap.len() - 1].iter().all(|b| b.is_ascii_hexdigit() || b.is_ascii_whitespace())
+}
+
+/// Verifica a mensagem CMS contra os bytes assinados
+fn verify_cms(contents: &[u8], parts: &[&[u8]], info: &mut SignatureInfo) -> Result<(), String> {
+    if info.sub_filter.as_deref() == Some("adbe.x509.rsa_sha1") {
+        return Err("adbe.x509.rsa_sha1 signatures (raw PKCS#1) are not verified".to_string());
+    }
+
+    // `/Contents` é completado com zeros depois da mensagem DER
+    let mut reader = SliceReader::new(contents).map_err(|e| format!("Invalid CMS message: {}", e))?;
+    let content_info = ContentInfo::decode(&mut reader).map_err(|e| format!("Invalid CMS message: {}", e))?;
+    if content_info.content_type != OID_SIGNED_DATA {
+        return Err(format!("CMS content type {} is not SignedData", content_info.content_type));
+    }
+    let signed_data: SignedData = content_info
+        .content
+        .decode_as()
+        .map_err(|e| format!("Invalid CMS SignedData: {}", e))?;
+    let signer = signed_data
+        .signer_infos
+        .0
+        .iter()
+        .next()
+        .ok_or_else(|| "CMS message has no signer".to_string())?;
+
+    let certificate = signer_certificate(signed_data.certificates.as_ref(), &signer.sid);
+    if let Some(certificate) = certificate {
+        let subject = &certificate.tbs_certificate.subject;
+        info.certificate_subject = Some(subject.to_string());
+        info.certificate_issuer = Some(certificate.tbs_certificate.issuer.to_string());
+        if info.signer_name.is_none() {
+            info.signer_name = common_name(certificate);
+        }
+    }
+    if info.signing_time.is_none() {
+        info.signing_time = signed_attribute(signer, OID_SIGNING_TIME)
+            .and_then(|value| value.to_der().ok())
+            .and_then(|der| Time::from_der(&der).ok())
+            .map(|time| time.to_date_time().to_string());
+    }
+
+    let algorithm = DigestAlgorithm::from_oid(&signer.digest_alg.oid)
+        .ok_or_else(|| format!("Unsupported digest algorithm {}", signer.digest_alg.oid))?;
+    info.digest_algorithm = Some(algorithm.name().to_string());
+
+    // Conteúdo encapsulado: o hash dos bytes (adbe.pkcs7.sha1) ou o TSTInfo (RFC 3161)
+    let econtent = signed_data
+        .encap_content_info
+        .econtent
+        .as_ref()
+        .map(|content| content.value().to_vec());
+    let signed_content = match &econtent {
+        Some(econtent) => {
+            info.digest_matches = Some(encapsulated_digest_matches(
+                &signed_data.encap_content_info.econtent_type,
+                econtent,
+                parts,
+            )?);
+            econtent.clone()
+        }
+        None => parts.concat(),
+    };
+
+    // Com atributos assinados, a assinatura cobre os atributos e o `messageDigest` cobre o conteúdo
+    let signed_message = match &signer.signed_attrs {
+        Some(attributes) => {
+            let message_digest = signed_attribute(signer, OID_MESSAGE_DIGEST)
+                .and_then(|value| value.decode_as::<OctetString>().ok())
+                .ok_or_else(|| "CMS signed attributes have no messageDigest".to_string())?;
+            let matches = message_digest.as_bytes() == algorithm.digest(&[&signed_content]).as_slice();
+            info.digest_matches = Some(info.digest_matches.unwrap_or(true) && matches);
+            attributes.to_der().map_err(|e| format!("Invalid CMS signed attributes: {}", e))?
+        }
+        None => signed_content,
+    };
+
+    info.signature_valid = match certificate {
+        Some(certificate) => verify_rsa(certificate, signer, algorithm, &signed_message),
+        None => {
+            return Err("Signer certificate is not included in the CMS message".to_string());
+        }
+    };
+    if info.signature_valid.is_none() {
+        return Err(format!(
+            "Signature algorithm {} is not verified",
+            signer.signature_algorithm.oid
+        ));
+    }
+
+    // Sem atributos assinados, só a assinatura prova o digest dos bytes
+    if signer.signed_attrs.is_none() && econtent.is_none() {
+        info.digest_matches = info.signature_valid;
+    }
+    Ok(())
+}
+
+/// Compara o hash dos bytes com o conteúdo encapsulado
+fn encapsulated_digest_matches(content_type: &ObjectIdentifier, econtent: &[u8], parts: &[&[u8]]) -> Result<bool, String> {
+    if *content_type == OID_TST_INFO {
+        let imprint = message_imprint(econtent).ok_or_else(|| "Invalid RFC 3161 timestamp token".to_string())?;
+        let algorithm = DigestAlgorithm::from_oid(&imprint.hash_algorithm.oid)
+            .ok_or_else(|| format!("Unsupported timestamp digest algorithm {}", imprint.hash_algorithm.oid))?;
+        return Ok(imprint.hashed_message.as_bytes() == algorithm.digest(parts).as_slice());
+    }
+
+    // adbe.pkcs7.sha1: o conteúdo é um OCTET STRING com o SHA-1 dos bytes
+    let hash = OctetString::from_der(econtent).map_err(|e| format!("Invalid encapsulated digest: {}", e))?;
+    Ok(hash.as_bytes() == DigestAlgorithm::Sha1.digest(parts).as_slice())
+}
+
+/// `messageImprint` de um `TSTInfo` (terceiro campo da sequência)
+fn message_imprint(tst_info: &[u8]) -> Option<MessageImprint> {
+    let fields = Vec::<Any>::from_der(tst_info).ok()?;
+    fields.get(2)?.decode_as().ok()
+}
+
+/// Certificado do signatário, por emissor e série ou pelo identificador da chave
+fn signer_certificate<'a>(certificates: Option<&'a CertificateSet>, sid: &SignerIdentifier) -> Option<&'a Certificate> {
+    certificates?.0.iter().find_map(|choice| {
+        let cms::cert::CertificateChoices::Certificate(certificate) = choice else {
+            return None;
+        };
+        let tbs = &certificate.tbs_certificate;
+        let matches = match sid {
+            SignerIdentifier::IssuerAndSerialNumber(id) => {
+                tbs.issuer == id.issuer && tbs.serial_number == id.serial_number
+            }
+            SignerIdentifier::SubjectKeyIdentifier(ski) => tbs
+                .extensions
+                .iter()
+                .flatten()
+                .filter(|extension| extension.extn_id == OID_SUBJECT_KEY_IDENTIFIER)
+                .filter_map(|extension| OctetString::from_der(extension.extn_value.as_bytes()).ok())
+                .any(|key_id| key_id == ski.0),
+        };
+        matches.then_some(certificate)
+    })
+}
+
+/// CN do titular do certificado
+pub(crate) fn common_name(certificate: &Certificate) -> Option<String> {
+    certificate
+        .tbs_certificate
+        .subject
+        .0
+        .iter()
+        .flat_map(|rdn| rdn.0.iter())
+        .find(|attribute| attribute.oid == OID_COMMON_NAME)
+        .and_then(|attribute| decode_directory_string(&attribute.value))
+}
+
+/// Decodifica um `DirectoryString` (RFC 5280) pelo tag ASN.1
+///
+/// Certificados antigos, inclusive de ACs brasileiras, ainda usam BMPString
+/// (UCS-2) e TeletexString, que o `DirectoryString` do x509-cert não cobre.
+fn decode_directory_string(value: &Any) -> Option<String> {
+    let bytes = value.value();
+    match value.tag() {
+        Tag::Utf8String | Tag::PrintableString | Tag::Ia5String | Tag::VisibleString | Tag::NumericString => {
+            Some(String::from_utf8_lossy(bytes).into_owned())
+        }
+        // Na prática os emissores gravam Latin-1, não o T.61 da norma
+        Tag::TeletexString => Some(bytes.iter().copied().map(char::from).collect()),
+        Tag::BmpString if bytes.len().is_multiple_of(2) => {
+            let units = bytes.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+            Some(char::decode_utf16(units).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)).collect())
+        }
+        _ => None,
+    }
+}
+
+/// Primeiro valor de um atributo assinado
+fn signed_attribute(signer: &SignerInfo, oid: ObjectIdentifier) -> Option<&Any> {
+    signer
+        .signed_attrs
+        .as_ref()?
+        .iter()
+        .find(|attribute| attribute.oid == oid)
+        .and_then(|attribute| attribute.values.iter().next())
+}
+
+/// Verifica uma assinatura RSA PKCS#1 v1.5 (None = outro algoritmo)
+fn verify_rsa(certificate: &Certificate, signer: &SignerInfo, algorithm: DigestAlgorithm, message: &[u8]) -> Option<bool> {
+    if !RSA_PKCS1_OIDS.contains(&signer.signature_algorithm.oid) {
+        return None;
+    }
+    let spki = certificate.tbs_certificate.subject_public_key_info.to_der().ok()?;
+    let key = RsaPublicKey::from_public_key_der(&spki).ok()?;
+
+    let hashed = algorithm.digest(&[message]);
+    let scheme = match algorithm {
+        DigestAlgorithm::Sha1 => Pkcs1v15Sign::new::<Sha1>(),
+        DigestAlgorithm::Sha256 => Pkcs1v15Sign::new::<Sha256>(),
+        DigestAlgorithm::Sha384 => Pkcs1v15Sign::new::<Sha384>(),
+        DigestAlgorithm::Sha512 => Pkcs1v15Sign::new::<Sha512>(),
+    };
+    Some(key.verify(scheme, &hashed, signer.signature.as_bytes()).is_ok())
+}
+
+fn resolve<'a>(doc: &'a Document, object: &'a Object) -> &'a Object {
+    match object {
+        Object::Reference(id) => doc.get_object(*id).unwrap_or(object),
+        _ => object,
+    }
+}
+
+fn text_string(doc: &Document, dict: &Dictionary, key: &[u8]) -> Option<String> {
+    let value = dict.get(key).ok()?;
+    resolve(doc, value).as_str().ok().map(pdf_encoding::decode_text_string)
+}
+
+/// `MessageImprint` do RFC 3161
+#[derive(Sequence)]
+struct MessageImprint {
+    hash_algorithm: AlgorithmIdentifierOwned,
+    hashed_message: OctetString,
+}
+
+/// Algoritmos de hash aceitos no digest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DigestAlgorithm {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    fn from_oid(oid: &ObjectIdentifier) -> Option<Self> {
+        match *oid {
+            OID_SHA1 => Some(Self::Sha1),
+            OID_SHA256 => Some(Self::Sha256),
+            OID_SHA384 => Some(Self::Sha384),
+            OID_SHA512 => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sha1 => "SHA-1",
+            Self::Sha256 => "SHA-256",
+            Self::Sha384 => "SHA-384",
+            Self::Sha512 => "SHA-512",
+        }
+    }
+
+    /// Hash da concatenação das partes
+    fn digest(self, parts: &[&[u8]]) -> Vec<u8> {
+        fn hash<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+            let mut hasher = D::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            hasher.finalize().to_vec()
+        }
+
+        match self {
+            Self::Sha1 => hash::<Sha1>(parts),
+            Self::Sha256 => hash::<Sha256>(parts),
+            Self::Sha384 => hash::<Sha384>(parts),
+            Self::Sha512 => hash::<Sha512>(parts),
+        }
+    }
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::test_support;
+    use der::asn1::SetOfVec;
+    use der::DecodePem;
+    use x509_cert::attr::AttributeTypeAndValue;
+    use x509_cert::name::{RdnSequence, RelativeDistinguishedName};
+
+    fn signed_sample() -> Vec<u8> {
+        test_support::sign_sample_pdf(&test_support::build_sample_pdf(2))
+    }
+
+    /// Certificado de teste com o CN trocado por `value`, gravado com o tag `tag`
+    fn certificate_with_common_name(tag: Tag, value: &[u8]) -> Certificate {
+        let mut certificate = Certificate::from_pem(test_support::TEST_SIGNER_CERTIFICATE).unwrap();
+        let common_name = AttributeTypeAndValue {
+            oid: OID_COMMON_NAME,
+            value: Any::new(tag, value).unwrap(),
+        };
+        let rdn = RelativeDistinguishedName(SetOfVec::try_from(vec![common_name]).unwrap());
+        certificate.tbs_certificate.subject = RdnSequence(vec![rdn]);
+        certificate
+    }
+
+    #[test]
+    fn test_common_name_decodes_directory_strings() {
+        let name = "São Paulo – 日本";
+        let ucs2: Vec<u8> = name.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        let certificate = certificate_with_common_name(Tag::BmpString, &ucs2);
+        assert_eq!(common_name(&certificate).as_deref(), Some(name));
+
+        let certificate = certificate_with_common_name(Tag::TeletexString, b"Jo\xE3o da Concei\xE7\xE3o");
+        assert_eq!(common_name(&certificate).as_deref(), Some("João da Conceição"));
+
+        let certificate = certificate_with_common_name(Tag::Utf8String, name.as_bytes());
+        assert_eq!(common_name(&certificate).as_deref(), Some(name));
+
+        // O certificado real usa UTF8String
+        let certificate = Certificate::from_pem(test_support::TEST_SIGNER_CERTIFICATE).unwrap();
+        assert_eq!(common_name(&certificate).as_deref(), Some("DocHub Test Signer"));
+    }
+
+    #[test]
+    fn test_unsigned_document_has_no_signatures() {
+        let mut bytes = Vec::new();
+        let mut doc = test_support::build_sample_pdf(1);
+        doc.save_to(&mut bytes).unwrap();
+        assert!(verify(&Document::load_mem(&bytes).unwrap(), &bytes).is_empty());
+    }
+
+    /// Intervalo ocupado pela string hexadecimal de `/Contents`, `<` e `>` incluídos
+    ///
+    /// A página também tem `/Contents`, mas com uma referência, não uma string.
+    fn contents_gap(bytes: &[u8]) -> (usize, usize) {
+        let start = bytes.windows(9)
+            .enumerate()
+            .filter(|(_, w)| *w == b"/Contents")
+            .map(|(key, _)| key + 9 + bytes[key + 9..].iter().take_while(|b| b.is_ascii_whitespace()).count())
+            .find(|&start| bytes[start] == b'<' && bytes[start + 1].is_ascii_hexdigit())
+            .unwrap();
+        let end = start + bytes[start..].iter().position(|&b| b == b'>').unwrap() + 1;
+        (start, end)
+    }
+
+    #[test]
+    fn test_valid_signature_covers_whole_file() {
+        let bytes = signed_sample();
+        let doc = Document::load_mem(&bytes).unwrap();
+        let signatures = verify(&doc, &bytes);
+
+        assert_eq!(signatures.len(), 1);
+        let signature = &signatures[0];
+        assert_eq!(signature.field_name.as_deref(), Some("Signature1"));
+        assert_eq!(signature.signer_name.as_deref(), Some("DocHub Test Signer"));
+        assert_eq!(signature.certificate_subject.as_deref(), Some("O=DocHub,CN=DocHub Test Signer"));
+        assert_eq!(signature.certificate_issuer.as_deref(), Some("O=DocHub,CN=DocHub Test Signer"));
+        assert_eq!(signature.signing_time.as_deref(), Some("D:20240101120000-03'00'"));
+        assert_eq!(signature.reason.as_deref(), Some("Aprovação"));
+        assert_eq!(signature.location, None);
+        assert_eq!(signature.contact_info, None);
+        assert_eq!(signature.sub_filter.as_deref(), Some("adbe.pkcs7.detached"));
+        assert_eq!(signature.digest_algorithm.as_deref(), Some("SHA-256"));
+
+        // O /ByteRange cobre o arquivo inteiro menos exatamente a string de /Contents
+        let (gap_start, gap_end) = contents_gap(&bytes);
+        let expected = [0, gap_start, gap_end, bytes.len() - gap_end].map(|value| value as u64);
+        assert_eq!(signature.byte_range, expected);
+        let signature_dict = doc.get_dictionary((signature.object_number.unwrap(), 0)).unwrap();
+        assert_eq!(signature_dict.get(b"Type").unwrap().as_name().unwrap(), b"Sig");
+        let contents = signature_dict.get(b"Contents").unwrap().as_str().unwrap();
+        let hex: String = contents.iter().map(|byte| format!("{:02X}", byte)).collect();
+        assert_eq!(&bytes[gap_start + 1..gap_end - 1], hex.as_bytes());
+        // A mensagem CMS (SEQUENCE DER) vem no início, o resto é preenchimento
+        assert_eq!(contents[0], 0x30);
+
+        assert!(signature.byte_range_valid);
+        assert!(signature.covers_whole_file);
+        assert_eq!(signature.bytes_after_signature, 0);
+        assert!(!signature.modified_after_signing());
+        assert_eq!(signature.signed_revision, Some(1));
+        assert_eq!(signature.digest_matches, Some(true));
+        assert_eq!(signature.signature_valid, Some(true));
+        assert_eq!(signature.status(), SignatureStatus::Intact);
+        assert!(signature.problems.is_empty(), "{:?}", signature.problems);
+    }
+
+    #[test]
+    fn test_bytes_appended_after_signing() {
+        let signed = signed_sample();
+        let doc = Document::load_mem(&signed).unwrap();
+        let info = doc.trailer.get(b"Info").unwrap().as_reference().unwrap().0;
+        let font = doc.objects.iter()
+            .find(|(_, object)| object.as_dict().is_ok_and(|d| d.has(b"BaseFont")))
+            .map(|(id, _)| id.0)
+            .unwrap();
+        let updated = test_support::append_incremental_update(&signed, info, font, doc.max_id + 1);
+
+        let signatures = verify(&Document::load_mem(&updated).unwrap(), &updated);
+        assert_eq!(signatures.len(), 1);
+        let signature = &signatures[0];
+        assert!(signature.is_intact());
+        // O intervalo assinado continua sendo o do arquivo original
+        let (gap_start, gap_end) = contents_gap(&signed);
+        let expected = [0, gap_start, gap_end, signed.len() - gap_end].map(|value| value as u64);
+        assert_eq!(signature.byte_range, expected);
+        assert_eq!(signature.digest_matches, Some(true));
+        assert!(!signature.covers_whole_file);
+        assert!(signature.modified_after_signing());
+        assert_eq!(signature.bytes_after_signature, (updated.len() - signed.len()) as u64);
+        assert_eq!(signature.signed_revision, Some(1));
+    }
+
+    #[test]
+    fn test_tampered_bytes_break_the_digest() {
+        let mut bytes = signed_sample();
+        // "Page 1" -> "Page 9" dentro do conteúdo assinado
+        let position = bytes.windows(6).position(|w| w == b"Page 1").unwrap();
+        bytes[position + 5] = b'9';
+
+        let signatures = verify(&Document::load_mem(&bytes).unwrap(), &bytes);
+        assert_eq!(signatures[0].digest_matches, Some(false));
+        assert_eq!(signatures[0].signature_valid, Some(true));
+        assert_eq!(signatures[0].status(), SignatureStatus::Altered);
+    }
+
+    #[test]
+    fn test_byte_range_must_exclude_exactly_the_contents() {
+        let mut bytes = signed_sample();
+        let (gap_start, gap_end) = contents_gap(&bytes);
+        let range = format!("[0 {} {} {}]", gap_start, gap_end, bytes.len() - gap_end);
+        let position = bytes.windows(range.len()).position(|w| w == range.as_bytes()).unwrap();
+
+        // Um byte a menos no primeiro intervalo: o byte antes de `<` cai na lacuna
+        let shifted = format!("[0 {} {} {}]", gap_start - 1, gap_end, bytes.len() - gap_end);
+        assert_eq!(shifted.len(), range.len());
+        bytes[position..position + range.len()].copy_from_slice(shifted.as_bytes());
+
+        let signatures = verify(&Document::load_mem(&bytes).unwrap(), &bytes);
+        let signature = &signatures[0];
+        assert_eq!(signature.byte_range, [0, gap_start - 1, gap_end, bytes.len() - gap_end].map(|value| value as u64));
+        assert!(!signature.byte_range_valid);
+        assert_eq!(signature.status(), SignatureStatus::Altered);
+        assert_eq!(signature.problems[0], "/ByteRange does not exclude exactly the /Contents string");
+    }
+
+    #[test]
+    fn test_unsupported_format_is_not_verifiable() {
+        let mut bytes = signed_sample();
+        // Mesmo tamanho: o /ByteRange continua válido
+        let position = bytes.windows(20).position(|w| w == b"/adbe.pkcs7.detached").unwrap();
+        bytes[position..position + 20].copy_from_slice(b"/adbe.x509.rsa_sha1 ");
+
+        let signatures = verify(&Document::load_mem(&bytes).unwrap(), &bytes);
+        let signature = &signatures[0];
+        assert!(signature.byte_range_valid);
+        assert_eq!(signature.digest_matches, None);
+        assert_eq!(signature.status(), SignatureStatus::NotVerifiable);
+        assert!(!signature.is_intact());
+        assert_eq!(signature.problems, vec!["adbe.x509.rsa_sha1 signatures (raw PKCS#1) are not verified"]);
+    }
+
+    #[test]
+    fn test_byte_range_outside_file_is_reported() {
+        assert_eq!(signed_ranges(&[0, 10, 20, 10], 30), Some(vec![(0, 10), (20, 10)]));
+        assert_eq!(signed_ranges(&[0, 10, 20, 20], 30), None);
+        assert_eq!(signed_ranges(&[0, 10, 5, 5], 30), None);
+        assert_eq!(signed_ranges(&[0, 10, 20], 30), None);
+        assert!(is_hex_string(b"<0a1B 00>"));
+        assert!(!is_hex_string(b"<0a1B> "));
+    }
+}
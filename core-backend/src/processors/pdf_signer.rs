@@ -0,0 +1,1045 @@
+//! Assinatura digital PAdES de PDFs para o DocHub
+//!
+//! Assina um PDF com um certificado PKCS#12 (`.p12`/`.pfx`) do disco do
+//! usuário, como os certificados A1 da ICP-Brasil, sem depender de serviços
+//! externos. A assinatura segue o perfil PAdES-B (`ETSI.CAdES.detached`).
+//!
+//! ## Funcionalidades:
+//! - Leitura de PKCS#12 com PBES2/AES e com os algoritmos legados (3DES, RC2)
+//! - Assinatura visível (página e retângulo) ou invisível
+//! - Motivo, local e contato do signatário
+//! - Gravação em atualização incremental: o arquivo original e as assinaturas
+//!   que ele já tem continuam intactos
+//!
+//! A mensagem CMS leva os atributos `contentType`, `messageDigest` e
+//! `signingCertificateV2`; a data da assinatura fica em `/M`, como pede o PAdES.
+
+// Erros de assinatura sobem como `AppError`; seu tamanho vem de `error_handling`
+#![allow(clippy::result_large_err)]
+
+use chrono::{DateTime, Local};
+use cms::cert::{CertificateChoices, IssuerAndSerialNumber};
+use cms::content_info::{CmsVersion, ContentInfo};
+use cms::signed_data::{
+    CertificateSet, EncapsulatedContentInfo, SignedData, SignerIdentifier, SignerInfo, SignerInfos,
+};
+use der::asn1::{Any, Null, ObjectIdentifier, OctetString, SetOfVec};
+use der::{Decode, Encode, Sequence};
+use lopdf::{dictionary, Document, Object, ObjectId, Stream, StringFormat};
+use p12_keystore::KeyStore;
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use rsa::{Pkcs1v15Sign, RsaPrivateKey, RsaPublicKey};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tracing::{info, error, instrument};
+use x509_cert::attr::Attribute;
+use x509_cert::spki::AlgorithmIdentifierOwned;
+use x509_cert::Certificate;
+
+use crate::utils::error_handling::{Result, AppError, PdfError};
+use crate::utils::error_handling::validate;
+use crate::api::file_handlers::FileHandler;
+use crate::processors::pdf_encoding;
+use crate::processors::pdf_signatures;
+use crate::processors::pdf_writer;
+use crate::processors::runtime::ProcessorRuntime;
+
+/// Valor provisório de `/ByteRange`: dez dígitos, o máximo que o valor final ocupa
+const BYTE_RANGE_PLACEHOLDER: i64 = 9_999_999_999;
+
+/// Tamanho máximo da fonte do texto da aparência visível
+const APPEARANCE_FONT_SIZE: f64 = 10.0;
+
+/// Flags do widget: Print (4) + Locked (128)
+const WIDGET_FLAGS: i64 = 132;
+
+/// `/SigFlags` do formulário: SignaturesExist (1) + AppendOnly (2)
+const SIG_FLAGS: i64 = 3;
+
+const OID_DATA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.7.1");
+const OID_SIGNED_DATA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.7.2");
+const OID_CONTENT_TYPE: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.3");
+const OID_MESSAGE_DIGEST: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.4");
+const OID_SIGNING_CERTIFICATE_V2: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.2.47");
+const OID_SHA256: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.1");
+const OID_SHA256_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.11");
+
+/// Configurações da assinatura
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SignConfig {
+    /// Página do campo de assinatura (1 = primeira)
+    pub page: u32,
+    /// Retângulo visível `[x1, y1, x2, y2]` em pontos (None = assinatura invisível)
+    pub rect: Option<[f64; 4]>,
+    /// Motivo da assinatura (`/Reason`)
+    pub reason: Option<String>,
+    /// Local da assinatura (`/Location`)
+    pub location: Option<String>,
+    /// Contato do signatário (`/ContactInfo`)
+    pub contact_info: Option<String>,
+    /// Nome do campo de assinatura (None = próximo "SignatureN" livre)
+    pub field_name: Option<String>,
+    /// Permite substituir o PDF de origem pela versão assinada
+    pub overwrite_input: bool,
+}
+
+impl Default for SignConfig {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            rect: None,
+            reason: None,
+            location: None,
+            contact_info: None,
+            field_name: None,
+            overwrite_input: false,
+        }
+    }
+}
+
+/// Request para assinatura de PDF
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SignRequest {
+    /// Caminho do PDF de origem
+    pub file_path: PathBuf,
+    /// Caminho do PDF assinado
+    pub output_path: PathBuf,
+    /// Caminho do certificado PKCS#12 (`.p12`/`.pfx`)
+    pub certificate_path: PathBuf,
+    /// Senha do certificado (nunca serializada nem registrada em log)
+    #[serde(default, skip_serializing)]
+    pub password: String,
+    /// Configurações da assinatura
+    #[serde(default)]
+    pub config: SignConfig,
+}
+
+impl fmt::Debug for SignRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignRequest")
+            .field("file_path", &self.file_path)
+            .field("output_path", &self.output_path)
+            .field("certificate_path", &self.certificate_path)
+            .field("password", &"<redacted>")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl SignRequest {
+    /// Cria um SignRequest a partir de JSON
+    pub fn from_value(data: &Value) -> Result<Self> {
+        let file_path = data["file"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'file' field"))?;
+
+        let output_path = data["output"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'output' field"))?;
+
+        let certificate_path = data["certificate"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'certificate' field"))?;
+
+        // Certificados sem senha são raros, mas válidos
+        let password = data.get("password").and_then(|v| v.as_str()).unwrap_or("").to_string();
+
+        // Configurações opcionais
+        let config = if let Some(config_val) = data.get("config") {
+            serde_json::from_value(config_val.clone())
+                .map_err(|e| AppError::validation(format!("Invalid config: {}", e)))?
+        } else {
+            SignConfig::default()
+        };
+
+        Ok(Self {
+            file_path,
+            output_path,
+            certificate_path,
+            password,
+            config,
+        })
+    }
+
+    /// Valida a request
+    pub fn validate(&self, file_handler: &FileHandler) -> Result<()> {
+        let metadata = file_handler.validate_file(self.file_path.to_str().unwrap_or(""))?;
+        file_handler.validate_file(self.certificate_path.to_str().unwrap_or(""))?;
+
+        // A origem só é substituída quando o chamador pede explicitamente
+        validate(
+            self.config.overwrite_input || !self.writes_over_input(),
+            AppError::validation(
+                "Output path must differ from the input file unless 'overwrite_input' is set",
+            ),
+        )?;
+        validate(
+            self.config.page >= 1,
+            AppError::validation("Page numbers start at 1"),
+        )?;
+        if let Some([x1, y1, x2, y2]) = self.config.rect {
+            validate(
+                x2 > x1 && y2 > y1,
+                AppError::validation("Signature rect must be [x1, y1, x2, y2] with x2 > x1 and y2 > y1"),
+            )?;
+        }
+
+        info!(
+            path = %self.file_path.display(),
+            size = metadata.len(),
+            "Input file validated for sign"
+        );
+
+        Ok(())
+    }
+
+    /// Indica se `output_path` aponta para o mesmo arquivo que `file_path`
+    fn writes_over_input(&self) -> bool {
+        match (self.file_path.canonicalize(), self.output_path.canonicalize()) {
+            (Ok(input), Ok(output)) => input == output,
+            _ => self.output_path == self.file_path,
+        }
+    }
+}
+
+/// Resultado da assinatura
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignResult {
+    /// Caminho do PDF assinado
+    pub output_path: PathBuf,
+    /// Nome do campo de assinatura criado
+    pub field_name: String,
+    /// Signatário (CN do certificado)
+    pub signer_name: Option<String>,
+    /// Titular do certificado
+    pub certificate_subject: String,
+    /// Emissor do certificado
+    pub certificate_issuer: String,
+    /// Data da assinatura gravada em `/M`
+    pub signing_time: String,
+    /// A assinatura tem aparência na página?
+    pub visible: bool,
+    /// `/ByteRange` gravado
+    pub byte_range: Vec<u64>,
+    /// Tamanho do PDF assinado (em bytes)
+    pub file_size: u64,
+    /// Tempo total de processamento (em milissegundos)
+    pub processing_time_ms: u128,
+}
+
+/// Processador de assinatura de PDFs
+#[derive(Debug, Clone)]
+pub struct PdfSigner {
+    runtime: ProcessorRuntime,
+}
+
+processor_constructors!(PdfSigner);
+
+impl PdfSigner {
+    /// Assina um PDF com o certificado PKCS#12 da request
+    #[instrument(name = "sign_pdf", skip(self, request), fields(
+        input_file = %request.file_path.display(),
+        output_file = %request.output_path.display()
+    ))]
+    pub async fn sign_pdf(&self, request: SignRequest) -> Result<SignResult> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.sign_pdf_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `sign_pdf`, executado no pool de CPU
+    fn sign_pdf_blocking(&self, request: SignRequest) -> Result<SignResult> {
+        let start_time = Instant::now();
+
+        info!("Starting PDF sign process");
+
+        // 1. Valida a request
+        request.validate(self.runtime.file_handler())?;
+
+        // 2. Carrega o certificado e o documento
+        let p12 = std::fs::read(&request.certificate_path)
+            .map_err(|e| AppError::from_io_error("reading certificate", request.certificate_path.clone(), e))?;
+        let credentials = SigningCredentials::from_pkcs12(&p12, &request.password)
+            .map_err(|reason| AppError::validation(format!("Cannot use certificate: {}", reason)))?;
+
+        let source = std::fs::read(&request.file_path)
+            .map_err(|e| AppError::from_io_error("reading PDF", request.file_path.clone(), e))?;
+        let mut doc = self.load_document(&source, &request.file_path)?;
+
+        validate(
+            doc.trailer.get(b"Encrypt").is_err(),
+            AppError::validation("Encrypted documents cannot be signed"),
+        )?;
+
+        // 3. Campo, widget e dicionário `/Sig` com espaço reservado para a assinatura
+        let signing_time = Local::now();
+        let field = add_signature_field(&mut doc, &request.config, &credentials, &signing_time)?;
+
+        // 4. Atualização incremental, preservando o arquivo e as assinaturas anteriores
+        let mut bytes = pdf_writer::incremental_update(&source, &doc, &field.changed)
+            .map_err(|reason| AppError::Pdf(PdfError::ProcessingFailed { reason }))?;
+        let byte_range = fill_signature(&mut bytes, source.len(), field.signature_id, &credentials)
+            .map_err(|reason| AppError::Pdf(PdfError::ProcessingFailed { reason }))?;
+
+        // 5. Grava o PDF assinado (um arquivo existente só é substituído se for a origem, com opt-in)
+        let file_handler = self.runtime.file_handler();
+        let output = request.output_path.to_str().unwrap_or("");
+        if request.config.overwrite_input && request.writes_over_input() {
+            file_handler.write_file(output, &bytes)?;
+        } else {
+            file_handler.write_new_file(output, &bytes)?;
+        }
+
+        let result = SignResult {
+            output_path: request.output_path.clone(),
+            field_name: field.name,
+            signer_name: credentials.signer_name(),
+            certificate_subject: credentials.certificate.tbs_certificate.subject.to_string(),
+            certificate_issuer: credentials.certificate.tbs_certificate.issuer.to_string(),
+            signing_time: pdf_encoding::format_pdf_date(&signing_time),
+            visible: request.config.rect.is_some(),
+            byte_range,
+            file_size: bytes.len() as u64,
+            processing_time_ms: start_time.elapsed().as_millis(),
+        };
+
+        info!(
+            output = %result.output_path.display(),
+            field = %result.field_name,
+            visible = result.visible,
+            processing_time_ms = result.processing_time_ms,
+            "PDF sign completed"
+        );
+
+        Ok(result)
+    }
+
+    // ==================== MÉTODOS PRIVADOS ====================
+
+    /// Carrega um documento PDF já lido em memória
+    fn load_document(&self, bytes: &[u8], path: &Path) -> Result<Document> {
+        info!(path = %path.display(), "Loading PDF document");
+
+        Document::load_mem(bytes)
+            .map_err(|e| {
+                error!(path = %path.display(), error = %e, "Failed to load PDF");
+                AppError::Pdf(PdfError::CorruptedPdf {
+                    path: path.to_path_buf(),
+                })
+            })
+    }
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+/// Chave privada e certificados extraídos do PKCS#12
+struct SigningCredentials {
+    key: RsaPrivateKey,
+    /// Certificado do signatário
+    certificate: Certificate,
+    /// Cadeia completa, começando pelo certificado do signatário
+    chain: Vec<Certificate>,
+}
+
+impl SigningCredentials {
+    /// Lê a primeira chave privada do PKCS#12 e o certificado correspondente
+    fn from_pkcs12(data: &[u8], password: &str) -> std::result::Result<Self, String> {
+        let keystore = KeyStore::from_pkcs12(data, password)
+            .map_err(|e| format!("invalid PKCS#12 file or wrong password ({})", e))?;
+        let (_, key_chain) = keystore
+            .private_key_chain()
+            .ok_or_else(|| "PKCS#12 file has no private key".to_string())?;
+
+        let key = RsaPrivateKey::from_pkcs8_der(key_chain.key())
+            .map_err(|_| "only RSA keys are supported".to_string())?;
+        let chain = key_chain
+            .chain()
+            .iter()
+            .map(|certificate| Certificate::from_der(certificate.as_der()))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| format!("invalid certificate ({})", e))?;
+
+        // O certificado do signatário é o que tem a chave pública do par
+        let public_key = key.to_public_key();
+        let certificate = chain
+            .iter()
+            .find(|certificate| {
+                certificate
+                    .tbs_certificate
+                    .subject_public_key_info
+                    .to_der()
+                    .ok()
+                    .and_then(|spki| RsaPublicKey::from_public_key_der(&spki).ok())
+                    .is_some_and(|key| key == public_key)
+            })
+            .cloned()
+            .ok_or_else(|| "no certificate matches the private key".to_string())?;
+
+        let validity = &certificate.tbs_certificate.validity;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        if now > validity.not_after.to_unix_duration() {
+            return Err(format!("certificate expired on {}", validity.not_after));
+        }
+        if now < validity.not_before.to_unix_duration() {
+            return Err(format!("certificate is not valid before {}", validity.not_before));
+        }
+
+        let mut ordered = vec![certificate.clone()];
+        ordered.extend(chain.into_iter().filter(|other| *other != certificate));
+        Ok(Self { key, certificate, chain: ordered })
+    }
+
+    /// CN do certificado do signatário
+    fn signer_name(&self) -> Option<String> {
+        pdf_signatures::common_name(&self.certificate)
+    }
+
+    /// Mensagem CMS SignedData destacada (CAdES-BES) para o digest dos bytes assinados
+    fn signed_data(&self, digest: &[u8]) -> std::result::Result<Vec<u8>, String> {
+        let der_error = |e: der::Error| format!("Failed to encode CMS message: {}", e);
+        let sha256 = AlgorithmIdentifierOwned { oid: OID_SHA256, parameters: None };
+
+        let certificate_hash = Sha256::digest(self.certificate.to_der().map_err(der_error)?);
+        let signing_certificate = SigningCertificateV2 {
+            certs: vec![EssCertIdV2 { cert_hash: OctetString::new(certificate_hash.as_slice()).map_err(der_error)? }],
+        };
+        let signed_attrs = SetOfVec::try_from(vec![
+            attribute(OID_CONTENT_TYPE, Any::encode_from(&OID_DATA).map_err(der_error)?)?,
+            attribute(OID_MESSAGE_DIGEST, Any::encode_from(&OctetString::new(digest).map_err(der_error)?).map_err(der_error)?)?,
+            attribute(OID_SIGNING_CERTIFICATE_V2, Any::encode_from(&signing_certificate).map_err(der_error)?)?,
+        ])
+        .map_err(der_error)?;
+
+        // A assinatura cobre a codificação DER do SET de atributos
+        let hashed = Sha256::digest(signed_attrs.to_der().map_err(der_error)?);
+        let signature = self
+            .key
+            .sign(Pkcs1v15Sign::new::<Sha256>(), &hashed)
+            .map_err(|e| format!("RSA signing failed: {}", e))?;
+
+        let signer = SignerInfo {
+            version: CmsVersion::V1,
+            sid: SignerIdentifier::IssuerAndSerialNumber(IssuerAndSerialNumber {
+                issuer: self.certificate.tbs_certificate.issuer.clone(),
+                serial_number: self.certificate.tbs_certificate.serial_number.clone(),
+            }),
+            digest_alg: sha256.clone(),
+            signed_attrs: Some(signed_attrs),
+            signature_algorithm: AlgorithmIdentifierOwned {
+                oid: OID_SHA256_WITH_RSA,
+                parameters: Some(Any::from(Null)),
+            },
+            signature: OctetString::new(signature).map_err(der_error)?,
+            unsigned_attrs: None,
+        };
+        let certificates = self.chain.iter().cloned().map(CertificateChoices::Certificate).collect::<Vec<_>>();
+        let signed_data = SignedData {
+            version: CmsVersion::V1,
+            digest_algorithms: SetOfVec::try_from(vec![sha256]).map_err(der_error)?,
+            encap_content_info: EncapsulatedContentInfo { econtent_type: OID_DATA, econtent: None },
+            certificates: Some(CertificateSet(SetOfVec::try_from(certificates).map_err(der_error)?)),
+            crls: None,
+            signer_infos: SignerInfos(SetOfVec::try_from(vec![signer]).map_err(der_error)?),
+        };
+
+        ContentInfo {
+            content_type: OID_SIGNED_DATA,
+            content: Any::encode_from(&signed_data).map_err(der_error)?,
+        }
+        .to_der()
+        .map_err(der_error)
+    }
+
+    /// Tamanho da mensagem CMS: não depende do digest, só da chave e dos certificados
+    fn signed_data_len(&self) -> std::result::Result<usize, String> {
+        Ok(self.signed_data(&[0; 32])?.len())
+    }
+}
+
+/// `SigningCertificateV2` (RFC 5035), com o hash SHA-256 padrão e sem `issuerSerial`
+#[derive(Sequence)]
+struct SigningCertificateV2 {
+    certs: Vec<EssCertIdV2>,
+}
+
+#[derive(Sequence)]
+struct EssCertIdV2 {
+    cert_hash: OctetString,
+}
+
+fn attribute(oid: ObjectIdentifier, value: Any) -> std::result::Result<Attribute, String> {
+    let values = SetOfVec::try_from(vec![value]).map_err(|e| format!("Failed to encode CMS attribute: {}", e))?;
+    Ok(Attribute { oid, values })
+}
+
+/// Campo de assinatura acrescentado ao documento
+struct SignatureField {
+    name: String,
+    signature_id: ObjectId,
+    /// Objetos novos e alterados, gravados na atualização incremental
+    changed: Vec<ObjectId>,
+}
+
+/// Cria o dicionário `/Sig`, o widget e a aparência, e liga o campo à página e ao `/AcroForm`
+fn add_signature_field(
+    doc: &mut Document,
+    config: &SignConfig,
+    credentials: &SigningCredentials,
+    signing_time: &DateTime<Local>,
+) -> Result<SignatureField> {
+    let page_id = *doc
+        .get_pages()
+        .get(&config.page)
+        .ok_or_else(|| AppError::validation(format!("Page {} does not exist", config.page)))?;
+    let reserved = credentials
+        .signed_data_len()
+        .map_err(|reason| AppError::Pdf(PdfError::ProcessingFailed { reason }))?;
+    let mut changed = Vec::new();
+
+    // `/AcroForm` indireto, para que só ele (e não o catálogo) precise ser regravado
+    let catalog_id = doc
+        .trailer
+        .get(b"Root")
+        .and_then(Object::as_reference)
+        .map_err(|_| AppError::Pdf(PdfError::ProcessingFailed { reason: "Document has no catalog".to_string() }))?;
+    let acro_form_id = match doc.get_dictionary(catalog_id).ok().and_then(|c| c.get(b"AcroForm").ok()) {
+        Some(Object::Reference(id)) => *id,
+        existing => {
+            let acro_form = existing.and_then(|form| form.as_dict().ok()).cloned().unwrap_or_default();
+            let id = doc.add_object(acro_form);
+            doc.get_dictionary_mut(catalog_id).map_err(pdf_error)?.set("AcroForm", id);
+            changed.push(catalog_id);
+            id
+        }
+    };
+
+    let name = match &config.field_name {
+        Some(name) => {
+            validate(
+                !field_names(doc, acro_form_id).contains(name),
+                AppError::validation(format!("A field named '{}' already exists", name)),
+            )?;
+            name.clone()
+        }
+        None => {
+            let taken = field_names(doc, acro_form_id);
+            (1..).map(|n| format!("Signature{}", n)).find(|name| !taken.contains(name)).unwrap_or_default()
+        }
+    };
+
+    let mut signature = dictionary! {
+        "Type" => "Sig",
+        "Filter" => "Adobe.PPKLite",
+        "SubFilter" => "ETSI.CAdES.detached",
+        "ByteRange" => vec![0.into(), BYTE_RANGE_PLACEHOLDER.into(), BYTE_RANGE_PLACEHOLDER.into(), BYTE_RANGE_PLACEHOLDER.into()],
+        "Contents" => Object::String(vec![0; reserved], StringFormat::Hexadecimal),
+        "M" => Object::string_literal(pdf_encoding::format_pdf_date(signing_time)),
+    };
+    for (key, value) in [
+        ("Reason", &config.reason),
+        ("Location", &config.location),
+        ("ContactInfo", &config.contact_info),
+    ] {
+        if let Some(value) = value {
+            signature.set(key, text_string(value));
+        }
+    }
+    let signature_id = doc.add_object(signature);
+
+    let rect = config.rect.unwrap_or([0.0; 4]);
+    let appearance = appearance_stream(rect, credentials, config, signing_time);
+    let appearance_id = doc.add_object(appearance);
+    let widget_id = doc.add_object(dictionary! {
+        "Type" => "Annot",
+        "Subtype" => "Widget",
+        "FT" => "Sig",
+        "T" => text_string(&name),
+        "V" => signature_id,
+        "F" => WIDGET_FLAGS,
+        "P" => page_id,
+        "Rect" => rect.iter().map(|&value| Object::Real(value as f32)).collect::<Vec<_>>(),
+        "AP" => dictionary! { "N" => appearance_id },
+    });
+    changed.extend([signature_id, appearance_id, widget_id]);
+
+    // O widget entra em `/Annots` da página e em `/Fields` do formulário
+    changed.push(append_to_array(doc, page_id, b"Annots", widget_id)?);
+    changed.push(append_to_array(doc, acro_form_id, b"Fields", widget_id)?);
+    doc.get_dictionary_mut(acro_form_id).map_err(pdf_error)?.set("SigFlags", SIG_FLAGS);
+    changed.push(acro_form_id);
+
+    Ok(SignatureField { name, signature_id, changed })
+}
+
+/// Acrescenta uma referência ao array `key` de `owner` e retorna o objeto alterado
+fn append_to_array(doc: &mut Document, owner: ObjectId, key: &[u8], item: ObjectId) -> Result<ObjectId> {
+    let current = doc.get_dictionary(owner).map_err(pdf_error)?.get(key).ok().cloned();
+    match current {
+        Some(Object::Reference(array_id)) => {
+            let array = doc.get_object_mut(array_id).and_then(Object::as_array_mut).map_err(pdf_error)?;
+            array.push(item.into());
+            Ok(array_id)
+        }
+        Some(Object::Array(mut array)) => {
+            array.push(item.into());
+            doc.get_dictionary_mut(owner).map_err(pdf_error)?.set(key, array);
+            Ok(owner)
+        }
+        _ => {
+            doc.get_dictionary_mut(owner).map_err(pdf_error)?.set(key, vec![item.into()]);
+            Ok(owner)
+        }
+    }
+}
+
+/// Nomes dos campos de primeiro nível do formulário
+fn field_names(doc: &Document, acro_form_id: ObjectId) -> HashSet<String> {
+    let fields = doc
+        .get_dictionary(acro_form_id)
+        .ok()
+        .and_then(|form| form.get(b"Fields").ok())
+        .and_then(|fields| match fields {
+            Object::Reference(id) => doc.get_object(*id).ok(),
+            _ => Some(fields),
+        })
+        .and_then(|fields| fields.as_array().ok());
+
+    fields
+        .into_iter()
+        .flatten()
+        .filter_map(|field| match field {
+            Object::Reference(id) => doc.get_dictionary(*id).ok(),
+            _ => field.as_dict().ok(),
+        })
+        .filter_map(|field| field.get(b"T").ok().and_then(|t| t.as_str().ok()))
+        .map(pdf_encoding::decode_text_string)
+        .collect()
+}
+
+/// Aparência do widget: borda e texto com signatário, data, motivo e local
+///
+/// Assinaturas invisíveis recebem uma aparência vazia, exigida pelo PDF/A.
+fn appearance_stream(
+    rect: [f64; 4],
+    credentials: &SigningCredentials,
+    config: &SignConfig,
+    signing_time: &DateTime<Local>,
+) -> Stream {
+    let width = (rect[2] - rect[0]).max(0.0);
+    let height = (rect[3] - rect[1]).max(0.0);
+    let bbox = vec![0.into(), 0.into(), Object::Real(width as f32), Object::Real(height as f32)];
+    if width == 0.0 || height == 0.0 {
+        return Stream::new(dictionary! { "Type" => "XObject", "Subtype" => "Form", "BBox" => bbox }, Vec::new());
+    }
+
+    let mut lines = vec![
+        format!("Digitally signed by {}", credentials.signer_name().unwrap_or_else(|| "unknown signer".to_string())),
+        format!("Date: {}", signing_time.format("%Y-%m-%d %H:%M:%S %:z")),
+    ];
+    if let Some(reason) = &config.reason {
+        lines.push(format!("Reason: {}", reason));
+    }
+    if let Some(location) = &config.location {
+        lines.push(format!("Location: {}", location));
+    }
+
+    let font_size = APPEARANCE_FONT_SIZE.min((height - 4.0) / (lines.len() as f64 * 1.2)).max(1.0);
+    let mut content = format!(
+        "q 0.5 w 0 0 {w:.2} {h:.2} re S Q\nq 1 1 {iw:.2} {ih:.2} re W n\nBT /F1 {size:.2} Tf {leading:.2} TL 3 {top:.2} Td\n",
+        w = width,
+        h = height,
+        iw = (width - 2.0).max(0.0),
+        ih = (height - 2.0).max(0.0),
+        size = font_size,
+        leading = font_size * 1.2,
+        top = height - 2.0 - font_size,
+    )
+    .into_bytes();
+    for (index, line) in lines.iter().enumerate() {
+        if index > 0 {
+            content.extend_from_slice(b"T* ");
+        }
+        pdf_writer::write_object(&mut content, &Object::String(win_ansi(line), StringFormat::Literal), &|id| Some(id));
+        content.extend_from_slice(b" Tj\n");
+    }
+    content.extend_from_slice(b"ET\nQ\n");
+
+    Stream::new(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Form",
+            "BBox" => bbox,
+            "Resources" => dictionary! {
+                "Font" => dictionary! {
+                    "F1" => dictionary! {
+                        "Type" => "Font",
+                        "Subtype" => "Type1",
+                        "BaseFont" => "Helvetica",
+                        "Encoding" => "WinAnsiEncoding",
+                    },
+                },
+            },
+        },
+        content,
+    )
+}
+
+/// Preenche `/ByteRange` e `/Contents` do dicionário `/Sig` gravado na atualização
+///
+/// Retorna o `/ByteRange` final. O hash cobre o arquivo inteiro, exceto a
+/// string hexadecimal de `/Contents`.
+fn fill_signature(
+    bytes: &mut [u8],
+    update_start: usize,
+    signature_id: ObjectId,
+    credentials: &SigningCredentials,
+) -> std::result::Result<Vec<u64>, String> {
+    let header = format!("{} {} obj", signature_id.0, signature_id.1);
+    let object_start = update_start + find(&bytes[update_start..], header.as_bytes())
+        .ok_or_else(|| "Signature dictionary not found in the update".to_string())?;
+
+    let key = object_start + find(&bytes[object_start..], b"/ByteRange")
+        .ok_or_else(|| "/ByteRange placeholder not found".to_string())?;
+    let range_start = key + find(&bytes[key..], b"[").ok_or_else(|| "/ByteRange placeholder not found".to_string())?;
+    let range_end = range_start + find(&bytes[range_start..], b"]").ok_or_else(|| "/ByteRange placeholder not found".to_string())? + 1;
+
+    let key = object_start + find(&bytes[object_start..], b"/Contents")
+        .ok_or_else(|| "/Contents placeholder not found".to_string())?;
+    let contents_start = key + find(&bytes[key..], b"<").ok_or_else(|| "/Contents placeholder not found".to_string())?;
+    let contents_end = contents_start + find(&bytes[contents_start..], b">").ok_or_else(|| "/Contents placeholder not found".to_string())? + 1;
+
+    let byte_range = vec![0, contents_start as u64, contents_end as u64, (bytes.len() - contents_end) as u64];
+    let range = format!("[{} {} {} {}]", byte_range[0], byte_range[1], byte_range[2], byte_range[3]);
+    if range.len() > range_end - range_start {
+        return Err("File is too large for the /ByteRange placeholder".to_string());
+    }
+    let padded = format!("{:width$}", range, width = range_end - range_start);
+    bytes[range_start..range_end].copy_from_slice(padded.as_bytes());
+
+    let digest = Sha256::new()
+        .chain_update(&bytes[..contents_start])
+        .chain_update(&bytes[contents_end..])
+        .finalize();
+    let message = credentials.signed_data(&digest)?;
+    let hex: String = message.iter().map(|byte| format!("{:02X}", byte)).collect();
+    if hex.len() > contents_end - contents_start - 2 {
+        return Err("CMS message does not fit in the reserved /Contents".to_string());
+    }
+    bytes[contents_start + 1..contents_start + 1 + hex.len()].copy_from_slice(hex.as_bytes());
+
+    Ok(byte_range)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Text string PDF (ver `pdf_encoding::encode_text_string`)
+fn text_string(text: &str) -> Object {
+    Object::String(pdf_encoding::encode_text_string(text), StringFormat::Literal)
+}
+
+/// Texto em WinAnsiEncoding para a fonte padrão da aparência ("?" fora do Latin-1)
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c as u32 {
+            code @ (0x20..=0x7E | 0xA0..=0xFF) => code as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+fn pdf_error(e: lopdf::Error) -> AppError {
+    AppError::Pdf(PdfError::ProcessingFailed { reason: e.to_string() })
+}
+
+// ==================== FUNÇÕES DE CONVENIÊNCIA ====================
+
+/// Função de conveniência para assinatura de PDF
+#[instrument(name = "sign_pdf", skip(data, runtime))]
+pub async fn sign_pdf(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
+    let request = SignRequest::from_value(&data)?;
+    let signer = PdfSigner::with_runtime(runtime);
+    let result = signer.sign_pdf(request).await?;
+
+    serde_json::to_value(result)
+        .map_err(|e| AppError::serialization(format!("Failed to serialize result: {}", e)))
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::test_support;
+    use crate::utils::error_handling::IoError;
+    use p12_keystore::EncryptionAlgorithm;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn sign(temp_dir: &TempDir, input: &[u8], config: Value) -> (SignResult, Vec<u8>) {
+        let file = temp_dir.path().join("input.pdf");
+        let output = temp_dir.path().join("signed.pdf");
+        let certificate = temp_dir.path().join("signer.pfx");
+        std::fs::write(&file, input).unwrap();
+        test_support::write_test_pkcs12(&certificate, "secret", EncryptionAlgorithm::PbeWithHmacSha256AndAes256);
+
+        let request = SignRequest::from_value(&json!({
+            "file": file,
+            "output": output,
+            "certificate": certificate,
+            "password": "secret",
+            "config": config,
+        }))
+        .unwrap();
+        let result = PdfSigner::new().sign_pdf_blocking(request).unwrap();
+        let bytes = std::fs::read(&output).unwrap();
+        (result, bytes)
+    }
+
+    fn sample_bytes() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        test_support::build_sample_pdf(2).save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// Intervalo da última string hexadecimal de `/Contents`, `<` e `>` incluídos
+    fn last_contents_gap(bytes: &[u8]) -> (usize, usize) {
+        let start = bytes.windows(9)
+            .enumerate()
+            .filter(|(_, w)| *w == b"/Contents")
+            .map(|(key, _)| key + 9 + bytes[key + 9..].iter().take_while(|b| b.is_ascii_whitespace()).count())
+            .filter(|&start| bytes[start] == b'<' && bytes[start + 1].is_ascii_hexdigit())
+            .last()
+            .unwrap();
+        let end = start + bytes[start..].iter().position(|&b| b == b'>').unwrap() + 1;
+        (start, end)
+    }
+
+    #[test]
+    fn test_invisible_signature_verifies() {
+        let temp_dir = TempDir::new().unwrap();
+        let original = sample_bytes();
+        let (result, signed) = sign(&temp_dir, &original, json!({ "reason": "Aprovação do contrato" }));
+
+        // A atualização incremental preserva o arquivo original byte a byte
+        assert_eq!(&signed[..original.len()], &original[..]);
+        assert_eq!(result.field_name, "Signature1");
+        assert_eq!(result.signer_name.as_deref(), Some("DocHub Test Signer"));
+        assert_eq!(result.certificate_subject, "O=DocHub,CN=DocHub Test Signer");
+        assert_eq!(result.certificate_issuer, "O=DocHub,CN=DocHub Test Signer");
+        assert_eq!(result.file_size, signed.len() as u64);
+        assert!(!result.visible);
+
+        // O /ByteRange exclui exatamente a string hexadecimal de /Contents
+        let (gap_start, gap_end) = last_contents_gap(&signed);
+        assert!(gap_start > original.len());
+        let expected = [0, gap_start, gap_end, signed.len() - gap_end].map(|value| value as u64);
+        assert_eq!(result.byte_range, expected);
+
+        // Dicionário /Sig e widget relidos do arquivo
+        let doc = Document::load_mem(&signed).unwrap();
+        let page = doc.get_pages()[&1];
+        let annots = doc.get_dictionary(page).unwrap().get(b"Annots").unwrap().as_array().unwrap();
+        assert_eq!(annots.len(), 1);
+        let widget_id = annots[0].as_reference().unwrap();
+        let widget = doc.get_dictionary(widget_id).unwrap();
+        assert_eq!(widget.get(b"FT").unwrap().as_name().unwrap(), b"Sig");
+        assert_eq!(widget.get(b"T").unwrap().as_str().unwrap(), b"Signature1");
+        assert_eq!(widget.get(b"F").unwrap().as_i64().unwrap(), WIDGET_FLAGS);
+        assert_eq!(widget.get(b"P").unwrap().as_reference().unwrap(), page);
+        let rect: Vec<f32> = widget.get(b"Rect").unwrap().as_array().unwrap().iter().map(|v| v.as_float().unwrap()).collect();
+        assert_eq!(rect, vec![0.0; 4]);
+
+        let signature = doc.get_dictionary(widget.get(b"V").unwrap().as_reference().unwrap()).unwrap();
+        assert_eq!(signature.get(b"Type").unwrap().as_name().unwrap(), b"Sig");
+        assert_eq!(signature.get(b"Filter").unwrap().as_name().unwrap(), b"Adobe.PPKLite");
+        assert_eq!(signature.get(b"SubFilter").unwrap().as_name().unwrap(), b"ETSI.CAdES.detached");
+        assert_eq!(signature.get(b"M").unwrap().as_str().unwrap(), result.signing_time.as_bytes());
+        let reason = signature.get(b"Reason").unwrap().as_str().unwrap();
+        assert_eq!(pdf_encoding::decode_text_string(reason), "Aprovação do contrato");
+        assert!(!signature.has(b"Location") && !signature.has(b"ContactInfo"));
+        let byte_range: Vec<u64> = signature.get(b"ByteRange").unwrap().as_array().unwrap()
+            .iter().map(|value| value.as_i64().unwrap() as u64).collect();
+        assert_eq!(byte_range, result.byte_range);
+        let contents = signature.get(b"Contents").unwrap().as_str().unwrap();
+        let hex: String = contents.iter().map(|byte| format!("{:02X}", byte)).collect();
+        assert_eq!(&signed[gap_start + 1..gap_end - 1], hex.as_bytes());
+
+        let catalog = doc.catalog().unwrap();
+        let acro_form = doc.get_dictionary(catalog.get(b"AcroForm").unwrap().as_reference().unwrap()).unwrap();
+        assert_eq!(acro_form.get(b"SigFlags").unwrap().as_i64().unwrap(), SIG_FLAGS);
+        let fields = acro_form.get(b"Fields").unwrap().as_array().unwrap();
+        assert_eq!(fields.iter().map(|field| field.as_reference().unwrap()).collect::<Vec<_>>(), vec![widget_id]);
+
+        let signatures = pdf_signatures::verify(&doc, &signed);
+        assert_eq!(signatures.len(), 1);
+        let signature = &signatures[0];
+        assert!(signature.is_intact(), "{:?}", signature.problems);
+        assert!(signature.covers_whole_file);
+        assert_eq!(signature.byte_range, result.byte_range);
+        assert_eq!(signature.signed_revision, Some(2));
+        assert_eq!(signature.sub_filter.as_deref(), Some("ETSI.CAdES.detached"));
+        assert_eq!(signature.reason.as_deref(), Some("Aprovação do contrato"));
+        assert_eq!(signature.signing_time.as_deref(), Some(result.signing_time.as_str()));
+    }
+
+    #[test]
+    fn test_second_signature_keeps_the_first_valid() {
+        let temp_dir = TempDir::new().unwrap();
+        let already_signed = test_support::sign_sample_pdf(&test_support::build_sample_pdf(1));
+        let (result, signed) = sign(&temp_dir, &already_signed, json!({}));
+        assert_eq!(result.field_name, "Signature2");
+        assert_eq!(&signed[..already_signed.len()], &already_signed[..]);
+
+        let signatures = pdf_signatures::verify(&Document::load_mem(&signed).unwrap(), &signed);
+        assert_eq!(signatures.len(), 2);
+        let first = signatures.iter().find(|s| s.field_name.as_deref() == Some("Signature1")).unwrap();
+        let second = signatures.iter().find(|s| s.field_name.as_deref() == Some("Signature2")).unwrap();
+
+        // A primeira assinatura cobre o arquivo original inteiro; a segunda, o novo
+        let (gap_start, gap_end) = last_contents_gap(&already_signed);
+        assert_eq!(first.byte_range, [0, gap_start, gap_end, already_signed.len() - gap_end].map(|value| value as u64));
+        assert_eq!(first.bytes_after_signature, (signed.len() - already_signed.len()) as u64);
+        assert!(first.is_intact());
+        assert!(first.modified_after_signing());
+
+        let (gap_start, gap_end) = last_contents_gap(&signed);
+        assert_eq!(second.byte_range, [0, gap_start, gap_end, signed.len() - gap_end].map(|value| value as u64));
+        assert_eq!(second.byte_range, result.byte_range);
+        assert!(second.is_intact());
+        assert!(second.covers_whole_file);
+    }
+
+    #[test]
+    fn test_visible_signature_has_appearance() {
+        let temp_dir = TempDir::new().unwrap();
+        let (result, signed) = sign(&temp_dir, &sample_bytes(), json!({
+            "page": 2,
+            "rect": [72.0, 72.0, 272.0, 132.0],
+            "location": "São Paulo",
+            "field_name": "Aprovador",
+        }));
+        assert!(result.visible);
+        assert_eq!(result.field_name, "Aprovador");
+
+        let doc = Document::load_mem(&signed).unwrap();
+        let page = doc.get_pages()[&2];
+        let annots = doc.get_dictionary(page).unwrap().get(b"Annots").unwrap().as_array().unwrap();
+        let widget = doc.get_dictionary(annots[0].as_reference().unwrap()).unwrap();
+        let rect: Vec<f32> = widget.get(b"Rect").unwrap().as_array().unwrap().iter().map(|v| v.as_float().unwrap()).collect();
+        assert_eq!(rect, vec![72.0, 72.0, 272.0, 132.0]);
+
+        let signature = doc.get_dictionary(widget.get(b"V").unwrap().as_reference().unwrap()).unwrap();
+        let location = signature.get(b"Location").unwrap().as_str().unwrap();
+        assert_eq!(pdf_encoding::decode_text_string(location), "São Paulo");
+
+        // Três linhas em Helvetica 10 dentro da caixa de 200 x 60
+        let ap = widget.get(b"AP").unwrap().as_dict().unwrap().get(b"N").unwrap().as_reference().unwrap();
+        let appearance = doc.get_object(ap).unwrap().as_stream().unwrap();
+        let bbox: Vec<f32> = appearance.dict.get(b"BBox").unwrap().as_array().unwrap().iter().map(|v| v.as_float().unwrap()).collect();
+        assert_eq!(bbox, vec![0.0, 0.0, 200.0, 60.0]);
+        // "D:20240101120000-03'00'" -> "2024-01-01 12:00:00 -03:00"
+        let m = &result.signing_time;
+        let date = format!("{}-{}-{} {}:{}:{} {}:{}", &m[2..6], &m[6..8], &m[8..10], &m[10..12], &m[12..14], &m[14..16], &m[16..19], &m[20..22]);
+        let lines = format!(
+            "q 0.5 w 0 0 200.00 60.00 re S Q\nq 1 1 198.00 58.00 re W n\nBT /F1 10.00 Tf 12.00 TL 3 48.00 Td\n\
+             (Digitally signed by DocHub Test Signer) Tj\nT* (Date: {}) Tj\nT* (Location: ",
+            date
+        );
+        // O texto vai em WinAnsiEncoding: "ã" é o byte 0xE3
+        let expected = [lines.as_bytes(), b"S\xE3o Paulo) Tj\nET\nQ\n"].concat();
+        assert_eq!(appearance.content, expected);
+    }
+
+    #[test]
+    fn test_legacy_triple_des_pfx_is_accepted() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("legacy.pfx");
+        test_support::write_test_pkcs12(&path, "1234", EncryptionAlgorithm::PbeWithShaAnd3KeyTripleDesCbc);
+
+        let credentials = SigningCredentials::from_pkcs12(&std::fs::read(&path).unwrap(), "1234").unwrap();
+        assert_eq!(credentials.signer_name().as_deref(), Some("DocHub Test Signer"));
+        assert!(SigningCredentials::from_pkcs12(&std::fs::read(&path).unwrap(), "wrong").is_err());
+    }
+
+    #[test]
+    fn test_request_rejects_same_output_and_bad_rect() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = test_support::write_sample_pdf(temp_dir.path(), "input.pdf", 1);
+
+        let request = SignRequest::from_value(&json!({
+            "file": file, "output": file, "certificate": file, "password": "x",
+        }))
+        .unwrap();
+        assert!(request.validate(&FileHandler::new()).is_err());
+        assert!(!format!("{:?}", request).contains("\"x\""));
+
+        let request = SignRequest::from_value(&json!({
+            "file": file,
+            "output": temp_dir.path().join("out.pdf"),
+            "certificate": file,
+            "config": { "rect": [100.0, 100.0, 50.0, 150.0] },
+        }))
+        .unwrap();
+        assert!(request.validate(&FileHandler::new()).is_err());
+    }
+
+    #[test]
+    fn test_sign_in_place_only_on_request() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("input.pdf");
+        let certificate = temp_dir.path().join("signer.pfx");
+        let original = sample_bytes();
+        std::fs::write(&file, &original).unwrap();
+        test_support::write_test_pkcs12(&certificate, "secret", EncryptionAlgorithm::PbeWithHmacSha256AndAes256);
+
+        let request = |overwrite_input: bool| SignRequest::from_value(&json!({
+            "file": file,
+            "output": temp_dir.path().join(".").join("input.pdf"),
+            "certificate": certificate,
+            "password": "secret",
+            "config": { "overwrite_input": overwrite_input },
+        }))
+        .unwrap();
+
+        assert!(PdfSigner::new().sign_pdf_blocking(request(false)).is_err());
+        assert_eq!(std::fs::read(&file).unwrap(), original);
+
+        let result = PdfSigner::new().sign_pdf_blocking(request(true)).unwrap();
+        let signed = std::fs::read(&file).unwrap();
+        assert_eq!(signed.len() as u64, result.file_size);
+        assert_eq!(&signed[..original.len()], &original[..]);
+        let signatures = pdf_signatures::verify(&Document::load_mem(&signed).unwrap(), &signed);
+        assert!(signatures[0].is_intact() && signatures[0].covers_whole_file);
+    }
+
+    #[test]
+    fn test_sign_keeps_existing_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = test_support::write_sample_pdf(temp_dir.path(), "input.pdf", 1);
+        let output = temp_dir.path().join("signed.pdf");
+        let certificate = temp_dir.path().join("signer.pfx");
+        std::fs::write(&output, "keep").unwrap();
+        test_support::write_test_pkcs12(&certificate, "secret", EncryptionAlgorithm::PbeWithHmacSha256AndAes256);
+
+        let request = SignRequest::from_value(&json!({
+            "file": file,
+            "output": output,
+            "certificate": certificate,
+            "password": "secret",
+        }))
+        .unwrap();
+        let result = PdfSigner::new().sign_pdf_blocking(request);
+        assert!(matches!(result, Err(AppError::Io(IoError::WriteFailed { .. }))));
+        assert_eq!(std::fs::read(&output).unwrap(), b"keep");
+    }
+}
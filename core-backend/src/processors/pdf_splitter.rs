@@ -7,6 +7,8 @@
 //! - Split por páginas individuais
 //! - Validação de intervalos (ordem, sobreposição, limites)
 //! - Preservação de metadados e estrutura
+//! - Empacotamento opcional das partes em um único ZIP com manifesto
+//! - Partes autocontidas, montadas em paralelo no pool de CPU
 //! - Tratamento de erros robusto
 //! 
 //! ## Formatos suportados:
@@ -14,19 +16,25 @@
 //! - Listas: "1,3,5-7,9"
 //! - Páginas específicas: vec![1, 3, 5]
 
-use lopdf::{Document, Object, ObjectId};
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::{BTreeMap, HashMap};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Instant;
 use tracing::{info, warn, error, instrument};
 
-use crate::utils::error_handling::{Result, AppError, PdfError, ValidationError};
+use crate::utils::error_handling::{Result, AppError, IoError, PdfError, ValidationError};
 use crate::utils::error_handling::validate;
 use crate::api::file_handlers::FileHandler;
+use crate::processors::pdf_linearize;
+use crate::processors::runtime::ProcessorRuntime;
 
 /// Representa um intervalo de páginas (inclusivo)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -210,6 +218,16 @@ impl PageRangeParser {
     }
 }
 
+/// Forma de entrega das partes geradas pelo split
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SplitOutputMode {
+    /// Um arquivo PDF por intervalo, gravado diretamente em `output_dir`
+    #[default]
+    Files,
+    /// Todas as partes em um único arquivo ZIP, acompanhadas de `manifest.json`
+    Zip,
+}
+
 /// Configurações para o split de PDFs
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SplitConfig {
@@ -221,6 +239,15 @@ pub struct SplitConfig {
     pub create_output_dir: bool,
     /// Manter a ordem original das páginas mesmo em intervalos não sequenciais
     pub preserve_page_order: bool,
+    /// Modo de saída (arquivos soltos ou ZIP)
+    #[serde(default)]
+    pub output_mode: SplitOutputMode,
+    /// Nome do arquivo ZIP no modo `Zip` (padrão: "<arquivo>_split.zip")
+    #[serde(default)]
+    pub archive_name: Option<String>,
+    /// Gravar cada parte linearizada (fast web view)
+    #[serde(default)]
+    pub linearize: bool,
 }
 
 impl Default for SplitConfig {
@@ -230,6 +257,9 @@ impl Default for SplitConfig {
             naming_pattern: "split_{index}".to_string(),
             create_output_dir: true,
             preserve_page_order: true,
+            output_mode: SplitOutputMode::Files,
+            archive_name: None,
+            linearize: false,
         }
     }
 }
@@ -316,7 +346,33 @@ impl SplitRequest {
             )?;
         }
 
-        // 4. Valida diretório de saída
+        // 4. Valida o nome do arquivo ZIP (não pode apontar para outro diretório)
+        if let Some(name) = &self.config.archive_name {
+            validate(
+                is_plain_file_name(name),
+                AppError::validation(format!("Invalid archive name: {}", name))
+            )?;
+        }
+
+        // 5. Valida os nomes das partes: sem diretórios e sem repetições, que um
+        //    naming_pattern sem {index} produziria
+        let mut file_names = HashSet::new();
+        for index in 0..self.page_ranges.len() {
+            let file_name = self.output_file_name(index);
+            validate(
+                is_plain_file_name(&file_name),
+                AppError::validation(format!("Invalid file name from naming pattern: {}", file_name))
+            )?;
+            validate(
+                file_names.insert(file_name.to_lowercase()),
+                AppError::validation(format!(
+                    "Naming pattern '{}' produces the file name '{}' more than once; include {{index}}",
+                    self.config.naming_pattern, file_name
+                ))
+            )?;
+        }
+
+        // 6. Valida diretório de saída
         if self.config.create_output_dir && !self.output_dir.exists() {
             info!(
                 output_dir = %self.output_dir.display(),
@@ -336,6 +392,11 @@ impl SplitRequest {
 
     /// Gera o caminho de saída para um split específico
     pub fn generate_output_path(&self, index: usize) -> PathBuf {
+        self.output_dir.join(self.output_file_name(index))
+    }
+
+    /// Nome do arquivo de um split específico (no modo `Zip`, o nome da entrada)
+    pub fn output_file_name(&self, index: usize) -> String {
         let pattern = self.config.naming_pattern.replace("{index}", &(index + 1).to_string());
         
         // Também pode suportar outros placeholders como {range}, {start}, {end}
@@ -343,7 +404,24 @@ impl SplitRequest {
         let pattern = pattern.replace("{start}", &self.page_ranges[index].start.to_string());
         let pattern = pattern.replace("{end}", &self.page_ranges[index].end.to_string());
         
-        self.output_dir.join(format!("{}.pdf", pattern))
+        format!("{}.pdf", pattern)
+    }
+
+    /// Caminho do arquivo ZIP gerado no modo `SplitOutputMode::Zip`
+    pub fn archive_path(&self) -> PathBuf {
+        let file_name = match &self.config.archive_name {
+            Some(name) if name.to_lowercase().ends_with(".zip") => name.clone(),
+            Some(name) => format!("{}.zip", name),
+            None => {
+                let stem = self.file_path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "document".to_string());
+                format!("{}_split.zip", stem)
+            }
+        };
+
+        self.output_dir.join(file_name)
     }
 }
 
@@ -364,6 +442,8 @@ pub struct SplitResult {
     pub metadata_preserved: bool,
     /// Estatísticas por intervalo
     pub range_stats: Vec<RangeStat>,
+    /// Caminho do arquivo ZIP (apenas no modo `Zip`)
+    pub archive_path: Option<PathBuf>,
 }
 
 /// Estatísticas para um intervalo específico
@@ -371,33 +451,352 @@ pub struct SplitResult {
 pub struct RangeStat {
     /// Intervalo de páginas
     pub range: PageRange,
-    /// Caminho do arquivo gerado
+    /// Caminho do arquivo gerado (no modo `Zip`, o nome da entrada no arquivo)
     pub output_file: PathBuf,
     /// Tamanho do arquivo (em bytes)
     pub file_size: u64,
     /// Número de páginas neste split
     pub page_count: u32,
+    /// Hash SHA-256 do conteúdo gerado (hexadecimal)
+    pub sha256: String,
 }
 
-/// Processador de split de PDFs
-#[derive(Debug)]
-pub struct PdfSplitter {
-    file_handler: FileHandler,
+/// Manifesto gravado como `manifest.json` dentro do ZIP de saída
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitManifest {
+    /// Nome do arquivo de origem
+    pub source_file: String,
+    /// Momento da geração
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Número de partes no arquivo
+    pub part_count: usize,
+    /// Descrição de cada parte, na ordem da requisição
+    pub parts: Vec<ManifestPart>,
 }
 
-impl PdfSplitter {
-    /// Cria um novo PdfSplitter
-    pub fn new() -> Self {
+/// Entrada do manifesto para uma parte
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestPart {
+    /// Nome do arquivo dentro do ZIP
+    pub file_name: String,
+    /// Intervalo de páginas da parte
+    pub range: PageRange,
+    /// Número de páginas
+    pub page_count: u32,
+    /// Tamanho em bytes
+    pub size_bytes: u64,
+    /// Hash SHA-256 (hexadecimal)
+    pub sha256: String,
+}
+
+impl ManifestPart {
+    fn from_stat(stat: &RangeStat) -> Self {
         Self {
-            file_handler: FileHandler::new(),
+            file_name: stat.output_file.to_string_lossy().to_string(),
+            range: stat.range,
+            page_count: stat.page_count,
+            size_bytes: stat.file_size,
+            sha256: stat.sha256.clone(),
         }
     }
+}
+
+/// Copia para um novo documento apenas os objetos alcançáveis a partir das
+/// páginas selecionadas, tornando cada parte autocontida
+struct PartBuilder<'a> {
+    source: &'a Document,
+    part: Document,
+    id_map: HashMap<ObjectId, ObjectId>,
+    pending: Vec<ObjectId>,
+}
+
+impl<'a> PartBuilder<'a> {
+    /// Atributos de página herdáveis da árvore de páginas
+    const INHERITED_KEYS: [&'static [u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+    fn new(source: &'a Document) -> Self {
+        Self {
+            source,
+            part: Document::with_version(source.version.clone()),
+            id_map: HashMap::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Monta o documento com as páginas informadas, na ordem dada
+    fn build(mut self, page_ids: &[ObjectId], preserve_metadata: bool) -> Document {
+        let pages_id = self.part.new_object_id();
+
+        // Reserva os IDs das páginas antes de copiar qualquer conteúdo, para que
+        // referências entre páginas da mesma parte (ex.: /P de anotações) sejam mantidas
+        let new_page_ids: Vec<ObjectId> = page_ids
+            .iter()
+            .map(|&page_id| {
+                let new_id = self.part.new_object_id();
+                self.id_map.insert(page_id, new_id);
+                new_id
+            })
+            .collect();
+
+        for (&page_id, &new_page_id) in page_ids.iter().zip(&new_page_ids) {
+            let mut page = self.source
+                .get_dictionary(page_id)
+                .cloned()
+                .unwrap_or_default();
+
+            for key in Self::INHERITED_KEYS {
+                if !page.has(key) {
+                    if let Some(value) = self.inherited_attribute(page_id, key) {
+                        page.set(key, value);
+                    }
+                }
+            }
+
+            page.remove(b"Parent");
+            let mut page = self.remap_dictionary(&page);
+            page.set("Parent", pages_id);
+            self.part.objects.insert(new_page_id, Object::Dictionary(page));
+        }
+
+        let kids: Vec<Object> = new_page_ids.iter().map(|&id| Object::Reference(id)).collect();
+        self.part.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => new_page_ids.len() as i64,
+            }),
+        );
+
+        let catalog_id = self.part.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        self.part.trailer.set("Root", catalog_id);
 
-    /// Cria um PdfSplitter com um FileHandler específico
-    pub fn with_file_handler(file_handler: FileHandler) -> Self {
-        Self { file_handler }
+        // Preserva metadados do original se configurado
+        if preserve_metadata {
+            if let Ok(info) = self.source.trailer.get(b"Info") {
+                let info = self.remap(info);
+                self.part.trailer.set("Info", info);
+            }
+        }
+
+        self.drain_pending();
+        self.part
     }
 
+    /// Procura um atributo herdável subindo pela cadeia de /Parent
+    fn inherited_attribute(&self, page_id: ObjectId, key: &[u8]) -> Option<Object> {
+        let mut current = self.source.get_dictionary(page_id).ok()?;
+
+        // Limite de profundidade contra árvores de páginas cíclicas
+        for _ in 0..64 {
+            let parent_id = current.get(b"Parent").and_then(Object::as_reference).ok()?;
+            current = self.source.get_dictionary(parent_id).ok()?;
+            if let Ok(value) = current.get(key) {
+                return Some(value.clone());
+            }
+        }
+
+        None
+    }
+
+    /// Copia os objetos indiretos pendentes, que podem revelar novas referências
+    fn drain_pending(&mut self) {
+        while let Some(old_id) = self.pending.pop() {
+            let new_id = self.id_map[&old_id];
+            let object = match self.source.get_object(old_id) {
+                Ok(object) => self.remap(object),
+                Err(_) => Object::Null,
+            };
+            self.part.objects.insert(new_id, object);
+        }
+    }
+
+    /// Reescreve as referências de um objeto para os IDs do novo documento
+    fn remap(&mut self, object: &Object) -> Object {
+        match object {
+            Object::Reference(id) => self.remap_reference(*id),
+            Object::Array(items) => {
+                let mut remapped = Vec::with_capacity(items.len());
+                for item in items {
+                    remapped.push(self.remap(item));
+                }
+                Object::Array(remapped)
+            }
+            Object::Dictionary(dict) => Object::Dictionary(self.remap_dictionary(dict)),
+            Object::Stream(stream) => {
+                let mut stream = stream.clone();
+                stream.dict = self.remap_dictionary(&stream.dict);
+                Object::Stream(stream)
+            }
+            other => other.clone(),
+        }
+    }
+
+    fn remap_dictionary(&mut self, dict: &Dictionary) -> Dictionary {
+        let mut remapped = Dictionary::new();
+        for (key, value) in dict.iter() {
+            remapped.set(key.clone(), self.remap(value));
+        }
+        remapped
+    }
+
+    fn remap_reference(&mut self, id: ObjectId) -> Object {
+        if let Some(&new_id) = self.id_map.get(&id) {
+            return Object::Reference(new_id);
+        }
+
+        match self.source.get_object(id) {
+            // Páginas fora da parte (ex.: destinos de links) e nós da árvore de
+            // páginas não são copiados, senão o documento inteiro viria junto
+            Ok(object) if is_page_tree_node(object) => Object::Null,
+            Ok(_) => {
+                let new_id = self.part.new_object_id();
+                self.id_map.insert(id, new_id);
+                self.pending.push(id);
+                Object::Reference(new_id)
+            }
+            Err(_) => Object::Null,
+        }
+    }
+}
+
+/// Indica se o objeto é um nó /Page ou /Pages
+fn is_page_tree_node(object: &Object) -> bool {
+    object
+        .as_dict()
+        .and_then(|dict| dict.get(b"Type"))
+        .and_then(Object::as_name)
+        .map(|name| name == b"Page" || name == b"Pages")
+        .unwrap_or(false)
+}
+
+/// Escritor do ZIP de saída
+struct SplitArchive {
+    path: PathBuf,
+    writer: zip::ZipWriter<File>,
+}
+
+impl SplitArchive {
+    /// Cria o arquivo ZIP no caminho indicado, sem sobrescrever um arquivo existente
+    fn create(file_handler: &FileHandler, path: &Path) -> Result<Self> {
+        let file = file_handler.create_new_file(path.to_str().unwrap_or(""))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: zip::ZipWriter::new(file),
+        })
+    }
+
+    /// Abre uma nova entrada no arquivo
+    fn start_entry(&mut self, name: &str) -> Result<()> {
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+
+        self.writer.start_file(name, options).map_err(|e| self.write_error(e))
+    }
+
+    /// Serializa um documento direto na entrada `name`, sem montar o PDF inteiro em memória
+    ///
+    /// Retorna o tamanho e o SHA-256 do conteúdo gravado.
+    fn add_document(&mut self, name: &str, doc: &mut Document, linearize: bool) -> Result<(u64, String)> {
+        self.start_entry(name)?;
+        let mut entry = DigestWriter::new(&mut self.writer);
+        if linearize {
+            // O layout linearizado depende dos offsets finais e é montado por inteiro
+            let bytes = pdf_linearize::linearize(doc).map_err(|reason| linearize_error(name, reason))?;
+            entry.write_all(&bytes).map_err(|e| archive_write_error(self.path.clone(), e))?;
+        } else {
+            doc.save_to(&mut entry).map_err(|e| save_error(name, e))?;
+        }
+        Ok(entry.finish())
+    }
+
+    /// Grava o manifesto e fecha o arquivo
+    fn finish(mut self, manifest: &SplitManifest) -> Result<u64> {
+        let manifest_json = serde_json::to_vec_pretty(manifest)
+            .map_err(|e| AppError::serialization(format!("Failed to serialize manifest: {}", e)))?;
+
+        self.start_entry("manifest.json")?;
+        self.writer.write_all(&manifest_json).map_err(|e| self.write_error(e))?;
+        let path = self.path.clone();
+        let file = self.writer.finish().map_err(|e| archive_write_error(path.clone(), e))?;
+        let metadata = file.metadata()
+            .map_err(|e| AppError::from_io_error("reading archive metadata", path, e))?;
+        Ok(metadata.len())
+    }
+
+    fn write_error(&self, error: impl std::error::Error + Send + Sync + 'static) -> AppError {
+        archive_write_error(self.path.clone(), error)
+    }
+}
+
+/// Escritor que repassa os bytes e calcula o tamanho e o SHA-256 do que passou
+struct DigestWriter<W> {
+    inner: W,
+    hasher: Sha256,
+    written: u64,
+}
+
+impl<W: Write> DigestWriter<W> {
+    fn new(inner: W) -> Self {
+        Self { inner, hasher: Sha256::new(), written: 0 }
+    }
+
+    /// Tamanho e SHA-256 (hexadecimal) do conteúdo escrito
+    fn finish(self) -> (u64, String) {
+        (self.written, hex(&self.hasher.finalize()))
+    }
+}
+
+impl<W: Write> Write for DigestWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Converte uma falha de escrita do ZIP em erro de I/O
+fn archive_write_error(path: PathBuf, error: impl std::error::Error + Send + Sync + 'static) -> AppError {
+    AppError::Io(IoError::WriteFailed {
+        path,
+        source: io::Error::other(error),
+    })
+}
+
+/// Erro de serialização de uma parte
+fn save_error(name: &str, error: impl fmt::Display) -> AppError {
+    error!(part = %name, error = %error, "Failed to serialize split PDF");
+    AppError::Pdf(PdfError::ProcessingFailed {
+        reason: format!("Failed to save split PDF: {}", error),
+    })
+}
+
+/// Erro de linearização de uma parte
+fn linearize_error(name: &str, reason: String) -> AppError {
+    error!(part = %name, error = %reason, "Failed to linearize split PDF");
+    AppError::Pdf(PdfError::ProcessingFailed {
+        reason: format!("Failed to linearize split PDF: {}", reason),
+    })
+}
+
+/// Processador de split de PDFs
+#[derive(Debug, Clone)]
+pub struct PdfSplitter {
+    runtime: ProcessorRuntime,
+}
+
+processor_constructors!(PdfSplitter);
+
+impl PdfSplitter {
     /// Divide um PDF em múltiplos arquivos baseado em intervalos de páginas
     #[instrument(name = "split_pdf", skip(self, request), fields(
         input_file = %request.file_path.display(),
@@ -405,6 +804,12 @@ impl PdfSplitter {
         output_dir = %request.output_dir.display()
     ))]
     pub async fn split_pdf(&self, request: SplitRequest) -> Result<SplitResult> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.split_pdf_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `split_pdf`, executado no pool de CPU
+    fn split_pdf_blocking(&self, request: SplitRequest) -> Result<SplitResult> {
         let start_time = Instant::now();
 
         info!("Starting PDF split process");
@@ -414,11 +819,11 @@ impl PdfSplitter {
         let total_pages = doc.get_pages().len() as u32;
 
         // 2. Valida a request com o número real de páginas
-        request.validate(&self.file_handler, total_pages)?;
+        request.validate(self.runtime.file_handler(), total_pages)?;
 
         // 3. Cria diretório de saída se necessário
         if request.config.create_output_dir && !request.output_dir.exists() {
-            self.file_handler.create_dir(request.output_dir.to_str().unwrap_or(""))?;
+            self.runtime.file_handler().create_dir(request.output_dir.to_str().unwrap_or(""))?;
             info!("Created output directory: {}", request.output_dir.display());
         }
 
@@ -427,14 +832,30 @@ impl PdfSplitter {
 
         let processing_time = start_time.elapsed();
 
+        let (output_files, total_output_size, archive_path) = match request.config.output_mode {
+            SplitOutputMode::Files => (
+                split_results.iter().map(|r| r.output_file.clone()).collect::<Vec<_>>(),
+                split_results.iter().map(|r| r.file_size).sum(),
+                None,
+            ),
+            SplitOutputMode::Zip => {
+                let archive_path = request.archive_path();
+                let archive_size = std::fs::metadata(&archive_path)
+                    .map_err(|e| AppError::from_io_error("reading archive metadata", archive_path.clone(), e))?
+                    .len();
+                (vec![archive_path.clone()], archive_size, Some(archive_path))
+            }
+        };
+
         let result = SplitResult {
-            output_files: split_results.iter().map(|r| r.output_file.clone()).collect(),
+            files_created: output_files.len(),
+            output_files,
             total_pages_processed: split_results.iter().map(|r| r.page_count).sum(),
-            total_output_size: split_results.iter().map(|r| r.file_size).sum(),
+            total_output_size,
             processing_time_ms: processing_time.as_millis(),
-            files_created: split_results.len(),
             metadata_preserved: request.config.preserve_metadata,
             range_stats: split_results,
+            archive_path,
         };
 
         info!(
@@ -466,128 +887,190 @@ impl PdfSplitter {
     }
 
     /// Executa o split real do documento
+    ///
+    /// Cada intervalo vira um documento independente, montado e serializado em
+    /// paralelo no pool de CPU. As estatísticas mantêm a ordem da requisição.
     #[instrument(name = "perform_split", skip(self, doc, request))]
     fn perform_split(&self, doc: &Document, request: &SplitRequest) -> Result<Vec<RangeStat>> {
         let pages = doc.get_pages();
-        let mut results = Vec::new();
-        let mut max_object_id = doc.max_id + 1;
 
-        // Mapa global para rastrear IDs de objetos entre splits
-        let mut global_object_id_map = HashMap::new();
+        let pool = self.runtime.thread_pool()?;
+        info!(threads = pool.current_num_threads(), "Building split parts in parallel");
+
+        match request.config.output_mode {
+            SplitOutputMode::Files => pool.install(|| {
+                request.page_ranges
+                    .par_iter()
+                    .enumerate()
+                    .map(|(range_index, range)| self.write_split_file(doc, &pages, request, range_index, range))
+                    .collect()
+            }),
+            SplitOutputMode::Zip => self.write_split_archive(doc, &pages, request, &pool),
+        }
+    }
 
-        for (range_index, range) in request.page_ranges.iter().enumerate() {
-            info!(
-                range_index,
-                range = %range,
-                page_count = range.page_count(),
-                "Processing page range"
-            );
+    /// Monta o documento autocontido de um intervalo
+    fn build_split_document(
+        &self,
+        doc: &Document,
+        pages: &BTreeMap<u32, ObjectId>,
+        request: &SplitRequest,
+        range_index: usize,
+        range: &PageRange,
+    ) -> Result<Document> {
+        info!(
+            range_index,
+            range = %range,
+            page_count = range.page_count(),
+            "Processing page range"
+        );
 
-            let mut split_doc = Document::with_version("1.5");
-            let mut object_id_map = HashMap::new();
+        let mut page_ids = Vec::with_capacity(range.page_count() as usize);
+        for page_num in range.expand() {
+            let &page_id = pages.get(&page_num)
+                .ok_or_else(|| AppError::Pdf(PdfError::PageNotFound {
+                    path: request.file_path.clone(),
+                    page: page_num,
+                }))?;
+            page_ids.push(page_id);
+        }
 
-            // Preserva metadados do original se configurado
-            if request.config.preserve_metadata {
-                if let Ok(info) = doc.trailer.get(b"Info") {
-                    split_doc.trailer.set(b"Info", info.clone());
-                }
-            }
+        Ok(PartBuilder::new(doc).build(&page_ids, request.config.preserve_metadata))
+    }
 
-            // Para cada página no intervalo
-            for page_num in range.expand() {
-                let &page_id = pages.get(&page_num)
-                    .ok_or_else(|| AppError::Pdf(PdfError::PageNotFound {
-                        path: request.file_path.clone(),
-                        page: page_num,
-                    }))?;
-
-                // Obtém o objeto da página
-                let page_obj = doc.get_object(page_id)
-                    .map_err(|e| {
-                        error!(
-                            page_num,
-                            error = %e,
-                            "Failed to get page object"
-                        );
-                        AppError::Pdf(PdfError::PageNotFound {
-                            path: request.file_path.clone(),
-                            page: page_num,
-                        })
-                    })?
-                    .clone();
-
-                // Cria um novo ID único para este objeto
-                let new_page_id = (max_object_id, 0);
-                max_object_id += 1;
-
-                // Mapeia IDs
-                object_id_map.insert(page_id, new_page_id);
-                global_object_id_map.insert(page_id, new_page_id);
-
-                // Adiciona ao documento splitado
-                split_doc.objects.insert(new_page_id, page_obj);
-
-                info!(range_index, page_num, "Page added to split document");
-            }
+    /// Monta, serializa e grava em disco a parte de um intervalo (modo `Files`)
+    fn write_split_file(
+        &self,
+        doc: &Document,
+        pages: &BTreeMap<u32, ObjectId>,
+        request: &SplitRequest,
+        range_index: usize,
+        range: &PageRange,
+    ) -> Result<RangeStat> {
+        let mut split_doc = self.build_split_document(doc, pages, request, range_index, range)?;
+
+        let output_path = request.generate_output_path(range_index);
+        let bytes = self.serialize_split_document(&mut split_doc, &output_path, request.config.linearize)?;
+        self.write_split_document(&output_path, &bytes)?;
+
+        let stat = RangeStat {
+            range: *range,
+            output_file: output_path,
+            file_size: bytes.len() as u64,
+            page_count: range.page_count(),
+            sha256: sha256_hex(&bytes),
+        };
 
-            // Atualiza referências de objetos no documento splitado
-            self.update_object_references(&mut split_doc, &object_id_map);
+        info!(
+            range_index,
+            output_file = %stat.output_file.display(),
+            file_size = stat.file_size,
+            "Split completed for range"
+        );
 
-            // Gera caminho de saída e salva
-            let output_path = request.generate_output_path(range_index);
-            self.save_split_document(&mut split_doc, &output_path)?;
+        Ok(stat)
+    }
 
-            // Obtém estatísticas deste split
-            let file_size = self.file_handler.validate_file(output_path.to_str().unwrap_or(""))?.len();
+    /// Grava as partes, na ordem da requisição, em um único ZIP com manifesto
+    ///
+    /// As partes são montadas em lotes do tamanho do pool e cada uma é serializada
+    /// direto na sua entrada do ZIP, de modo que só um lote fica em memória por vez.
+    /// Se a gravação falhar, o ZIP incompleto é removido.
+    fn write_split_archive(
+        &self,
+        doc: &Document,
+        pages: &BTreeMap<u32, ObjectId>,
+        request: &SplitRequest,
+        pool: &rayon::ThreadPool,
+    ) -> Result<Vec<RangeStat>> {
+        let archive_path = request.archive_path();
+        let archive = SplitArchive::create(self.runtime.file_handler(), &archive_path)?;
+
+        match self.fill_split_archive(archive, doc, pages, request, pool) {
+            Ok((results, archive_size)) => {
+                info!(archive = %archive_path.display(), size = archive_size, "Split archive written");
+                Ok(results)
+            }
+            Err(e) => {
+                if let Err(remove_error) = self.runtime.file_handler().remove_file(archive_path.to_str().unwrap_or("")) {
+                    warn!(archive = %archive_path.display(), error = %remove_error, "Failed to remove incomplete split archive");
+                }
+                Err(e)
+            }
+        }
+    }
 
-            results.push(RangeStat {
-                range: *range,
-                output_file: output_path,
-                file_size,
-                page_count: range.page_count(),
-            });
+    /// Preenche o ZIP com as partes e o manifesto; retorna as estatísticas e o tamanho do ZIP
+    fn fill_split_archive(
+        &self,
+        mut archive: SplitArchive,
+        doc: &Document,
+        pages: &BTreeMap<u32, ObjectId>,
+        request: &SplitRequest,
+        pool: &rayon::ThreadPool,
+    ) -> Result<(Vec<RangeStat>, u64)> {
+        let ranges: Vec<(usize, &PageRange)> = request.page_ranges.iter().enumerate().collect();
+        let mut results = Vec::with_capacity(ranges.len());
+
+        for batch in ranges.chunks(pool.current_num_threads().max(1)) {
+            let documents = pool.install(|| {
+                batch
+                    .par_iter()
+                    .map(|&(range_index, range)| self.build_split_document(doc, pages, request, range_index, range))
+                    .collect::<Result<Vec<_>>>()
+            })?;
 
-            info!(
-                range_index,
-                output_file = %results.last().unwrap().output_file.display(),
-                file_size = results.last().unwrap().file_size,
-                "Split completed for range"
-            );
+            for (&(range_index, range), mut split_doc) in batch.iter().zip(documents) {
+                let entry_name = request.output_file_name(range_index);
+                let (file_size, sha256) = archive.add_document(&entry_name, &mut split_doc, request.config.linearize)?;
+                info!(range_index, entry = %entry_name, file_size, "Split completed for range");
+
+                results.push(RangeStat {
+                    range: *range,
+                    output_file: PathBuf::from(entry_name),
+                    file_size,
+                    page_count: range.page_count(),
+                    sha256,
+                });
+            }
         }
 
-        Ok(results)
-    }
+        let manifest = SplitManifest {
+            source_file: request.file_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            created_at: chrono::Utc::now(),
+            part_count: results.len(),
+            parts: results.iter().map(ManifestPart::from_stat).collect(),
+        };
+        let archive_size = archive.finish(&manifest)?;
 
-    /// Atualiza referências de objetos no documento splitado
-    fn update_object_references(&self, doc: &mut Document, id_mapping: &HashMap<ObjectId, ObjectId>) {
-        let objects_to_update: Vec<_> = doc.objects
-            .iter()
-            .filter(|(_, obj)| matches!(obj, Object::Reference(_)))
-            .map(|(id, _)| *id)
-            .collect();
+        Ok((results, archive_size))
+    }
 
-        for obj_id in objects_to_update {
-            if let Ok(Object::Reference(ref_id)) = doc.get_object(obj_id) {
-                if let Some(&mapped_id) = id_mapping.get(ref_id) {
-                    doc.objects.insert(obj_id, Object::Reference(mapped_id));
-                }
-            }
+    /// Serializa o PDF de uma parte em memória
+    #[instrument(name = "serialize_split_document", skip(self, doc, output_path))]
+    fn serialize_split_document(&self, doc: &mut Document, output_path: &Path, linearize: bool) -> Result<Vec<u8>> {
+        let name = output_path.display().to_string();
+        if linearize {
+            return pdf_linearize::linearize(doc).map_err(|reason| linearize_error(&name, reason));
         }
+
+        let mut buffer = Vec::new();
+        doc.save_to(&mut buffer).map_err(|e| save_error(&name, e))?;
+
+        Ok(buffer)
     }
 
-    /// Salva um documento splitado
-    #[instrument(name = "save_split_document", skip(self, doc, output_path))]
-    fn save_split_document(&self, doc: &mut Document, output_path: &Path) -> Result<()> {
+    /// Grava uma parte serializada em disco, sem sobrescrever arquivos existentes
+    #[instrument(name = "write_split_document", skip(self, output_path, bytes))]
+    fn write_split_document(&self, output_path: &Path, bytes: &[u8]) -> Result<()> {
         info!(path = %output_path.display(), "Saving split PDF");
-        
-        doc.save(output_path)
-            .map_err(|e| {
-                error!(path = %output_path.display(), error = %e, "Failed to save split PDF");
-                AppError::Pdf(PdfError::ProcessingFailed {
-                    reason: format!("Failed to save split PDF: {}", e),
-                })
-            })?;
-            
+
+        self.runtime.file_handler()
+            .write_new_file(output_path.to_str().unwrap_or(""), bytes)?;
+
         info!(path = %output_path.display(), "Split PDF saved successfully");
         Ok(())
     }
@@ -609,13 +1092,30 @@ impl PdfSplitter {
     }
 }
 
+// ==================== FUNÇÕES AUXILIARES ====================
+
+/// Calcula o SHA-256 de um buffer em hexadecimal
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex(&Sha256::digest(bytes))
+}
+
+/// Bytes em hexadecimal minúsculo
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Nome de arquivo simples: não vazio, sem separadores de diretório nem ".."
+fn is_plain_file_name(name: &str) -> bool {
+    !name.trim().is_empty() && !name.contains(['/', '\\']) && !name.contains("..")
+}
+
 // ==================== FUNÇÕES DE CONVENIÊNCIA ====================
 
 /// Função de conveniência para split de PDFs (mantém compatibilidade)
-#[instrument(name = "split_pdf", skip(data))]
-pub async fn split_pdf(data: Value) -> Result<Value> {
+#[instrument(name = "split_pdf", skip(data, runtime))]
+pub async fn split_pdf(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
     let request = SplitRequest::from_value(&data)?;
-    let splitter = PdfSplitter::new();
+    let splitter = PdfSplitter::with_runtime(runtime);
     let result = splitter.split_pdf(request).await?;
     
     Ok(serde_json::to_value(result)
@@ -627,6 +1127,8 @@ pub async fn split_pdf(data: Value) -> Result<Value> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::processors::test_support;
+    use crate::utils::config::PerformanceConfig;
     use serde_json::json;
     use tempfile::TempDir;
 
@@ -744,16 +1246,241 @@ mod tests {
         assert_eq!(config.naming_pattern, "split_{index}");
         assert!(config.create_output_dir);
         assert!(config.preserve_page_order);
+        assert_eq!(config.output_mode, SplitOutputMode::Files);
+        assert!(config.archive_name.is_none());
+    }
+
+    #[test]
+    fn test_split_request_archive_path() -> Result<()> {
+        let data = json!({
+            "file": "docs/contrato.pdf",
+            "ranges": "1-2",
+            "output_dir": "./output",
+            "config": {
+                "preserve_metadata": true,
+                "naming_pattern": "parte_{index}",
+                "create_output_dir": true,
+                "preserve_page_order": true,
+                "output_mode": "Zip"
+            }
+        });
+
+        let mut request = SplitRequest::from_value(&data)?;
+        assert_eq!(request.config.output_mode, SplitOutputMode::Zip);
+        assert_eq!(request.archive_path(), PathBuf::from("./output/contrato_split.zip"));
+
+        request.config.archive_name = Some("lote".to_string());
+        assert_eq!(request.archive_path(), PathBuf::from("./output/lote.zip"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_split_to_zip_archive() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let input = test_support::write_sample_pdf(temp_dir.path(), "input.pdf", 5);
+        let output_dir = temp_dir.path().join("out");
+
+        let request = SplitRequest {
+            file_path: input,
+            page_ranges: PageRangeParser::parse_ranges("1-2,3,4-5")?,
+            output_dir: output_dir.clone(),
+            config: SplitConfig {
+                output_mode: SplitOutputMode::Zip,
+                ..SplitConfig::default()
+            },
+        };
+
+        let result = PdfSplitter::new().split_pdf(request).await?;
+        let archive_path = result.archive_path.clone().expect("archive path");
+        assert_eq!(archive_path, output_dir.join("input_split.zip"));
+        assert_eq!(result.output_files, vec![archive_path.clone()]);
+        assert_eq!(result.files_created, 1);
+        assert_eq!(result.range_stats.len(), 3);
+
+        // Nenhuma parte solta deve ter sido gravada
+        let loose_pdfs = std::fs::read_dir(&output_dir)?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().map(|x| x == "pdf").unwrap_or(false))
+            .count();
+        assert_eq!(loose_pdfs, 0);
+
+        let mut archive = zip::ZipArchive::new(File::open(&archive_path)?).unwrap();
+        assert_eq!(archive.len(), 4);
+
+        let manifest: SplitManifest = {
+            let entry = archive.by_name("manifest.json").unwrap();
+            serde_json::from_reader(entry).unwrap()
+        };
+        assert_eq!(manifest.source_file, "input.pdf");
+        assert_eq!(manifest.part_count, 3);
+        assert_eq!(manifest.parts[0].file_name, "split_1.pdf");
+        assert_eq!(manifest.parts[0].range, PageRange::new(1, 2)?);
+        assert_eq!(manifest.parts[2].page_count, 2);
+
+        for part in &manifest.parts {
+            let mut bytes = Vec::new();
+            std::io::Read::read_to_end(&mut archive.by_name(&part.file_name).unwrap(), &mut bytes)?;
+            assert_eq!(bytes.len() as u64, part.size_bytes);
+            assert_eq!(sha256_hex(&bytes), part.sha256);
+            assert_eq!(Document::load_mem(&bytes).unwrap().get_pages().len() as u32, part.page_count);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_split_to_zip_keeps_existing_archive() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let input = test_support::write_sample_pdf(temp_dir.path(), "input.pdf", 2);
+        let existing = temp_dir.path().join("input_split.zip");
+        std::fs::write(&existing, "keep")?;
+
+        let request = SplitRequest {
+            file_path: input,
+            page_ranges: PageRangeParser::parse_ranges("1,2")?,
+            output_dir: temp_dir.path().to_path_buf(),
+            config: SplitConfig {
+                output_mode: SplitOutputMode::Zip,
+                ..SplitConfig::default()
+            },
+        };
+
+        let result = PdfSplitter::new().split_pdf(request).await;
+        assert!(matches!(result, Err(AppError::Io(IoError::WriteFailed { .. }))));
+        assert_eq!(std::fs::read(&existing)?, b"keep");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_split_to_files_keeps_existing_part() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let input = test_support::write_sample_pdf(temp_dir.path(), "input.pdf", 2);
+        let existing = temp_dir.path().join("split_2.pdf");
+        std::fs::write(&existing, "keep")?;
+
+        let request = SplitRequest {
+            file_path: input,
+            page_ranges: PageRangeParser::parse_ranges("1,2")?,
+            output_dir: temp_dir.path().to_path_buf(),
+            config: SplitConfig::default(),
+        };
+
+        let result = PdfSplitter::new().split_pdf(request).await;
+        assert!(matches!(result, Err(AppError::Io(IoError::WriteFailed { .. }))));
+        assert_eq!(std::fs::read(&existing)?, b"keep");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_split_parts_are_self_contained() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let input = test_support::write_sample_pdf(temp_dir.path(), "input.pdf", 5);
+
+        let request = SplitRequest {
+            file_path: input,
+            page_ranges: PageRangeParser::parse_ranges("2-3,5")?,
+            output_dir: temp_dir.path().join("out"),
+            config: SplitConfig::default(),
+        };
+
+        let result = PdfSplitter::new().split_pdf(request).await?;
+        assert_eq!(result.files_created, 2);
+
+        let part = Document::load(&result.output_files[0]).unwrap();
+        let pages = part.get_pages();
+        assert_eq!(pages.len(), 2);
+
+        // Recursos herdados da árvore original são copiados para a página
+        let first_page = part.get_dictionary(pages[&1]).unwrap();
+        assert!(first_page.has(b"Resources"));
+        assert!(first_page.has(b"MediaBox"));
+        assert_eq!(part.extract_text(&[1]).unwrap().trim(), "Page 2");
+        assert_eq!(part.extract_text(&[2]).unwrap().trim(), "Page 3");
+
+        // A parte não carrega as páginas que ficaram de fora
+        let page_objects = part.objects.values()
+            .filter(|object| is_page_tree_node(object))
+            .count();
+        assert_eq!(page_objects, 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_parallel_split_keeps_request_order() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let input = test_support::write_sample_pdf(temp_dir.path(), "input.pdf", 200);
+
+        let ranges = (0..100)
+            .map(|i| PageRange::new(i * 2 + 1, i * 2 + 2))
+            .collect::<Result<Vec<_>>>()?;
+
+        let request = SplitRequest {
+            file_path: input,
+            page_ranges: ranges.clone(),
+            output_dir: temp_dir.path().join("out"),
+            config: SplitConfig::default(),
+        };
+
+        let splitter = PdfSplitter::with_performance_config(&PerformanceConfig {
+            cpu_threads: 4,
+            ..PerformanceConfig::default()
+        });
+        let result = splitter.split_pdf(request).await?;
+
+        assert_eq!(result.range_stats.len(), 100);
+        for (index, (stat, range)) in result.range_stats.iter().zip(&ranges).enumerate() {
+            assert_eq!(stat.range, *range);
+            assert_eq!(stat.output_file, temp_dir.path().join("out").join(format!("split_{}.pdf", index + 1)));
+        }
+
+        let last = Document::load(&result.output_files[99]).unwrap();
+        assert_eq!(last.extract_text(&[2]).unwrap().trim(), "Page 200");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_request_rejects_archive_name_with_path() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let input = test_support::write_sample_pdf(temp_dir.path(), "input.pdf", 2);
+
+        let mut request = SplitRequest {
+            file_path: input,
+            page_ranges: PageRangeParser::parse_ranges("1,2")?,
+            output_dir: temp_dir.path().to_path_buf(),
+            config: SplitConfig {
+                output_mode: SplitOutputMode::Zip,
+                ..SplitConfig::default()
+            },
+        };
+        request.validate(&FileHandler::new(), 2)?;
+
+        for name in ["sub/parts.zip", "..\\parts", ".."] {
+            request.config.archive_name = Some(name.to_string());
+            assert!(request.validate(&FileHandler::new(), 2).is_err(), "{}", name);
+        }
+        request.config.archive_name = None;
+
+        // Sem {index}, as duas partes teriam o mesmo nome
+        request.config.naming_pattern = "parte".to_string();
+        let error = request.validate(&FileHandler::new(), 2).unwrap_err();
+        assert!(error.to_string().contains("{index}"), "{}", error);
+
+        request.config.naming_pattern = "../parte_{index}".to_string();
+        assert!(request.validate(&FileHandler::new(), 2).is_err());
+        Ok(())
     }
 
     #[test]
     fn test_pdf_splitter_creation() {
         let splitter = PdfSplitter::new();
-        assert!(splitter.file_handler.config.max_file_size > 0);
+        assert!(splitter.runtime.file_handler().config().max_file_size > 0);
         
         let file_handler = FileHandler::new();
         let splitter = PdfSplitter::with_file_handler(file_handler);
-        assert!(splitter.file_handler.config.max_file_size > 0);
+        assert!(splitter.runtime.file_handler().config().max_file_size > 0);
     }
 
     // Nota: Testes de split real requerem PDFs de teste
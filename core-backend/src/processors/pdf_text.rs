@@ -0,0 +1,1833 @@
+//! Extração de texto de PDFs para o DocHub
+//!
+//! O texto de uma página não fica gravado como texto: o content stream
+//! posiciona códigos com os operadores `Tj`, `TJ`, `'` e `"`, e é a fonte em
+//! uso que define quantos bytes forma cada código e qual caractere ele
+//! representa. Este módulo interpreta o conteúdo das páginas (inclusive Form
+//! XObjects) e decodifica cada código pela fonte; busca, redação e divisão
+//! por marcadores partem deste resultado.
+//!
+//! ## Funcionalidades:
+//! - Estado de texto: `Tm`/`Td`/`TD`/`T*`/`TL`, `Tc`/`Tw`/`Tz`/`Ts` e a CTM (`cm`, `q`/`Q`)
+//! - CMaps `/ToUnicode` (`bfchar`/`bfrange`, códigos de 1 a 4 bytes)
+//! - Fontes simples com `/Encoding` (WinAnsi, MacRoman, Standard, PDFDoc) e `/Differences`
+//!   (nomes de glifos pela Adobe Glyph List, também onde o `/ToUnicode` é omisso)
+//! - Fontes Type0/CID: `Identity-H/V`, CMaps incorporados e CMaps predefinidos UCS-2/UTF-16
+//! - Larguras de `/Widths` e `/W` para posicionar os glifos e detectar espaços
+//! - Texto por página na ordem do conteúdo ou preservando o layout da página
+//! - Saída em `.txt` (páginas separadas por form feed) ou JSON
+
+// A extração devolve o `Result` do crate; o tamanho de `AppError` não é escolha deste módulo
+#![allow(clippy::result_large_err)]
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Instant;
+use tracing::{info, error, instrument};
+
+use crate::utils::error_handling::{Result, AppError, PdfError};
+use crate::utils::error_handling::validate;
+use crate::api::file_handlers::FileHandler;
+use crate::processors::pdf_content::{self, ContentItem};
+use crate::processors::pdf_encoding;
+use crate::processors::pdf_filters;
+use crate::processors::pdf_inventory::{self, Matrix, IDENTITY, MAX_DRAWS_PER_PAGE};
+use crate::processors::runtime::ProcessorRuntime;
+
+/// Limite de Form XObjects aninhados seguidos ao percorrer o conteúdo
+const MAX_FORM_DEPTH: usize = 16;
+
+/// Limite de glifos registrados por página
+const MAX_GLYPHS_PER_PAGE: usize = 1_000_000;
+
+/// Limite de CIDs expandidos por faixa de `/W` (`c_first c_last w`)
+const MAX_WIDTH_RANGE: u32 = 0xFFFF;
+
+/// Caractere emitido para códigos sem mapeamento Unicode
+const UNKNOWN_CHAR: char = '\u{FFFD}';
+
+/// Distância horizontal, em frações do tamanho da fonte, tratada como espaço entre palavras
+const WORD_GAP: f64 = 0.15;
+
+/// Diferença vertical, em frações do tamanho da fonte, que ainda conta como a mesma linha
+const LINE_TOLERANCE: f64 = 0.5;
+
+/// Entrelinha assumida no modo de layout, em frações do tamanho da fonte
+const LINE_SPACING: f64 = 1.2;
+
+/// Limite de linhas em branco consecutivas no modo de layout
+const MAX_BLANK_LINES: usize = 3;
+
+/// Separador de páginas na saída `.txt`
+const PAGE_SEPARATOR: char = '\u{0C}';
+
+/// Caracteres de 0x80-0x9F em WinAnsiEncoding (0 = não definido)
+const WIN_ANSI_0X80: [u16; 32] = [
+    0x20AC, 0, 0x201A, 0x0192, 0x201E, 0x2026, 0x2020, 0x2021, 0x02C6, 0x2030, 0x0160, 0x2039, 0x0152, 0, 0x017D, 0,
+    0, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013, 0x2014, 0x02DC, 0x2122, 0x0161, 0x203A, 0x0153, 0, 0x017E, 0x0178,
+];
+
+/// Caracteres de 0x80-0xFF em MacRomanEncoding (0 = não definido)
+const MAC_ROMAN_0X80: [u16; 128] = [
+    0x00C4, 0x00C5, 0x00C7, 0x00C9, 0x00D1, 0x00D6, 0x00DC, 0x00E1, 0x00E0, 0x00E2, 0x00E4, 0x00E3, 0x00E5, 0x00E7, 0x00E9, 0x00E8,
+    0x00EA, 0x00EB, 0x00ED, 0x00EC, 0x00EE, 0x00EF, 0x00F1, 0x00F3, 0x00F2, 0x00F4, 0x00F6, 0x00F5, 0x00FA, 0x00F9, 0x00FB, 0x00FC,
+    0x2020, 0x00B0, 0x00A2, 0x00A3, 0x00A7, 0x2022, 0x00B6, 0x00DF, 0x00AE, 0x00A9, 0x2122, 0x00B4, 0x00A8, 0x2260, 0x00C6, 0x00D8,
+    0x221E, 0x00B1, 0x2264, 0x2265, 0x00A5, 0x00B5, 0x2202, 0x2211, 0x220F, 0x03C0, 0x222B, 0x00AA, 0x00BA, 0x03A9, 0x00E6, 0x00F8,
+    0x00BF, 0x00A1, 0x00AC, 0x221A, 0x0192, 0x2248, 0x2206, 0x00AB, 0x00BB, 0x2026, 0x00A0, 0x00C0, 0x00C3, 0x00D5, 0x0152, 0x0153,
+    0x2013, 0x2014, 0x201C, 0x201D, 0x2018, 0x2019, 0x00F7, 0x25CA, 0x00FF, 0x0178, 0x2044, 0x00A4, 0x2039, 0x203A, 0xFB01, 0xFB02,
+    0x2021, 0x00B7, 0x201A, 0x201E, 0x2030, 0x00C2, 0x00CA, 0x00C1, 0x00CB, 0x00C8, 0x00CD, 0x00CE, 0x00CF, 0x00CC, 0x00D3, 0x00D4,
+    0, 0x00D2, 0x00DA, 0x00DB, 0x00D9, 0x0131, 0x02C6, 0x02DC, 0x00AF, 0x02D8, 0x02D9, 0x02DA, 0x00B8, 0x02DD, 0x02DB, 0x02C7,
+];
+
+/// Posições acima de 0x7F definidas em StandardEncoding
+const STANDARD_HIGH: &[(u8, u16)] = &[
+    (0xA1, 0x00A1), (0xA2, 0x00A2), (0xA3, 0x00A3), (0xA4, 0x2044), (0xA5, 0x00A5), (0xA6, 0x0192), (0xA7, 0x00A7),
+    (0xA8, 0x00A4), (0xA9, 0x0027), (0xAA, 0x201C), (0xAB, 0x00AB), (0xAC, 0x2039), (0xAD, 0x203A), (0xAE, 0xFB01),
+    (0xAF, 0xFB02), (0xB1, 0x2013), (0xB2, 0x2020), (0xB3, 0x2021), (0xB4, 0x00B7), (0xB6, 0x00B6), (0xB7, 0x2022),
+    (0xB8, 0x201A), (0xB9, 0x201E), (0xBA, 0x201D), (0xBB, 0x00BB), (0xBC, 0x2026), (0xBD, 0x2030), (0xBF, 0x00BF),
+    (0xC1, 0x0060), (0xC2, 0x00B4), (0xC3, 0x02C6), (0xC4, 0x02DC), (0xC5, 0x00AF), (0xC6, 0x02D8), (0xC7, 0x02D9),
+    (0xC8, 0x00A8), (0xCA, 0x02DA), (0xCB, 0x00B8), (0xCD, 0x02DD), (0xCE, 0x02DB), (0xCF, 0x02C7), (0xD0, 0x2014),
+    (0xE1, 0x00C6), (0xE3, 0x00AA), (0xE8, 0x0141), (0xE9, 0x00D8), (0xEA, 0x0152), (0xEB, 0x00BA), (0xF1, 0x00E6),
+    (0xF5, 0x0131), (0xF8, 0x0142), (0xF9, 0x00F8), (0xFA, 0x0153), (0xFB, 0x00DF),
+];
+
+/// Adobe Glyph List: nomes de glifos usados em `/Differences`
+///
+/// `uniXXXX`, `uXXXX` e ligaduras `f_f_i` são lidos em `glyph_to_unicode`.
+static GLYPH_LIST: Lazy<HashMap<&'static str, char>> = Lazy::new(|| {
+    include_str!("../../data/glyphlist.txt")
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let (name, code) = line.split_once(';')?;
+            let c = u32::from_str_radix(code, 16).ok().and_then(char::from_u32)?;
+            Some((name, c))
+        })
+        .collect()
+});
+
+/// Larguras da Helvetica para 0x20-0x7E, usadas nas fontes padrão sem `/Widths`
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 222, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556, 556, 556, 556, 556, 556,
+    556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722,
+    778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556, 222, 556, 556, 500, 556, 556,
+    278, 556, 556, 222, 222, 500, 222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260,
+    334, 584,
+];
+
+/// Largura de todos os glifos das fontes Courier
+const COURIER_WIDTH: f64 = 600.0;
+
+/// Texto extraído de uma página
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PageText {
+    /// Número da página (1-based)
+    pub page_number: u32,
+    /// Texto da página
+    pub text: String,
+    /// Caracteres extraídos, sem contar espaços em branco
+    pub character_count: usize,
+}
+
+impl PageText {
+    fn new(page_number: u32, text: String) -> Self {
+        let character_count = text.chars().filter(|c| !c.is_whitespace()).count();
+        Self { page_number, text, character_count }
+    }
+}
+
+/// Texto de uma página
+///
+/// Com `layout`, os glifos são dispostos em uma grade de caracteres pela
+/// posição na página (colunas e recuos preservados); sem ele, seguem a ordem
+/// do content stream, com quebras de linha na mudança de linha de base.
+pub fn page_text(doc: &Document, page_id: ObjectId, layout: bool) -> String {
+    let glyphs = page_glyphs(doc, page_id);
+    if layout {
+        layout_text(&glyphs)
+    } else {
+        reading_text(&glyphs)
+    }
+}
+
+/// Texto das páginas informadas (vazio = todas), na ordem do documento
+pub fn extract(doc: &Document, pages: &[u32], layout: bool) -> Vec<PageText> {
+    doc.get_pages()
+        .into_iter()
+        .filter(|(page_number, _)| pages.is_empty() || pages.contains(page_number))
+        .map(|(page_number, page_id)| PageText::new(page_number, page_text(doc, page_id, layout)))
+        .collect()
+}
+
+/// Formato do arquivo de saída
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TextOutputFormat {
+    /// Texto puro, com as páginas separadas por form feed (`\f`)
+    Txt,
+    /// Lista de `PageText` em JSON
+    Json,
+}
+
+/// Configurações da extração de texto
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExtractTextConfig {
+    /// Preservar o layout da página (colunas e recuos) em vez da ordem do conteúdo?
+    pub layout: bool,
+    /// Formato do arquivo de saída (None = pela extensão: `.json` ou texto)
+    pub format: Option<TextOutputFormat>,
+}
+
+/// Request para extração de texto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractTextRequest {
+    /// Caminho para o PDF
+    pub file_path: PathBuf,
+    /// Arquivo onde gravar o texto (None = texto apenas na resposta)
+    pub output_path: Option<PathBuf>,
+    /// Páginas desejadas (vazio = todas)
+    #[serde(default)]
+    pub pages: Vec<u32>,
+    /// Configurações da extração
+    #[serde(default)]
+    pub config: ExtractTextConfig,
+}
+
+impl ExtractTextRequest {
+    /// Cria um ExtractTextRequest a partir de JSON
+    pub fn from_value(data: &Value) -> Result<Self> {
+        let file_path = data["file"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'file' field"))?;
+
+        let output_path = match data.get("output") {
+            Some(output) => Some(
+                output
+                    .as_str()
+                    .map(PathBuf::from)
+                    .ok_or_else(|| AppError::validation("Missing or invalid 'output' field"))?,
+            ),
+            None => None,
+        };
+
+        let pages = match data.get("pages") {
+            Some(pages) => serde_json::from_value(pages.clone())
+                .map_err(|e| AppError::validation(format!("Invalid pages: {}", e)))?,
+            None => Vec::new(),
+        };
+
+        // Configurações opcionais
+        let config = if let Some(config_val) = data.get("config") {
+            serde_json::from_value(config_val.clone())
+                .map_err(|e| AppError::validation(format!("Invalid config: {}", e)))?
+        } else {
+            ExtractTextConfig::default()
+        };
+
+        Ok(Self {
+            file_path,
+            output_path,
+            pages,
+            config,
+        })
+    }
+
+    /// Valida a request
+    pub fn validate(&self, file_handler: &FileHandler) -> Result<()> {
+        let metadata = file_handler.validate_file(self.file_path.to_str().unwrap_or(""))?;
+        validate(
+            self.output_path.as_ref() != Some(&self.file_path),
+            AppError::validation("Output path must differ from the input file"),
+        )?;
+        validate(
+            !self.pages.contains(&0),
+            AppError::validation("Page numbers start at 1"),
+        )?;
+
+        info!(
+            path = %self.file_path.display(),
+            size = metadata.len(),
+            "Input file validated for extract_text"
+        );
+
+        Ok(())
+    }
+
+    /// Formato do arquivo de saída: o configurado ou o da extensão
+    pub fn output_format(&self) -> Option<TextOutputFormat> {
+        let output_path = self.output_path.as_ref()?;
+        let json = output_path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        Some(self.config.format.unwrap_or(if json { TextOutputFormat::Json } else { TextOutputFormat::Txt }))
+    }
+}
+
+/// Resultado da extração de texto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractTextResult {
+    /// Caminho do PDF
+    pub file_path: PathBuf,
+    /// Arquivo gravado (None = texto apenas na resposta)
+    pub output_path: Option<PathBuf>,
+    /// Formato do arquivo gravado
+    pub format: Option<TextOutputFormat>,
+    /// Número de páginas do documento
+    pub page_count: u32,
+    /// Texto das páginas extraídas, na ordem do documento
+    pub pages: Vec<PageText>,
+    /// Total de caracteres extraídos (sem espaços em branco)
+    pub character_count: usize,
+    /// Páginas sem texto extraível (ex.: digitalizadas, que precisam de OCR)
+    pub pages_without_text: Vec<u32>,
+    /// Tempo total de processamento (em milissegundos)
+    pub processing_time_ms: u128,
+}
+
+/// Processador de extração de texto
+#[derive(Debug, Clone)]
+pub struct PdfTextExtractor {
+    runtime: ProcessorRuntime,
+}
+
+processor_constructors!(PdfTextExtractor);
+
+impl PdfTextExtractor {
+    /// Extrai o texto das páginas de um PDF
+    #[instrument(name = "extract_text", skip(self, request), fields(
+        file = %request.file_path.display(),
+        layout = request.config.layout
+    ))]
+    pub async fn extract_text(&self, request: ExtractTextRequest) -> Result<ExtractTextResult> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.extract_text_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `extract_text`, executado no pool de CPU
+    ///
+    /// As páginas são interpretadas em paralelo; o resultado mantém a ordem do documento.
+    fn extract_text_blocking(&self, request: ExtractTextRequest) -> Result<ExtractTextResult> {
+        let start_time = Instant::now();
+
+        request.validate(self.runtime.file_handler())?;
+        let doc = self.load_document(&request.file_path)?;
+
+        let all_pages = doc.get_pages();
+        if let Some(missing) = request.pages.iter().find(|page| !all_pages.contains_key(page)) {
+            return Err(AppError::validation(format!("Page {} does not exist", missing)));
+        }
+        let selected: Vec<(u32, ObjectId)> = all_pages
+            .iter()
+            .filter(|(page_number, _)| request.pages.is_empty() || request.pages.contains(page_number))
+            .map(|(page_number, page_id)| (*page_number, *page_id))
+            .collect();
+
+        let layout = request.config.layout;
+        let pool = self.runtime.thread_pool()?;
+        let pages: Vec<PageText> = pool.install(|| {
+            selected
+                .par_iter()
+                .map(|&(page_number, page_id)| PageText::new(page_number, page_text(&doc, page_id, layout)))
+                .collect()
+        });
+
+        let format = request.output_format();
+        if let (Some(output_path), Some(format)) = (&request.output_path, format) {
+            self.runtime
+                .file_handler()
+                .write_file(output_path.to_str().unwrap_or(""), &render(&pages, format)?)?;
+        }
+
+        let result = ExtractTextResult {
+            file_path: request.file_path,
+            output_path: request.output_path,
+            format,
+            page_count: all_pages.len() as u32,
+            character_count: pages.iter().map(|page| page.character_count).sum(),
+            pages_without_text: pages
+                .iter()
+                .filter(|page| page.character_count == 0)
+                .map(|page| page.page_number)
+                .collect(),
+            pages,
+            processing_time_ms: start_time.elapsed().as_millis(),
+        };
+
+        info!(
+            file = %result.file_path.display(),
+            pages = result.pages.len(),
+            characters = result.character_count,
+            without_text = result.pages_without_text.len(),
+            processing_time_ms = result.processing_time_ms,
+            "Text extracted"
+        );
+
+        Ok(result)
+    }
+
+    // ==================== MÉTODOS PRIVADOS ====================
+
+    /// Carrega um documento PDF
+    fn load_document(&self, path: &Path) -> Result<Document> {
+        info!(path = %path.display(), "Loading PDF document");
+
+        Document::load(path)
+            .map_err(|e| {
+                error!(path = %path.display(), error = %e, "Failed to load PDF");
+                AppError::Pdf(PdfError::CorruptedPdf {
+                    path: path.to_path_buf(),
+                })
+            })
+    }
+}
+
+// ==================== INTERPRETAÇÃO DO CONTEÚDO ====================
+
+/// Glifo posicionado na página, em pontos e com a rotação da página aplicada
+#[derive(Debug, Clone)]
+struct Glyph {
+    text: String,
+    /// Origem do glifo (linha de base)
+    x: f64,
+    y: f64,
+    /// Fim do desenho do glifo, sem o espaçamento de `Tc`/`Tw`
+    end_x: f64,
+    /// Tamanho efetivo da fonte
+    size: f64,
+}
+
+/// Parte do estado gráfico que afeta o texto (salva por `q`/`Q`)
+#[derive(Debug, Clone, Copy)]
+struct TextState {
+    ctm: Matrix,
+    /// Fonte atual (índice em `TextWalker::fonts`)
+    font: Option<usize>,
+    font_size: f64,
+    char_spacing: f64,
+    word_spacing: f64,
+    /// `Tz` como fração (100% = 1.0)
+    horizontal_scaling: f64,
+    leading: f64,
+    rise: f64,
+}
+
+impl Default for TextState {
+    fn default() -> Self {
+        Self {
+            ctm: IDENTITY,
+            font: None,
+            font_size: 0.0,
+            char_spacing: 0.0,
+            word_spacing: 0.0,
+            horizontal_scaling: 1.0,
+            leading: 0.0,
+            rise: 0.0,
+        }
+    }
+}
+
+/// Matrizes de texto e de início de linha (válidas entre `BT` e `ET`)
+#[derive(Debug, Clone, Copy)]
+struct TextObject {
+    matrix: Matrix,
+    line_matrix: Matrix,
+}
+
+impl TextObject {
+    fn new() -> Self {
+        Self { matrix: IDENTITY, line_matrix: IDENTITY }
+    }
+
+    /// `Tm`
+    fn set(&mut self, matrix: Matrix) {
+        self.matrix = matrix;
+        self.line_matrix = matrix;
+    }
+
+    /// `Td`: desloca o início da linha
+    fn move_line(&mut self, tx: f64, ty: f64) {
+        self.set(pdf_inventory::multiply(&translation(tx, ty), &self.line_matrix));
+    }
+
+    /// Avança a posição do texto na linha
+    fn advance(&mut self, tx: f64) {
+        self.matrix = pdf_inventory::multiply(&translation(tx, 0.0), &self.matrix);
+    }
+}
+
+/// Percorre o conteúdo de uma página acompanhando o estado de texto
+struct TextWalker<'a> {
+    doc: &'a Document,
+    /// `/Rotate` da página, em graus
+    rotation: u32,
+    fonts: Vec<Font>,
+    /// Fontes já carregadas, por objeto
+    loaded: HashMap<ObjectId, usize>,
+    /// Form XObjects em execução (evita ciclos)
+    forms: Vec<ObjectId>,
+    /// Conteúdo já decodificado de cada Form usado na página
+    decoded_forms: HashMap<ObjectId, Rc<[u8]>>,
+    /// `Do` restantes do orçamento da página
+    draws_left: usize,
+    glyphs: Vec<Glyph>,
+}
+
+impl<'a> TextWalker<'a> {
+    fn walk(&mut self, content: &[u8], resources: &'a Dictionary, mut state: TextState) {
+        let mut saved = Vec::new();
+        let mut text = TextObject::new();
+
+        for item in pdf_content::parse(content) {
+            let ContentItem::Operation(operation) = item else {
+                continue;
+            };
+            let operands = operation.operands.as_slice();
+
+            match operation.operator.as_str() {
+                "q" => saved.push(state),
+                "Q" => {
+                    if let Some(previous) = saved.pop() {
+                        state = previous;
+                    }
+                }
+                "cm" => {
+                    if let Some(matrix) = pdf_inventory::matrix(operands) {
+                        state.ctm = pdf_inventory::multiply(&matrix, &state.ctm);
+                    }
+                }
+                "Do" => {
+                    if let Some(Ok(name)) = operands.first().map(Object::as_name) {
+                        if self.draws_left == 0 {
+                            return;
+                        }
+                        self.draws_left -= 1;
+                        self.draw_form(name, resources, state);
+                    }
+                }
+                "BT" => text = TextObject::new(),
+                "Tf" => {
+                    if let [name, size] = operands {
+                        state.font = name.as_name().ok().and_then(|name| self.font(name, resources));
+                        state.font_size = number(size).unwrap_or(state.font_size);
+                    }
+                }
+                "Tc" => set_number(&mut state.char_spacing, operands),
+                "Tw" => set_number(&mut state.word_spacing, operands),
+                "TL" => set_number(&mut state.leading, operands),
+                "Ts" => set_number(&mut state.rise, operands),
+                "Tz" => {
+                    if let Some(scale) = operands.first().and_then(number) {
+                        state.horizontal_scaling = scale / 100.0;
+                    }
+                }
+                operator @ ("Td" | "TD") => {
+                    if let [Some(tx), Some(ty)] = [operands.first(), operands.get(1)].map(|o| o.and_then(number)) {
+                        if operator == "TD" {
+                            state.leading = -ty;
+                        }
+                        text.move_line(tx, ty);
+                    }
+                }
+                "Tm" => {
+                    if let Some(matrix) = pdf_inventory::matrix(operands) {
+                        text.set(matrix);
+                    }
+                }
+                "T*" => text.move_line(0.0, -state.leading),
+                "Tj" => {
+                    if let Some(Ok(bytes)) = operands.first().map(Object::as_str) {
+                        self.show(bytes, &state, &mut text);
+                    }
+                }
+                "'" => {
+                    text.move_line(0.0, -state.leading);
+                    if let Some(Ok(bytes)) = operands.first().map(Object::as_str) {
+                        self.show(bytes, &state, &mut text);
+                    }
+                }
+                "\"" => {
+                    if let [word_spacing, char_spacing, string] = operands {
+                        state.word_spacing = number(word_spacing).unwrap_or(state.word_spacing);
+                        state.char_spacing = number(char_spacing).unwrap_or(state.char_spacing);
+                        text.move_line(0.0, -state.leading);
+                        if let Ok(bytes) = string.as_str() {
+                            self.show(bytes, &state, &mut text);
+                        }
+                    }
+                }
+                "TJ" => {
+                    let Some(Ok(items)) = operands.first().map(Object::as_array) else {
+                        continue;
+                    };
+                    for item in items {
+                        match item {
+                            Object::String(bytes, _) => self.show(bytes, &state, &mut text),
+                            // Ajuste em milésimos de unidade de texto, subtraído do avanço
+                            other => {
+                                if let Some(adjustment) = number(other) {
+                                    text.advance(-adjustment / 1000.0 * state.font_size * state.horizontal_scaling);
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Mostra uma string com a fonte atual, registrando um glifo por código
+    fn show(&mut self, bytes: &[u8], state: &TextState, text: &mut TextObject) {
+        let Some(font) = state.font.map(|index| &self.fonts[index]) else {
+            return;
+        };
+        let scaling = state.horizontal_scaling;
+        let mut rest = bytes;
+
+        while !rest.is_empty() {
+            let (code, length) = font.next_code(rest);
+            let is_space = rest[..length] == [b' '];
+            rest = &rest[length..];
+
+            let width = font.width(code) * state.font_size * scaling;
+            let device = pdf_inventory::multiply(&text.matrix, &state.ctm);
+            let (x, y) = rotate(transform(&device, 0.0, state.rise), self.rotation);
+            let (end_x, _) = rotate(transform(&device, width, state.rise), self.rotation);
+
+            let unicode = normalize(font.decode(code, length));
+            if !unicode.is_empty() && self.glyphs.len() < MAX_GLYPHS_PER_PAGE {
+                self.glyphs.push(Glyph {
+                    text: unicode,
+                    x,
+                    y,
+                    end_x,
+                    size: (state.font_size * device[2].hypot(device[3])).abs(),
+                });
+            }
+
+            let word_spacing = if is_space { state.word_spacing } else { 0.0 };
+            text.advance(width + (state.char_spacing + word_spacing) * scaling);
+        }
+    }
+
+    /// Executa um Form XObject (imagens não têm texto)
+    fn draw_form(&mut self, name: &[u8], resources: &'a Dictionary, state: TextState) {
+        let doc = self.doc;
+        let Some(xobject) = resources
+            .get_deref(b"XObject", doc)
+            .and_then(Object::as_dict)
+            .and_then(|xobjects| xobjects.get(name))
+            .ok()
+        else {
+            return;
+        };
+        let Ok((Some(id), Object::Stream(stream))) = doc.dereference(xobject) else {
+            return;
+        };
+        if !pdf_inventory::is_subtype(&stream.dict, b"Form") || self.forms.contains(&id) || self.forms.len() >= MAX_FORM_DEPTH {
+            return;
+        }
+        let content = match self.decoded_forms.get(&id) {
+            Some(content) => Rc::clone(content),
+            None => {
+                let Ok(decoded) = pdf_filters::decode_stream(doc, stream) else {
+                    return;
+                };
+                let content: Rc<[u8]> = decoded.data.into();
+                self.decoded_forms.insert(id, Rc::clone(&content));
+                content
+            }
+        };
+
+        let form_matrix = stream
+            .dict
+            .get(b"Matrix")
+            .and_then(Object::as_array)
+            .ok()
+            .and_then(|operands| pdf_inventory::matrix(operands))
+            .unwrap_or(IDENTITY);
+        let form_resources = stream
+            .dict
+            .get_deref(b"Resources", doc)
+            .and_then(Object::as_dict)
+            .unwrap_or(resources);
+
+        self.forms.push(id);
+        let form_state = TextState { ctm: pdf_inventory::multiply(&form_matrix, &state.ctm), ..state };
+        self.walk(&content, form_resources, form_state);
+        self.forms.pop();
+    }
+
+    /// Índice da fonte `name` dos recursos, carregando-a no primeiro uso
+    fn font(&mut self, name: &[u8], resources: &'a Dictionary) -> Option<usize> {
+        let doc = self.doc;
+        let font = resources
+            .get_deref(b"Font", doc)
+            .and_then(Object::as_dict)
+            .and_then(|fonts| fonts.get(name))
+            .ok()?;
+        let (font_id, font) = doc.dereference(font).ok()?;
+        let font = font.as_dict().ok()?;
+
+        if let Some(index) = font_id.and_then(|id| self.loaded.get(&id)) {
+            return Some(*index);
+        }
+        self.fonts.push(Font::load(doc, font));
+        let index = self.fonts.len() - 1;
+        if let Some(id) = font_id {
+            self.loaded.insert(id, index);
+        }
+        Some(index)
+    }
+}
+
+/// Glifos de uma página, na ordem do content stream
+fn page_glyphs(doc: &Document, page_id: ObjectId) -> Vec<Glyph> {
+    // Sem recursos não há fontes, e sem fontes não há texto
+    let Some(resources) = pdf_inventory::page_resources(doc, page_id) else {
+        return Vec::new();
+    };
+    let rotation = pdf_inventory::inherited(doc, page_id, b"Rotate")
+        .and_then(|rotate| rotate.as_i64().ok())
+        .map(pdf_inventory::normalize_rotation)
+        .unwrap_or(0);
+
+    let mut walker = TextWalker {
+        doc,
+        rotation,
+        fonts: Vec::new(),
+        loaded: HashMap::new(),
+        forms: Vec::new(),
+        decoded_forms: HashMap::new(),
+        draws_left: MAX_DRAWS_PER_PAGE,
+        glyphs: Vec::new(),
+    };
+    walker.walk(&pdf_inventory::page_content(doc, page_id), resources, TextState::default());
+    walker.glyphs
+}
+
+// ==================== FONTES ====================
+
+/// Como os códigos de uma fonte viram CIDs (Type0) ou caracteres (fontes simples)
+#[derive(Debug, Clone)]
+enum CodeMapping {
+    /// Fonte simples: tabela de 256 posições (`/Encoding` + `/Differences`)
+    Simple(Vec<Option<String>>),
+    /// O código é o próprio CID (`Identity-H`/`Identity-V`)
+    Identity,
+    /// O código é um valor UCS-2/UTF-16 (CMaps predefinidos `Uni*-UCS2-*`/`Uni*-UTF16-*`)
+    Unicode,
+    /// CMap incorporado em `/Encoding`
+    Embedded(CMap),
+}
+
+/// Decodificação de uma fonte: códigos → texto e larguras
+#[derive(Debug, Clone)]
+struct Font {
+    /// Faixas de códigos válidos (define quantos bytes forma cada código)
+    codespace: Vec<CodeRange>,
+    mapping: CodeMapping,
+    /// CMap `/ToUnicode` (tem precedência sobre o mapeamento da fonte)
+    to_unicode: Option<CMap>,
+    /// Larguras por código (fontes simples) ou por CID (Type0), em unidades do glifo
+    widths: HashMap<u32, f64>,
+    default_width: f64,
+    /// Fator das unidades do glifo para o espaço de texto (1/1000 ou a `/FontMatrix` do Type3)
+    width_scale: f64,
+}
+
+impl Font {
+    fn load(doc: &Document, font: &Dictionary) -> Self {
+        let to_unicode = font
+            .get_deref(b"ToUnicode", doc)
+            .and_then(Object::as_stream)
+            .ok()
+            .and_then(|stream| pdf_filters::decode_stream(doc, stream).ok())
+            .map(|decoded| CMap::parse(&decoded.data));
+
+        if pdf_inventory::is_subtype(font, b"Type0") {
+            Self::load_composite(doc, font, to_unicode)
+        } else {
+            Self::load_simple(doc, font, to_unicode)
+        }
+    }
+
+    /// Fonte Type0: códigos pelo CMap de `/Encoding`, larguras de `/W` da fonte descendente
+    fn load_composite(doc: &Document, font: &Dictionary, to_unicode: Option<CMap>) -> Self {
+        let two_bytes = vec![CodeRange::new(&[0x00, 0x00], &[0xFF, 0xFF])];
+
+        let (mapping, codespace) = match font.get_deref(b"Encoding", doc) {
+            Ok(Object::Stream(stream)) => {
+                let cmap = pdf_filters::decode_stream(doc, stream)
+                    .map(|decoded| CMap::parse(&decoded.data))
+                    .unwrap_or_default();
+                let codespace = cmap.codespace.clone();
+                (CodeMapping::Embedded(cmap), codespace)
+            }
+            Ok(Object::Name(name)) if name.starts_with(b"Identity") => (CodeMapping::Identity, two_bytes.clone()),
+            Ok(Object::Name(name)) if contains(name, b"UTF16") => (
+                CodeMapping::Unicode,
+                vec![
+                    CodeRange::new(&[0x00, 0x00], &[0xD7, 0xFF]),
+                    CodeRange::new(&[0xD8, 0x00, 0xDC, 0x00], &[0xDB, 0xFF, 0xDF, 0xFF]),
+                    CodeRange::new(&[0xE0, 0x00], &[0xFF, 0xFF]),
+                ],
+            ),
+            Ok(Object::Name(name)) if contains(name, b"UCS2") => (CodeMapping::Unicode, two_bytes.clone()),
+            // Outros CMaps predefinidos (ex.: 90ms-RKSJ-H): os tamanhos vêm do /ToUnicode
+            _ => (CodeMapping::Identity, Vec::new()),
+        };
+        let codespace = if !codespace.is_empty() {
+            codespace
+        } else {
+            to_unicode
+                .as_ref()
+                .map(|cmap| cmap.codespace.clone())
+                .filter(|codespace| !codespace.is_empty())
+                .unwrap_or(two_bytes)
+        };
+
+        let descendant = font
+            .get_deref(b"DescendantFonts", doc)
+            .and_then(Object::as_array)
+            .ok()
+            .and_then(|fonts| fonts.first())
+            .and_then(|descendant| doc.dereference(descendant).ok())
+            .and_then(|(_, descendant)| descendant.as_dict().ok());
+        let widths = descendant
+            .and_then(|descendant| descendant.get_deref(b"W", doc).and_then(Object::as_array).ok())
+            .map(|items| cid_widths(doc, items))
+            .unwrap_or_default();
+        let default_width = descendant
+            .and_then(|descendant| descendant.get_deref(b"DW", doc).ok())
+            .and_then(number)
+            .unwrap_or(1000.0);
+
+        Self {
+            codespace,
+            mapping,
+            to_unicode,
+            widths,
+            default_width,
+            width_scale: 0.001,
+        }
+    }
+
+    /// Fonte simples (Type1, TrueType, Type3): um byte por código
+    fn load_simple(doc: &Document, font: &Dictionary, to_unicode: Option<CMap>) -> Self {
+        let encoding = simple_encoding(doc, font);
+
+        let first_char = font
+            .get_deref(b"FirstChar", doc)
+            .and_then(Object::as_i64)
+            .ok()
+            .and_then(|first| u32::try_from(first).ok())
+            .unwrap_or(0);
+        let mut widths: HashMap<u32, f64> = font
+            .get_deref(b"Widths", doc)
+            .and_then(Object::as_array)
+            .map(|items| {
+                (first_char..)
+                    .zip(items)
+                    .filter_map(|(code, width)| Some((code, doc.dereference(width).ok().and_then(|(_, w)| number(w))?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut default_width = font
+            .get_deref(b"FontDescriptor", doc)
+            .and_then(Object::as_dict)
+            .and_then(|descriptor| descriptor.get_deref(b"MissingWidth", doc))
+            .ok()
+            .and_then(number)
+            .unwrap_or(0.0);
+
+        // Fontes padrão podem omitir /Widths: estima pelas métricas da Helvetica ou Courier
+        if font.get(b"Widths").is_err() {
+            let monospaced = font
+                .get(b"BaseFont")
+                .and_then(Object::as_name)
+                .is_ok_and(|name| contains(name, b"Courier"));
+            default_width = if monospaced { COURIER_WIDTH } else { 500.0 };
+            for (code, text) in (0u32..).zip(&encoding) {
+                let ascii = text.as_deref().and_then(|text| text.chars().next()).filter(|c| (' '..='~').contains(c));
+                if let Some(c) = ascii {
+                    let width = if monospaced { COURIER_WIDTH } else { f64::from(HELVETICA_WIDTHS[c as usize - 0x20]) };
+                    widths.insert(code, width);
+                }
+            }
+        }
+
+        let width_scale = if pdf_inventory::is_subtype(font, b"Type3") {
+            font.get_deref(b"FontMatrix", doc)
+                .and_then(Object::as_array)
+                .ok()
+                .and_then(|matrix| matrix.first())
+                .and_then(number)
+                .map(f64::abs)
+                .unwrap_or(0.001)
+        } else {
+            0.001
+        };
+
+        Self {
+            codespace: vec![CodeRange::new(&[0x00], &[0xFF])],
+            mapping: CodeMapping::Simple(encoding),
+            to_unicode,
+            widths,
+            default_width,
+            width_scale,
+        }
+    }
+
+    /// Próximo código da string: valor e número de bytes
+    fn next_code(&self, bytes: &[u8]) -> (u32, usize) {
+        let length = self
+            .codespace
+            .iter()
+            .filter(|range| range.matches(bytes))
+            .map(|range| range.low.len())
+            .min()
+            // Código fora do codespace: consome o menor tamanho declarado
+            .or_else(|| self.codespace.iter().map(|range| range.low.len()).min())
+            .unwrap_or(1)
+            .min(bytes.len());
+
+        (code_value(&bytes[..length]), length)
+    }
+
+    /// Texto de um código (`UNKNOWN_CHAR` quando a fonte não permite saber)
+    ///
+    /// Códigos ausentes do `/ToUnicode`, ou mapeados para vazio, NUL ou U+FFFD,
+    /// caem na codificação da fonte (inclusive o nome do glifo em `/Differences`).
+    fn decode(&self, code: u32, length: usize) -> String {
+        let mapped = self
+            .to_unicode
+            .as_ref()
+            .and_then(|cmap| cmap.unicode(code))
+            .filter(|text| text.chars().any(|c| c != UNKNOWN_CHAR && c != '\0'));
+        if let Some(text) = mapped {
+            return text;
+        }
+
+        let text = match &self.mapping {
+            CodeMapping::Simple(encoding) => encoding.get(code as usize).cloned().flatten(),
+            CodeMapping::Unicode => {
+                let units: Vec<u16> = if length == 4 { vec![(code >> 16) as u16, code as u16] } else { vec![code as u16] };
+                Some(String::from_utf16_lossy(&units))
+            }
+            CodeMapping::Identity | CodeMapping::Embedded(_) => None,
+        };
+        text.unwrap_or_else(|| UNKNOWN_CHAR.to_string())
+    }
+
+    /// Largura de um código, no espaço de texto (multiplicar pelo tamanho da fonte)
+    fn width(&self, code: u32) -> f64 {
+        let key = match &self.mapping {
+            CodeMapping::Simple(_) | CodeMapping::Identity => Some(code),
+            CodeMapping::Embedded(cmap) => cmap.cid(code),
+            CodeMapping::Unicode => None,
+        };
+        key.and_then(|key| self.widths.get(&key)).copied().unwrap_or(self.default_width) * self.width_scale
+    }
+}
+
+/// Faixa do codespace: códigos com o tamanho de `low` e cada byte entre `low` e `high`
+#[derive(Debug, Clone)]
+struct CodeRange {
+    low: Vec<u8>,
+    high: Vec<u8>,
+}
+
+impl CodeRange {
+    fn new(low: &[u8], high: &[u8]) -> Self {
+        Self { low: low.to_vec(), high: high.to_vec() }
+    }
+
+    /// O início de `bytes` é um código desta faixa?
+    fn matches(&self, bytes: &[u8]) -> bool {
+        bytes.len() >= self.low.len()
+            && self.low.iter().zip(&self.high).zip(bytes).all(|((low, high), byte)| (low..=high).contains(&byte))
+    }
+}
+
+/// Destino de uma faixa `bfrange`
+#[derive(Debug, Clone)]
+enum RangeTarget {
+    /// Texto do primeiro código; os seguintes incrementam a última unidade UTF-16
+    Offset(Vec<u16>),
+    /// Texto de cada código da faixa
+    List(Vec<String>),
+}
+
+/// CMap lido de um stream (`/ToUnicode` ou `/Encoding` de uma fonte Type0)
+#[derive(Debug, Clone, Default)]
+struct CMap {
+    codespace: Vec<CodeRange>,
+    /// `bfchar`: código → texto
+    chars: HashMap<u32, String>,
+    /// `bfrange`: (primeiro código, último código, destino)
+    ranges: Vec<(u32, u32, RangeTarget)>,
+    /// `cidchar`/`cidrange`: (primeiro código, último código, primeiro CID)
+    cids: Vec<(u32, u32, u32)>,
+}
+
+impl CMap {
+    /// Lê as seções de um CMap; a sintaxe PostScript segue a dos content streams
+    fn parse(data: &[u8]) -> Self {
+        let mut cmap = Self::default();
+
+        for item in pdf_content::parse(data) {
+            let ContentItem::Operation(operation) = item else {
+                continue;
+            };
+            let operands = operation.operands.as_slice();
+
+            match operation.operator.as_str() {
+                "endcodespacerange" => {
+                    for pair in operands.chunks_exact(2) {
+                        if let (Ok(low), Ok(high)) = (pair[0].as_str(), pair[1].as_str()) {
+                            if low.len() == high.len() && (1..=4).contains(&low.len()) {
+                                cmap.codespace.push(CodeRange::new(low, high));
+                            }
+                        }
+                    }
+                }
+                "endbfchar" => {
+                    for pair in operands.chunks_exact(2) {
+                        if let (Ok(source), Ok(target)) = (pair[0].as_str(), pair[1].as_str()) {
+                            cmap.chars.insert(code_value(source), String::from_utf16_lossy(&utf16_units(target)));
+                        }
+                    }
+                }
+                "endbfrange" => {
+                    for triple in operands.chunks_exact(3) {
+                        let (Ok(low), Ok(high)) = (triple[0].as_str(), triple[1].as_str()) else {
+                            continue;
+                        };
+                        let target = match &triple[2] {
+                            Object::String(target, _) => RangeTarget::Offset(utf16_units(target)),
+                            Object::Array(items) => RangeTarget::List(
+                                items
+                                    .iter()
+                                    .map(|item| item.as_str().map(|t| String::from_utf16_lossy(&utf16_units(t))).unwrap_or_default())
+                                    .collect(),
+                            ),
+                            _ => continue,
+                        };
+                        cmap.ranges.push((code_value(low), code_value(high), target));
+                    }
+                }
+                "endcidchar" => {
+                    for pair in operands.chunks_exact(2) {
+                        if let (Ok(code), Some(cid)) = (pair[0].as_str(), pair[1].as_i64().ok()) {
+                            let code = code_value(code);
+                            cmap.cids.push((code, code, cid as u32));
+                        }
+                    }
+                }
+                "endcidrange" => {
+                    for triple in operands.chunks_exact(3) {
+                        if let (Ok(low), Ok(high), Ok(cid)) = (triple[0].as_str(), triple[1].as_str(), triple[2].as_i64()) {
+                            cmap.cids.push((code_value(low), code_value(high), cid as u32));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        cmap
+    }
+
+    /// Texto Unicode de um código (`bfchar`, senão `bfrange`)
+    fn unicode(&self, code: u32) -> Option<String> {
+        if let Some(text) = self.chars.get(&code) {
+            return Some(text.clone());
+        }
+
+        let (low, _, target) = self.ranges.iter().find(|(low, high, _)| (*low..=*high).contains(&code))?;
+        let offset = code - low;
+        match target {
+            RangeTarget::Offset(units) => {
+                let mut units = units.clone();
+                if let Some(last) = units.last_mut() {
+                    *last = last.wrapping_add(offset as u16);
+                }
+                Some(String::from_utf16_lossy(&units))
+            }
+            RangeTarget::List(items) => items.get(offset as usize).cloned(),
+        }
+    }
+
+    /// CID de um código (`cidchar`/`cidrange`)
+    fn cid(&self, code: u32) -> Option<u32> {
+        self.cids
+            .iter()
+            .find(|(low, high, _)| (*low..=*high).contains(&code))
+            .map(|(low, _, cid)| cid + (code - low))
+    }
+}
+
+/// Tabela de 256 posições de uma fonte simples: codificação base e `/Differences`
+fn simple_encoding(doc: &Document, font: &Dictionary) -> Vec<Option<String>> {
+    let (base, differences) = match font.get_deref(b"Encoding", doc) {
+        Ok(Object::Name(name)) => (Some(name.as_slice()), None),
+        Ok(Object::Dictionary(encoding)) => (
+            encoding.get(b"BaseEncoding").and_then(Object::as_name).ok(),
+            encoding.get_deref(b"Differences", doc).and_then(Object::as_array).ok(),
+        ),
+        _ => (None, None),
+    };
+
+    let mut table: Vec<Option<String>> = base_encoding(base.unwrap_or(b"StandardEncoding"))
+        .iter()
+        .map(|c| c.map(String::from))
+        .collect();
+
+    // Números definem o próximo código; cada nome ocupa um código e avança
+    let mut code = 0usize;
+    for item in differences.into_iter().flatten() {
+        match doc.dereference(item).map(|(_, item)| item) {
+            Ok(Object::Integer(next)) => code = usize::try_from(*next).unwrap_or(usize::MAX),
+            Ok(Object::Name(name)) => {
+                if let Some(slot) = table.get_mut(code) {
+                    *slot = glyph_to_unicode(&String::from_utf8_lossy(name));
+                }
+                code = code.saturating_add(1);
+            }
+            _ => {}
+        }
+    }
+
+    table
+}
+
+/// Caracteres de uma codificação base (`/BaseEncoding` ou `/Encoding` por nome)
+fn base_encoding(name: &[u8]) -> [Option<char>; 256] {
+    let mut table = [None; 256];
+    for code in 0x20..=0x7E_u8 {
+        table[usize::from(code)] = Some(char::from(code));
+    }
+
+    match name {
+        b"WinAnsiEncoding" => {
+            for (slot, &unicode) in table[0x80..].iter_mut().zip(&WIN_ANSI_0X80) {
+                *slot = unicode_char(unicode);
+            }
+            for code in 0xA0..=0xFF_u8 {
+                table[usize::from(code)] = Some(char::from(code));
+            }
+        }
+        b"MacRomanEncoding" => {
+            for (slot, &unicode) in table[0x80..].iter_mut().zip(&MAC_ROMAN_0X80) {
+                *slot = unicode_char(unicode);
+            }
+        }
+        b"PDFDocEncoding" => {
+            for code in 0x18..=0xFF_u8 {
+                table[usize::from(code)] = Some(pdf_encoding::pdf_doc_char(code)).filter(|&c| c != UNKNOWN_CHAR);
+            }
+        }
+        // StandardEncoding (padrão das fontes Type1 sem /Encoding)
+        _ => {
+            table[0x27] = Some('\u{2019}');
+            table[0x60] = Some('\u{2018}');
+            for &(code, unicode) in STANDARD_HIGH {
+                table[usize::from(code)] = unicode_char(unicode);
+            }
+        }
+    }
+
+    table
+}
+
+/// Caractere de um nome de glifo (Adobe Glyph List, `uniXXXX`, `uXXXX` e ligaduras `f_f_i`)
+fn glyph_to_unicode(name: &str) -> Option<String> {
+    // Sufixos de variante (".sc", ".alt") não mudam o caractere; ".notdef" não tem
+    let name = name.split('.').next().filter(|name| !name.is_empty())?;
+
+    if name.contains('_') {
+        return name.split('_').map(glyph_to_unicode).collect();
+    }
+    if let Some(hex) = name.strip_prefix("uni").filter(|hex| hex.len() % 4 == 0 && is_hex(hex)) {
+        let units = (0..hex.len())
+            .step_by(4)
+            .map(|start| u16::from_str_radix(&hex[start..start + 4], 16).ok())
+            .collect::<Option<Vec<_>>>()?;
+        return Some(String::from_utf16_lossy(&units));
+    }
+    if let Some(hex) = name.strip_prefix('u').filter(|hex| (4..=6).contains(&hex.len()) && is_hex(hex)) {
+        return u32::from_str_radix(hex, 16).ok().and_then(char::from_u32).map(String::from);
+    }
+
+    GLYPH_LIST.get(name).map(|c| c.to_string())
+}
+
+/// Larguras por CID de `/W`: `c [w1 w2 ...]` ou `c_first c_last w`
+fn cid_widths(doc: &Document, items: &[Object]) -> HashMap<u32, f64> {
+    let items: Vec<&Object> = items.iter().filter_map(|item| doc.dereference(item).ok().map(|(_, item)| item)).collect();
+    let mut widths = HashMap::new();
+    let mut position = 0;
+
+    while let Some(first) = items.get(position).and_then(|first| first.as_i64().ok()).and_then(|first| u32::try_from(first).ok()) {
+        match items.get(position + 1) {
+            Some(Object::Array(list)) => {
+                for (cid, width) in (first..).zip(list) {
+                    if let Some(width) = doc.dereference(width).ok().and_then(|(_, width)| number(width)) {
+                        widths.insert(cid, width);
+                    }
+                }
+                position += 2;
+            }
+            Some(last) => {
+                let (Some(last), Some(width)) = (last.as_i64().ok(), items.get(position + 2).and_then(|width| number(width))) else {
+                    break;
+                };
+                let last = u32::try_from(last).unwrap_or(0).min(first.saturating_add(MAX_WIDTH_RANGE));
+                for cid in first..=last {
+                    widths.insert(cid, width);
+                }
+                position += 3;
+            }
+            None => break,
+        }
+    }
+
+    widths
+}
+
+// ==================== MONTAGEM DO TEXTO ====================
+
+/// Texto na ordem do conteúdo: quebra de linha na mudança de linha de base
+fn reading_text(glyphs: &[Glyph]) -> String {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut previous: Option<&Glyph> = None;
+
+    for glyph in glyphs {
+        if let Some(previous) = previous {
+            let size = previous.size.min(glyph.size).max(1.0);
+            if (glyph.y - previous.y).abs() > size * LINE_TOLERANCE {
+                lines.push(std::mem::take(&mut line));
+            } else if needs_space(previous, glyph, &line) {
+                line.push(' ');
+            }
+        }
+        line.push_str(&glyph.text);
+        previous = Some(glyph);
+    }
+    lines.push(line);
+
+    lines.iter().map(|line| line.trim_end()).collect::<Vec<_>>().join("\n").trim_end().to_string()
+}
+
+/// Texto preservando o layout: linhas de cima para baixo e colunas pela posição horizontal
+///
+/// A largura de uma coluna é a largura média dos caracteres da página; linhas
+/// em branco representam os espaços verticais maiores que a entrelinha.
+fn layout_text(glyphs: &[Glyph]) -> String {
+    let mut visible: Vec<&Glyph> = glyphs.iter().filter(|glyph| !glyph.text.trim().is_empty()).collect();
+    if visible.is_empty() {
+        return String::new();
+    }
+
+    let (total_width, characters) = visible
+        .iter()
+        .fold((0.0, 0usize), |(width, count), glyph| (width + (glyph.end_x - glyph.x).max(0.0), count + glyph.text.chars().count()));
+    let cell = if total_width > 0.0 {
+        total_width / characters as f64
+    } else {
+        visible.iter().map(|glyph| glyph.size).sum::<f64>() / visible.len() as f64 * 0.5
+    }
+    .max(1.0);
+    let left = visible.iter().map(|glyph| glyph.x).fold(f64::INFINITY, f64::min);
+
+    // Agrupa em linhas pela linha de base, de cima para baixo
+    visible.sort_by(|a, b| b.y.total_cmp(&a.y));
+    let mut lines: Vec<Vec<&Glyph>> = Vec::new();
+    for glyph in visible {
+        match lines.last_mut() {
+            Some(line) if (line[0].y - glyph.y).abs() <= line[0].size.max(1.0) * LINE_TOLERANCE => line.push(glyph),
+            _ => lines.push(vec![glyph]),
+        }
+    }
+
+    let mut text = String::new();
+    let mut previous_line: Option<(f64, f64)> = None;
+    for mut line in lines {
+        line.sort_by(|a, b| a.x.total_cmp(&b.x));
+
+        if let Some((y, size)) = previous_line {
+            let breaks = ((y - line[0].y) / (size.max(1.0) * LINE_SPACING)).round();
+            text.push_str(&"\n".repeat(breaks.clamp(1.0, (MAX_BLANK_LINES + 1) as f64) as usize));
+        }
+        previous_line = Some((line[0].y, line[0].size));
+
+        let mut row = String::new();
+        let mut columns = 0;
+        let mut last: Option<&Glyph> = None;
+        for glyph in line {
+            // Glifos colados seguem o anterior; a grade só posiciona o início de cada trecho
+            if last.is_none_or(|last| needs_space(last, glyph, &row)) {
+                let column = ((glyph.x - left) / cell).round().max(0.0) as usize;
+                if column > columns {
+                    row.push_str(&" ".repeat(column - columns));
+                    columns = column;
+                } else if last.is_some() {
+                    row.push(' ');
+                    columns += 1;
+                }
+            }
+            row.push_str(&glyph.text);
+            columns += glyph.text.chars().count();
+            last = Some(glyph);
+        }
+        text.push_str(row.trim_end());
+    }
+
+    text
+}
+
+/// Dois glifos da mesma linha estão separados por um espaço que o texto não traz?
+fn needs_space(previous: &Glyph, glyph: &Glyph, line: &str) -> bool {
+    let size = previous.size.min(glyph.size).max(1.0);
+    // Distância maior que a de um kerning ou recuo para trás (outro trecho da linha)
+    let separated = glyph.x - previous.end_x > size * WORD_GAP || glyph.x < previous.x - size;
+    separated && !line.ends_with(char::is_whitespace) && !glyph.text.starts_with(char::is_whitespace)
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+/// Conteúdo do arquivo de saída no formato pedido
+fn render(pages: &[PageText], format: TextOutputFormat) -> Result<Vec<u8>> {
+    match format {
+        TextOutputFormat::Txt => {
+            let mut text = String::new();
+            for page in pages {
+                text.push_str(&page.text);
+                text.push('\n');
+                text.push(PAGE_SEPARATOR);
+            }
+            Ok(text.into_bytes())
+        }
+        TextOutputFormat::Json => serde_json::to_vec_pretty(pages)
+            .map_err(|e| AppError::serialization(format!("Failed to serialize pages: {}", e))),
+    }
+}
+
+/// Remove caracteres de controle e expande ligaduras tipográficas (U+FB00-FB06), para busca
+fn normalize(text: String) -> String {
+    if !text.chars().any(|c| c.is_control() || ('\u{FB00}'..='\u{FB06}').contains(&c)) {
+        return text;
+    }
+
+    let mut normalized = String::with_capacity(text.len() + 2);
+    for c in text.chars().filter(|c| !c.is_control()) {
+        match c {
+            '\u{FB00}' => normalized.push_str("ff"),
+            '\u{FB01}' => normalized.push_str("fi"),
+            '\u{FB02}' => normalized.push_str("fl"),
+            '\u{FB03}' => normalized.push_str("ffi"),
+            '\u{FB04}' => normalized.push_str("ffl"),
+            '\u{FB05}' | '\u{FB06}' => normalized.push_str("st"),
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Valor big-endian de um código de 1 a 4 bytes
+fn code_value(bytes: &[u8]) -> u32 {
+    bytes.iter().take(4).fold(0, |value, &byte| (value << 8) | u32::from(byte))
+}
+
+/// Unidades UTF-16BE de um destino de CMap (um byte solto vale como um caractere)
+fn utf16_units(bytes: &[u8]) -> Vec<u16> {
+    if bytes.len() == 1 {
+        return vec![u16::from(bytes[0])];
+    }
+    bytes
+        .chunks(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair.get(1).copied().unwrap_or(0)]))
+        .collect()
+}
+
+fn unicode_char(unicode: u16) -> Option<char> {
+    char::from_u32(u32::from(unicode)).filter(|&c| c != '\0')
+}
+
+fn is_hex(text: &str) -> bool {
+    text.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+fn number(object: &Object) -> Option<f64> {
+    object.as_float().ok().map(f64::from)
+}
+
+fn set_number(target: &mut f64, operands: &[Object]) {
+    if let Some(value) = operands.first().and_then(number) {
+        *target = value;
+    }
+}
+
+/// Matriz de translação `[1 0 0 1 tx ty]`
+fn translation(tx: f64, ty: f64) -> Matrix {
+    [1.0, 0.0, 0.0, 1.0, tx, ty]
+}
+
+/// Aplica uma matriz a um ponto
+fn transform(m: &Matrix, x: f64, y: f64) -> (f64, f64) {
+    (m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5])
+}
+
+/// Ponto como visto com a página girada por `/Rotate` (sentido horário)
+fn rotate((x, y): (f64, f64), rotation: u32) -> (f64, f64) {
+    match rotation {
+        90 => (y, -x),
+        180 => (-x, -y),
+        270 => (-y, x),
+        _ => (x, y),
+    }
+}
+
+// ==================== FUNÇÕES DE CONVENIÊNCIA ====================
+
+/// Função de conveniência para extração de texto
+#[instrument(name = "extract_text", skip(data, runtime))]
+pub async fn extract_text(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
+    let request = ExtractTextRequest::from_value(&data)?;
+    let extractor = PdfTextExtractor::with_runtime(runtime);
+    let result = extractor.extract_text(request).await?;
+
+    serde_json::to_value(result)
+        .map_err(|e| AppError::serialization(format!("Failed to serialize result: {}", e)))
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::test_support;
+    use lopdf::{dictionary, Stream};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    /// Página gerada pelo pdfTeX com fontes cm-super (codificação T1): Type1
+    /// sem `/ToUnicode`, acentos e ligaduras só pelos nomes de `/Differences`,
+    /// espaços entre palavras como kerning em `TJ` e conteúdo comprimido
+    const PDFTEX_PAGE: &[u8] = b"%PDF-1.5\n\
+    %\xD0\xD4\xC5\xD8\n\
+    3 0 obj\n\
+    <<\n\
+    /Length 263      \n\
+    /Filter /FlateDecode\n\
+    >>\n\
+    stream\n\
+    x\xDAm\x91=O\xC40\x0C\x86\xF7\xFB\x15\x19\x9B\xA1!\x8E\x9B\xB4\x199\x09$XX\xB2Q\x86\xA3\xCD\xA1C\\\x8A\xFA\x81\xF8\xF9\xB8\x17C\x05b\x88\x1C\xBF\xB6\xDF<I\xF6awu\xDB\x08\xA8\x14V\xCE\x88p\x14\x80\xA8j\xD7\x88Z\xD7\n\
+    \x8C\x15\xA1\x17\x8F\x05\xC8\x12()\xEE\x92D(\xE6q\x90%\xC5~i\xB1\xAAi\xE1 \x9F\xC2=9\x01\x08\xAF\xBC3nu\xD2\xA24\xA0\x1A\x03\xD9\xE3\x9Af\x10\x8B\xD8j\xAC\xBAS\x8B\xD6\xA4\xEEt\xC8b?\xE4x&\x19fJL\xF3\xA3\xAD\x12\xEF\xB4\xD1i\x98\xE9@\xF8\x90\xD4\x11\xDFH\xD9j6\xEFr\xE9\x95\x9D9LqC\xBD\x08F]\x90\x89\x11@y\xCB\xF7|\xC8\xC5\x81\x18q\x8A\xF3o8\x12\xED\xF2\xC9I\x1Fs|\x1F3m\x17\xA7\xF2?\xC7\xE9p\x8Ei%\x9A7\x97\xAAc\xAA\xC4\xDA\xF3\xB2\x1E\x18G\xEE\xE0+\x8C\xDB\xC0\xCB2\xF2\x08\xB42\x93\xDF\x84\xDD>\xFC}r\xD4\xA8\xAC\xF6\xC2k\x05\x0E\xBE\xFF\x8E\xDB\xBF\x00=Uu\x19\n\
+    endstream\n\
+    endobj\n\
+    2 0 obj\n\
+    <<\n\
+    /Type /Page\n\
+    /Contents 3 0 R\n\
+    /Resources 1 0 R\n\
+    /MediaBox [0 0 612 792]\n\
+    /Parent 6 0 R\n\
+    >>\n\
+    endobj\n\
+    1 0 obj\n\
+    <<\n\
+    /Font << /F8 4 0 R /F11 5 0 R >>\n\
+    /ProcSet [ /PDF /Text ]\n\
+    >>\n\
+    endobj\n\
+    7 0 obj\n\
+    <<\n\
+    /Type /Encoding\n\
+    /Differences [16/quotedblleft/quotedblright 21/endash 27/ff/fi/fl 40/parenleft/parenright 45/hyphen/period 49/one/two 65/A 73/I 79/O 97/a 99/c/d/e/f/g 105/i/j 108/l/m/n/o/p 114/r/s/t/u/v 225/aacute 227/atilde 231/ccedilla 233/eacute/ecircumflex]\n\
+    >>\n\
+    endobj\n\
+    8 0 obj\n\
+    <<\n\
+    /Type /FontDescriptor\n\
+    /FontName /MQJXKB+SFBX1440\n\
+    /Flags 4\n\
+    /FontBBox [-54 -250 1137 750]\n\
+    /Ascent 694\n\
+    /CapHeight 686\n\
+    /Descent -194\n\
+    /ItalicAngle 0\n\
+    /StemV 166\n\
+    /XHeight 444\n\
+    /CharSet (/I/c/d/o/one/r/t/u/n/atilde/ccedilla)\n\
+    >>\n\
+    endobj\n\
+    9 0 obj\n\
+    <<\n\
+    /Type /FontDescriptor\n\
+    /FontName /ZHRDHB+SFRM1000\n\
+    /Flags 4\n\
+    /FontBBox [-40 -250 1009 750]\n\
+    /Ascent 694\n\
+    /CapHeight 683\n\
+    /Descent -194\n\
+    /ItalicAngle 0\n\
+    /StemV 93\n\
+    /XHeight 431\n\
+    /CharSet (/A/O/a/aacute/atilde/c/ccedilla/d/e/eacute/ecircumflex/endash/ff/fi/fl/g/hyphen/i/j/l/m/n/o/one/p/parenleft/parenright/period/quotedblleft/quotedblright/r/s/t/two/u/v)\n\
+    >>\n\
+    endobj\n\
+    4 0 obj\n\
+    <<\n\
+    /Type /Font\n\
+    /Subtype /Type1\n\
+    /BaseFont /MQJXKB+SFBX1440\n\
+    /FontDescriptor 8 0 R\n\
+    /FirstChar 49\n\
+    /LastChar 231\n\
+    /Widths [562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 361.1 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 277.8 305.6 562.5 277.8 562.5 562.5 562.5 562.5 562.5 391.7 562.5 388.9 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5 562.5]\n\
+    /Encoding 7 0 R\n\
+    >>\n\
+    endobj\n\
+    5 0 obj\n\
+    <<\n\
+    /Type /Font\n\
+    /Subtype /Type1\n\
+    /BaseFont /ZHRDHB+SFRM1000\n\
+    /FontDescriptor 9 0 R\n\
+    /FirstChar 16\n\
+    /LastChar 234\n\
+    /Widths [500 500 500 500 500 500 500 500 500 500 500 500 555.6 500 500 500 500 500 500 500 500 500 500 500 388.9 388.9 500 500 500 333.3 277.8 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 361.1 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 277.8 305.6 500 277.8 500 500 500 500 500 391.7 500 388.9 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500 500]\n\
+    /Encoding 7 0 R\n\
+    >>\n\
+    endobj\n\
+    6 0 obj\n\
+    <<\n\
+    /Type /Pages\n\
+    /Count 1\n\
+    /Kids [2 0 R]\n\
+    >>\n\
+    endobj\n\
+    10 0 obj\n\
+    <<\n\
+    /Type /Catalog\n\
+    /Pages 6 0 R\n\
+    >>\n\
+    endobj\n\
+    11 0 obj\n\
+    <<\n\
+    /Producer (pdfTeX-1.40.25)\n\
+    /Creator (TeX)\n\
+    /CreationDate (D:20240405103000-03'00')\n\
+    /ModDate (D:20240405103000-03'00')\n\
+    /Trapped /False\n\
+    /PTEX.Fullbanner (This is pdfTeX, Version 3.141592653-2.6-1.40.25 \\(TeX Live 2023\\) kpathsea version 6.3.5)\n\
+    >>\n\
+    endobj\n\
+    xref\n\
+    0 12\n\
+    0000000000 65535 f \n\
+    0000000460 00000 n \n\
+    0000000356 00000 n \n\
+    0000000015 00000 n \n\
+    0000001424 00000 n \n\
+    0000002674 00000 n \n\
+    0000003724 00000 n \n\
+    0000000538 00000 n \n\
+    0000000821 00000 n \n\
+    0000001058 00000 n \n\
+    0000003781 00000 n \n\
+    0000003831 00000 n \n\
+    trailer\n\
+    << /Size 12\n\
+    /Root 10 0 R\n\
+    /Info 11 0 R\n\
+    /ID [<6E3AEF0B1C5D3A2F4B8E9C7D1A2B3C4D> <6E3AEF0B1C5D3A2F4B8E9C7D1A2B3C4D>] >>\n\
+    startxref\n\
+    4094\n\
+    %%EOF\n";
+
+    /// Substitui o conteúdo e os recursos da página 1 de um documento de uma página
+    fn single_page(content: &[u8], fonts: Dictionary) -> Document {
+        let mut doc = test_support::build_sample_pdf(1);
+        let page_id = doc.get_pages()[&1];
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content.to_vec()));
+        let page = doc.get_dictionary_mut(page_id).unwrap();
+        page.set("Contents", content_id);
+        page.set("Resources", dictionary! { "Font" => fonts });
+        doc
+    }
+
+    fn text_of(doc: &Document, layout: bool) -> String {
+        page_text(doc, doc.get_pages()[&1], layout)
+    }
+
+    #[test]
+    fn test_sample_pages_text() {
+        let doc = test_support::build_sample_pdf(3);
+        let pages = extract(&doc, &[], false);
+        let texts: Vec<&str> = pages.iter().map(|page| page.text.as_str()).collect();
+        assert_eq!(texts, vec!["Page 1", "Page 2", "Page 3"]);
+        assert_eq!(pages[0].character_count, 5);
+
+        let second = extract(&doc, &[2], false);
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].page_number, 2);
+    }
+
+    #[test]
+    fn test_simple_font_encoding_differences_and_spacing() {
+        let fonts = dictionary! {
+            "F1" => dictionary! {
+                "Type" => "Font",
+                "Subtype" => "Type1",
+                "BaseFont" => "Helvetica",
+                "Encoding" => dictionary! {
+                    "Type" => "Encoding",
+                    "BaseEncoding" => "WinAnsiEncoding",
+                    "Differences" => vec![1.into(), "Eacute".into(), "f_i".into(), "uni00E7".into()],
+                },
+            },
+        };
+        // Linha 1: WinAnsi (ó, –) e /Differences; linha 2: TJ com espaço e kerning; linha 3: '
+        let content = b"BT /F1 12 Tf 14 TL 72 700 Td (Relat\\363rio \\226 \\001 \\002 \\003 ) Tj \
+            [(Hello) -300 (W) 80 (orld)] TJ ET \
+            BT /F1 12 Tf 14 TL 72 680 Td (Line one) Tj (Line two) ' ET";
+        let doc = single_page(content, fonts);
+
+        assert_eq!(text_of(&doc, false), "Relatório – É fi ç Hello World\nLine one\nLine two");
+    }
+
+    #[test]
+    fn test_differences_glyph_names_fill_gaps_in_to_unicode() {
+        // Subconjunto TrueType como os gerados por editores de texto: o /ToUnicode
+        // cobre só parte dos códigos e marca um deles como <FFFD>
+        let mut doc = test_support::build_sample_pdf(1);
+        let to_unicode = doc.add_object(Stream::new(
+            dictionary! {},
+            b"/CIDInit /ProcSet findresource begin 12 dict begin begincmap\n\
+              1 begincodespacerange <00> <FF> endcodespacerange\n\
+              2 beginbfchar <20> <0020> <80> <FFFD> endbfchar\n\
+              1 beginbfrange <41> <43> <0041> endbfrange\n\
+              endcmap CMapName currentdict /CMap defineresource pop end end"
+                .to_vec(),
+        ));
+        let font = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "TrueType",
+            "BaseFont" => "QWERTY+Calibri",
+            "FirstChar" => 32,
+            "LastChar" => 132,
+            "Encoding" => dictionary! {
+                "Type" => "Encoding",
+                "BaseEncoding" => "WinAnsiEncoding",
+                "Differences" => vec![
+                    128.into(), "Gbreve".into(), "copyright".into(), "f_f_l".into(), "Scedilla".into(), "Omegatonos".into(),
+                ],
+            },
+            "ToUnicode" => to_unicode,
+        });
+        let page_id = doc.get_pages()[&1];
+        let content = doc.add_object(Stream::new(
+            dictionary! {},
+            b"BT /TT1 11 Tf 72 700 Td (ABC \\200\\201\\202\\203\\204) Tj ET".to_vec(),
+        ));
+        let page = doc.get_dictionary_mut(page_id).unwrap();
+        page.set("Contents", content);
+        page.set("Resources", dictionary! { "Font" => dictionary! { "TT1" => font } });
+
+        assert_eq!(text_of(&doc, false), "ABC Ğ©fflŞΏ");
+    }
+
+    #[test]
+    fn test_type0_font_with_to_unicode_and_form_xobject() {
+        let mut doc = single_page(b"", Dictionary::new());
+        let to_unicode = doc.add_object(Stream::new(
+            dictionary! {},
+            b"/CIDInit /ProcSet findresource begin 12 dict begin begincmap\n\
+              1 begincodespacerange <0000> <FFFF> endcodespacerange\n\
+              2 beginbfchar <0001> <0041> <0002> <00E7> endbfchar\n\
+              2 beginbfrange <0010> <0012> <0061> <0020> <0021> [<0066006C> <D835DC00>] endbfrange\n\
+              endcmap CMapName currentdict /CMap defineresource pop end end"
+                .to_vec(),
+        ));
+        let descendant = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "CIDFontType2",
+            "BaseFont" => "ABCDEF+NotoSans",
+            "DW" => 1000,
+            "W" => vec![1.into(), vec![600.into(), 500.into()].into(), 16.into(), 18.into(), 500.into()],
+        });
+        let type0 = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type0",
+            "BaseFont" => "ABCDEF+NotoSans",
+            "Encoding" => "Identity-H",
+            "DescendantFonts" => vec![descendant.into()],
+            "ToUnicode" => to_unicode,
+        });
+
+        // O texto só existe dentro de um Form XObject
+        let form = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Form",
+                "Resources" => dictionary! { "Font" => dictionary! { "F9" => type0 } },
+            },
+            b"BT /F9 10 Tf 0 0 Td <000100020010001100120020> Tj [<0021>] TJ <0099> Tj ET".to_vec(),
+        ));
+        let page_id = doc.get_pages()[&1];
+        let content = doc.add_object(Stream::new(dictionary! {}, b"q 1 0 0 1 72 700 cm /X1 Do Q".to_vec()));
+        let page = doc.get_dictionary_mut(page_id).unwrap();
+        page.set("Contents", content);
+        page.set("Resources", dictionary! { "XObject" => dictionary! { "X1" => form } });
+
+        assert_eq!(text_of(&doc, false), "Açabcfl\u{1D400}\u{FFFD}");
+    }
+
+    #[test]
+    fn test_form_fan_out_stops_at_draw_budget() {
+        let fonts = dictionary! {
+            "F1" => dictionary! { "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "Helvetica" },
+        };
+        let mut doc = single_page(b"", fonts.clone());
+        let mut level = doc.add_object(Stream::new(
+            dictionary! { "Subtype" => "Form", "Resources" => dictionary! { "Font" => fonts } },
+            b"BT /F1 12 Tf (x) Tj ET".to_vec(),
+        ));
+
+        // Cada nível chama o anterior 8 vezes: 8^15 execuções sem o orçamento
+        for _ in 0..15 {
+            level = doc.add_object(Stream::new(
+                dictionary! {
+                    "Subtype" => "Form",
+                    "Resources" => dictionary! { "XObject" => dictionary! { "X" => level } },
+                },
+                b"/X Do ".repeat(8),
+            ));
+        }
+        let page_id = doc.get_pages()[&1];
+        let content = doc.add_object(Stream::new(dictionary! {}, b"/Fm Do".to_vec()));
+        let page = doc.get_dictionary_mut(page_id).unwrap();
+        page.set("Contents", content);
+        page.set("Resources", dictionary! { "XObject" => dictionary! { "Fm" => level } });
+
+        // Cada `Do` consome o orçamento de 10_000: o percurso em profundidade
+        // chega a 2 * 8^4 + 8^3 + 4 * 8 + 2 formulários folha antes de parar
+        assert_eq!(MAX_DRAWS_PER_PAGE, 10_000);
+        let glyphs = page_glyphs(&doc, page_id);
+        assert_eq!(glyphs.len(), 2 * 4096 + 512 + 4 * 8 + 2);
+        assert!(glyphs.iter().all(|glyph| glyph.text == "x"));
+    }
+
+    #[test]
+    fn test_cmap_codespace_with_mixed_lengths() {
+        let cmap = CMap::parse(
+            b"2 begincodespacerange <00> <80> <8140> <9FFC> endcodespacerange\n\
+              1 begincidrange <8140> <817E> 633 endcidrange\n\
+              1 begincidchar <41> 34 endcidchar",
+        );
+        let font = Font {
+            codespace: cmap.codespace.clone(),
+            mapping: CodeMapping::Embedded(cmap.clone()),
+            to_unicode: None,
+            widths: HashMap::from([(34, 500.0), (634, 1000.0)]),
+            default_width: 0.0,
+            width_scale: 0.001,
+        };
+
+        assert_eq!(font.next_code(b"A\x81\x41"), (0x41, 1));
+        assert_eq!(font.next_code(b"\x81\x41A"), (0x8141, 2));
+        assert_eq!(cmap.cid(0x8141), Some(634));
+        assert_eq!(font.width(0x41), 0.5);
+        assert_eq!(font.width(0x8141), 1.0);
+    }
+
+    #[test]
+    fn test_layout_mode_keeps_columns_and_blank_lines() {
+        let fonts = dictionary! {
+            "F1" => dictionary! { "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "Courier" },
+        };
+        // Courier 10pt: cada caractere ocupa 6pt; "Right" começa 300pt (50 colunas) depois
+        let content = b"BT /F1 10 Tf 72 700 Td (Left) Tj 300 0 Td (Right) Tj ET \
+            BT /F1 10 Tf 72 676 Td (Below) Tj ET";
+        let doc = single_page(content, fonts);
+
+        assert_eq!(text_of(&doc, true), format!("Left{}Right\n\nBelow", " ".repeat(46)));
+        assert_eq!(text_of(&doc, false), "Left Right\nBelow");
+    }
+
+    #[tokio::test]
+    async fn test_extract_text_writes_txt_and_json() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let input = test_support::write_sample_pdf(temp_dir.path(), "input.pdf", 2);
+        let extractor = PdfTextExtractor::new();
+
+        let txt = temp_dir.path().join("text.txt");
+        let result = extractor
+            .extract_text(ExtractTextRequest::from_value(&json!({ "file": input, "output": txt }))?)
+            .await?;
+        assert_eq!(result.page_count, 2);
+        assert_eq!(result.format, Some(TextOutputFormat::Txt));
+        assert_eq!(result.character_count, 10);
+        assert!(result.pages_without_text.is_empty());
+        assert_eq!(std::fs::read_to_string(&txt)?, "Page 1\n\u{0C}Page 2\n\u{0C}");
+
+        let json_path = temp_dir.path().join("text.json");
+        let result = extractor
+            .extract_text(ExtractTextRequest::from_value(&json!({
+                "file": input,
+                "output": json_path,
+                "pages": [2],
+                "config": { "layout": true },
+            }))?)
+            .await?;
+        assert_eq!(result.format, Some(TextOutputFormat::Json));
+        let written: Vec<PageText> = serde_json::from_slice(&std::fs::read(&json_path)?).unwrap();
+        assert_eq!(written, result.pages);
+        assert_eq!(written[0].text, "Page 2");
+
+        let missing = ExtractTextRequest::from_value(&json!({ "file": input, "pages": [3] }))?;
+        assert!(extractor.extract_text(missing).await.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_pdftex_page_with_t1_encoding() {
+        let doc = Document::load_mem(PDFTEX_PAGE).unwrap();
+
+        // Ligaduras (ff, fi, fl) viram as letras; kerning de -333 vira espaço, o de ±28 não
+        assert_eq!(
+            text_of(&doc, false),
+            "1 Introdução\n\
+             A eficiência do método é “notável” – veja a seção 2.\n\
+             O offset do fluxo de proces-\n\
+             samento fica no buffer (ver figura 1).\n\
+             1"
+        );
+
+        // No layout, o número da página fica centralizado depois das linhas em branco
+        assert_eq!(
+            text_of(&doc, true),
+            "1    Introdução\n\
+             A eficiência do método é “notável” – veja a seção 2.\n\
+             O offset do fluxo de proces-\n\
+             samento fica no buffer (ver figura 1).\n\n\n\n\
+             \x20                                   1"
+        );
+    }
+}
@@ -8,6 +8,18 @@
 //! - Detecção de PDFs criptografados/protegidos
 //! - Verificação de conformidade com padrões
 //! - Análise de estrutura interna
+//! - Verificação da tabela xref a partir dos bytes do arquivo (`pdf_xref`)
+//! - Varredura completa de objetos e streams no nível Deep
+//! - Verificação de conformidade PDF/A-1b/2b/3b (`pdf_pdfa`)
+//! - Verificação de acessibilidade PDF/UA, com falhas por página (`pdf_pdfua`)
+//! - Detecção de conteúdo ativo (JavaScript, ações, arquivos incorporados) (`pdf_security`)
+//! - Inventário de páginas, fontes, imagens, anotações e bookmarks (`pdf_inventory`)
+//! - Leitura de `/Encrypt`: algoritmo, permissões `/P` e senha de usuário vazia (`pdf_encryption`)
+//! - Listagem de anotações com filtro por tipo e página
+//! - Histórico de atualizações incrementais, extração de revisões e colapso para a revisão final (`pdf_revisions`)
+//! - Verificação offline de assinaturas digitais e de alterações posteriores à assinatura (`pdf_signatures`)
+//! - Leitura do XMP e divergências entre ele e o dicionário Info (`pdf_xmp`)
+//! - Texto de cada página na análise detalhada (`pdf_text`)
 //! 
 //! ## Métricas coletadas:
 //! - Informações básicas do arquivo
@@ -15,16 +27,36 @@
 //! - Estatísticas de páginas
 //! - Informações técnicas
 
+use chrono::{DateTime, FixedOffset};
 use lopdf::{Document, Object};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 use tracing::{info, warn, error, instrument};
 
 use crate::utils::error_handling::{Result, AppError, PdfError, ValidationError};
 use crate::api::file_handlers::FileHandler;
+use crate::processors::pdf_encoding;
+use crate::processors::pdf_encryption::{self, EncryptionInfo};
+use crate::processors::pdf_filters::{self, FilterError};
+use crate::processors::pdf_inventory;
+use crate::processors::pdf_pdfa::{self, PdfaLevel, PdfaViolation};
+use crate::processors::pdf_pdfua::{self, PdfuaFailure, PdfuaReport};
+use crate::processors::pdf_revisions::{self, Revision, RevisionHistory};
+use crate::processors::pdf_security::{self, ActiveContentKind, RiskLevel, SecurityReport};
+use crate::processors::pdf_signatures::{self, SignatureInfo, SignatureStatus};
+use crate::processors::pdf_text;
+use crate::processors::pdf_xmp::{self, DocumentInfo, MetadataMismatch, XmpMetadata};
+use crate::processors::pdf_xref::{self, StreamLength, XrefEntryKind, XrefProblem};
+use crate::types::pdf_types::{
+    AnnotationInfo, AnnotationType, BookmarkInfo, FontInfo, ImageInfo, PageInfo, PdfPermissions,
+};
+use crate::processors::runtime::ProcessorRuntime;
+
+/// Limite de problemas relatados pela varredura de objetos (nível Deep)
+const MAX_DEEP_SCAN_ISSUES: usize = 1000;
 
 /// Níveis de validação disponíveis
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -58,6 +90,9 @@ pub struct ValidationConfig {
     pub extract_metadata: bool,
     /// Tamanho máximo do arquivo para análise profunda (em bytes)
     pub max_size_for_deep_analysis: u64,
+    /// Nível PDF/A exigido (padrão: o declarado no próprio documento)
+    #[serde(default)]
+    pub pdfa_level: Option<PdfaLevel>,
 }
 
 impl Default for ValidationConfig {
@@ -69,6 +104,7 @@ impl Default for ValidationConfig {
             detect_encryption: true,
             extract_metadata: true,
             max_size_for_deep_analysis: 50 * 1024 * 1024, // 50MB
+            pdfa_level: None,
         }
     }
 }
@@ -96,26 +132,59 @@ pub struct PdfMetadata {
     pub creator: Option<String>,
     /// Produtor do PDF
     pub producer: Option<String>,
-    /// Data de criação
-    pub creation_date: Option<String>,
-    /// Data de modificação
-    pub modification_date: Option<String>,
+    /// Data de criação (ISO 8601, com fuso horário)
+    pub creation_date: Option<DateTime<FixedOffset>>,
+    /// `/CreationDate` como gravado no arquivo
+    #[serde(default)]
+    pub creation_date_raw: Option<String>,
+    /// Data de modificação (ISO 8601, com fuso horário)
+    pub modification_date: Option<DateTime<FixedOffset>>,
+    /// `/ModDate` como gravado no arquivo
+    #[serde(default)]
+    pub modification_date_raw: Option<String>,
     /// O PDF está criptografado/protegido?
     pub is_encrypted: bool,
+    /// Detalhes da criptografia (se criptografado)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<EncryptionInfo>,
+    /// Operações permitidas (todas, se não criptografado)
+    #[serde(default)]
+    pub permissions: PdfPermissions,
     /// O PDF contém formulários?
     pub has_forms: bool,
     /// O PDF contém anotações?
     pub has_annotations: bool,
+    /// Anotações de todas as páginas
+    #[serde(default)]
+    pub annotations: Vec<AnnotationInfo>,
     /// O PDF contém javascript?
     pub has_javascript: bool,
     /// O PDF contém fontes incorporadas?
     pub has_embedded_fonts: bool,
+    /// Fontes usadas pelo documento
+    #[serde(default)]
+    pub embedded_fonts: Vec<FontInfo>,
     /// O PDF contém imagens?
     pub has_images: bool,
+    /// Imagens do documento, com a resolução efetiva de cada uso
+    #[serde(default)]
+    pub images: Vec<ImageInfo>,
     /// Dimensões da primeira página (largura x altura em pontos)
     pub first_page_dimensions: Option<(f64, f64)>,
+    /// Análise de cada página (caixas, rotação, formato, conteúdo)
+    #[serde(default)]
+    pub pages: Vec<PageInfo>,
     /// Contagem de objetos por tipo
     pub object_counts: HashMap<String, usize>,
+    /// Árvore de bookmarks (apenas com `include_detailed_analysis`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bookmarks: Option<Vec<BookmarkInfo>>,
+    /// Metadados XMP do catálogo (se houver `/Metadata`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xmp: Option<XmpMetadata>,
+    /// Campos do Info que não conferem com o XMP
+    #[serde(default)]
+    pub metadata_mismatches: Vec<MetadataMismatch>,
     /// Tempo de carregamento em milissegundos
     pub load_time_ms: u128,
 }
@@ -133,6 +202,9 @@ pub struct ValidationResult {
     pub issues: Vec<ValidationIssue>,
     /// Recomendações
     pub recommendations: Vec<String>,
+    /// Conteúdo ativo encontrado (se o documento foi carregado)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security: Option<SecurityReport>,
     /// Tempo total de validação em milissegundos
     pub validation_time_ms: u128,
 }
@@ -156,6 +228,9 @@ pub struct ValidationDetails {
     pub pdfa_compliant: Option<bool>,
     /// Conformidade com padrões PDF/UA?
     pub pdfua_compliant: Option<bool>,
+    /// Falhas de acessibilidade por página (nível Deep)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accessibility: Option<PdfuaReport>,
 }
 
 /// Problemas encontrados durante a validação
@@ -174,7 +249,7 @@ pub struct ValidationIssue {
 }
 
 /// Severidade de um problema
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd)]
 pub enum IssueSeverity {
     /// Informação (não afeta funcionalidade)
     Info,
@@ -205,6 +280,12 @@ pub enum IssueType {
     InvalidMetadata,
     /// Problema de compatibilidade
     CompatibilityIssue,
+    /// Conteúdo ativo potencialmente perigoso
+    SecurityRisk,
+    /// Versões anteriores de objetos guardadas por atualizações incrementais
+    PriorRevisionContent,
+    /// Assinatura digital alterada, incompleta ou seguida de modificações
+    SignatureIntegrity,
     /// Outro problema
     Other,
 }
@@ -302,40 +383,197 @@ impl MetadataRequest {
     }
 }
 
-/// Validador de PDFs
-#[derive(Debug)]
-pub struct PdfValidator {
-    file_handler: FileHandler,
+/// Request para listagem de anotações
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnotationsRequest {
+    /// Caminho para o PDF
+    pub file_path: PathBuf,
+    /// Tipos de anotação desejados (vazio = todos)
+    #[serde(default)]
+    pub types: Vec<AnnotationType>,
+    /// Páginas desejadas, 1-indexed (vazio = todas)
+    #[serde(default)]
+    pub pages: Vec<u32>,
 }
 
-impl PdfValidator {
-    /// Cria um novo PdfValidator
-    pub fn new() -> Self {
-        Self {
-            file_handler: FileHandler::new(),
-        }
+impl AnnotationsRequest {
+    /// Cria um AnnotationsRequest a partir de JSON
+    pub fn from_value(data: &Value) -> Result<Self> {
+        let file_path = data["file"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'file' field"))?;
+
+        // Filtros opcionais
+        let types = match data.get("types") {
+            Some(types) => serde_json::from_value(types.clone())
+                .map_err(|e| AppError::validation(format!("Invalid annotation types: {}", e)))?,
+            None => Vec::new(),
+        };
+        let pages = match data.get("pages") {
+            Some(pages) => serde_json::from_value(pages.clone())
+                .map_err(|e| AppError::validation(format!("Invalid pages: {}", e)))?,
+            None => Vec::new(),
+        };
+
+        Ok(Self {
+            file_path,
+            types,
+            pages,
+        })
     }
 
-    /// Cria um PdfValidator com um FileHandler específico
-    pub fn with_file_handler(file_handler: FileHandler) -> Self {
-        Self { file_handler }
+    /// A anotação passa pelos filtros da request?
+    pub fn matches(&self, annotation: &AnnotationInfo) -> bool {
+        (self.types.is_empty() || self.types.contains(&annotation.annotation_type))
+            && (self.pages.is_empty() || self.pages.contains(&annotation.page))
     }
+}
+
+/// Resultado da listagem de anotações
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnotationsResult {
+    /// Caminho do PDF
+    pub file_path: PathBuf,
+    /// Total de anotações no documento, antes dos filtros
+    pub total_annotations: usize,
+    /// Anotações que passaram pelos filtros, em ordem de página
+    pub annotations: Vec<AnnotationInfo>,
+    /// Tempo total de processamento (em milissegundos)
+    pub processing_time_ms: u128,
+}
+
+/// Request para análise do histórico de revisões
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevisionsRequest {
+    /// Caminho para o PDF
+    pub file_path: PathBuf,
+    /// Diretório onde gravar cada revisão como um PDF separado (None = só analisar)
+    #[serde(default)]
+    pub extract_dir: Option<PathBuf>,
+    /// Caminho onde gravar o documento reduzido à revisão final, sem o histórico
+    #[serde(default)]
+    pub collapse_output: Option<PathBuf>,
+}
+
+impl RevisionsRequest {
+    /// Cria um RevisionsRequest a partir de JSON
+    pub fn from_value(data: &Value) -> Result<Self> {
+        let file_path = data["file"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'file' field"))?;
+
+        // Saídas opcionais
+        let extract_dir = data.get("extract_dir").and_then(|v| v.as_str()).map(PathBuf::from);
+        let collapse_output = data.get("collapse_output").and_then(|v| v.as_str()).map(PathBuf::from);
+
+        Ok(Self {
+            file_path,
+            extract_dir,
+            collapse_output,
+        })
+    }
+
+    /// Caminho do arquivo de uma revisão extraída ("<arquivo>_rev<N>.pdf")
+    pub fn revision_path(&self, dir: &Path, revision: &Revision) -> PathBuf {
+        let stem = self.file_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "document".to_string());
+        dir.join(format!("{}_rev{}.pdf", stem, revision.number))
+    }
+}
 
+/// Resultado da análise do histórico de revisões
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevisionsResult {
+    /// Caminho do PDF
+    pub file_path: PathBuf,
+    /// Número de revisões (1 = sem atualizações incrementais)
+    pub revision_count: usize,
+    /// Revisões, da original para a mais recente
+    pub revisions: Vec<Revision>,
+    /// Bytes depois do último `%%EOF`
+    pub trailing_bytes: u64,
+    /// Objetos cujas versões anteriores continuam recuperáveis no arquivo
+    pub superseded_objects: Vec<u32>,
+    /// Revisões gravadas em `extract_dir`, na ordem das revisões
+    pub extracted_files: Vec<PathBuf>,
+    /// Documento reduzido à revisão final, se solicitado
+    pub collapsed_file: Option<PathBuf>,
+    /// Tempo total de processamento (em milissegundos)
+    pub processing_time_ms: u128,
+}
+
+/// Request para verificação de assinaturas digitais
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignaturesRequest {
+    /// Caminho para o PDF
+    pub file_path: PathBuf,
+}
+
+impl SignaturesRequest {
+    /// Cria um SignaturesRequest a partir de JSON
+    pub fn from_value(data: &Value) -> Result<Self> {
+        let file_path = data["file"]
+            .as_str()
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::validation("Missing or invalid 'file' field"))?;
+
+        Ok(Self { file_path })
+    }
+}
+
+/// Resultado da verificação de assinaturas digitais
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignaturesResult {
+    /// Caminho do PDF
+    pub file_path: PathBuf,
+    /// Número de assinaturas encontradas
+    pub signature_count: usize,
+    /// Assinaturas, na ordem do formulário
+    pub signatures: Vec<SignatureInfo>,
+    /// Todas as assinaturas conferem com os bytes assinados
+    pub all_intact: bool,
+    /// O arquivo recebeu alterações depois da última assinatura
+    pub modified_after_signing: bool,
+    /// Tempo total de processamento (em milissegundos)
+    pub processing_time_ms: u128,
+}
+
+/// Validador de PDFs
+#[derive(Debug, Clone)]
+pub struct PdfValidator {
+    runtime: ProcessorRuntime,
+}
+
+processor_constructors!(PdfValidator);
+
+impl PdfValidator {
     /// Valida um arquivo PDF
     #[instrument(name = "validate_pdf", skip(self, request), fields(
         file = %request.file_path.display(),
         level = ?request.config.level
     ))]
     pub async fn validate_pdf(&self, request: ValidateRequest) -> Result<ValidationResult> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.validate_pdf_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `validate_pdf`, executado no pool de CPU
+    fn validate_pdf_blocking(&self, request: ValidateRequest) -> Result<ValidationResult> {
         let start_time = Instant::now();
 
         info!("Starting PDF validation");
 
         // 1. Validação básica da request
-        request.validate_basic(&self.file_handler)?;
+        request.validate_basic(self.runtime.file_handler())?;
 
-        // 2. Carrega o documento
-        let load_result = self.load_document(&request.file_path);
+        // 2. Carrega o documento (os bytes brutos também servem às análises de xref e streams)
+        let source = std::fs::read(&request.file_path)
+            .map_err(|e| AppError::from_io_error("reading PDF", request.file_path.clone(), e))?;
+        let load_result = self.load_document_from_bytes(&source, &request.file_path);
         let load_time = start_time.elapsed();
 
         match load_result {
@@ -343,24 +581,33 @@ impl PdfValidator {
                 info!("PDF loaded successfully, performing validation");
 
                 // 3. Executa validação baseada no nível
+                let mut detailed_issues = Vec::new();
                 let validation_details = match request.config.level {
                     ValidationLevel::Basic => self.validate_basic(&doc),
-                    ValidationLevel::Full => self.validate_full(&doc, &request.config).await,
-                    ValidationLevel::Deep => self.validate_deep(&doc, &request.config).await,
+                    ValidationLevel::Full => {
+                        self.validate_full(&doc, &source, &request.config, &mut detailed_issues)
+                    }
+                    ValidationLevel::Deep => {
+                        self.validate_deep(&doc, &source, &request.config, &mut detailed_issues)
+                    }
                 };
 
-                // 4. Extrai metadados se solicitado
+                // 4. Procura conteúdo ativo (executado em todos os níveis)
+                let security = pdf_security::scan(&doc);
+
+                // 5. Extrai metadados se solicitado
                 let metadata = if request.extract_metadata || request.config.extract_metadata {
-                    Some(self.extract_metadata(&doc, &request.file_path, load_time.as_millis()).await?)
+                    Some(self.extract_metadata(&doc, &request.file_path, load_time.as_millis(), &security)?)
                 } else {
                     None
                 };
 
-                // 5. Coleta problemas encontrados
-                let issues = self.collect_issues(&doc, &validation_details);
+                // 6. Coleta problemas encontrados
+                let mut issues = self.collect_issues(&doc, &validation_details, &security);
+                issues.extend(detailed_issues);
                 
-                // 6. Gera recomendações
-                let recommendations = self.generate_recommendations(&issues, metadata.as_ref());
+                // 7. Gera recomendações
+                let recommendations = self.generate_recommendations(&issues, metadata.as_ref(), &security);
 
                 let total_time = start_time.elapsed();
 
@@ -373,6 +620,7 @@ impl PdfValidator {
                     issues,
                     recommendations,
                     validation_time_ms: total_time.as_millis(),
+                    security: Some(security),
                 };
 
                 info!(
@@ -400,6 +648,7 @@ impl PdfValidator {
                         is_web_optimized: false,
                         pdfa_compliant: None,
                         pdfua_compliant: None,
+                        accessibility: None,
                     },
                     metadata: None,
                     issues: vec![ValidationIssue {
@@ -414,6 +663,7 @@ impl PdfValidator {
                         "Check if file is a valid PDF".to_string(),
                     ],
                     validation_time_ms: load_time.as_millis(),
+                    security: None,
                 })
             }
         }
@@ -424,19 +674,38 @@ impl PdfValidator {
         file = %request.file_path.display()
     ))]
     pub async fn get_pdf_metadata(&self, request: MetadataRequest) -> Result<PdfMetadata> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.get_pdf_metadata_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `get_pdf_metadata`, executado no pool de CPU
+    fn get_pdf_metadata_blocking(&self, request: MetadataRequest) -> Result<PdfMetadata> {
         let start_time = Instant::now();
 
         info!("Extracting PDF metadata");
 
         // Valida arquivo
-        self.file_handler.validate_file(request.file_path.to_str().unwrap_or(""))?;
+        self.runtime.file_handler().validate_file(request.file_path.to_str().unwrap_or(""))?;
 
         // Carrega documento
         let doc = self.load_document(&request.file_path)?;
         let load_time = start_time.elapsed();
 
         // Extrai metadados
-        let metadata = self.extract_metadata(&doc, &request.file_path, load_time.as_millis()).await?;
+        let security = pdf_security::scan(&doc);
+        let mut metadata = self.extract_metadata(&doc, &request.file_path, load_time.as_millis(), &security)?;
+
+        // Análise detalhada
+        if request.include_detailed_analysis {
+            metadata.bookmarks = Some(pdf_inventory::bookmarks(&doc));
+
+            let page_ids = doc.get_pages();
+            for page in &mut metadata.pages {
+                if let Some(page_id) = page_ids.get(&page.page_number) {
+                    page.content = Some(pdf_text::page_text(&doc, *page_id, false));
+                }
+            }
+        }
 
         info!(
             file = %request.file_path.display(),
@@ -448,8 +717,187 @@ impl PdfValidator {
         Ok(metadata)
     }
 
+    /// Lista as anotações de um PDF, filtradas por tipo e página
+    #[instrument(name = "list_annotations", skip(self, request), fields(
+        file = %request.file_path.display()
+    ))]
+    pub async fn list_annotations(&self, request: AnnotationsRequest) -> Result<AnnotationsResult> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.list_annotations_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `list_annotations`, executado no pool de CPU
+    fn list_annotations_blocking(&self, request: AnnotationsRequest) -> Result<AnnotationsResult> {
+        let start_time = Instant::now();
+
+        info!("Listing PDF annotations");
+
+        // Valida arquivo
+        self.runtime.file_handler().validate_file(request.file_path.to_str().unwrap_or(""))?;
+
+        // Carrega documento
+        let doc = self.load_document(&request.file_path)?;
+
+        let all = pdf_inventory::annotations(&doc);
+        let total_annotations = all.len();
+        let annotations: Vec<AnnotationInfo> = all.into_iter().filter(|a| request.matches(a)).collect();
+
+        info!(
+            file = %request.file_path.display(),
+            total = total_annotations,
+            listed = annotations.len(),
+            "Annotations listed successfully"
+        );
+
+        Ok(AnnotationsResult {
+            file_path: request.file_path,
+            total_annotations,
+            annotations,
+            processing_time_ms: start_time.elapsed().as_millis(),
+        })
+    }
+
+    /// Analisa as atualizações incrementais de um PDF
+    ///
+    /// Opcionalmente grava cada revisão como um PDF separado e reescreve o
+    /// documento apenas com a revisão final. O colapso descarta as versões
+    /// anteriores dos objetos, e com elas qualquer assinatura digital.
+    #[instrument(name = "analyze_revisions", skip(self, request), fields(
+        file = %request.file_path.display()
+    ))]
+    pub async fn analyze_revisions(&self, request: RevisionsRequest) -> Result<RevisionsResult> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.analyze_revisions_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `analyze_revisions`, executado no pool de CPU
+    fn analyze_revisions_blocking(&self, request: RevisionsRequest) -> Result<RevisionsResult> {
+        let start_time = Instant::now();
+
+        info!("Analyzing PDF revision history");
+
+        // Valida arquivo
+        self.runtime.file_handler().validate_file(request.file_path.to_str().unwrap_or(""))?;
+
+        let source = std::fs::read(&request.file_path)
+            .map_err(|e| AppError::from_io_error("reading PDF", request.file_path.clone(), e))?;
+        let history = pdf_revisions::history(&source);
+
+        // Cada revisão é um prefixo do arquivo
+        let mut extracted_files = Vec::new();
+        if let Some(dir) = &request.extract_dir {
+            if !dir.exists() {
+                self.runtime.file_handler().create_dir(dir.to_str().unwrap_or(""))?;
+            }
+            for revision in &history.revisions {
+                let path = request.revision_path(dir, revision);
+                self.runtime.file_handler()
+                    .write_new_file(path.to_str().unwrap_or(""), pdf_revisions::revision_bytes(&source, revision))?;
+                extracted_files.push(path);
+            }
+        }
+
+        let collapsed_file = match &request.collapse_output {
+            Some(output) => {
+                self.collapse_to_final_revision(&source, &request.file_path, output)?;
+                Some(output.clone())
+            }
+            None => None,
+        };
+
+        let superseded_objects = history.superseded_objects();
+        info!(
+            file = %request.file_path.display(),
+            revisions = history.revisions.len(),
+            superseded = superseded_objects.len(),
+            extracted = extracted_files.len(),
+            "Revision history analyzed"
+        );
+
+        Ok(RevisionsResult {
+            file_path: request.file_path,
+            revision_count: history.revisions.len(),
+            revisions: history.revisions,
+            trailing_bytes: history.trailing_bytes,
+            superseded_objects,
+            extracted_files,
+            collapsed_file,
+            processing_time_ms: start_time.elapsed().as_millis(),
+        })
+    }
+
+    /// Verifica as assinaturas digitais de um PDF
+    ///
+    /// A verificação usa apenas os dados do arquivo: o digest dos bytes
+    /// assinados e o certificado incluído na mensagem CMS. Não consulta
+    /// OCSP/CRL nem avalia a confiança na cadeia de certificados.
+    #[instrument(name = "verify_signatures", skip(self, request), fields(
+        file = %request.file_path.display()
+    ))]
+    pub async fn verify_signatures(&self, request: SignaturesRequest) -> Result<SignaturesResult> {
+        let processor = self.clone();
+        self.runtime.spawn(move || processor.verify_signatures_blocking(request)).await
+    }
+
+    /// Corpo síncrono de `verify_signatures`, executado no pool de CPU
+    fn verify_signatures_blocking(&self, request: SignaturesRequest) -> Result<SignaturesResult> {
+        let start_time = Instant::now();
+
+        info!("Verifying PDF digital signatures");
+
+        // Valida arquivo
+        self.runtime.file_handler().validate_file(request.file_path.to_str().unwrap_or(""))?;
+
+        let source = std::fs::read(&request.file_path)
+            .map_err(|e| AppError::from_io_error("reading PDF", request.file_path.clone(), e))?;
+        let doc = self.load_document_from_bytes(&source, &request.file_path)?;
+        let signatures = pdf_signatures::verify(&doc, &source);
+
+        let all_intact = signatures.iter().all(SignatureInfo::is_intact);
+        // Se alguma assinatura chega ao fim do arquivo, nada foi acrescentado depois dela
+        let modified_after_signing = !signatures.is_empty()
+            && signatures.iter().all(SignatureInfo::modified_after_signing);
+
+        info!(
+            file = %request.file_path.display(),
+            signatures = signatures.len(),
+            all_intact,
+            modified_after_signing,
+            "Digital signatures verified"
+        );
+
+        Ok(SignaturesResult {
+            file_path: request.file_path,
+            signature_count: signatures.len(),
+            signatures,
+            all_intact,
+            modified_after_signing,
+            processing_time_ms: start_time.elapsed().as_millis(),
+        })
+    }
+
     // ==================== MÉTODOS PRIVADOS ====================
 
+    /// Reescreve o documento só com os objetos vivos na revisão final, em um arquivo novo
+    fn collapse_to_final_revision(&self, source: &[u8], path: &Path, output: &Path) -> Result<()> {
+        // O lopdf carrega só as versões atuais dos objetos; a regravação não leva o histórico
+        let mut doc = self.load_document_from_bytes(source, path)?;
+        doc.prune_objects();
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes)
+            .map_err(|e| {
+                error!(path = %output.display(), error = %e, "Failed to save collapsed PDF");
+                AppError::Pdf(PdfError::ProcessingFailed {
+                    reason: format!("Failed to save collapsed PDF: {}", e),
+                })
+            })?;
+        self.runtime.file_handler().write_new_file(output.to_str().unwrap_or(""), &bytes)?;
+
+        info!(path = %output.display(), "PDF collapsed to its final revision");
+        Ok(())
+    }
+
     /// Carrega um documento PDF
     #[instrument(name = "load_document", skip(self, path))]
     fn load_document(&self, path: &Path) -> Result<Document> {
@@ -464,6 +912,19 @@ impl PdfValidator {
             })
     }
 
+    /// Carrega um documento PDF já lido em memória
+    fn load_document_from_bytes(&self, bytes: &[u8], path: &Path) -> Result<Document> {
+        info!(path = %path.display(), "Loading PDF document");
+
+        Document::load_mem(bytes)
+            .map_err(|e| {
+                error!(path = %path.display(), error = %e, "Failed to load PDF");
+                AppError::Pdf(PdfError::CorruptedPdf {
+                    path: path.to_path_buf(),
+                })
+            })
+    }
+
     /// Validação básica (apenas carregamento)
     fn validate_basic(&self, doc: &Document) -> ValidationDetails {
         ValidationDetails {
@@ -475,57 +936,117 @@ impl PdfValidator {
             is_web_optimized: false, // Não verificado no nível básico
             pdfa_compliant: None,
             pdfua_compliant: None,
+            accessibility: None,
         }
     }
 
     /// Validação completa
-    async fn validate_full(&self, doc: &Document, config: &ValidationConfig) -> ValidationDetails {
+    fn validate_full(
+        &self,
+        doc: &Document,
+        source: &[u8],
+        config: &ValidationConfig,
+        issues: &mut Vec<ValidationIssue>,
+    ) -> ValidationDetails {
         let mut details = self.validate_basic(doc);
 
         // Verifica estrutura básica
-        details.structure_valid = self.validate_structure(doc).await;
+        details.structure_valid = self.validate_structure(doc);
 
         // Verifica referências cruzadas se configurado
         if config.check_xref {
-            details.xref_valid = self.validate_xref(doc).await;
+            let xref_issues = self.validate_xref(source);
+            details.xref_valid = xref_issues.is_empty();
+            issues.extend(xref_issues);
+        }
+
+        // Atualizações incrementais guardam as versões anteriores dos objetos
+        if let Some(issue) = revisions_issue(&pdf_revisions::history(source)) {
+            issues.push(issue);
+        }
+
+        // Assinaturas digitais: bytes assinados e alterações posteriores
+        issues.extend(pdf_signatures::verify(doc, source).iter().map(signature_issue));
+
+        // Info e XMP precisam dizer a mesma coisa (exigência do PDF/A)
+        if let Some(xmp) = pdf_xmp::read(doc) {
+            issues.extend(pdf_xmp::compare(&DocumentInfo::read(doc), &xmp).iter().map(metadata_mismatch_issue));
         }
 
         // Verifica objetos corrompidos
         if config.validate_structure {
-            details.no_corrupted_objects = self.check_corrupted_objects(doc).await;
+            details.no_corrupted_objects = self.check_corrupted_objects(doc);
         }
 
         // Verifica referências
-        details.all_references_valid = self.validate_references(doc).await;
+        details.all_references_valid = self.validate_references(doc);
 
         // Verifica otimização para web (linearizado)
-        details.is_web_optimized = self.check_web_optimized(doc).await;
+        details.is_web_optimized = self.check_web_optimized(source, issues);
 
         info!("Full validation completed");
         details
     }
 
     /// Validação profunda
-    async fn validate_deep(&self, doc: &Document, config: &ValidationConfig) -> ValidationDetails {
-        let mut details = self.validate_full(doc, config).await;
+    fn validate_deep(
+        &self,
+        doc: &Document,
+        source: &[u8],
+        config: &ValidationConfig,
+        issues: &mut Vec<ValidationIssue>,
+    ) -> ValidationDetails {
+        let mut details = self.validate_full(doc, source, config, issues);
+
+        // Varredura completa: todos os objetos, em qualquer profundidade
+        if source.len() as u64 > config.max_size_for_deep_analysis {
+            issues.push(ValidationIssue {
+                severity: IssueSeverity::Info,
+                issue_type: IssueType::FileTooLarge,
+                description: format!(
+                    "File exceeds the deep analysis limit of {} bytes; object scan skipped",
+                    config.max_size_for_deep_analysis
+                ),
+                location: None,
+                suggestion: None,
+            });
+        } else {
+            let scan_issues = self.scan_all_objects(doc, source, config);
+            details.no_corrupted_objects = !scan_issues
+                .iter()
+                .any(|i| i.issue_type == IssueType::CorruptedObject);
+            details.all_references_valid = !scan_issues
+                .iter()
+                .any(|i| i.issue_type == IssueType::InvalidReference);
+            issues.extend(scan_issues);
+        }
+
+        // Conformidade PDF/A: uma issue por cláusula violada
+        let pdfa = pdf_pdfa::check(doc, config.pdfa_level);
+        info!(level = %pdfa.level, violations = pdfa.violations.len(), "PDF/A check completed");
+        details.pdfa_compliant = Some(pdfa.is_compliant());
+        issues.extend(pdfa.violations.iter().map(|violation| pdfa_issue(violation, pdfa.level)));
+
+        // Acessibilidade PDF/UA: falhas do documento e de cada página
+        let accessibility = pdf_pdfua::check(doc);
+        info!(failures = accessibility.failure_count(), "PDF/UA check completed");
+        details.pdfua_compliant = Some(accessibility.is_compliant());
+        issues.extend(accessibility.document_failures.iter().map(|failure| pdfua_issue(failure, None)));
+        for page in &accessibility.pages {
+            issues.extend(page.failures.iter().map(|failure| pdfua_issue(failure, Some(page.page_number))));
+        }
+        details.accessibility = Some(accessibility);
 
         // Análises adicionais podem ser adicionadas aqui:
-        // - Verificação de conformidade PDF/A
-        // - Verificação de acessibilidade PDF/UA
-        // - Análise de todos os objetos
         // - Verificação de compactação
         // - Análise de fontes e imagens
 
-        // Por enquanto, definimos como desconhecido
-        details.pdfa_compliant = Some(false);
-        details.pdfua_compliant = Some(false);
-
         info!("Deep validation completed");
         details
     }
 
     /// Valida a estrutura do documento
-    async fn validate_structure(&self, doc: &Document) -> bool {
+    fn validate_structure(&self, doc: &Document) -> bool {
         // Verifica se tem catálogo raiz
         if doc.trailer.get(b"Root").is_err() {
             warn!("PDF missing root catalog");
@@ -546,24 +1067,24 @@ impl PdfValidator {
         true
     }
 
-    /// Valida a tabela de referências cruzadas
-    async fn validate_xref(&self, doc: &Document) -> bool {
-        // lopdf não expõe XRef diretamente, mas podemos verificar indiretamente
-        // tentando acessar objetos aleatórios
-        let test_object_ids: Vec<_> = doc.objects.keys().take(5).collect();
-        
-        for &obj_id in &test_object_ids {
-            if doc.get_object(*obj_id).is_err() {
-                warn!(object_id = ?obj_id, "Failed to access object, possible XRef issue");
-                return false;
-            }
-        }
+    /// Valida a tabela de referências cruzadas a partir dos bytes do arquivo
+    ///
+    /// O `lopdf` reconstrói a tabela ao carregar, então a análise é feita sobre
+    /// o arquivo bruto: `startxref`, todas as seções da cadeia `/Prev` e o
+    /// offset de cada objeto em uso.
+    fn validate_xref(&self, source: &[u8]) -> Vec<ValidationIssue> {
+        let report = pdf_xref::analyze(source);
+        info!(
+            sections = report.sections.len(),
+            problems = report.problems.len(),
+            "Cross-reference analysis completed"
+        );
 
-        true
+        report.problems.iter().map(xref_issue).collect()
     }
 
     /// Verifica objetos corrompidos
-    async fn check_corrupted_objects(&self, doc: &Document) -> bool {
+    fn check_corrupted_objects(&self, doc: &Document) -> bool {
         let mut corrupted_count = 0;
         let total_objects = doc.objects.len();
 
@@ -596,7 +1117,7 @@ impl PdfValidator {
     }
 
     /// Valida referências entre objetos
-    async fn validate_references(&self, doc: &Document) -> bool {
+    fn validate_references(&self, doc: &Document) -> bool {
         let mut invalid_refs = 0;
         
         for (&obj_id, obj) in &doc.objects {
@@ -620,28 +1141,256 @@ impl PdfValidator {
         }
     }
 
-    /// Verifica se o PDF está otimizado para web (linearizado)
-    async fn check_web_optimized(&self, doc: &Document) -> bool {
-        // PDF linearizado tem objeto /Linearized no catálogo
-        // Esta é uma verificação simplificada
-        if let Ok(root) = doc.trailer.get(b"Root") {
-            if let Object::Reference(root_id) = *root {
-                if let Ok(Object::Dictionary(ref dict)) = doc.get_object(root_id) {
-                    return dict.get(b"Linearized").is_ok();
+    /// Varre todos os objetos do documento (nível Deep)
+    ///
+    /// Gera um problema por ocorrência: referências quebradas em qualquer
+    /// profundidade, streams que não decodificam, `/Length` divergente e objetos
+    /// da tabela xref que não puderam ser lidos.
+    fn scan_all_objects(&self, doc: &Document, source: &[u8], config: &ValidationConfig) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        self.scan_references(doc, &mut issues);
+        self.scan_streams(doc, config, &mut issues);
+        self.scan_raw_objects(doc, source, &mut issues);
+
+        if issues.len() > MAX_DEEP_SCAN_ISSUES {
+            let omitted = issues.len() - MAX_DEEP_SCAN_ISSUES;
+            issues.truncate(MAX_DEEP_SCAN_ISSUES);
+            issues.push(ValidationIssue {
+                severity: IssueSeverity::Info,
+                issue_type: IssueType::Other,
+                description: format!("{} further object problems were omitted", omitted),
+                location: None,
+                suggestion: None,
+            });
+        }
+
+        info!(issues = issues.len(), objects = doc.objects.len(), "Deep object scan completed");
+        issues
+    }
+
+    /// Procura referências para objetos inexistentes em qualquer nível de aninhamento
+    fn scan_references(&self, doc: &Document, issues: &mut Vec<ValidationIssue>) {
+        for (&object_id, object) in &doc.objects {
+            let mut pending: Vec<(&Object, String)> = vec![(object, String::new())];
+
+            while let Some((current, path)) = pending.pop() {
+                let dict = match current {
+                    Object::Reference(target) => {
+                        if !doc.objects.contains_key(target) {
+                            issues.push(ValidationIssue {
+                                severity: IssueSeverity::Warning,
+                                issue_type: IssueType::InvalidReference,
+                                description: format!("Reference to missing object {} {} R", target.0, target.1),
+                                location: Some(object_location(object_id, &path)),
+                                suggestion: Some("Missing objects are read as null; remove or repair the reference".to_string()),
+                            });
+                        }
+                        continue;
+                    }
+                    Object::Array(items) => {
+                        for (index, item) in items.iter().enumerate().rev() {
+                            pending.push((item, format!("{}[{}]", path, index)));
+                        }
+                        continue;
+                    }
+                    Object::Dictionary(dict) => dict,
+                    Object::Stream(stream) => &stream.dict,
+                    _ => continue,
+                };
+
+                for (key, value) in dict.iter().rev() {
+                    pending.push((value, format!("{}/{}", path, String::from_utf8_lossy(key))));
                 }
             }
         }
-        
+    }
+
+    /// Tenta decodificar cada stream com seus filtros
+    ///
+    /// Cada stream pode gerar no máximo `max_size_for_deep_analysis` bytes decodificados.
+    fn scan_streams(&self, doc: &Document, config: &ValidationConfig, issues: &mut Vec<ValidationIssue>) {
+        // Sem a senha o conteúdo cifrado não pode ser decodificado
+        if doc.trailer.get(b"Encrypt").is_ok() {
+            issues.push(ValidationIssue {
+                severity: IssueSeverity::Info,
+                issue_type: IssueType::EncryptedDocument,
+                description: "Stream contents are encrypted and were not decoded".to_string(),
+                location: None,
+                suggestion: None,
+            });
+            return;
+        }
+
+        let limit = usize::try_from(config.max_size_for_deep_analysis).unwrap_or(usize::MAX);
+        for (&object_id, object) in &doc.objects {
+            let Object::Stream(stream) = object else {
+                continue;
+            };
+
+            match pdf_filters::decode_stream_with_limit(doc, stream, limit) {
+                Ok(_) => {}
+                Err(FilterError::LimitExceeded { limit, .. }) => issues.push(ValidationIssue {
+                    severity: IssueSeverity::Warning,
+                    issue_type: IssueType::UnsupportedFormat,
+                    description: format!("Stream decodes to more than {} bytes and was not analyzed", limit),
+                    location: Some(object_location(object_id, "")),
+                    suggestion: None,
+                }),
+                Err(FilterError::Unsupported { filter }) => issues.push(ValidationIssue {
+                    severity: IssueSeverity::Warning,
+                    issue_type: IssueType::UnsupportedFormat,
+                    description: format!("Stream uses unsupported filter /{}", filter),
+                    location: Some(object_location(object_id, "")),
+                    suggestion: None,
+                }),
+                Err(e) => issues.push(ValidationIssue {
+                    severity: IssueSeverity::Error,
+                    issue_type: IssueType::CorruptedObject,
+                    description: format!("Stream could not be decoded: {}", e),
+                    location: Some(object_location(object_id, "")),
+                    suggestion: Some("The stream data is damaged; re-export the document".to_string()),
+                }),
+            }
+        }
+    }
+
+    /// Confere, nos bytes do arquivo, cada objeto declarado na tabela xref
+    fn scan_raw_objects(&self, doc: &Document, source: &[u8], issues: &mut Vec<ValidationIssue>) {
+        let report = pdf_xref::analyze(source);
+        let mut seen = HashSet::new();
+
+        // As seções vêm da mais recente para a mais antiga: vale a primeira definição
+        for entry in report.sections.iter().flat_map(|section| &section.entries) {
+            if !seen.insert(entry.object_number) {
+                continue;
+            }
+
+            match entry.kind {
+                XrefEntryKind::InUse { offset } => {
+                    let object_id = (entry.object_number, entry.generation);
+
+                    // Offsets que não apontam para o objeto já são relatados pela análise xref
+                    if pdf_xref::object_header_at(source, offset) != Some(object_id) {
+                        continue;
+                    }
+
+                    if let Some(issue) = self.check_stream_length(doc, source, object_id, offset) {
+                        issues.push(issue);
+                    } else if !doc.objects.contains_key(&object_id) {
+                        issues.push(ValidationIssue {
+                            severity: IssueSeverity::Error,
+                            issue_type: IssueType::CorruptedObject,
+                            description: format!(
+                                "Object {} {} is listed in the cross-reference table but could not be parsed",
+                                object_id.0, object_id.1
+                            ),
+                            location: Some(format!("object {}, offset {}", object_id.0, offset)),
+                            suggestion: Some("The document may need to be repaired".to_string()),
+                        });
+                    }
+                }
+                XrefEntryKind::Compressed { stream_object, .. } => {
+                    if !doc.objects.contains_key(&(entry.object_number, 0)) {
+                        issues.push(ValidationIssue {
+                            severity: IssueSeverity::Error,
+                            issue_type: IssueType::CorruptedObject,
+                            description: format!(
+                                "Object {} could not be read from object stream {}",
+                                entry.object_number, stream_object
+                            ),
+                            location: Some(format!("object {}", entry.object_number)),
+                            suggestion: Some("The document may need to be repaired".to_string()),
+                        });
+                    }
+                }
+                XrefEntryKind::Free => {}
+            }
+        }
+    }
+
+    /// Compara o `/Length` declarado de uma stream com os dados reais no arquivo
+    fn check_stream_length(
+        &self,
+        doc: &Document,
+        source: &[u8],
+        object_id: (u32, u16),
+        offset: u64,
+    ) -> Option<ValidationIssue> {
+        let extent = pdf_xref::stream_extent(source, offset)?;
+        let location = Some(format!("object {}, offset {}", object_id.0, extent.data_offset));
+
+        let declared = match extent.declared_length {
+            StreamLength::Direct(length) => Some(length),
+            StreamLength::Indirect(number, generation) => doc
+                .get_object((number, generation))
+                .and_then(Object::as_i64)
+                .ok()
+                .and_then(|length| u64::try_from(length).ok()),
+            StreamLength::Missing => None,
+        };
+
+        match declared {
+            Some(length) if extent.matches(length) => None,
+            Some(length) => Some(ValidationIssue {
+                severity: IssueSeverity::Warning,
+                issue_type: IssueType::CorruptedObject,
+                description: format!(
+                    "Stream /Length of object {} is {} but {} bytes of data were found",
+                    object_id.0, length, extent.actual_length
+                ),
+                location,
+                suggestion: Some("Re-save the PDF to rewrite stream lengths".to_string()),
+            }),
+            None => Some(ValidationIssue {
+                severity: IssueSeverity::Error,
+                issue_type: IssueType::CorruptedObject,
+                description: format!("Stream of object {} has no valid /Length", object_id.0),
+                location,
+                suggestion: Some("Re-save the PDF to rewrite stream lengths".to_string()),
+            }),
+        }
+    }
+
+    /// Verifica se o PDF está otimizado para web (linearizado)
+    ///
+    /// O dicionário `/Linearized` é o primeiro objeto do arquivo, e só vale
+    /// enquanto `/L` corresponder ao tamanho real: uma atualização incremental
+    /// posterior invalida a linearização.
+    fn check_web_optimized(&self, source: &[u8], issues: &mut Vec<ValidationIssue>) -> bool {
+        let Some(linearization) = pdf_xref::linearization(source) else {
+            return false;
+        };
+
+        let file_length = source.len() as u64;
+        if linearization.matches_length(file_length) {
+            return true;
+        }
+
+        issues.push(ValidationIssue {
+            severity: IssueSeverity::Info,
+            issue_type: IssueType::CompatibilityIssue,
+            description: match linearization.file_length {
+                Some(declared) => format!(
+                    "Linearization dictionary declares a file length of {} bytes, but the file has {}; fast web view is disabled",
+                    declared, file_length
+                ),
+                None => "Linearization dictionary has no valid /L entry; fast web view is disabled".to_string(),
+            },
+            location: Some(format!("object {}", linearization.object_number)),
+            suggestion: Some("Re-save the PDF with the linearize option to restore fast web view".to_string()),
+        });
         false
     }
 
     /// Extrai metadados do PDF
-    #[instrument(name = "extract_metadata", skip(self, doc, load_time_ms))]
-    async fn extract_metadata(
+    #[instrument(name = "extract_metadata", skip(self, doc, load_time_ms, security))]
+    fn extract_metadata(
         &self,
         doc: &Document,
         file_path: &Path,
         load_time_ms: u128,
+        security: &SecurityReport,
     ) -> Result<PdfMetadata> {
         let mut metadata = PdfMetadata {
             file_path: file_path.to_path_buf(),
@@ -655,15 +1404,26 @@ impl PdfValidator {
             creator: None,
             producer: None,
             creation_date: None,
+            creation_date_raw: None,
             modification_date: None,
+            modification_date_raw: None,
             is_encrypted: false,
+            encryption: None,
+            permissions: PdfPermissions::unrestricted(),
             has_forms: false,
             has_annotations: false,
+            annotations: Vec::new(),
             has_javascript: false,
             has_embedded_fonts: false,
+            embedded_fonts: Vec::new(),
             has_images: false,
+            images: Vec::new(),
             first_page_dimensions: None,
+            pages: Vec::new(),
             object_counts: HashMap::new(),
+            bookmarks: None,
+            xmp: None,
+            metadata_mismatches: Vec::new(),
             load_time_ms,
         };
 
@@ -677,7 +1437,28 @@ impl PdfValidator {
         metadata.pdf_version = format!("{:.1}", doc.version);
 
         // Verifica se está criptografado
-        metadata.is_encrypted = doc.trailer.get(b"Encrypt").is_ok();
+        metadata.encryption = pdf_encryption::analyze(doc);
+        metadata.is_encrypted = metadata.encryption.is_some();
+        if let Some(encryption) = &metadata.encryption {
+            metadata.permissions = encryption.permissions.clone();
+        }
+        metadata.has_javascript = security.has_javascript();
+
+        // Análise por página
+        metadata.pages = pdf_inventory::pages(doc);
+        metadata.first_page_dimensions = metadata.pages.first().map(|page| page.dimensions);
+
+        // Inventário de fontes
+        metadata.embedded_fonts = pdf_inventory::fonts(doc);
+        metadata.has_embedded_fonts = metadata.embedded_fonts.iter().any(|font| font.embedded);
+
+        // Inventário de imagens
+        metadata.images = pdf_inventory::images(doc);
+        metadata.has_images = !metadata.images.is_empty();
+
+        // Inventário de anotações
+        metadata.annotations = pdf_inventory::annotations(doc);
+        metadata.has_annotations = !metadata.annotations.is_empty();
 
         // Extrai metadados do Info dictionary
         if let Ok(info) = doc.trailer.get(b"Info") {
@@ -705,16 +1486,24 @@ impl PdfValidator {
                     metadata.producer = info_dict.get(b"Producer")
                         .ok()
                         .and_then(|obj| extract_string(obj, doc));
-                    metadata.creation_date = info_dict.get(b"CreationDate")
+                    metadata.creation_date_raw = info_dict.get(b"CreationDate")
                         .ok()
                         .and_then(|obj| extract_string(obj, doc));
-                    metadata.modification_date = info_dict.get(b"ModDate")
+                    metadata.creation_date = metadata.creation_date_raw.as_deref().and_then(parse_info_date);
+                    metadata.modification_date_raw = info_dict.get(b"ModDate")
                         .ok()
                         .and_then(|obj| extract_string(obj, doc));
+                    metadata.modification_date = metadata.modification_date_raw.as_deref().and_then(parse_info_date);
                 }
             }
         }
 
+        // Metadados XMP e divergências com o Info
+        metadata.xmp = pdf_xmp::read(doc);
+        if let Some(xmp) = &metadata.xmp {
+            metadata.metadata_mismatches = pdf_xmp::compare(&DocumentInfo::read(doc), xmp);
+        }
+
         // Conta objetos por tipo
         for (_, obj) in &doc.objects {
             let type_name = match obj {
@@ -759,7 +1548,12 @@ impl PdfValidator {
     }
 
     /// Coleta problemas encontrados durante a validação
-    fn collect_issues(&self, doc: &Document, details: &ValidationDetails) -> Vec<ValidationIssue> {
+    fn collect_issues(
+        &self,
+        doc: &Document,
+        details: &ValidationDetails,
+        security: &SecurityReport,
+    ) -> Vec<ValidationIssue> {
         let mut issues = Vec::new();
 
         if !details.loaded_successfully {
@@ -782,16 +1576,6 @@ impl PdfValidator {
             });
         }
 
-        if !details.xref_valid {
-            issues.push(ValidationIssue {
-                severity: IssueSeverity::Warning,
-                issue_type: IssueType::InvalidReference,
-                description: "Cross-reference table issues detected".to_string(),
-                location: None,
-                suggestion: Some("Consider rebuilding the PDF".to_string()),
-            });
-        }
-
         if !details.no_corrupted_objects {
             issues.push(ValidationIssue {
                 severity: IssueSeverity::Error,
@@ -803,13 +1587,23 @@ impl PdfValidator {
         }
 
         // Verifica se está criptografado
-        if doc.trailer.get(b"Encrypt").is_ok() {
+        if let Some(encryption) = pdf_encryption::analyze(doc) {
+            issues.push(encryption_issue(&encryption));
+        }
+
+        // Conteúdo ativo, com a severidade derivada do risco
+        for finding in &security.findings {
             issues.push(ValidationIssue {
-                severity: IssueSeverity::Warning,
-                issue_type: IssueType::EncryptedDocument,
-                description: "PDF is encrypted or password protected".to_string(),
-                location: None,
-                suggestion: Some("Encrypted PDFs may have limited functionality".to_string()),
+                severity: match finding.risk {
+                    RiskLevel::Low => IssueSeverity::Info,
+                    RiskLevel::Medium => IssueSeverity::Warning,
+                    RiskLevel::High => IssueSeverity::Error,
+                    RiskLevel::Critical => IssueSeverity::Critical,
+                },
+                issue_type: IssueType::SecurityRisk,
+                description: format!("{:?} risk: {}", finding.risk, finding.description),
+                location: Some(finding.location.clone()),
+                suggestion: None,
             });
         }
 
@@ -817,11 +1611,16 @@ impl PdfValidator {
     }
 
     /// Gera recomendações baseadas nos problemas e metadados
-    fn generate_recommendations(&self, issues: &[ValidationIssue], metadata: Option<&PdfMetadata>) -> Vec<String> {
+    fn generate_recommendations(
+        &self,
+        issues: &[ValidationIssue],
+        metadata: Option<&PdfMetadata>,
+        security: &SecurityReport,
+    ) -> Vec<String> {
         let mut recommendations = Vec::new();
 
-        // Recomendações baseadas em problemas
-        for issue in issues {
+        // Recomendações baseadas em problemas (conteúdo ativo é tratado abaixo)
+        for issue in issues.iter().filter(|issue| issue.issue_type != IssueType::SecurityRisk) {
             match issue.severity {
                 IssueSeverity::Critical | IssueSeverity::Error => {
                     if !recommendations.contains(&"Consider repairing or recreating the PDF".to_string()) {
@@ -837,6 +1636,23 @@ impl PdfValidator {
             }
         }
 
+        // Recomendações baseadas no conteúdo ativo
+        if !security.is_safe() {
+            recommendations.push("Open this file only if you trust its source: it contains active content".to_string());
+        }
+        if security.has_javascript() {
+            recommendations.push("Disable JavaScript in your PDF viewer before opening this file".to_string());
+        }
+        if security.contains(ActiveContentKind::Launch) {
+            recommendations.push("Do not allow the viewer to launch external applications from this file".to_string());
+        }
+        if security.contains(ActiveContentKind::EmbeddedFile) {
+            recommendations.push("Scan embedded files with an antivirus before opening them".to_string());
+        }
+        if security.contains(ActiveContentKind::SubmitForm) || security.contains(ActiveContentKind::ImportData) {
+            recommendations.push("The document exchanges form data with external locations; review them before filling it in".to_string());
+        }
+
         // Recomendações baseadas em metadados
         if let Some(meta) = metadata {
             if meta.file_size > 10 * 1024 * 1024 { // 10MB
@@ -888,12 +1704,10 @@ impl PdfValidator {
 
 // ==================== FUNÇÕES AUXILIARES ====================
 
-/// Extrai uma string de um objeto PDF
+/// Extrai uma text string de um objeto PDF (PDFDocEncoding, UTF-16BE ou UTF-8)
 fn extract_string(obj: &Object, doc: &Document) -> Option<String> {
     match obj {
-        Object::String(bytes, _) => {
-            String::from_utf8(bytes.clone()).ok()
-        }
+        Object::String(bytes, _) => Some(pdf_encoding::decode_text_string(bytes)),
         Object::Reference(ref_id) => {
             doc.get_object(*ref_id).ok().and_then(|ref_obj| extract_string(ref_obj, doc))
         }
@@ -901,13 +1715,216 @@ fn extract_string(obj: &Object, doc: &Document) -> Option<String> {
     }
 }
 
+/// Localização de um valor dentro de um objeto (ex.: "object 5, /Resources/Font/F1")
+fn object_location(object_id: (u32, u16), path: &str) -> String {
+    if path.is_empty() {
+        format!("object {}", object_id.0)
+    } else {
+        format!("object {}, {}", object_id.0, path)
+    }
+}
+
+/// Descreve a criptografia e as permissões em um `ValidationIssue`
+fn encryption_issue(encryption: &EncryptionInfo) -> ValidationIssue {
+    let password = if encryption.empty_user_password {
+        "opens without a password"
+    } else {
+        "requires a password to open"
+    };
+    let restrictions = encryption.permissions.restrictions();
+
+    let mut description = format!(
+        "PDF is encrypted ({} handler, {:?}, {}-bit key, revision {}) and {}",
+        encryption.filter, encryption.cipher, encryption.key_length, encryption.revision, password
+    );
+    if !restrictions.is_empty() {
+        description.push_str(&format!("; restricted: {}", restrictions.join(", ")));
+    }
+
+    let suggestion = if !encryption.permissions.allow_document_assembly {
+        "Page assembly is not permitted, so split and merge need the owner password to remove the restriction"
+    } else {
+        "Encrypted PDFs may have limited functionality"
+    };
+
+    ValidationIssue {
+        severity: IssueSeverity::Warning,
+        issue_type: IssueType::EncryptedDocument,
+        description,
+        location: None,
+        suggestion: Some(suggestion.to_string()),
+    }
+}
+
+/// Issue de atualizações incrementais, quando o arquivo tem mais de uma revisão
+fn revisions_issue(history: &RevisionHistory) -> Option<ValidationIssue> {
+    if !history.has_incremental_updates() {
+        return None;
+    }
+
+    let superseded = history.superseded_objects();
+    let revisions = history.revisions.len();
+    Some(if superseded.is_empty() {
+        ValidationIssue {
+            severity: IssueSeverity::Info,
+            issue_type: IssueType::PriorRevisionContent,
+            description: format!("File has {} revisions from incremental updates; no object was changed or removed", revisions),
+            location: None,
+            suggestion: None,
+        }
+    } else {
+        ValidationIssue {
+            severity: IssueSeverity::Warning,
+            issue_type: IssueType::PriorRevisionContent,
+            description: format!(
+                "File has {} revisions from incremental updates; {} objects were changed or removed after the original version and their earlier contents are still in the file",
+                revisions,
+                superseded.len()
+            ),
+            location: Some(format!(
+                "objects {}",
+                superseded.iter().map(u32::to_string).collect::<Vec<_>>().join(", ")
+            )),
+            suggestion: Some("Collapse the PDF to its final revision before sharing it".to_string()),
+        }
+    })
+}
+
+/// Converte uma violação PDF/A em um `ValidationIssue`
+fn pdfa_issue(violation: &PdfaViolation, level: PdfaLevel) -> ValidationIssue {
+    ValidationIssue {
+        severity: IssueSeverity::Warning,
+        issue_type: IssueType::CompatibilityIssue,
+        description: format!("{} ({}): {}", level, violation.clause, violation.description),
+        location: violation.object_id.map(|id| object_location(id, "")),
+        suggestion: Some(format!("Convert the document to {} before submitting it", level)),
+    }
+}
+
+/// Interpreta uma data do Info: formato PDF e, de geradores que o ignoram, ISO 8601
+fn parse_info_date(raw: &str) -> Option<DateTime<FixedOffset>> {
+    pdf_encoding::parse_pdf_date(raw).or_else(|| pdf_xmp::parse_xmp_date(raw))
+}
+
+/// Converte uma divergência entre Info e XMP em um `ValidationIssue`
+fn metadata_mismatch_issue(mismatch: &MetadataMismatch) -> ValidationIssue {
+    let description = match &mismatch.xmp_value {
+        Some(xmp_value) => format!(
+            "Info /{} is \"{}\" but XMP {} is \"{}\"",
+            mismatch.info_key, mismatch.info_value, mismatch.xmp_property, xmp_value
+        ),
+        None => format!(
+            "Info /{} is \"{}\" but XMP has no {}",
+            mismatch.info_key, mismatch.info_value, mismatch.xmp_property
+        ),
+    };
+
+    ValidationIssue {
+        severity: IssueSeverity::Warning,
+        issue_type: IssueType::InvalidMetadata,
+        description,
+        location: Some(format!("/Info /{}", mismatch.info_key)),
+        suggestion: Some("Edit the metadata with DocHub to keep the Info dictionary and XMP in sync".to_string()),
+    }
+}
+
+/// Converte uma falha de acessibilidade em um `ValidationIssue`
+fn pdfua_issue(failure: &PdfuaFailure, page_number: Option<u32>) -> ValidationIssue {
+    let object = failure.object_id.map(|id| object_location(id, ""));
+    let location = match (page_number, object) {
+        (Some(page), Some(object)) => Some(format!("page {}, {}", page, object)),
+        (Some(page), None) => Some(format!("page {}", page)),
+        (None, object) => object,
+    };
+
+    ValidationIssue {
+        severity: IssueSeverity::Warning,
+        issue_type: IssueType::CompatibilityIssue,
+        description: format!("PDF/UA ({}): {}", failure.clause, failure.description),
+        location,
+        suggestion: Some("Export the document as a tagged, accessible PDF".to_string()),
+    }
+}
+
+/// Descreve uma assinatura digital em um `ValidationIssue`
+///
+/// Bytes assinados que não conferem são um erro; assinaturas que não puderam
+/// ser verificadas e alterações depois da assinatura, avisos; uma assinatura
+/// íntegra que cobre o arquivo, informação.
+fn signature_issue(signature: &SignatureInfo) -> ValidationIssue {
+    let signer = signature.signer_name.as_deref().unwrap_or("unknown signer");
+    let location = signature
+        .field_name
+        .as_ref()
+        .map(|name| format!("field {}", name))
+        .or_else(|| signature.object_number.map(|number| format!("object {}", number)));
+
+    let problems = if signature.problems.is_empty() {
+        String::new()
+    } else {
+        format!(": {}", signature.problems.join("; "))
+    };
+
+    let (severity, description, suggestion) = match signature.status() {
+        SignatureStatus::Altered => (
+            IssueSeverity::Error,
+            format!("Signature by {} does not match the signed bytes{}", signer, problems),
+            Some("Treat the document as altered and request a newly signed copy".to_string()),
+        ),
+        SignatureStatus::NotVerifiable => (
+            IssueSeverity::Warning,
+            format!("Signature by {} could not be verified{}", signer, problems),
+            Some("Verify the signature with a tool that supports its format".to_string()),
+        ),
+        SignatureStatus::Intact if !signature.covers_whole_file => (
+            IssueSeverity::Warning,
+            format!(
+                "Signature by {} is intact, but {} bytes were added to the file after signing",
+                signer, signature.bytes_after_signature
+            ),
+            Some("Extract the signed revision to see the document as it was signed".to_string()),
+        ),
+        SignatureStatus::Intact => (
+            IssueSeverity::Info,
+            format!("Signature by {} is intact and covers the whole file", signer),
+            None,
+        ),
+    };
+
+    ValidationIssue {
+        severity,
+        issue_type: IssueType::SignatureIntegrity,
+        description,
+        location,
+        suggestion,
+    }
+}
+
+/// Converte um problema da análise xref em um `ValidationIssue`
+fn xref_issue(problem: &XrefProblem) -> ValidationIssue {
+    let severity = match problem {
+        XrefProblem::MissingStartxref
+        | XrefProblem::InvalidSection { .. }
+        | XrefProblem::PrevLoop { .. } => IssueSeverity::Error,
+        _ => IssueSeverity::Warning,
+    };
+
+    ValidationIssue {
+        severity,
+        issue_type: IssueType::InvalidReference,
+        description: problem.to_string(),
+        location: Some(problem.location()),
+        suggestion: Some("Rebuild the cross-reference table (e.g. re-save the PDF)".to_string()),
+    }
+}
+
 // ==================== FUNÇÕES DE CONVENIÊNCIA ====================
 
 /// Função de conveniência para validação de PDFs (mantém compatibilidade)
-#[instrument(name = "validate_pdf", skip(data))]
-pub async fn validate_pdf(data: Value) -> Result<Value> {
+#[instrument(name = "validate_pdf", skip(data, runtime))]
+pub async fn validate_pdf(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
     let request = ValidateRequest::from_value(&data)?;
-    let validator = PdfValidator::new();
+    let validator = PdfValidator::with_runtime(runtime);
     let result = validator.validate_pdf(request).await?;
     
     // Para compatibilidade com código antigo que espera um booleano
@@ -922,21 +1939,57 @@ pub async fn validate_pdf(data: Value) -> Result<Value> {
 }
 
 /// Função de conveniência para obtenção de metadados
-#[instrument(name = "get_pdf_metadata", skip(data))]
-pub async fn get_pdf_metadata(data: Value) -> Result<Value> {
+#[instrument(name = "get_pdf_metadata", skip(data, runtime))]
+pub async fn get_pdf_metadata(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
     let request = MetadataRequest::from_value(&data)?;
-    let validator = PdfValidator::new();
+    let validator = PdfValidator::with_runtime(runtime);
     let result = validator.get_pdf_metadata(request).await?;
     
     Ok(serde_json::to_value(result)
         .map_err(|e| AppError::serialization(format!("Failed to serialize result: {}", e)))?)
 }
 
+/// Função de conveniência para listagem de anotações
+#[instrument(name = "list_annotations", skip(data, runtime))]
+pub async fn list_annotations(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
+    let request = AnnotationsRequest::from_value(&data)?;
+    let validator = PdfValidator::with_runtime(runtime);
+    let result = validator.list_annotations(request).await?;
+
+    serde_json::to_value(result)
+        .map_err(|e| AppError::serialization(format!("Failed to serialize result: {}", e)))
+}
+
+/// Função de conveniência para análise do histórico de revisões
+#[instrument(name = "analyze_revisions", skip(data, runtime))]
+pub async fn analyze_revisions(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
+    let request = RevisionsRequest::from_value(&data)?;
+    let validator = PdfValidator::with_runtime(runtime);
+    let result = validator.analyze_revisions(request).await?;
+
+    serde_json::to_value(result)
+        .map_err(|e| AppError::serialization(format!("Failed to serialize result: {}", e)))
+}
+
+/// Função de conveniência para verificação de assinaturas digitais
+#[instrument(name = "verify_signatures", skip(data, runtime))]
+pub async fn verify_signatures(data: Value, runtime: ProcessorRuntime) -> Result<Value> {
+    let request = SignaturesRequest::from_value(&data)?;
+    let validator = PdfValidator::with_runtime(runtime);
+    let result = validator.verify_signatures(request).await?;
+
+    serde_json::to_value(result)
+        .map_err(|e| AppError::serialization(format!("Failed to serialize result: {}", e)))
+}
+
 // ==================== TESTES ====================
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::processors::test_support;
+    use crate::utils::error_handling::IoError;
+    use lopdf::dictionary;
     use serde_json::json;
     use tempfile::TempDir;
 
@@ -1023,6 +2076,7 @@ mod tests {
             is_web_optimized: false,
             pdfa_compliant: None,
             pdfua_compliant: None,
+            accessibility: None,
         };
         
         assert!(!details.loaded_successfully);
@@ -1032,13 +2086,654 @@ mod tests {
     #[test]
     fn test_pdf_validator_creation() {
         let validator = PdfValidator::new();
-        assert!(validator.file_handler.config.max_file_size > 0);
+        assert!(validator.runtime.file_handler().config().max_file_size > 0);
         
         let file_handler = FileHandler::new();
         let validator = PdfValidator::with_file_handler(file_handler);
-        assert!(validator.file_handler.config.max_file_size > 0);
+        assert!(validator.runtime.file_handler().config().max_file_size > 0);
+    }
+
+    fn full_request(file_path: PathBuf) -> ValidateRequest {
+        ValidateRequest {
+            file_path,
+            config: ValidationConfig::default(),
+            extract_metadata: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_full_accepts_valid_xref() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = test_support::write_sample_pdf(temp_dir.path(), "valid.pdf", 3);
+
+        let result = PdfValidator::new().validate_pdf(full_request(path)).await?;
+        assert!(result.details.xref_valid);
+        assert!(result.issues.iter().all(|i| i.issue_type != IssueType::InvalidReference));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_full_reports_broken_xref_entry() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = test_support::write_sample_pdf(temp_dir.path(), "broken.pdf", 3);
+
+        // Desloca o offset da entrada do objeto 2 na tabela xref
+        let mut bytes = std::fs::read(&path)?;
+        let table = bytes.windows(5).rposition(|w| w == b"\nxref").unwrap();
+        let entry = table + bytes[table..].windows(10).position(|w| w == b"0000000000").unwrap() + 2 * 20;
+        let offset: u64 = std::str::from_utf8(&bytes[entry..entry + 10]).unwrap().parse().unwrap();
+        bytes[entry..entry + 10].copy_from_slice(format!("{:010}", offset + 1).as_bytes());
+        std::fs::write(&path, &bytes)?;
+
+        let result = PdfValidator::new().validate_pdf(full_request(path)).await?;
+        assert!(!result.details.xref_valid);
+
+        let issue = result.issues.iter()
+            .find(|i| i.issue_type == IssueType::InvalidReference)
+            .expect("xref issue");
+        assert_eq!(issue.location, Some(format!("object 2, offset {}", offset + 1)));
+        Ok(())
+    }
+
+    fn deep_request(file_path: PathBuf) -> ValidateRequest {
+        let mut request = full_request(file_path);
+        request.config.level = ValidationLevel::Deep;
+        request
+    }
+
+    #[tokio::test]
+    async fn test_validate_deep_accepts_sample() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = test_support::write_sample_pdf(temp_dir.path(), "valid.pdf", 3);
+
+        let result = PdfValidator::new().validate_pdf(deep_request(path)).await?;
+        assert!(result.details.no_corrupted_objects);
+        assert!(result.details.all_references_valid);
+        assert!(result.issues.iter().all(|i| i.issue_type != IssueType::CorruptedObject));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_deep_reports_nested_dangling_reference() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("dangling.pdf");
+
+        // Objeto 3 é o dicionário de recursos; a fonte F2 aponta para um objeto inexistente
+        let mut doc = test_support::build_sample_pdf(1);
+        let resources = doc.get_object_mut((3, 0)).unwrap().as_dict_mut().unwrap();
+        resources.get_mut(b"Font").unwrap().as_dict_mut().unwrap().set("F2", Object::Reference((999, 0)));
+        doc.save(&path)?;
+
+        let result = PdfValidator::new().validate_pdf(deep_request(path)).await?;
+        assert!(!result.details.all_references_valid);
+
+        let issue = result.issues.iter()
+            .find(|i| i.issue_type == IssueType::InvalidReference)
+            .expect("dangling reference issue");
+        assert_eq!(issue.severity, IssueSeverity::Warning);
+        assert_eq!(issue.location.as_deref(), Some("object 3, /Font/F2"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_deep_reports_corrupt_stream() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("corrupt.pdf");
+
+        let mut doc = test_support::build_sample_pdf(1);
+        let broken_id = doc.add_object(lopdf::Stream::new(
+            dictionary! { "Filter" => "FlateDecode" },
+            b"this is not zlib data".to_vec(),
+        ));
+        doc.save(&path)?;
+
+        let result = PdfValidator::new().validate_pdf(deep_request(path)).await?;
+        assert!(!result.details.no_corrupted_objects);
+
+        let issue = result.issues.iter()
+            .find(|i| i.location == Some(format!("object {}", broken_id.0)))
+            .expect("corrupt stream issue");
+        assert_eq!(issue.issue_type, IssueType::CorruptedObject);
+        assert_eq!(issue.severity, IssueSeverity::Error);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_deep_reports_stream_length_mismatch() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("length.pdf");
+
+        let mut doc = test_support::build_sample_pdf(1);
+        let stream_id = doc.add_object(lopdf::Stream::new(dictionary! {}, b"hello world".to_vec()));
+        doc.save(&path)?;
+
+        // Mesmo número de dígitos, para não deslocar os offsets da tabela xref
+        let mut bytes = std::fs::read(&path)?;
+        let length = bytes.windows(10).position(|w| w == b"/Length 11").unwrap();
+        bytes[length..length + 10].copy_from_slice(b"/Length 19");
+        std::fs::write(&path, &bytes)?;
+
+        let result = PdfValidator::new().validate_pdf(deep_request(path)).await?;
+        assert!(!result.details.no_corrupted_objects);
+
+        let issue = result.issues.iter()
+            .find(|i| i.description.contains("/Length"))
+            .expect("length mismatch issue");
+        assert_eq!(issue.severity, IssueSeverity::Warning);
+        assert!(issue.description.contains(&format!("object {}", stream_id.0)));
+        assert!(issue.description.contains("is 19 but 11 bytes"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_deep_reports_pdfa_clauses() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = test_support::write_sample_pdf(temp_dir.path(), "plain.pdf", 1);
+
+        let mut request = deep_request(path);
+        request.config.pdfa_level = Some(PdfaLevel::Pdfa1b);
+        let result = PdfValidator::new().validate_pdf(request).await?;
+        assert_eq!(result.details.pdfa_compliant, Some(false));
+        // Não ser PDF/A não torna o documento inválido
+        assert!(result.is_valid);
+
+        let font = result.issues.iter()
+            .find(|i| i.issue_type == IssueType::CompatibilityIssue && i.description.contains("6.3.4"))
+            .expect("font embedding issue");
+        assert_eq!(font.location.as_deref(), Some("object 2"));
+        assert!(font.description.starts_with("PDF/A-1b"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_deep_lists_accessibility_failures_per_page() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("annotated.pdf");
+
+        let mut doc = test_support::build_sample_pdf(2);
+        let second_page = doc.get_pages()[&2];
+        let link = doc.add_object(dictionary! { "Type" => "Annot", "Subtype" => "Link" });
+        doc.get_dictionary_mut(second_page).unwrap().set("Annots", vec![link.into()]);
+        doc.save(&path)?;
+
+        let result = PdfValidator::new().validate_pdf(deep_request(path)).await?;
+        assert_eq!(result.details.pdfua_compliant, Some(false));
+
+        let accessibility = result.details.accessibility.as_ref().expect("accessibility report");
+        assert_eq!(accessibility.pages.len(), 1);
+        assert_eq!(accessibility.pages[0].page_number, 2);
+
+        let issue = result.issues.iter()
+            .find(|i| i.description.contains("annotation is not tagged"))
+            .expect("annotation issue");
+        assert_eq!(issue.location, Some(format!("page 2, object {}", link.0)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_reports_active_content() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("script.pdf");
+
+        let mut doc = test_support::build_sample_pdf(1);
+        let action = doc.add_object(dictionary! {
+            "S" => "JavaScript",
+            "JS" => Object::string_literal("app.launchURL('https://example.com')"),
+        });
+        doc.catalog_mut().unwrap().set("OpenAction", action);
+        doc.save(&path)?;
+
+        let mut request = full_request(path);
+        request.extract_metadata = true;
+        let result = PdfValidator::new().validate_pdf(request).await?;
+
+        assert!(result.metadata.as_ref().unwrap().has_javascript);
+        assert!(result.is_valid);
+
+        let script = result.issues.iter()
+            .find(|i| i.issue_type == IssueType::SecurityRisk && i.severity == IssueSeverity::Error)
+            .expect("javascript issue");
+        assert_eq!(script.location, Some(format!("object {}", action.0)));
+
+        let security = result.security.as_ref().unwrap();
+        assert_eq!(security.highest_risk(), Some(RiskLevel::High));
+        assert!(result.recommendations.iter().any(|r| r.contains("Disable JavaScript")));
+        assert!(!result.recommendations.iter().any(|r| r.contains("repairing")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_metadata_lists_fonts() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = test_support::write_sample_pdf(temp_dir.path(), "fonts.pdf", 2);
+
+        let mut request = full_request(path);
+        request.extract_metadata = true;
+        let result = PdfValidator::new().validate_pdf(request).await?;
+
+        let metadata = result.metadata.as_ref().unwrap();
+        assert!(!metadata.has_embedded_fonts);
+        assert_eq!(metadata.embedded_fonts.len(), 1);
+        assert_eq!(metadata.embedded_fonts[0].name, "Helvetica");
+        assert_eq!(metadata.embedded_fonts[0].pages, vec![1, 2]);
+        assert!(!metadata.has_images && metadata.images.is_empty());
+        assert_eq!(metadata.pages.len(), 2);
+        assert_eq!(metadata.first_page_dimensions, Some((595.0, 842.0)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_annotations_filters_by_type_and_page() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("annotated.pdf");
+
+        let mut doc = test_support::build_sample_pdf(2);
+        let pages = doc.get_pages();
+        for (page_number, page_id) in pages {
+            // Conteúdo em UTF-16BE e autor em PDFDocEncoding, como gravam os leitores
+            let note = doc.add_object(dictionary! {
+                "Subtype" => "Text",
+                "Rect" => vec![40.into(), 700.into(), 20.into(), 680.into()],
+                "Contents" => Object::String(
+                    pdf_encoding::encode_text_string(&format!("Nota {} – revisão", page_number)),
+                    lopdf::StringFormat::Hexadecimal,
+                ),
+                "T" => Object::String(b"Jo\xE3o".to_vec(), lopdf::StringFormat::Literal),
+            });
+            let highlight = doc.add_object(dictionary! {
+                "Subtype" => "Highlight",
+                "Rect" => vec![72.into(), 500.into(), 300.into(), 512.into()],
+            });
+            doc.get_dictionary_mut(page_id).unwrap().set("Annots", vec![note.into(), highlight.into()]);
+        }
+        doc.save(&path)?;
+
+        let data = json!({
+            "file": path.to_str().unwrap(),
+            "types": ["Text"],
+            "pages": [2],
+        });
+        let request = AnnotationsRequest::from_value(&data)?;
+        let result = PdfValidator::new().list_annotations(request).await?;
+
+        assert_eq!(result.total_annotations, 4);
+        assert_eq!(result.annotations.len(), 1);
+        let note = &result.annotations[0];
+        assert_eq!(note.page, 2);
+        assert_eq!(note.annotation_type, AnnotationType::Text);
+        assert_eq!(note.contents.as_deref(), Some("Nota 2 – revisão"));
+        assert_eq!(note.author.as_deref(), Some("João"));
+        assert_eq!(note.rectangle, (20.0, 680.0, 40.0, 700.0));
+
+        // Sem filtros, todas as anotações são listadas na ordem das páginas
+        let request = AnnotationsRequest::from_value(&json!({ "file": path.to_str().unwrap() }))?;
+        let all: Vec<(u32, AnnotationType, Option<String>, (f64, f64, f64, f64))> = PdfValidator::new()
+            .list_annotations(request)
+            .await?
+            .annotations
+            .into_iter()
+            .map(|a| (a.page, a.annotation_type, a.contents, a.rectangle))
+            .collect();
+        assert_eq!(all, vec![
+            (1, AnnotationType::Text, Some("Nota 1 – revisão".to_string()), (20.0, 680.0, 40.0, 700.0)),
+            (1, AnnotationType::Highlight, None, (72.0, 500.0, 300.0, 512.0)),
+            (2, AnnotationType::Text, Some("Nota 2 – revisão".to_string()), (20.0, 680.0, 40.0, 700.0)),
+            (2, AnnotationType::Highlight, None, (72.0, 500.0, 300.0, 512.0)),
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_annotations_request_rejects_unknown_type() {
+        let data = json!({ "file": "test.pdf", "types": ["Sticky"] });
+        assert!(AnnotationsRequest::from_value(&data).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_detailed_metadata_includes_bookmarks() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("outlined.pdf");
+
+        let mut doc = test_support::build_sample_pdf(2);
+        let second_page = doc.get_pages()[&2];
+        let outlines = doc.new_object_id();
+        let item = doc.add_object(dictionary! {
+            "Title" => Object::string_literal("Chapter 2"),
+            "Parent" => outlines,
+            "Dest" => vec![second_page.into(), "Fit".into()],
+        });
+        doc.objects.insert(outlines, Object::Dictionary(dictionary! { "First" => item, "Last" => item }));
+        doc.catalog_mut().unwrap().set("Outlines", outlines);
+        doc.save(&path)?;
+
+        let validator = PdfValidator::new();
+        let metadata = validator.get_pdf_metadata(MetadataRequest {
+            file_path: path.clone(),
+            include_detailed_analysis: false,
+        }).await?;
+        assert!(metadata.bookmarks.is_none());
+        assert!(metadata.pages.iter().all(|page| page.content.is_none()));
+
+        let metadata = validator.get_pdf_metadata(MetadataRequest {
+            file_path: path,
+            include_detailed_analysis: true,
+        }).await?;
+        let bookmarks = metadata.bookmarks.unwrap();
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].title, "Chapter 2");
+        assert_eq!(bookmarks[0].page, 2);
+        assert_eq!(metadata.pages[1].content.as_deref(), Some("Page 2"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_metadata_reports_encryption_permissions() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("restricted.pdf");
+
+        // RC4 128 bits, senha de usuário vazia, montagem de páginas proibida (bit 11)
+        let hex = |value: &str| Object::String(
+            (0..value.len()).step_by(2).map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap()).collect(),
+            lopdf::StringFormat::Hexadecimal,
+        );
+        let mut doc = test_support::build_sample_pdf(1);
+        let encrypt = doc.add_object(dictionary! {
+            "Filter" => "Standard",
+            "V" => 2,
+            "R" => 3,
+            "Length" => 128,
+            "P" => -1028,
+            "O" => hex("566fa873ee33c797cd3b904fdadf814afa34df9a38f6ed41b984e2c6da2aa6f5"),
+            "U" => hex("6e1ba987de98ebd10303a12278e47cf600000000000000000000000000000000"),
+        });
+        doc.trailer.set("Encrypt", encrypt);
+        let id = hex("0123456789abcdef0123456789abcdef");
+        doc.trailer.set("ID", vec![id.clone(), id]);
+        doc.save(&path)?;
+
+        let mut request = full_request(path);
+        request.extract_metadata = true;
+        let result = PdfValidator::new().validate_pdf(request).await?;
+
+        let metadata = result.metadata.as_ref().unwrap();
+        assert!(metadata.is_encrypted);
+        assert!(metadata.encryption.as_ref().unwrap().empty_user_password);
+        assert!(metadata.permissions.allow_printing);
+        assert!(!metadata.permissions.allow_document_assembly);
+
+        let issue = result.issues.iter()
+            .find(|i| i.issue_type == IssueType::EncryptedDocument)
+            .expect("encryption issue");
+        assert!(issue.description.contains("opens without a password"));
+        assert!(issue.description.contains("restricted: page assembly"));
+        assert!(issue.suggestion.as_ref().unwrap().contains("split and merge"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unencrypted_metadata_allows_everything() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = test_support::write_sample_pdf(temp_dir.path(), "open.pdf", 1);
+
+        let metadata = PdfValidator::new().get_pdf_metadata(MetadataRequest {
+            file_path: path,
+            include_detailed_analysis: false,
+        }).await?;
+        assert!(!metadata.is_encrypted && metadata.encryption.is_none());
+        assert_eq!(metadata.permissions, PdfPermissions::unrestricted());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_web_optimized_requires_matching_linearization_length() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let plain = test_support::write_sample_pdf(temp_dir.path(), "plain.pdf", 2);
+        let result = PdfValidator::new().validate_pdf(full_request(plain)).await?;
+        assert!(!result.details.is_web_optimized);
+
+        let mut bytes = crate::processors::pdf_linearize::linearize(&test_support::build_sample_pdf(2)).unwrap();
+        let linearized = temp_dir.path().join("linearized.pdf");
+        std::fs::write(&linearized, &bytes)?;
+        let result = PdfValidator::new().validate_pdf(full_request(linearized.clone())).await?;
+        assert!(result.details.is_web_optimized);
+
+        // Bytes acrescentados depois da linearização deixam /L desatualizado
+        bytes.extend_from_slice(b"\n% appended\n");
+        std::fs::write(&linearized, &bytes)?;
+        let result = PdfValidator::new().validate_pdf(full_request(linearized)).await?;
+        assert!(!result.details.is_web_optimized);
+        let issue = result.issues.iter()
+            .find(|i| i.description.contains("Linearization dictionary"))
+            .expect("stale linearization issue");
+        assert_eq!(issue.severity, IssueSeverity::Info);
+        Ok(())
+    }
+
+    /// PDF de exemplo com uma atualização incremental que troca o título e remove a fonte
+    fn write_updated_pdf(dir: &Path) -> (PathBuf, u32, u32) {
+        let mut original = Vec::new();
+        test_support::build_sample_pdf(2).save_to(&mut original).unwrap();
+        let doc = Document::load_mem(&original).unwrap();
+        let info = doc.trailer.get(b"Info").unwrap().as_reference().unwrap().0;
+        let font = doc.objects.iter()
+            .find(|(_, object)| object.as_dict().is_ok_and(|d| d.has(b"BaseFont")))
+            .map(|(id, _)| id.0)
+            .unwrap();
+
+        let path = dir.join("edited.pdf");
+        std::fs::write(&path, test_support::append_incremental_update(&original, info, font, doc.max_id + 1)).unwrap();
+        (path, info, font)
+    }
+
+    #[tokio::test]
+    async fn test_analyze_revisions_extracts_and_collapses() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let (path, info, font) = write_updated_pdf(temp_dir.path());
+        let extract_dir = temp_dir.path().join("revisions");
+        let collapsed = temp_dir.path().join("collapsed.pdf");
+
+        let request = RevisionsRequest::from_value(&json!({
+            "file": path,
+            "extract_dir": extract_dir,
+            "collapse_output": collapsed,
+        }))?;
+        let result = PdfValidator::new().analyze_revisions(request).await?;
+
+        assert_eq!(result.revision_count, 2);
+        assert_eq!(result.superseded_objects, vec![info.min(font), info.max(font)]);
+        assert_eq!(result.extracted_files, vec![
+            extract_dir.join("edited_rev1.pdf"),
+            extract_dir.join("edited_rev2.pdf"),
+        ]);
+
+        // A primeira revisão ainda tem o título "apagado"
+        let original = Document::load(&result.extracted_files[0]).unwrap();
+        let title = original.get_dictionary((info, 0)).unwrap().get(b"Title").unwrap();
+        assert_eq!(title.as_str().unwrap(), b"Sample");
+
+        // O documento colapsado tem uma única revisão e não guarda o título antigo
+        let collapsed_bytes = std::fs::read(result.collapsed_file.as_ref().unwrap())?;
+        assert!(!pdf_revisions::history(&collapsed_bytes).has_incremental_updates());
+        assert!(!collapsed_bytes.windows(8).any(|w| w == b"(Sample)"));
+        let doc = Document::load_mem(&collapsed_bytes).unwrap();
+        assert_eq!(doc.get_pages().len(), 2);
+        Ok(())
     }
 
-    // Testes com PDFs reais seriam adicionados aqui
-    // usando arquivos de teste específicos
-}
\ No newline at end of file
+    #[tokio::test]
+    async fn test_analyze_revisions_keeps_existing_files() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let (path, _, _) = write_updated_pdf(temp_dir.path());
+        let collapsed = temp_dir.path().join("collapsed.pdf");
+        std::fs::write(&collapsed, "keep")?;
+
+        let request = RevisionsRequest::from_value(&json!({
+            "file": path,
+            "collapse_output": collapsed,
+        }))?;
+        let result = PdfValidator::new().analyze_revisions(request).await;
+        assert!(matches!(result, Err(AppError::Io(IoError::WriteFailed { .. }))));
+        assert_eq!(std::fs::read(&collapsed)?, b"keep");
+
+        // Coincidir com a origem também não a substitui
+        let original = std::fs::read(&path)?;
+        let request = RevisionsRequest::from_value(&json!({
+            "file": path,
+            "collapse_output": path,
+        }))?;
+        assert!(PdfValidator::new().analyze_revisions(request).await.is_err());
+        assert_eq!(std::fs::read(&path)?, original);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_full_reports_prior_revision_content() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let (path, _, _) = write_updated_pdf(temp_dir.path());
+
+        let result = PdfValidator::new().validate_pdf(full_request(path)).await?;
+        let issue = result.issues.iter()
+            .find(|i| i.issue_type == IssueType::PriorRevisionContent)
+            .expect("revision issue");
+        assert_eq!(issue.severity, IssueSeverity::Warning);
+        assert!(issue.description.contains("2 revisions"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_metadata_decodes_text_strings() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("accents.pdf");
+
+        let mut doc = test_support::build_sample_pdf(1);
+        let info_id = doc.trailer.get(b"Info").unwrap().as_reference().unwrap();
+        let info = doc.get_dictionary_mut(info_id).unwrap();
+        // UTF-16BE em string hexadecimal e PDFDocEncoding com parênteses e barra escapados
+        info.set("Title", Object::String(pdf_encoding::encode_text_string("Petição inicial – 日本"), lopdf::StringFormat::Hexadecimal));
+        info.set("Author", Object::String(b"Jo\xE3o (Procuradoria) \\ S\xE3o Paulo".to_vec(), lopdf::StringFormat::Literal));
+        info.set("Keywords", Object::String(b"a\xE7\xE3o, recurso".to_vec(), lopdf::StringFormat::Literal));
+        doc.save(&path)?;
+
+        let mut request = full_request(path);
+        request.extract_metadata = true;
+        let result = PdfValidator::new().validate_pdf(request).await?;
+        let metadata = result.metadata.as_ref().unwrap();
+
+        assert_eq!(metadata.title.as_deref(), Some("Petição inicial – 日本"));
+        assert_eq!(metadata.author.as_deref(), Some("João (Procuradoria) \\ São Paulo"));
+        assert_eq!(metadata.keywords, Some(vec!["ação".to_string(), "recurso".to_string()]));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_metadata_parses_info_dates() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("dates.pdf");
+
+        let mut doc = test_support::build_sample_pdf(1);
+        let info_id = doc.trailer.get(b"Info").unwrap().as_reference().unwrap();
+        let info = doc.get_dictionary_mut(info_id).unwrap();
+        info.set("CreationDate", Object::string_literal("D:20240101120000-03'00"));
+        info.set("ModDate", Object::string_literal("2024-02-01T08:00:00Z"));
+        doc.save(&path)?;
+
+        let mut request = full_request(path);
+        request.extract_metadata = true;
+        let result = PdfValidator::new().validate_pdf(request).await?;
+        let metadata = result.metadata.as_ref().unwrap();
+
+        assert_eq!(metadata.creation_date_raw.as_deref(), Some("D:20240101120000-03'00"));
+        assert!(metadata.modification_date > metadata.creation_date);
+        let json = serde_json::to_value(metadata).unwrap();
+        assert_eq!(json["creation_date"], "2024-01-01T12:00:00-03:00");
+        assert_eq!(json["modification_date"], "2024-02-01T08:00:00Z");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_metadata_exposes_xmp_and_mismatches() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("xmp.pdf");
+
+        let mut doc = test_support::build_sample_pdf(1);
+        let packet = "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"><rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\
+            <rdf:Description rdf:about=\"\" xmlns:pdf=\"http://ns.adobe.com/pdf/1.3/\" pdf:Producer=\"Other tool\"/>\
+            </rdf:RDF></x:xmpmeta>";
+        let metadata_id = doc.add_object(lopdf::Stream::new(lopdf::Dictionary::new(), packet.as_bytes().to_vec()));
+        doc.catalog_mut().unwrap().set("Metadata", metadata_id);
+        doc.save(&path)?;
+
+        let mut request = full_request(path);
+        request.extract_metadata = true;
+        let result = PdfValidator::new().validate_pdf(request).await?;
+
+        let metadata = result.metadata.as_ref().unwrap();
+        assert_eq!(metadata.xmp.as_ref().unwrap().producer.as_deref(), Some("Other tool"));
+        let keys: Vec<_> = metadata.metadata_mismatches.iter().map(|m| m.info_key.as_str()).collect();
+        assert_eq!(keys, vec!["Title", "Producer"]);
+
+        let issues: Vec<_> = result.issues.iter()
+            .filter(|i| i.issue_type == IssueType::InvalidMetadata)
+            .collect();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[1].location.as_deref(), Some("/Info /Producer"));
+        assert!(issues[1].description.contains("\"Other tool\""));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_signatures_reports_changes_after_signing() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let signed = test_support::sign_sample_pdf(&test_support::build_sample_pdf(2));
+        let path = temp_dir.path().join("signed.pdf");
+        std::fs::write(&path, &signed)?;
+
+        let request = SignaturesRequest::from_value(&json!({ "file": path }))?;
+        let result = PdfValidator::new().verify_signatures(request.clone()).await?;
+        assert_eq!(result.signature_count, 1);
+        assert!(result.all_intact);
+        assert!(!result.modified_after_signing);
+
+        // Uma edição incremental mantém a assinatura íntegra, mas fica registrada
+        let doc = Document::load_mem(&signed).unwrap();
+        let info = doc.trailer.get(b"Info").unwrap().as_reference().unwrap().0;
+        let font = doc.objects.iter()
+            .find(|(_, object)| object.as_dict().is_ok_and(|d| d.has(b"BaseFont")))
+            .map(|(id, _)| id.0)
+            .unwrap();
+        let updated = test_support::append_incremental_update(&signed, info, font, doc.max_id + 1);
+        std::fs::write(&path, &updated)?;
+
+        let result = PdfValidator::new().verify_signatures(request).await?;
+        assert!(result.all_intact);
+        assert!(result.modified_after_signing);
+
+        let result = PdfValidator::new().validate_pdf(full_request(path)).await?;
+        let issue = result.issues.iter()
+            .find(|i| i.issue_type == IssueType::SignatureIntegrity)
+            .expect("signature issue");
+        assert_eq!(issue.severity, IssueSeverity::Warning);
+        assert_eq!(issue.location.as_deref(), Some("field Signature1"));
+        assert!(issue.description.contains("DocHub Test Signer"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_signature_issue_separates_altered_from_unverifiable() {
+        let unverifiable = SignatureInfo {
+            field_name: Some("Signature1".to_string()),
+            byte_range_valid: true,
+            covers_whole_file: true,
+            problems: vec!["adbe.x509.rsa_sha1 signatures (raw PKCS#1) are not verified".to_string()],
+            ..SignatureInfo::default()
+        };
+        let issue = signature_issue(&unverifiable);
+        assert_eq!(issue.severity, IssueSeverity::Warning);
+        assert!(issue.description.contains("could not be verified"), "{}", issue.description);
+
+        let altered = SignatureInfo { digest_matches: Some(false), signature_valid: Some(true), ..unverifiable };
+        let issue = signature_issue(&altered);
+        assert_eq!(issue.severity, IssueSeverity::Error);
+        assert!(issue.description.contains("does not match the signed bytes"));
+    }
+}
@@ -0,0 +1,363 @@
+//! Serialização de objetos PDF e gravação de atualizações incrementais
+//!
+//! O `Writer` do `lopdf` não é público e sempre regrava o arquivo inteiro.
+//! Assinaturas e edições que precisam preservar o arquivo original (e as
+//! assinaturas que ele já tem) acrescentam só os objetos alterados, com uma
+//! nova seção xref cujo `/Prev` aponta para a seção anterior.
+//!
+//! ## Funcionalidades:
+//! - Serialização de objetos com tradução de referências (usada também por `pdf_linearize`)
+//! - Atualização incremental no formato da seção anterior (tabela clássica ou xref stream)
+
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream, StringFormat};
+use std::io::Write;
+
+use crate::processors::pdf_xref::{self, XrefSectionKind};
+
+/// Chaves do trailer copiadas para o trailer da atualização
+const TRAILER_KEYS: [&[u8]; 3] = [b"Root", b"Info", b"ID"];
+
+/// Acrescenta ao arquivo `source` uma revisão com os objetos `objects` de `doc`
+///
+/// `doc` deve ter sido carregado de `source`; os objetos listados são
+/// gravados como estão em `doc` (novos ou alterados) e os demais continuam
+/// valendo da revisão anterior. Os bytes de `source` não mudam.
+pub fn incremental_update(source: &[u8], doc: &Document, objects: &[ObjectId]) -> Result<Vec<u8>, String> {
+    if doc.trailer.has(b"Encrypt") {
+        return Err("Encrypted documents cannot be updated".to_string());
+    }
+    let report = pdf_xref::analyze(source);
+    let prev = report
+        .startxref
+        .ok_or_else(|| "File has no startxref; it cannot receive an incremental update".to_string())?;
+    // A primeira seção da análise é a apontada por startxref, ou seja, a última gravada
+    let last_section = report.sections.first();
+
+    let mut out = source.to_vec();
+    if out.last() != Some(&b'\n') {
+        out.push(b'\n');
+    }
+
+    let mut ids = objects.to_vec();
+    ids.sort();
+    ids.dedup();
+
+    let mut offsets = Vec::with_capacity(ids.len());
+    for id in &ids {
+        let object = doc.get_object(*id).map_err(|e| format!("Object {} {} R: {}", id.0, id.1, e))?;
+        offsets.push(out.len());
+        let _ = writeln!(out, "{} {} obj", id.0, id.1);
+        write_object(&mut out, object, &|reference| Some(reference));
+        out.extend_from_slice(b"\nendobj\n");
+    }
+
+    let mut trailer = Dictionary::new();
+    for key in TRAILER_KEYS {
+        if let Ok(value) = doc.trailer.get(key) {
+            trailer.set(key, value.clone());
+        }
+    }
+    trailer.set("Prev", prev as i64);
+
+    let xref = out.len();
+    match last_section {
+        Some(section) if section.kind == XrefSectionKind::Stream => {
+            write_xref_stream(&mut out, doc, &ids, &offsets, trailer);
+        }
+        _ => {
+            // Em arquivos híbridos os objetos comprimidos só são achados pela
+            // xref stream complementar, que precisa continuar referenciada
+            if let Some(xref_stream) = last_section.and_then(|section| section.xref_stream) {
+                trailer.set("XRefStm", xref_stream as i64);
+            }
+            write_xref_table(&mut out, doc, &ids, &offsets, trailer);
+        }
+    }
+    let _ = write!(out, "\nstartxref\n{}\n%%EOF\n", xref);
+
+    Ok(out)
+}
+
+/// Grava uma tabela xref clássica e seu trailer
+fn write_xref_table(out: &mut Vec<u8>, doc: &Document, ids: &[ObjectId], offsets: &[usize], mut trailer: Dictionary) {
+    // Uma subseção por objeto: os números raramente são contíguos
+    out.extend_from_slice(b"xref\n");
+    for (id, offset) in ids.iter().zip(offsets) {
+        let _ = write!(out, "{} 1\n{:010} {:05} n \n", id.0, offset, id.1);
+    }
+
+    trailer.set("Size", i64::from(doc.max_id) + 1);
+    out.extend_from_slice(b"trailer\n");
+    write_dictionary(out, &trailer, None, &|reference| Some(reference));
+}
+
+/// Grava uma xref stream, com as chaves do trailer no próprio dicionário
+///
+/// A stream recebe o número seguinte ao maior do documento e lista a si mesma.
+fn write_xref_stream(out: &mut Vec<u8>, doc: &Document, ids: &[ObjectId], offsets: &[usize], mut dict: Dictionary) {
+    let stream_id = (doc.max_id + 1, 0);
+    let stream_offset = out.len();
+    let entries: Vec<(ObjectId, usize)> = ids
+        .iter()
+        .copied()
+        .zip(offsets.iter().copied())
+        .chain(std::iter::once((stream_id, stream_offset)))
+        .collect();
+
+    // Campos: tipo (1 byte), offset (largura mínima) e geração (2 bytes)
+    let offset_width = (usize::BITS - stream_offset.leading_zeros()).div_ceil(8).max(1) as usize;
+    let mut data = Vec::with_capacity(entries.len() * (offset_width + 3));
+    let mut index = Vec::with_capacity(entries.len() * 2);
+    for ((number, generation), offset) in entries {
+        data.push(1);
+        data.extend_from_slice(&offset.to_be_bytes()[std::mem::size_of::<usize>() - offset_width..]);
+        data.extend_from_slice(&generation.to_be_bytes());
+        index.push(Object::Integer(i64::from(number)));
+        index.push(Object::Integer(1));
+    }
+
+    dict.set("Type", Object::Name(b"XRef".to_vec()));
+    dict.set("Size", i64::from(stream_id.0) + 1);
+    dict.set("Index", Object::Array(index));
+    dict.set("W", Object::Array(vec![1.into(), (offset_width as i64).into(), 2.into()]));
+    let stream = Object::Stream(Stream::new(dict, data));
+
+    let _ = writeln!(out, "{} {} obj", stream_id.0, stream_id.1);
+    write_object(out, &stream, &|reference| Some(reference));
+    out.extend_from_slice(b"\nendobj");
+}
+
+/// Serializa um objeto; `reference` traduz cada referência (None = null)
+pub(crate) fn write_object(out: &mut Vec<u8>, object: &Object, reference: &dyn Fn(ObjectId) -> Option<ObjectId>) {
+    match object {
+        Object::Null => out.extend_from_slice(b"null"),
+        Object::Boolean(value) => out.extend_from_slice(if *value { b"true" } else { b"false" }),
+        Object::Integer(value) => out.extend_from_slice(value.to_string().as_bytes()),
+        Object::Real(value) => out.extend_from_slice(value.to_string().as_bytes()),
+        Object::Name(name) => write_name(out, name),
+        Object::String(text, StringFormat::Literal) if is_printable(text) => write_literal_string(out, text),
+        // Strings UTF-16BE e binárias vão em hexadecimal, imunes a fins de linha
+        Object::String(text, _) => {
+            out.push(b'<');
+            for byte in text {
+                out.extend_from_slice(format!("{:02X}", byte).as_bytes());
+            }
+            out.push(b'>');
+        }
+        Object::Array(items) => {
+            out.push(b'[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(b' ');
+                }
+                write_object(out, item, reference);
+            }
+            out.push(b']');
+        }
+        Object::Dictionary(dict) => write_dictionary(out, dict, None, reference),
+        Object::Stream(stream) => {
+            write_dictionary(out, &stream.dict, Some(stream.content.len()), reference);
+            out.extend_from_slice(b"\nstream\n");
+            out.extend_from_slice(&stream.content);
+            out.extend_from_slice(b"\nendstream");
+        }
+        Object::Reference(id) => match reference(*id) {
+            Some((number, generation)) => {
+                let _ = write!(out, "{} {} R", number, generation);
+            }
+            // Referência a objeto inexistente equivale a null
+            None => out.extend_from_slice(b"null"),
+        },
+    }
+}
+
+/// Serializa um dicionário; em streams, `/Length` é regravado com o tamanho real
+pub(crate) fn write_dictionary(
+    out: &mut Vec<u8>,
+    dict: &Dictionary,
+    stream_length: Option<usize>,
+    reference: &dyn Fn(ObjectId) -> Option<ObjectId>,
+) {
+    out.extend_from_slice(b"<<");
+    for (key, value) in dict.iter() {
+        if stream_length.is_some() && key == b"Length" {
+            continue;
+        }
+        out.push(b' ');
+        write_name(out, key);
+        out.push(b' ');
+        write_object(out, value, reference);
+    }
+    if let Some(length) = stream_length {
+        let _ = write!(out, " /Length {}", length);
+    }
+    out.extend_from_slice(b" >>");
+}
+
+/// String de texto de um byte por caractere: sem BOM UTF-16 nem bytes de controle
+/// além de tabulação e fins de linha
+fn is_printable(text: &[u8]) -> bool {
+    !text.starts_with(&[0xFE, 0xFF])
+        && text.iter().all(|&byte| !byte.is_ascii_control() || matches!(byte, b'\t' | b'\n' | b'\r'))
+}
+
+/// Grava uma string literal; CR e LF viram `\r` e `\n`, pois uma barra seguida de
+/// fim de linha real é continuação de linha e o leitor descartaria o byte
+fn write_literal_string(out: &mut Vec<u8>, text: &[u8]) {
+    out.push(b'(');
+    for &byte in text {
+        match byte {
+            b'(' | b')' | b'\\' => out.extend_from_slice(&[b'\\', byte]),
+            b'\r' => out.extend_from_slice(b"\\r"),
+            b'\n' => out.extend_from_slice(b"\\n"),
+            _ => out.push(byte),
+        }
+    }
+    out.push(b')');
+}
+
+pub(crate) fn write_name(out: &mut Vec<u8>, name: &[u8]) {
+    out.push(b'/');
+    for &byte in name {
+        // Espaços, delimitadores e bytes fora do ASCII visível viram #XX
+        if b" \t\n\r\x0C()<>[]{}/%#".contains(&byte) || !(33..=126).contains(&byte) {
+            out.extend_from_slice(format!("#{:02X}", byte).as_bytes());
+        } else {
+            out.push(byte);
+        }
+    }
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::pdf_encoding;
+    use crate::processors::pdf_revisions;
+    use crate::processors::test_support;
+    use lopdf::xref::XrefType;
+
+    fn saved(mut doc: Document) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_incremental_update_appends_changed_objects() {
+        let source = saved(test_support::build_sample_pdf(2));
+        let mut doc = Document::load_mem(&source).unwrap();
+        let info = doc.trailer.get(b"Info").unwrap().as_reference().unwrap();
+        doc.get_dictionary_mut(info).unwrap().set("Title", Object::string_literal("Edited (v2)"));
+        let note = doc.add_object(Object::string_literal("new"));
+
+        let updated = incremental_update(&source, &doc, &[info, note]).unwrap();
+        assert_eq!(&updated[..source.len()], &source[..]);
+
+        let history = pdf_revisions::history(&updated);
+        assert_eq!(history.revisions.len(), 2);
+        assert_eq!(history.revisions[1].modified_objects, vec![info.0]);
+        assert_eq!(history.revisions[1].added_objects, vec![note.0]);
+
+        let reloaded = Document::load_mem(&updated).unwrap();
+        let title = reloaded.get_dictionary(info).unwrap().get(b"Title").unwrap().as_str().unwrap().to_vec();
+        assert_eq!(title, b"Edited (v2)");
+        assert_eq!(reloaded.get_pages().len(), 2);
+    }
+
+    #[test]
+    fn test_incremental_update_over_xref_stream() {
+        let mut original = test_support::build_sample_pdf(1);
+        original.reference_table.cross_reference_type = XrefType::CrossReferenceStream;
+        let source = saved(original);
+        let mut doc = Document::load_mem(&source).unwrap();
+        let info = doc.trailer.get(b"Info").unwrap().as_reference().unwrap();
+        doc.get_dictionary_mut(info).unwrap().set("Author", Object::string_literal("DocHub"));
+
+        let updated = incremental_update(&source, &doc, &[info]).unwrap();
+
+        // A nova seção segue o formato da anterior e encadeia-se a ela
+        let report = pdf_xref::analyze(&updated);
+        assert!(report.is_valid(), "{:?}", report.problems);
+        assert_eq!(report.sections.len(), 2);
+        assert_eq!(report.sections[0].kind, XrefSectionKind::Stream);
+        assert_eq!(report.sections[0].prev, Some(report.sections[1].offset));
+        assert_eq!(report.sections[0].size, Some(doc.max_id + 2));
+
+        let reloaded = Document::load_mem(&updated).unwrap();
+        let author = reloaded.get_dictionary(info).unwrap().get(b"Author").unwrap().as_str().unwrap().to_vec();
+        assert_eq!(author, b"DocHub");
+        assert_eq!(reloaded.get_pages().len(), 1);
+    }
+
+    #[test]
+    fn test_incremental_update_keeps_hybrid_xref_stream() {
+        let mut original = test_support::build_sample_pdf(1);
+        original.reference_table.cross_reference_type = XrefType::CrossReferenceStream;
+        let mut source = saved(original);
+        let doc = Document::load_mem(&source).unwrap();
+        let xref_stream = pdf_xref::analyze(&source).startxref.unwrap();
+
+        // Arquivo híbrido: tabela clássica vazia e todos os objetos na xref stream de /XRefStm
+        let table = source.len();
+        let root = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        let _ = write!(
+            source,
+            "xref\n0 1\n0000000000 65535 f \ntrailer\n<< /Size {} /Root {} 0 R /XRefStm {} >>\nstartxref\n{}\n%%EOF\n",
+            doc.max_id + 1, root.0, xref_stream, table
+        );
+        assert_eq!(pdf_xref::analyze(&source).sections[0].xref_stream, Some(xref_stream));
+
+        let updated = incremental_update(&source, &doc, &[root]).unwrap();
+        let report = pdf_xref::analyze(&updated);
+        assert_eq!(report.sections[0].kind, XrefSectionKind::Table);
+        assert_eq!(report.sections[0].xref_stream, Some(xref_stream));
+        assert_eq!(report.sections[0].prev, Some(table as u64));
+
+        let reloaded = Document::load_mem(&updated).unwrap();
+        assert_eq!(reloaded.get_pages().len(), 1);
+    }
+
+    #[test]
+    fn test_incremental_update_keeps_utf16_title() {
+        let source = saved(test_support::build_sample_pdf(1));
+        let mut doc = Document::load_mem(&source).unwrap();
+        let info = doc.trailer.get(b"Info").unwrap().as_reference().unwrap();
+        let title = pdf_encoding::encode_text_string("Relatório č Ѝ");
+        doc.get_dictionary_mut(info).unwrap().set("Title", Object::String(title, StringFormat::Literal));
+
+        let updated = incremental_update(&source, &doc, &[info]).unwrap();
+        let reloaded = Document::load_mem(&updated).unwrap();
+        let title = reloaded.get_dictionary(info).unwrap().get(b"Title").unwrap().as_str().unwrap().to_vec();
+        assert_eq!(pdf_encoding::decode_text_string(&title), "Relatório č Ѝ");
+    }
+
+    #[test]
+    fn test_strings_round_trip_through_the_parser() {
+        let strings = [
+            Object::String(b"a\rb\nc (d) \\e \xE3".to_vec(), StringFormat::Literal),
+            // "č Ѝ" em UTF-16BE: 0x0D no byte baixo de cada caractere
+            Object::String(vec![0xFE, 0xFF, 0x01, 0x0D, 0x00, 0x20, 0x04, 0x0D], StringFormat::Literal),
+            Object::String(vec![0x00, 0x0D, 0xFF], StringFormat::Hexadecimal),
+        ];
+        let mut content = Vec::new();
+        for string in &strings {
+            write_object(&mut content, string, &|id| Some(id));
+            content.extend_from_slice(b" Tj\n");
+        }
+
+        let parsed = lopdf::content::Content::decode(&content).unwrap();
+        let decoded: Vec<_> = parsed.operations.iter().map(|op| op.operands[0].as_str().unwrap()).collect();
+        let expected: Vec<_> = strings.iter().map(|string| string.as_str().unwrap()).collect();
+        assert_eq!(decoded, expected);
+        assert!(content.starts_with(b"(a\\rb\\nc \\(d\\) \\\\e \xE3) Tj\n<FEFF010D0020040D> Tj"));
+    }
+
+    #[test]
+    fn test_write_name_escapes_delimiters() {
+        let mut out = Vec::new();
+        write_name(&mut out, b"A B/#");
+        assert_eq!(out, b"/A#20B#2F#23");
+    }
+}
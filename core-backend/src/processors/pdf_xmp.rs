@@ -0,0 +1,726 @@
+//! Metadados XMP do catálogo (`/Metadata`)
+//!
+//! O dicionário Info foi descontinuado no PDF 2.0, e o PDF/A exige que ele e
+//! o pacote XMP digam a mesma coisa. Este módulo lê o XMP, compara com o Info
+//! campo a campo e regrava os dois juntos quando os metadados são editados.
+//!
+//! ## Funcionalidades:
+//! - Dublin Core (`dc:title`, `dc:creator`, `dc:description`, `dc:subject`)
+//! - Datas e ferramenta `xmp:` (`CreateDate`, `ModifyDate`, `MetadataDate`, `CreatorTool`)
+//! - `pdf:Producer` e `pdf:Keywords`
+//! - Identificação PDF/A (`pdfaid:part` e `pdfaid:conformance`)
+//! - Divergências entre Info e XMP
+//! - Gravação sincronizada, preservando as propriedades de outros esquemas
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, SecondsFormat};
+use lopdf::{dictionary, Document, Object, ObjectId, Stream, StringFormat};
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use quick_xml::name::ResolveResult;
+use quick_xml::NsReader;
+use serde::{Deserialize, Serialize};
+
+use crate::processors::pdf_encoding;
+use crate::processors::pdf_filters;
+
+const NS_RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const NS_DC: &str = "http://purl.org/dc/elements/1.1/";
+const NS_XMP: &str = "http://ns.adobe.com/xap/1.0/";
+const NS_PDF: &str = "http://ns.adobe.com/pdf/1.3/";
+const NS_PDFAID: &str = "http://www.aiim.org/pdfa/ns/id/";
+const NS_XML: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Prefixos usados na gravação dos esquemas conhecidos
+const KNOWN_PREFIXES: [(&str, &str); 4] = [("dc", NS_DC), ("xmp", NS_XMP), ("pdf", NS_PDF), ("pdfaid", NS_PDFAID)];
+
+/// Espaço em branco no fim do pacote, para edições no próprio lugar
+const PACKET_PADDING: usize = 2048;
+
+/// Metadados lidos do pacote XMP
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct XmpMetadata {
+    /// Título (`dc:title`, alternativa `x-default`)
+    pub title: Option<String>,
+    /// Autores (`dc:creator`)
+    pub creators: Vec<String>,
+    /// Descrição (`dc:description`), equivalente ao `/Subject` do Info
+    pub description: Option<String>,
+    /// Assuntos (`dc:subject`)
+    pub subjects: Vec<String>,
+    /// Data de criação (`xmp:CreateDate`, ISO 8601)
+    pub create_date: Option<String>,
+    /// Data de modificação (`xmp:ModifyDate`, ISO 8601)
+    pub modify_date: Option<String>,
+    /// Data da última alteração dos metadados (`xmp:MetadataDate`)
+    pub metadata_date: Option<String>,
+    /// Software de criação (`xmp:CreatorTool`)
+    pub creator_tool: Option<String>,
+    /// Produtor do PDF (`pdf:Producer`)
+    pub producer: Option<String>,
+    /// Palavras-chave (`pdf:Keywords`)
+    pub keywords: Option<String>,
+    /// Parte PDF/A declarada (`pdfaid:part`)
+    pub pdfa_part: Option<String>,
+    /// Conformidade PDF/A declarada (`pdfaid:conformance`)
+    pub pdfa_conformance: Option<String>,
+}
+
+/// Campos do dicionário Info que têm equivalente no XMP
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct DocumentInfo {
+    /// `/Title`
+    pub title: Option<String>,
+    /// `/Author`
+    pub author: Option<String>,
+    /// `/Subject`
+    pub subject: Option<String>,
+    /// `/Keywords`
+    pub keywords: Option<String>,
+    /// `/Creator`
+    pub creator: Option<String>,
+    /// `/Producer`
+    pub producer: Option<String>,
+    /// `/CreationDate` (data PDF)
+    pub creation_date: Option<String>,
+    /// `/ModDate` (data PDF)
+    pub modification_date: Option<String>,
+}
+
+impl DocumentInfo {
+    /// Lê o dicionário Info do trailer
+    pub fn read(doc: &Document) -> Self {
+        let Some(info) = doc
+            .trailer
+            .get(b"Info")
+            .ok()
+            .and_then(|info| doc.dereference(info).ok())
+            .and_then(|(_, info)| info.as_dict().ok())
+        else {
+            return Self::default();
+        };
+
+        let text = |key: &[u8]| {
+            info.get_deref(key, doc)
+                .and_then(Object::as_str)
+                .ok()
+                .map(pdf_encoding::decode_text_string)
+        };
+        Self {
+            title: text(b"Title"),
+            author: text(b"Author"),
+            subject: text(b"Subject"),
+            keywords: text(b"Keywords"),
+            creator: text(b"Creator"),
+            producer: text(b"Producer"),
+            creation_date: text(b"CreationDate"),
+            modification_date: text(b"ModDate"),
+        }
+    }
+}
+
+/// Campo do Info cujo valor não confere com o XMP
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MetadataMismatch {
+    /// Chave do dicionário Info (ex.: "Title")
+    pub info_key: String,
+    /// Propriedade XMP equivalente (ex.: "dc:title")
+    pub xmp_property: String,
+    /// Valor no Info
+    pub info_value: String,
+    /// Valor no XMP (None = propriedade ausente)
+    pub xmp_value: Option<String>,
+}
+
+/// Lê o pacote XMP do catálogo (None = sem `/Metadata` ou stream ilegível)
+pub fn read(doc: &Document) -> Option<XmpMetadata> {
+    metadata_packet(doc).map(|packet| parse(&packet))
+}
+
+/// Interpreta um pacote XMP
+///
+/// Propriedades em forma de atributo ou de elemento são aceitas; XML mal
+/// formado interrompe a leitura e mantém o que foi lido até ali.
+pub fn parse(xml: &str) -> XmpMetadata {
+    parse_packet(xml).metadata
+}
+
+/// Compara o Info com o XMP, no sentido exigido pelo PDF/A
+///
+/// Cada campo presente no Info precisa existir no XMP com o mesmo valor;
+/// datas são comparadas como instantes. Campos que só existem no XMP não
+/// são divergências.
+pub fn compare(info: &DocumentInfo, xmp: &XmpMetadata) -> Vec<MetadataMismatch> {
+    let creators = (!xmp.creators.is_empty()).then(|| xmp.creators.join(", "));
+    let fields = [
+        ("Title", "dc:title", &info.title, xmp.title.as_ref(), false),
+        ("Author", "dc:creator", &info.author, creators.as_ref(), false),
+        ("Subject", "dc:description", &info.subject, xmp.description.as_ref(), false),
+        ("Keywords", "pdf:Keywords", &info.keywords, xmp.keywords.as_ref(), false),
+        ("Creator", "xmp:CreatorTool", &info.creator, xmp.creator_tool.as_ref(), false),
+        ("Producer", "pdf:Producer", &info.producer, xmp.producer.as_ref(), false),
+        ("CreationDate", "xmp:CreateDate", &info.creation_date, xmp.create_date.as_ref(), true),
+        ("ModDate", "xmp:ModifyDate", &info.modification_date, xmp.modify_date.as_ref(), true),
+    ];
+
+    fields
+        .into_iter()
+        .filter_map(|(info_key, xmp_property, info_value, xmp_value, is_date)| {
+            let info_value = info_value.as_ref()?;
+            let matches = xmp_value.is_some_and(|xmp_value| {
+                if is_date {
+                    match (pdf_encoding::parse_pdf_date(info_value), parse_xmp_date(xmp_value)) {
+                        (Some(info_date), Some(xmp_date)) => info_date == xmp_date,
+                        _ => false,
+                    }
+                } else {
+                    info_value.trim() == xmp_value.trim()
+                }
+            });
+            (!matches).then(|| MetadataMismatch {
+                info_key: info_key.to_string(),
+                xmp_property: xmp_property.to_string(),
+                info_value: info_value.clone(),
+                xmp_value: xmp_value.cloned(),
+            })
+        })
+        .collect()
+}
+
+/// Grava `info` no dicionário Info e no XMP do catálogo, mantendo os dois em sincronia
+///
+/// Campos `None` são removidos dos dois. O XMP é regravado com as propriedades
+/// equivalentes ao Info e `xmp:MetadataDate` atual; a identificação PDF/A e as
+/// propriedades de outros esquemas do pacote anterior são preservadas. Retorna
+/// os objetos alterados ou criados, para uso em atualizações incrementais.
+pub fn write(doc: &mut Document, info: &DocumentInfo) -> Result<Vec<ObjectId>, String> {
+    if doc.trailer.has(b"Encrypt") {
+        return Err("Encrypted documents cannot be edited".to_string());
+    }
+
+    let mut changed = Vec::new();
+
+    // Dicionário Info
+    let info_id = match doc.trailer.get(b"Info").and_then(Object::as_reference) {
+        Ok(id) if doc.get_dictionary(id).is_ok() => id,
+        _ => {
+            // Um Info direto no trailer vira objeto, para caber numa atualização incremental
+            let existing = doc.trailer.get(b"Info").and_then(Object::as_dict).cloned().unwrap_or_default();
+            let id = doc.add_object(existing);
+            doc.trailer.set("Info", id);
+            id
+        }
+    };
+    let info_dict = doc.get_dictionary_mut(info_id).map_err(|e| e.to_string())?;
+    for (key, value) in info_entries(info) {
+        match value {
+            Some(value) => info_dict.set(key, value),
+            None => {
+                info_dict.remove(key.as_bytes());
+            }
+        }
+    }
+    changed.push(info_id);
+
+    // Pacote XMP, preservando o que não vem do Info
+    let previous = metadata_packet(doc).map(|packet| parse_packet(&packet)).unwrap_or_default();
+    let mut metadata = to_xmp(info);
+    metadata.metadata_date = Some(Local::now().to_rfc3339_opts(SecondsFormat::Secs, false));
+    metadata.pdfa_part = previous.metadata.pdfa_part.clone();
+    metadata.pdfa_conformance = previous.metadata.pdfa_conformance.clone();
+    let content = build_packet(&metadata, &previous).into_bytes();
+
+    let catalog_id = doc.trailer.get(b"Root").and_then(Object::as_reference).map_err(|e| e.to_string())?;
+    let existing = doc
+        .get_dictionary(catalog_id)
+        .map_err(|e| e.to_string())?
+        .get(b"Metadata")
+        .and_then(Object::as_reference)
+        .ok()
+        .filter(|id| doc.get_object(*id).and_then(Object::as_stream).is_ok());
+
+    // O PDF/A pede o XMP sem filtros, legível por ferramentas que não entendem PDF
+    let stream = Stream::new(dictionary! { "Type" => "Metadata", "Subtype" => "XML" }, content);
+    match existing {
+        Some(metadata_id) => {
+            doc.objects.insert(metadata_id, Object::Stream(stream));
+            changed.push(metadata_id);
+        }
+        None => {
+            let metadata_id = doc.add_object(stream);
+            doc.get_dictionary_mut(catalog_id).map_err(|e| e.to_string())?.set("Metadata", metadata_id);
+            changed.push(metadata_id);
+            changed.push(catalog_id);
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Lê uma data XMP (ISO 8601, com precisão e fuso opcionais; sem fuso = UTC)
+pub fn parse_xmp_date(text: &str) -> Option<DateTime<FixedOffset>> {
+    let text = text.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Some(date);
+    }
+
+    // Sem segundos, com fuso ("2024-01-01T12:00-03:00" ou "...Z")
+    let zoned = text.strip_suffix('Z').map(|rest| format!("{}+00:00", rest));
+    if let Ok(date) = DateTime::parse_from_str(zoned.as_deref().unwrap_or(text), "%Y-%m-%dT%H:%M%:z") {
+        return Some(date);
+    }
+
+    let utc = FixedOffset::east_opt(0)?;
+    let naive = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            let date = match text.len() {
+                4 => format!("{}-01-01", text),
+                7 => format!("{}-01", text),
+                _ => text.to_string(),
+            };
+            NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0)
+        })?;
+    naive.and_local_timezone(utc).single()
+}
+
+// ==================== FUNÇÕES AUXILIARES ====================
+
+/// Pacote lido, com o que precisa ser preservado ao regravá-lo
+#[derive(Debug, Default)]
+struct Packet {
+    metadata: XmpMetadata,
+    /// Declarações `xmlns` encontradas (prefixo, namespace)
+    namespaces: Vec<(String, String)>,
+    /// Propriedades desconhecidas em forma de atributo (nome qualificado, valor)
+    extra_attributes: Vec<(String, String)>,
+    /// Propriedades desconhecidas em forma de elemento, como XML original
+    extra_elements: Vec<String>,
+}
+
+/// Valor de uma propriedade em leitura
+#[derive(Default)]
+struct PropertyValue {
+    text: String,
+    /// Itens de `rdf:Seq`, `rdf:Bag` ou `rdf:Alt` (idioma, texto)
+    items: Vec<(Option<String>, String)>,
+}
+
+/// Texto do pacote XMP do catálogo
+fn metadata_packet(doc: &Document) -> Option<String> {
+    let stream = doc
+        .catalog()
+        .and_then(|catalog| catalog.get_deref(b"Metadata", doc))
+        .and_then(Object::as_stream)
+        .ok()?;
+    let decoded = pdf_filters::decode_stream(doc, stream).ok()?;
+    Some(String::from_utf8_lossy(&decoded.data).into_owned())
+}
+
+fn parse_packet(xml: &str) -> Packet {
+    let mut packet = Packet::default();
+    let mut reader = NsReader::from_str(xml);
+    reader.config_mut().expand_empty_elements = true;
+
+    // Elementos abertos: (namespace, nome local)
+    let mut stack: Vec<(String, String)> = Vec::new();
+    let mut description_depth: Option<usize> = None;
+    let mut property: Option<(String, String, usize)> = None;
+    let mut value = PropertyValue::default();
+    let mut item: Option<(Option<String>, String)> = None;
+
+    loop {
+        let position = reader.buffer_position() as usize;
+        let Ok((namespace, event)) = reader.read_resolved_event() else {
+            break;
+        };
+        match event {
+            Event::Start(element) => {
+                let namespace = namespace_of(&namespace);
+                let local = String::from_utf8_lossy(element.local_name().as_ref()).into_owned();
+
+                for attribute in element.attributes().flatten() {
+                    let key = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+                    if let Some(prefix) = key.strip_prefix("xmlns:") {
+                        if let Ok(uri) = attribute.unescape_value() {
+                            if !packet.namespaces.iter().any(|(known, _)| known == prefix) {
+                                packet.namespaces.push((prefix.to_string(), uri.into_owned()));
+                            }
+                        }
+                    }
+                }
+
+                if property.is_none() && namespace == NS_RDF && local == "Description" {
+                    // Propriedades simples em forma de atributo
+                    for attribute in element.attributes().flatten() {
+                        let (attribute_namespace, attribute_local) = reader.resolve_attribute(attribute.key);
+                        let attribute_namespace = namespace_of(&attribute_namespace);
+                        if attribute_namespace.is_empty() || attribute_namespace == NS_RDF {
+                            continue;
+                        }
+                        let Ok(text) = attribute.unescape_value() else {
+                            continue;
+                        };
+                        let attribute_local = String::from_utf8_lossy(attribute_local.as_ref()).into_owned();
+                        let simple = PropertyValue { text: text.to_string(), items: Vec::new() };
+                        if !assign(&mut packet.metadata, &attribute_namespace, &attribute_local, simple) {
+                            let key = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+                            packet.extra_attributes.push((key, text.into_owned()));
+                        }
+                    }
+                    stack.push((namespace, local));
+                    description_depth = Some(stack.len());
+                    continue;
+                }
+
+                if property.is_none() && description_depth == Some(stack.len()) {
+                    property = Some((namespace.clone(), local.clone(), position));
+                    value = PropertyValue::default();
+                } else if property.is_some() && namespace == NS_RDF && local == "li" {
+                    let language = element
+                        .attributes()
+                        .flatten()
+                        .find(|attribute| {
+                            let (attribute_namespace, attribute_local) = reader.resolve_attribute(attribute.key);
+                            namespace_of(&attribute_namespace) == NS_XML && attribute_local.as_ref() == b"lang"
+                        })
+                        .and_then(|attribute| attribute.unescape_value().ok().map(|lang| lang.into_owned()));
+                    item = Some((language, String::new()));
+                }
+                stack.push((namespace, local));
+            }
+            Event::Text(text) => {
+                let Ok(text) = text.unescape() else {
+                    continue;
+                };
+                match (&mut item, &property) {
+                    (Some((_, item_text)), _) => item_text.push_str(&text),
+                    (None, Some(_)) => value.text.push_str(&text),
+                    _ => {}
+                }
+            }
+            Event::CData(data) => {
+                let text = String::from_utf8_lossy(&data);
+                match (&mut item, &property) {
+                    (Some((_, item_text)), _) => item_text.push_str(&text),
+                    (None, Some(_)) => value.text.push_str(&text),
+                    _ => {}
+                }
+            }
+            Event::End(_) => {
+                let Some((namespace, local)) = stack.pop() else {
+                    break;
+                };
+                if namespace == NS_RDF && local == "li" {
+                    if let Some((language, text)) = item.take() {
+                        value.items.push((language, text.trim().to_string()));
+                    }
+                }
+
+                if description_depth == Some(stack.len()) {
+                    if let Some((namespace, local, start)) = property.take() {
+                        let end = reader.buffer_position() as usize;
+                        if !assign(&mut packet.metadata, &namespace, &local, std::mem::take(&mut value)) {
+                            packet.extra_elements.push(xml[start..end].to_string());
+                        }
+                    }
+                } else if description_depth == Some(stack.len() + 1) && property.is_none() {
+                    description_depth = None;
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    packet
+}
+
+/// Namespace resolvido de um elemento ou atributo ("" quando sem namespace)
+fn namespace_of(result: &ResolveResult) -> String {
+    match result {
+        ResolveResult::Bound(namespace) => String::from_utf8_lossy(namespace.as_ref()).into_owned(),
+        _ => String::new(),
+    }
+}
+
+/// Guarda uma propriedade conhecida; retorna false se ela não é de um esquema tratado aqui
+fn assign(metadata: &mut XmpMetadata, namespace: &str, local: &str, value: PropertyValue) -> bool {
+    let text = value.text.trim().to_string();
+    let simple = value
+        .items
+        .first()
+        .map(|(_, item)| item.clone())
+        .or_else(|| (!text.is_empty()).then(|| text.clone()));
+    // Alternativas de idioma: "x-default", senão a primeira
+    let alternative = value
+        .items
+        .iter()
+        .find(|(language, _)| language.as_deref() == Some("x-default"))
+        .map(|(_, item)| item.clone())
+        .or_else(|| simple.clone());
+    let list = if value.items.is_empty() {
+        simple.clone().into_iter().collect()
+    } else {
+        value.items.into_iter().map(|(_, item)| item).collect()
+    };
+
+    match (namespace, local) {
+        (NS_DC, "title") => metadata.title = alternative,
+        (NS_DC, "creator") => metadata.creators = list,
+        (NS_DC, "description") => metadata.description = alternative,
+        (NS_DC, "subject") => metadata.subjects = list,
+        (NS_XMP, "CreateDate") => metadata.create_date = simple,
+        (NS_XMP, "ModifyDate") => metadata.modify_date = simple,
+        (NS_XMP, "MetadataDate") => metadata.metadata_date = simple,
+        (NS_XMP, "CreatorTool") => metadata.creator_tool = simple,
+        (NS_PDF, "Producer") => metadata.producer = simple,
+        (NS_PDF, "Keywords") => metadata.keywords = simple,
+        (NS_PDFAID, "part") => metadata.pdfa_part = simple,
+        (NS_PDFAID, "conformance") => metadata.pdfa_conformance = simple,
+        _ => return false,
+    }
+    true
+}
+
+/// Valores do Info a gravar (None = remover a chave)
+fn info_entries(info: &DocumentInfo) -> [(&'static str, Option<Object>); 8] {
+    let text = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(|value| Object::String(pdf_encoding::encode_text_string(value), StringFormat::Literal))
+    };
+    [
+        ("Title", text(&info.title)),
+        ("Author", text(&info.author)),
+        ("Subject", text(&info.subject)),
+        ("Keywords", text(&info.keywords)),
+        ("Creator", text(&info.creator)),
+        ("Producer", text(&info.producer)),
+        ("CreationDate", text(&info.creation_date)),
+        ("ModDate", text(&info.modification_date)),
+    ]
+}
+
+/// Propriedades XMP equivalentes ao Info
+fn to_xmp(info: &DocumentInfo) -> XmpMetadata {
+    let date = |value: &Option<String>| {
+        value
+            .as_deref()
+            .and_then(pdf_encoding::parse_pdf_date)
+            .map(|date| date.to_rfc3339_opts(SecondsFormat::Secs, false))
+    };
+
+    XmpMetadata {
+        title: info.title.clone(),
+        // O Author inteiro é um único autor: dividir "Silva, João" criaria dois
+        creators: info.author.clone().into_iter().collect(),
+        description: info.subject.clone(),
+        subjects: info
+            .keywords
+            .as_deref()
+            .map(|keywords| {
+                keywords
+                    .split([',', ';'])
+                    .map(str::trim)
+                    .filter(|keyword| !keyword.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        create_date: date(&info.creation_date),
+        modify_date: date(&info.modification_date),
+        metadata_date: None,
+        creator_tool: info.creator.clone(),
+        producer: info.producer.clone(),
+        keywords: info.keywords.clone(),
+        pdfa_part: None,
+        pdfa_conformance: None,
+    }
+}
+
+/// Monta o pacote XMP com as propriedades conhecidas e as preservadas de `previous`
+fn build_packet(metadata: &XmpMetadata, previous: &Packet) -> String {
+    let mut namespaces: Vec<(&str, &str)> = KNOWN_PREFIXES.to_vec();
+    for (prefix, uri) in &previous.namespaces {
+        let reserved = ["x", "rdf", "xml"].contains(&prefix.as_str());
+        if !reserved && !namespaces.iter().any(|(known, _)| known == prefix) {
+            namespaces.push((prefix, uri));
+        }
+    }
+
+    let mut xml = String::from("<?xpacket begin=\"\u{FEFF}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n");
+    xml.push_str("<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n");
+    xml.push_str(&format!(" <rdf:RDF xmlns:rdf=\"{}\">\n", NS_RDF));
+    xml.push_str("  <rdf:Description rdf:about=\"\"");
+    for (prefix, uri) in &namespaces {
+        xml.push_str(&format!("\n    xmlns:{}=\"{}\"", prefix, escape(*uri)));
+    }
+    for (name, value) in &previous.extra_attributes {
+        xml.push_str(&format!("\n    {}=\"{}\"", name, escape(value.as_str())));
+    }
+    xml.push_str(">\n");
+
+    let alternative = |name: &str, value: &Option<String>| {
+        value.as_ref().map(|value| {
+            format!(
+                "   <{0}><rdf:Alt><rdf:li xml:lang=\"x-default\">{1}</rdf:li></rdf:Alt></{0}>\n",
+                name,
+                escape(value.as_str())
+            )
+        })
+    };
+    let list = |name: &str, container: &str, values: &[String]| {
+        (!values.is_empty()).then(|| {
+            let items: String = values
+                .iter()
+                .map(|value| format!("<rdf:li>{}</rdf:li>", escape(value.as_str())))
+                .collect();
+            format!("   <{0}><rdf:{1}>{2}</rdf:{1}></{0}>\n", name, container, items)
+        })
+    };
+    let simple = |name: &str, value: &Option<String>| {
+        value
+            .as_ref()
+            .map(|value| format!("   <{0}>{1}</{0}>\n", name, escape(value.as_str())))
+    };
+
+    let properties = [
+        alternative("dc:title", &metadata.title),
+        list("dc:creator", "Seq", &metadata.creators),
+        alternative("dc:description", &metadata.description),
+        list("dc:subject", "Bag", &metadata.subjects),
+        simple("xmp:CreateDate", &metadata.create_date),
+        simple("xmp:ModifyDate", &metadata.modify_date),
+        simple("xmp:MetadataDate", &metadata.metadata_date),
+        simple("xmp:CreatorTool", &metadata.creator_tool),
+        simple("pdf:Producer", &metadata.producer),
+        simple("pdf:Keywords", &metadata.keywords),
+        simple("pdfaid:part", &metadata.pdfa_part),
+        simple("pdfaid:conformance", &metadata.pdfa_conformance),
+    ];
+    for property in properties.into_iter().flatten() {
+        xml.push_str(&property);
+    }
+    for element in &previous.extra_elements {
+        xml.push_str(&format!("   {}\n", element.trim()));
+    }
+
+    xml.push_str("  </rdf:Description>\n </rdf:RDF>\n</x:xmpmeta>\n");
+    for _ in 0..PACKET_PADDING / 64 {
+        xml.push_str(&" ".repeat(63));
+        xml.push('\n');
+    }
+    xml.push_str("<?xpacket end=\"w\"?>");
+    xml
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::test_support;
+
+    const SAMPLE_PACKET: &str = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+<rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:pdf="http://ns.adobe.com/pdf/1.3/"
+  xmlns:xmpMM="http://ns.adobe.com/xap/1.0/mm/" pdf:Producer="Writer &amp; Co" xmpMM:DocumentID="uuid:1234">
+ <dc:title><rdf:Alt><rdf:li xml:lang="pt-BR">Relatório</rdf:li><rdf:li xml:lang="x-default">Report</rdf:li></rdf:Alt></dc:title>
+ <dc:creator><rdf:Seq><rdf:li>Ana</rdf:li><rdf:li>Bruno</rdf:li></rdf:Seq></dc:creator>
+ <dc:subject><rdf:Bag><rdf:li>finanças</rdf:li><rdf:li>2024</rdf:li></rdf:Bag></dc:subject>
+ <xmpMM:History><rdf:Seq><rdf:li>saved</rdf:li></rdf:Seq></xmpMM:History>
+</rdf:Description>
+<rdf:Description rdf:about="" xmlns:xmp="http://ns.adobe.com/xap/1.0/" xmlns:pdfaid="http://www.aiim.org/pdfa/ns/id/">
+ <xmp:CreateDate>2024-01-01T12:00:00-03:00</xmp:CreateDate>
+ <pdfaid:part>2</pdfaid:part><pdfaid:conformance>B</pdfaid:conformance>
+</rdf:Description>
+</rdf:RDF></x:xmpmeta><?xpacket end="w"?>"#;
+
+    #[test]
+    fn test_parse_element_and_attribute_forms() {
+        let xmp = parse(SAMPLE_PACKET);
+        assert_eq!(xmp.title.as_deref(), Some("Report"));
+        assert_eq!(xmp.creators, vec!["Ana", "Bruno"]);
+        assert_eq!(xmp.subjects, vec!["finanças", "2024"]);
+        assert_eq!(xmp.producer.as_deref(), Some("Writer & Co"));
+        assert_eq!(xmp.create_date.as_deref(), Some("2024-01-01T12:00:00-03:00"));
+        assert_eq!(xmp.pdfa_part.as_deref(), Some("2"));
+        assert_eq!(xmp.pdfa_conformance.as_deref(), Some("B"));
+        assert_eq!(xmp.description, None);
+    }
+
+    #[test]
+    fn test_compare_reports_disagreements() {
+        let info = DocumentInfo {
+            title: Some("Report".to_string()),
+            author: Some("Ana".to_string()),
+            producer: Some("Writer & Co".to_string()),
+            creation_date: Some("D:20240101150000Z".to_string()),
+            keywords: Some("finanças".to_string()),
+            ..Default::default()
+        };
+        let mismatches = compare(&info, &parse(SAMPLE_PACKET));
+        let keys: Vec<_> = mismatches.iter().map(|m| m.info_key.as_str()).collect();
+        // Mesmo instante em fusos diferentes não é divergência
+        assert_eq!(keys, vec!["Author", "Keywords"]);
+        assert_eq!(mismatches[0].xmp_value.as_deref(), Some("Ana, Bruno"));
+        assert_eq!(mismatches[1].xmp_value, None);
+    }
+
+    #[test]
+    fn test_write_keeps_info_and_xmp_in_sync() {
+        let mut doc = test_support::build_sample_pdf(1);
+        let metadata = doc.add_object(Stream::new(
+            dictionary! { "Type" => "Metadata", "Subtype" => "XML" },
+            SAMPLE_PACKET.as_bytes().to_vec(),
+        ));
+        doc.catalog_mut().unwrap().set("Metadata", metadata);
+
+        let info = DocumentInfo {
+            title: Some("Contrato <n.º 7>".to_string()),
+            author: Some("Silva, João".to_string()),
+            keywords: Some("contrato; 2024".to_string()),
+            creation_date: Some("D:20240102030405-03'00'".to_string()),
+            ..Default::default()
+        };
+        let changed = write(&mut doc, &info).unwrap();
+        assert!(changed.contains(&metadata));
+
+        assert_eq!(DocumentInfo::read(&doc), info);
+        let xmp = read(&doc).unwrap();
+        assert!(compare(&info, &xmp).is_empty(), "{:?}", compare(&info, &xmp));
+        assert_eq!(xmp.creators, vec!["Silva, João"]);
+        assert_eq!(xmp.subjects, vec!["contrato", "2024"]);
+        assert_eq!(xmp.create_date.as_deref(), Some("2024-01-02T03:04:05-03:00"));
+        // Producer saiu do Info e, portanto, do XMP; pdfaid e outros esquemas ficam
+        assert_eq!(xmp.producer, None);
+        assert_eq!(xmp.pdfa_part.as_deref(), Some("2"));
+        let packet = metadata_packet(&doc).unwrap();
+        assert!(packet.contains("xmpMM:DocumentID=\"uuid:1234\""));
+        assert!(packet.contains("<xmpMM:History><rdf:Seq><rdf:li>saved</rdf:li></rdf:Seq></xmpMM:History>"));
+    }
+
+    #[test]
+    fn test_write_creates_metadata_stream() {
+        let mut doc = test_support::build_sample_pdf(1);
+        let catalog_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        let info = DocumentInfo { title: Some("Novo".to_string()), ..Default::default() };
+
+        let changed = write(&mut doc, &info).unwrap();
+        assert!(changed.contains(&catalog_id));
+        assert_eq!(read(&doc).unwrap().title.as_deref(), Some("Novo"));
+        assert!(read(&doc).unwrap().metadata_date.is_some());
+    }
+
+    #[test]
+    fn test_parse_xmp_date_precisions() {
+        let expected = |text: &str| DateTime::parse_from_rfc3339(text).unwrap();
+        assert_eq!(parse_xmp_date("2024-01-01T12:00-03:00"), Some(expected("2024-01-01T12:00:00-03:00")));
+        assert_eq!(parse_xmp_date("2024-01-01T12:00Z"), Some(expected("2024-01-01T12:00:00Z")));
+        assert_eq!(parse_xmp_date("2024-05"), Some(expected("2024-05-01T00:00:00Z")));
+        assert_eq!(parse_xmp_date("2024"), Some(expected("2024-01-01T00:00:00Z")));
+        assert_eq!(parse_xmp_date("not a date"), None);
+    }
+}
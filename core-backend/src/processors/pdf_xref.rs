@@ -0,0 +1,1500 @@
+//! Analisador da tabela de referências cruzadas (xref) a partir dos bytes brutos
+//!
+//! O `lopdf` reconstrói a tabela ao carregar o documento e esconde os erros que
+//! encontra pelo caminho. Este módulo lê o arquivo diretamente para que o
+//! validador consiga apontar exatamente onde a tabela está quebrada.
+//!
+//! ## Funcionalidades:
+//! - Localização do `startxref` no final do arquivo
+//! - Tabelas xref clássicas e xref streams (PDF 1.5+), incluindo `/XRefStm` de arquivos híbridos
+//! - Cadeia completa de `/Prev` com detecção de ciclos
+//! - Verificação de que cada offset em uso aponta para um cabeçalho `N G obj`
+//! - Medição do tamanho real dos dados de streams, para conferir `/Length`
+//! - Leitura do dicionário de linearização no início do arquivo
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::processors::pdf_filters::{self, DecodeParams};
+
+/// Janela no final do arquivo onde `startxref` é procurado
+const STARTXREF_SEARCH_WINDOW: usize = 2048;
+
+/// Janela no início do arquivo onde o dicionário de linearização deve estar
+const LINEARIZATION_SEARCH_WINDOW: usize = 1024;
+
+/// Profundidade máxima de objetos aninhados aceita pelo parser
+const MAX_NESTING: usize = 32;
+
+/// Tipo de seção xref
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum XrefSectionKind {
+    /// Tabela clássica (`xref` ... `trailer`)
+    Table,
+    /// Xref stream (`/Type /XRef`)
+    Stream,
+}
+
+/// Estado de uma entrada da tabela
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum XrefEntryKind {
+    /// Entrada livre
+    Free,
+    /// Objeto em uso no offset indicado
+    InUse { offset: u64 },
+    /// Objeto armazenado dentro de um object stream
+    Compressed { stream_object: u32, index: u32 },
+}
+
+/// Entrada da tabela de referências cruzadas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct XrefEntry {
+    /// Número do objeto
+    pub object_number: u32,
+    /// Número de geração
+    pub generation: u16,
+    /// Estado da entrada
+    pub kind: XrefEntryKind,
+}
+
+/// Seção xref (uma por revisão do arquivo)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XrefSection {
+    /// Offset onde a seção começa
+    pub offset: u64,
+    /// Formato da seção
+    pub kind: XrefSectionKind,
+    /// Entradas declaradas
+    pub entries: Vec<XrefEntry>,
+    /// Valor de `/Size` do trailer
+    pub size: Option<u32>,
+    /// Offset da seção anterior (`/Prev`)
+    pub prev: Option<u64>,
+    /// Offset da xref stream complementar (`/XRefStm`, arquivos híbridos)
+    pub xref_stream: Option<u64>,
+}
+
+/// Problema encontrado na análise
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum XrefProblem {
+    /// Não há `startxref` válido no final do arquivo
+    MissingStartxref,
+    /// Não foi possível ler uma seção xref no offset indicado
+    InvalidSection { offset: u64, reason: String },
+    /// A cadeia de `/Prev` volta para uma seção já visitada
+    PrevLoop { offset: u64 },
+    /// O offset de um objeto em uso está além do fim do arquivo
+    OffsetOutOfBounds { object_number: u32, generation: u16, offset: u64 },
+    /// O offset de um objeto em uso não aponta para o cabeçalho esperado
+    OffsetMismatch {
+        object_number: u32,
+        generation: u16,
+        offset: u64,
+        /// Cabeçalho encontrado no offset, se houver algum
+        found: Option<(u32, u16)>,
+    },
+    /// Objeto comprimido em um object stream que não existe na tabela
+    MissingObjectStream { object_number: u32, stream_object: u32 },
+}
+
+impl XrefProblem {
+    /// Número do objeto afetado, quando o problema se refere a um objeto
+    pub fn object_number(&self) -> Option<u32> {
+        match self {
+            Self::OffsetOutOfBounds { object_number, .. }
+            | Self::OffsetMismatch { object_number, .. }
+            | Self::MissingObjectStream { object_number, .. } => Some(*object_number),
+            _ => None,
+        }
+    }
+
+    /// Offset em bytes relacionado ao problema
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            Self::MissingStartxref | Self::MissingObjectStream { .. } => None,
+            Self::InvalidSection { offset, .. }
+            | Self::PrevLoop { offset }
+            | Self::OffsetOutOfBounds { offset, .. }
+            | Self::OffsetMismatch { offset, .. } => Some(*offset),
+        }
+    }
+
+    /// Localização legível (objeto e offset)
+    pub fn location(&self) -> String {
+        match (self.object_number(), self.offset()) {
+            (Some(object), Some(offset)) => format!("object {}, offset {}", object, offset),
+            (Some(object), None) => format!("object {}", object),
+            (None, Some(offset)) => format!("offset {}", offset),
+            (None, None) => "end of file".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for XrefProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingStartxref => write!(f, "No valid 'startxref' found at the end of the file"),
+            Self::InvalidSection { offset, reason } => {
+                write!(f, "Invalid cross-reference section at offset {}: {}", offset, reason)
+            }
+            Self::PrevLoop { offset } => {
+                write!(f, "Cross-reference /Prev chain loops back to offset {}", offset)
+            }
+            Self::OffsetOutOfBounds { object_number, generation, offset } => write!(
+                f,
+                "Xref entry for object {} {} points to offset {}, beyond the end of the file",
+                object_number, generation, offset
+            ),
+            Self::OffsetMismatch { object_number, generation, offset, found: Some((n, g)) } => write!(
+                f,
+                "Xref entry for object {} {} points to offset {}, which holds object {} {} instead",
+                object_number, generation, offset, n, g
+            ),
+            Self::OffsetMismatch { object_number, generation, offset, found: None } => write!(
+                f,
+                "Xref entry for object {} {} points to offset {}, which is not an object header",
+                object_number, generation, offset
+            ),
+            Self::MissingObjectStream { object_number, stream_object } => write!(
+                f,
+                "Object {} is stored in object stream {}, which has no in-use xref entry",
+                object_number, stream_object
+            ),
+        }
+    }
+}
+
+/// Resultado da análise da estrutura xref
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct XrefReport {
+    /// Offset declarado em `startxref`
+    pub startxref: Option<u64>,
+    /// Seções lidas, da mais recente para a mais antiga
+    pub sections: Vec<XrefSection>,
+    /// Problemas encontrados
+    pub problems: Vec<XrefProblem>,
+}
+
+impl XrefReport {
+    /// A estrutura xref está íntegra?
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Valor de `/Length` no dicionário de uma stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StreamLength {
+    /// Inteiro direto
+    Direct(u64),
+    /// Referência indireta (`N G R`)
+    Indirect(u32, u16),
+    /// Ausente ou inválido
+    Missing,
+}
+
+/// Posição e tamanho dos dados de uma stream no arquivo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamExtent {
+    /// Offset do primeiro byte de dados
+    pub data_offset: u64,
+    /// `/Length` declarado
+    pub declared_length: StreamLength,
+    /// Bytes até `endstream`, sem o fim de linha que o precede
+    pub actual_length: u64,
+    /// Bytes até `endstream`, incluindo o fim de linha
+    pub raw_length: u64,
+}
+
+impl StreamExtent {
+    /// O tamanho declarado corresponde aos dados encontrados?
+    pub fn matches(&self, declared: u64) -> bool {
+        declared == self.actual_length || declared == self.raw_length
+    }
+}
+
+/// Mede os dados da stream do objeto que começa em `offset`
+///
+/// Retorna `None` se não houver um objeto stream nessa posição.
+pub fn stream_extent(bytes: &[u8], offset: u64) -> Option<StreamExtent> {
+    let start = usize::try_from(offset).ok().filter(|&start| start < bytes.len())?;
+    let mut cursor = Cursor::new(bytes, start);
+    cursor.read_object_header()?;
+    cursor.skip_whitespace_and_comments();
+
+    let RawObject::Dictionary(dict) = cursor.parse_object(0).ok()? else {
+        return None;
+    };
+
+    cursor.skip_whitespace_and_comments();
+    if !cursor.consume_keyword(b"stream") {
+        return None;
+    }
+    if cursor.peek() == Some(b'\r') {
+        cursor.pos += 1;
+    }
+    if cursor.peek() == Some(b'\n') {
+        cursor.pos += 1;
+    }
+
+    let data_start = cursor.pos;
+    let keyword = b"endstream";
+    let end = bytes[data_start..]
+        .windows(keyword.len())
+        .position(|w| w == keyword)
+        .map(|p| data_start + p)?;
+
+    let mut data_end = end;
+    if data_end > data_start && bytes[data_end - 1] == b'\n' {
+        data_end -= 1;
+    }
+    if data_end > data_start && bytes[data_end - 1] == b'\r' {
+        data_end -= 1;
+    }
+
+    let declared_length = match dict_get(&dict, b"Length") {
+        Some(RawObject::Reference(number, generation)) => StreamLength::Indirect(*number, *generation),
+        Some(value) => value.as_unsigned().map(StreamLength::Direct).unwrap_or(StreamLength::Missing),
+        None => StreamLength::Missing,
+    };
+
+    Some(StreamExtent {
+        data_offset: data_start as u64,
+        declared_length,
+        actual_length: (data_end - data_start) as u64,
+        raw_length: (end - data_start) as u64,
+    })
+}
+
+/// Lê o cabeçalho `N G obj` que começa exatamente em `offset`
+pub fn object_header_at(bytes: &[u8], offset: u64) -> Option<(u32, u16)> {
+    let start = usize::try_from(offset).ok().filter(|&start| start < bytes.len())?;
+    Cursor::new(bytes, start).read_object_header()
+}
+
+/// Parâmetros do dicionário de linearização (`/Linearized`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Linearization {
+    /// Número do objeto que contém o dicionário
+    pub object_number: u32,
+    /// `/L`: tamanho do arquivo quando foi linearizado
+    pub file_length: Option<u64>,
+    /// `/O`: objeto da primeira página
+    pub first_page_object: Option<u64>,
+    /// `/E`: fim da seção da primeira página
+    pub first_page_end: Option<u64>,
+    /// `/N`: número de páginas
+    pub page_count: Option<u64>,
+    /// `/T`: offset da primeira entrada da tabela xref principal
+    pub main_xref_offset: Option<u64>,
+}
+
+impl Linearization {
+    /// O dicionário ainda descreve este arquivo?
+    ///
+    /// Uma atualização incremental acrescenta bytes ao final e deixa `/L`
+    /// desatualizado; o leitor então ignora a linearização.
+    pub fn matches_length(&self, length: u64) -> bool {
+        self.file_length == Some(length)
+    }
+}
+
+/// Lê o dicionário de linearização do primeiro objeto do arquivo
+///
+/// Retorna `None` se o primeiro objeto não for um dicionário com `/Linearized` numérico.
+pub fn linearization(bytes: &[u8]) -> Option<Linearization> {
+    let window = &bytes[..bytes.len().min(LINEARIZATION_SEARCH_WINDOW)];
+    let header = window.windows(5).position(|w| w == b"%PDF-")?;
+
+    // Pula o cabeçalho e os comentários que o seguem (marcador binário)
+    let mut cursor = Cursor::new(window, header);
+    cursor.skip_whitespace_and_comments();
+    let (object_number, _) = cursor.read_object_header()?;
+
+    let RawObject::Dictionary(dict) = cursor.parse_object(0).ok()? else {
+        return None;
+    };
+    // O valor é a versão da linearização; qualquer outra coisa não é um dicionário de linearização
+    if !matches!(dict_get(&dict, b"Linearized")?, RawObject::Integer(_) | RawObject::Real(_)) {
+        return None;
+    }
+
+    Some(Linearization {
+        object_number,
+        file_length: dict_unsigned(&dict, b"L"),
+        first_page_object: dict_unsigned(&dict, b"O"),
+        first_page_end: dict_unsigned(&dict, b"E"),
+        page_count: dict_unsigned(&dict, b"N"),
+        main_xref_offset: dict_unsigned(&dict, b"T"),
+    })
+}
+
+/// Analisa a estrutura xref completa de um arquivo PDF
+pub fn analyze(bytes: &[u8]) -> XrefReport {
+    let mut report = XrefReport::default();
+
+    let Some(startxref) = find_startxref(bytes) else {
+        report.problems.push(XrefProblem::MissingStartxref);
+        return report;
+    };
+    report.startxref = Some(startxref);
+
+    let (sections, problems) = section_chain(bytes, startxref, &mut HashSet::new());
+    report.sections = sections;
+    report.problems = problems;
+
+    check_entries(bytes, &mut report);
+    report
+}
+
+/// Lê as seções da cadeia `/Prev` que começa em `startxref`
+///
+/// Offsets já presentes em `visited` não são lidos de novo: quem percorre
+/// várias cadeias do mesmo arquivo lê cada seção uma única vez. Seções
+/// retornadas na ordem da cadeia (a mais recente primeiro).
+pub fn section_chain(
+    bytes: &[u8],
+    startxref: u64,
+    visited: &mut HashSet<u64>,
+) -> (Vec<XrefSection>, Vec<XrefProblem>) {
+    let mut sections = Vec::new();
+    let mut problems = Vec::new();
+    let mut next = Some(startxref);
+
+    while let Some(offset) = next {
+        if !visited.insert(offset) {
+            problems.push(XrefProblem::PrevLoop { offset });
+            break;
+        }
+
+        let section = match parse_section(bytes, offset) {
+            Ok(section) => section,
+            Err(reason) => {
+                problems.push(XrefProblem::InvalidSection { offset, reason });
+                break;
+            }
+        };
+        next = section.prev;
+        let hybrid_stream = section.xref_stream;
+        sections.push(section);
+
+        // Em arquivos híbridos a xref stream complementa a tabela clássica
+        if let Some(stream_offset) = hybrid_stream {
+            if visited.insert(stream_offset) {
+                match parse_section(bytes, stream_offset) {
+                    Ok(section) => sections.push(section),
+                    Err(reason) => problems.push(XrefProblem::InvalidSection {
+                        offset: stream_offset,
+                        reason,
+                    }),
+                }
+            }
+        }
+    }
+
+    (sections, problems)
+}
+
+// ==================== MÉTODOS PRIVADOS ====================
+
+/// Procura o último `startxref` e lê o offset que o segue
+fn find_startxref(bytes: &[u8]) -> Option<u64> {
+    let window_start = bytes.len().saturating_sub(STARTXREF_SEARCH_WINDOW);
+    let tail = &bytes[window_start..];
+    let keyword = b"startxref";
+
+    let position = tail.windows(keyword.len()).rposition(|w| w == keyword)?;
+    let mut cursor = Cursor::new(bytes, window_start + position + keyword.len());
+    cursor.skip_whitespace();
+    cursor.read_unsigned()
+}
+
+/// Lê a seção xref (tabela ou stream) que começa em `offset`
+fn parse_section(bytes: &[u8], offset: u64) -> Result<XrefSection, String> {
+    let start = usize::try_from(offset)
+        .ok()
+        .filter(|&start| start < bytes.len())
+        .ok_or_else(|| "offset is beyond the end of the file".to_string())?;
+
+    let mut cursor = Cursor::new(bytes, start);
+    cursor.skip_whitespace();
+
+    if cursor.consume_keyword(b"xref") {
+        return parse_table(&mut cursor, offset);
+    }
+
+    if cursor.read_object_header().is_some() {
+        return parse_stream(&mut cursor, offset);
+    }
+
+    Err("expected 'xref' keyword or an xref stream object".to_string())
+}
+
+/// Lê uma tabela clássica e seu trailer
+fn parse_table(cursor: &mut Cursor<'_>, offset: u64) -> Result<XrefSection, String> {
+    let mut entries = Vec::new();
+
+    loop {
+        cursor.skip_whitespace_and_comments();
+        if cursor.consume_keyword(b"trailer") {
+            break;
+        }
+
+        let header_offset = cursor.pos;
+        let first = cursor
+            .read_unsigned()
+            .ok_or_else(|| format!("malformed subsection header at offset {}", cursor.pos))?;
+        cursor.skip_whitespace();
+        let count = cursor
+            .read_unsigned()
+            .ok_or_else(|| format!("malformed subsection header at offset {}", cursor.pos))?;
+
+        // Números de objeto vão até u32::MAX; uma subseção além disso é inválida
+        let end = first
+            .checked_add(count)
+            .filter(|&end| end <= u64::from(u32::MAX) + 1)
+            .ok_or_else(|| {
+                format!("malformed subsection header at offset {}: {} {} exceeds the object number range", header_offset, first, count)
+            })?;
+
+        for object_number in first..end {
+            let entry_offset = cursor.pos;
+            let malformed = || format!("malformed entry for object {} at offset {}", object_number, entry_offset);
+
+            cursor.skip_whitespace();
+            let field = cursor.read_unsigned().ok_or_else(malformed)?;
+            cursor.skip_whitespace();
+            let generation = cursor.read_unsigned().ok_or_else(malformed)?;
+            cursor.skip_whitespace();
+            let kind = match cursor.next_byte() {
+                Some(b'n') => XrefEntryKind::InUse { offset: field },
+                Some(b'f') => XrefEntryKind::Free,
+                _ => return Err(malformed()),
+            };
+
+            entries.push(XrefEntry {
+                object_number: u32::try_from(object_number).map_err(|_| malformed())?,
+                generation: u16::try_from(generation).map_err(|_| malformed())?,
+                kind,
+            });
+        }
+    }
+
+    cursor.skip_whitespace_and_comments();
+    let trailer = match cursor.parse_object(0)? {
+        RawObject::Dictionary(dict) => dict,
+        _ => return Err("trailer is not a dictionary".to_string()),
+    };
+
+    Ok(XrefSection {
+        offset,
+        kind: XrefSectionKind::Table,
+        entries,
+        size: dict_unsigned(&trailer, b"Size").and_then(|v| u32::try_from(v).ok()),
+        prev: dict_unsigned(&trailer, b"Prev"),
+        xref_stream: dict_unsigned(&trailer, b"XRefStm"),
+    })
+}
+
+/// Lê uma xref stream (o cursor já passou pelo cabeçalho `N G obj`)
+fn parse_stream(cursor: &mut Cursor<'_>, offset: u64) -> Result<XrefSection, String> {
+    cursor.skip_whitespace_and_comments();
+    let dict = match cursor.parse_object(0)? {
+        RawObject::Dictionary(dict) => dict,
+        _ => return Err("xref stream object is not a stream".to_string()),
+    };
+
+    if dict_get(&dict, b"Type") != Some(&RawObject::Name(b"XRef".to_vec())) {
+        return Err("object is not an xref stream (/Type is not /XRef)".to_string());
+    }
+
+    let data = read_stream_data(cursor, &dict)?;
+    let data = decode_stream(&dict, data)?;
+
+    let widths: Vec<usize> = match dict_get(&dict, b"W") {
+        Some(RawObject::Array(items)) if items.len() == 3 => items
+            .iter()
+            .map(|item| item.as_unsigned().and_then(|v| usize::try_from(v).ok()).filter(|&v| v <= 8))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| "invalid /W array".to_string())?,
+        _ => return Err("missing or invalid /W array".to_string()),
+    };
+
+    let size = dict_unsigned(&dict, b"Size").ok_or_else(|| "missing /Size".to_string())?;
+    let index: Vec<u64> = match dict_get(&dict, b"Index") {
+        Some(RawObject::Array(items)) if items.len() % 2 == 0 => items
+            .iter()
+            .map(RawObject::as_unsigned)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| "invalid /Index array".to_string())?,
+        Some(_) => return Err("invalid /Index array".to_string()),
+        None => vec![0, size],
+    };
+
+    let entry_width: usize = widths.iter().sum();
+    if entry_width == 0 {
+        return Err("xref stream entries have zero width".to_string());
+    }
+
+    let mut rows = data.chunks_exact(entry_width);
+    let mut entries = Vec::new();
+
+    for pair in index.chunks(2) {
+        let (first, count) = (pair[0], pair[1]);
+        for object_number in first..first.saturating_add(count) {
+            let row = rows
+                .next()
+                .ok_or_else(|| format!("xref stream data ends before object {}", object_number))?;
+
+            let (type_field, rest) = row.split_at(widths[0]);
+            let (field2, field3) = rest.split_at(widths[1]);
+            // Com largura zero o tipo padrão é 1 (objeto em uso)
+            let entry_type = if widths[0] == 0 { 1 } else { read_be(type_field) };
+            let (field2, field3) = (read_be(field2), read_be(field3));
+
+            let kind = match entry_type {
+                0 => XrefEntryKind::Free,
+                1 => XrefEntryKind::InUse { offset: field2 },
+                2 => XrefEntryKind::Compressed {
+                    stream_object: u32::try_from(field2).map_err(|_| "invalid object stream number".to_string())?,
+                    index: u32::try_from(field3).map_err(|_| "invalid object stream index".to_string())?,
+                },
+                // Tipos desconhecidos devem ser tratados como referência a null
+                _ => continue,
+            };
+
+            entries.push(XrefEntry {
+                object_number: u32::try_from(object_number).map_err(|_| "object number out of range".to_string())?,
+                generation: if entry_type == 1 { u16::try_from(field3).unwrap_or(u16::MAX) } else { 0 },
+                kind,
+            });
+        }
+    }
+
+    Ok(XrefSection {
+        offset,
+        kind: XrefSectionKind::Stream,
+        entries,
+        size: u32::try_from(size).ok(),
+        prev: dict_unsigned(&dict, b"Prev"),
+        xref_stream: None,
+    })
+}
+
+/// Lê os bytes brutos de uma stream após o dicionário
+fn read_stream_data<'a>(cursor: &mut Cursor<'a>, dict: &[(Vec<u8>, RawObject)]) -> Result<&'a [u8], String> {
+    cursor.skip_whitespace_and_comments();
+    if !cursor.consume_keyword(b"stream") {
+        return Err("missing 'stream' keyword".to_string());
+    }
+
+    // A palavra-chave é seguida por CRLF ou LF
+    if cursor.peek() == Some(b'\r') {
+        cursor.pos += 1;
+    }
+    if cursor.peek() == Some(b'\n') {
+        cursor.pos += 1;
+    }
+
+    let start = cursor.pos;
+    let bytes = cursor.bytes;
+
+    let end = match dict_unsigned(dict, b"Length").and_then(|v| usize::try_from(v).ok()) {
+        Some(length) => start
+            .checked_add(length)
+            .filter(|&end| end <= bytes.len())
+            .ok_or_else(|| "stream /Length runs past the end of the file".to_string())?,
+        // /Length indireto: usa a posição de `endstream`
+        None => {
+            let keyword = b"endstream";
+            bytes[start..]
+                .windows(keyword.len())
+                .position(|w| w == keyword)
+                .map(|p| start + p)
+                .ok_or_else(|| "missing 'endstream' keyword".to_string())?
+        }
+    };
+
+    cursor.pos = end;
+    Ok(&bytes[start..end])
+}
+
+/// Aplica os filtros da xref stream
+fn decode_stream(dict: &[(Vec<u8>, RawObject)], data: &[u8]) -> Result<Vec<u8>, String> {
+    let filters: Vec<Vec<u8>> = match dict_get(dict, b"Filter") {
+        None => Vec::new(),
+        Some(RawObject::Name(name)) => vec![name.clone()],
+        Some(RawObject::Array(items)) => items
+            .iter()
+            .map(|item| match item {
+                RawObject::Name(name) => Ok(name.clone()),
+                _ => Err("invalid /Filter".to_string()),
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err("invalid /Filter".to_string()),
+    };
+
+    let params: Vec<DecodeParams> = match dict_get(dict, b"DecodeParms") {
+        Some(RawObject::Dictionary(params)) => vec![decode_params(params)],
+        Some(RawObject::Array(items)) => items
+            .iter()
+            .map(|item| match item {
+                RawObject::Dictionary(params) => decode_params(params),
+                _ => DecodeParams::default(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let mut data = data.to_vec();
+    for (index, filter) in filters.iter().enumerate() {
+        let params = params.get(index).copied().unwrap_or_default();
+        data = pdf_filters::apply_filter(filter, &data, &params, pdf_filters::DEFAULT_MAX_DECODED_SIZE).map_err(|e| e.to_string())?;
+    }
+
+    Ok(data)
+}
+
+/// Converte um dicionário `/DecodeParms` bruto
+fn decode_params(dict: &[(Vec<u8>, RawObject)]) -> DecodeParams {
+    let defaults = DecodeParams::default();
+    let value = |key: &[u8]| dict_unsigned(dict, key).and_then(|v| usize::try_from(v).ok());
+
+    DecodeParams {
+        predictor: value(b"Predictor").map(|v| v as u32).unwrap_or(defaults.predictor),
+        colors: value(b"Colors").unwrap_or(defaults.colors),
+        bits_per_component: value(b"BitsPerComponent").unwrap_or(defaults.bits_per_component),
+        columns: value(b"Columns").unwrap_or(defaults.columns),
+        early_change: value(b"EarlyChange").map(|v| v != 0).unwrap_or(defaults.early_change),
+    }
+}
+
+/// Lê um inteiro big-endian de largura variável
+fn read_be(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64)
+}
+
+/// Entrada já conferida por `check_entries`
+#[derive(PartialEq, Eq, Hash)]
+enum CheckedEntry {
+    /// Objeto em uso no offset indicado
+    InUse { object_number: u32, generation: u16, offset: u64 },
+    /// Objeto guardado em object stream
+    Compressed { object_number: u32 },
+}
+
+/// Confere cada entrada em uso contra o cabeçalho encontrado no arquivo
+fn check_entries(bytes: &[u8], report: &mut XrefReport) {
+    let in_use: HashSet<u32> = report
+        .sections
+        .iter()
+        .flat_map(|section| &section.entries)
+        .filter(|entry| matches!(entry.kind, XrefEntryKind::InUse { .. }))
+        .map(|entry| entry.object_number)
+        .collect();
+
+    let mut checked = HashSet::new();
+    let mut problems = Vec::new();
+
+    for entry in report.sections.iter().flat_map(|section| &section.entries) {
+        match entry.kind {
+            XrefEntryKind::InUse { offset } => {
+                let key = CheckedEntry::InUse {
+                    object_number: entry.object_number,
+                    generation: entry.generation,
+                    offset,
+                };
+                if !checked.insert(key) {
+                    continue;
+                }
+
+                let header = usize::try_from(offset)
+                    .ok()
+                    .filter(|&position| position < bytes.len())
+                    .map(|_| object_header_at(bytes, offset));
+
+                match header {
+                    None => problems.push(XrefProblem::OffsetOutOfBounds {
+                        object_number: entry.object_number,
+                        generation: entry.generation,
+                        offset,
+                    }),
+                    Some(Some((number, generation)))
+                        if number == entry.object_number && generation == entry.generation => {}
+                    Some(found) => problems.push(XrefProblem::OffsetMismatch {
+                        object_number: entry.object_number,
+                        generation: entry.generation,
+                        offset,
+                        found,
+                    }),
+                }
+            }
+            XrefEntryKind::Compressed { stream_object, .. } => {
+                if !in_use.contains(&stream_object) && checked.insert(CheckedEntry::Compressed { object_number: entry.object_number }) {
+                    problems.push(XrefProblem::MissingObjectStream {
+                        object_number: entry.object_number,
+                        stream_object,
+                    });
+                }
+            }
+            XrefEntryKind::Free => {}
+        }
+    }
+
+    report.problems.extend(problems);
+}
+
+// ==================== PARSER DE OBJETOS ====================
+
+/// Objeto PDF mínimo, suficiente para ler trailers e dicionários de xref streams
+#[derive(Debug, Clone, PartialEq)]
+enum RawObject {
+    Null,
+    Boolean(bool),
+    Integer(i64),
+    Real(f64),
+    Name(Vec<u8>),
+    String(Vec<u8>),
+    Array(Vec<RawObject>),
+    Dictionary(Vec<(Vec<u8>, RawObject)>),
+    Reference(u32, u16),
+}
+
+impl RawObject {
+    fn as_unsigned(&self) -> Option<u64> {
+        match self {
+            RawObject::Integer(value) => u64::try_from(*value).ok(),
+            _ => None,
+        }
+    }
+}
+
+fn dict_get<'a>(dict: &'a [(Vec<u8>, RawObject)], key: &[u8]) -> Option<&'a RawObject> {
+    dict.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+fn dict_unsigned(dict: &[(Vec<u8>, RawObject)], key: &[u8]) -> Option<u64> {
+    dict_get(dict, key).and_then(RawObject::as_unsigned)
+}
+
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | b'\x0c' | b'\0')
+}
+
+fn is_delimiter(byte: u8) -> bool {
+    matches!(byte, b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%')
+}
+
+/// Cursor sobre os bytes do arquivo
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(bytes: &'a [u8], pos: usize) -> Self {
+        Self { bytes, pos }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn next_byte(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'%') {
+                break;
+            }
+            while self.peek().is_some_and(|b| b != b'\n' && b != b'\r') {
+                self.pos += 1;
+            }
+        }
+    }
+
+    /// Consome a palavra-chave se ela estiver na posição atual e terminar em um delimitador
+    fn consume_keyword(&mut self, keyword: &[u8]) -> bool {
+        let end = self.pos + keyword.len();
+        let matches = self.bytes.get(self.pos..end) == Some(keyword)
+            && self.bytes.get(end).is_none_or(|&b| is_whitespace(b) || is_delimiter(b));
+
+        if matches {
+            self.pos = end;
+        }
+        matches
+    }
+
+    fn read_unsigned(&mut self) -> Option<u64> {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        if self.pos == start {
+            return None;
+        }
+        std::str::from_utf8(&self.bytes[start..self.pos]).ok()?.parse().ok()
+    }
+
+    /// Lê um cabeçalho `N G obj` na posição atual, sem pular espaços iniciais
+    fn read_object_header(&mut self) -> Option<(u32, u16)> {
+        let number = u32::try_from(self.read_unsigned()?).ok()?;
+        if !self.peek().is_some_and(is_whitespace) {
+            return None;
+        }
+        self.skip_whitespace();
+        let generation = u16::try_from(self.read_unsigned()?).ok()?;
+        if !self.peek().is_some_and(is_whitespace) {
+            return None;
+        }
+        self.skip_whitespace();
+        self.consume_keyword(b"obj").then_some((number, generation))
+    }
+
+    fn parse_object(&mut self, depth: usize) -> Result<RawObject, String> {
+        if depth > MAX_NESTING {
+            return Err("objects nested too deeply".to_string());
+        }
+
+        self.skip_whitespace_and_comments();
+        let start = self.pos;
+
+        match self.peek() {
+            None => Err("unexpected end of file".to_string()),
+            Some(b'<') if self.bytes.get(self.pos + 1) == Some(&b'<') => {
+                self.pos += 2;
+                self.parse_dictionary(depth)
+            }
+            Some(b'<') => self.parse_hex_string(),
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                loop {
+                    self.skip_whitespace_and_comments();
+                    if self.peek() == Some(b']') {
+                        self.pos += 1;
+                        return Ok(RawObject::Array(items));
+                    }
+                    items.push(self.parse_object(depth + 1)?);
+                }
+            }
+            Some(b'(') => self.parse_literal_string(),
+            Some(b'/') => self.parse_name().map(RawObject::Name),
+            Some(b) if b.is_ascii_digit() || matches!(b, b'+' | b'-' | b'.') => self.parse_number(),
+            Some(_) => {
+                if self.consume_keyword(b"true") {
+                    Ok(RawObject::Boolean(true))
+                } else if self.consume_keyword(b"false") {
+                    Ok(RawObject::Boolean(false))
+                } else if self.consume_keyword(b"null") {
+                    Ok(RawObject::Null)
+                } else {
+                    Err(format!("unexpected token at offset {}", start))
+                }
+            }
+        }
+    }
+
+    fn parse_dictionary(&mut self, depth: usize) -> Result<RawObject, String> {
+        let mut entries = Vec::new();
+        loop {
+            self.skip_whitespace_and_comments();
+            if self.bytes.get(self.pos..self.pos + 2) == Some(b">>") {
+                self.pos += 2;
+                return Ok(RawObject::Dictionary(entries));
+            }
+            if self.peek() != Some(b'/') {
+                return Err(format!("expected dictionary key at offset {}", self.pos));
+            }
+            let key = self.parse_name()?;
+            let value = self.parse_object(depth + 1)?;
+            entries.push((key, value));
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<Vec<u8>, String> {
+        self.pos += 1; // '/'
+        let mut name = Vec::new();
+        while let Some(byte) = self.peek().filter(|&b| !is_whitespace(b) && !is_delimiter(b)) {
+            self.pos += 1;
+            if byte == b'#' {
+                let hex = self.bytes.get(self.pos..self.pos + 2)
+                    .and_then(|h| std::str::from_utf8(h).ok())
+                    .and_then(|h| u8::from_str_radix(h, 16).ok());
+                if let Some(decoded) = hex {
+                    name.push(decoded);
+                    self.pos += 2;
+                    continue;
+                }
+            }
+            name.push(byte);
+        }
+        Ok(name)
+    }
+
+    fn parse_number(&mut self) -> Result<RawObject, String> {
+        let start = self.pos;
+        if matches!(self.peek(), Some(b'+') | Some(b'-')) {
+            self.pos += 1;
+        }
+        while self.peek().is_some_and(|b| b.is_ascii_digit() || b == b'.') {
+            self.pos += 1;
+        }
+
+        let text = std::str::from_utf8(&self.bytes[start..self.pos])
+            .map_err(|_| format!("invalid number at offset {}", start))?;
+
+        if text.contains('.') {
+            return text
+                .parse()
+                .map(RawObject::Real)
+                .map_err(|_| format!("invalid number at offset {}", start));
+        }
+
+        let value: i64 = text.parse().map_err(|_| format!("invalid number at offset {}", start))?;
+
+        // `N G R` é uma referência indireta
+        if value >= 0 {
+            let after_number = self.pos;
+            self.skip_whitespace();
+            if let Some(generation) = self.read_unsigned() {
+                self.skip_whitespace();
+                if self.consume_keyword(b"R") {
+                    if let (Ok(number), Ok(generation)) = (u32::try_from(value), u16::try_from(generation)) {
+                        return Ok(RawObject::Reference(number, generation));
+                    }
+                }
+            }
+            self.pos = after_number;
+        }
+
+        Ok(RawObject::Integer(value))
+    }
+
+    fn parse_literal_string(&mut self) -> Result<RawObject, String> {
+        let start = self.pos;
+        self.pos += 1; // '('
+        let mut depth = 1;
+        let mut content = Vec::new();
+
+        while let Some(byte) = self.next_byte() {
+            match byte {
+                b'\\' => {
+                    if let Some(escaped) = self.next_byte() {
+                        content.push(escaped);
+                    }
+                }
+                b'(' => {
+                    depth += 1;
+                    content.push(byte);
+                }
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(RawObject::String(content));
+                    }
+                    content.push(byte);
+                }
+                _ => content.push(byte),
+            }
+        }
+
+        Err(format!("unterminated string at offset {}", start))
+    }
+
+    fn parse_hex_string(&mut self) -> Result<RawObject, String> {
+        let start = self.pos;
+        self.pos += 1; // '<'
+        let mut digits = Vec::new();
+
+        while let Some(byte) = self.next_byte() {
+            match byte {
+                b'>' => {
+                    if digits.len() % 2 == 1 {
+                        digits.push(b'0');
+                    }
+                    let content = digits
+                        .chunks(2)
+                        .map(|pair| {
+                            std::str::from_utf8(pair)
+                                .ok()
+                                .and_then(|h| u8::from_str_radix(h, 16).ok())
+                                .ok_or_else(|| format!("invalid hex string at offset {}", start))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    return Ok(RawObject::String(content));
+                }
+                b if is_whitespace(b) => {}
+                b => digits.push(b),
+            }
+        }
+
+        Err(format!("unterminated hex string at offset {}", start))
+    }
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processors::test_support;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    /// Arquivo híbrido como os gravados pelo Word (tabela clássica com `/XRefStm`,
+    /// objetos em `/ObjStm`, lista de livres encadeada e fins de linha CRLF),
+    /// depois atualizado pelo iText com uma tabela de várias subseções
+    const WORD_HYBRID_UPDATED: &[u8] = b"%PDF-1.5\r\n\
+    %\xB5\xB5\xB5\xB5\r\n\
+    1 0 obj\r\n\
+    <</Type/Catalog/Pages 2 0 R/Lang(pt-BR) /StructTreeRoot 7 0 R/MarkInfo<</Marked true>>>>\r\n\
+    endobj\r\n\
+    2 0 obj\r\n\
+    <</Type/Pages/Count 1/Kids[ 3 0 R] >>\r\n\
+    endobj\r\n\
+    3 0 obj\r\n\
+    <</Type/Page/Parent 2 0 R/Resources<</Font<</F1 5 0 R>>/ProcSet[/PDF/Text]>>/MediaBox[ 0 0 595.32 841.92] /Contents 4 0 R/Group<</Type/Group/S/Transparency/CS/DeviceRGB>>/Tabs/S/StructParents 0>>\r\n\
+    endobj\r\n\
+    4 0 obj\r\n\
+    <</Length 86>>\r\n\
+    stream\r\n\
+    BT\r\n\
+    /F1 11.04 Tf\r\n\
+    1 0 0 1 85.104 759.1 Tm\r\n\
+    [(Contrato de Presta)4(\\347\\343o)] TJ\r\n\
+    ET\r\n\
+    \r\n\
+    endstream\r\n\
+    endobj\r\n\
+    5 0 obj\r\n\
+    <</Type/Font/Subtype/TrueType/Name/F1/BaseFont/Calibri/Encoding/WinAnsiEncoding/FirstChar 32/LastChar 32>>\r\n\
+    endobj\r\n\
+    6 0 obj\r\n\
+    <</Type/ObjStm/N 2/First 9/Length 65>>\r\n\
+    stream\r\n\
+    7 0 8 33 <</Type/StructTreeRoot/K 8 0 R>> <</S/Document/P 7 0 R>>\r\n\
+    endstream\r\n\
+    endobj\r\n\
+    9 0 obj\r\n\
+    <</Author(Maria Silva) /Creator(\\376\\377\\000M\\000i\\000c\\000r\\000o\\000s\\000o\\000f\\000t\\000\\256\\000 \\000W\\000o\\000r\\000d) /CreationDate(D:20240305101500-03'00') /Producer(Microsoft\\256 Word 2016) >>\r\n\
+    endobj\r\n\
+    10 0 obj\r\n\
+    <</Type/XRef/Size 11/W[ 1 2 2] /Root 1 0 R/Info 9 0 R/ID[<B5A1C3E2F0D94E4A9A0B8D9E61E2F7A1><B5A1C3E2F0D94E4A9A0B8D9E61E2F7A1>] /Index[ 7 2 10 1] /Length 15>>\r\n\
+    stream\r\n\
+    \x02\x00\x06\x00\x00\x02\x00\x06\x00\x01\x01\x03\xF9\x00\x00\r\n\
+    endstream\r\n\
+    endobj\r\n\
+    xref\r\n\
+    0 11\r\n\
+    0000000007 65535 f\r\n\
+    0000000017 00000 n\r\n\
+    0000000124 00000 n\r\n\
+    0000000180 00000 n\r\n\
+    0000000394 00000 n\r\n\
+    0000000534 00000 n\r\n\
+    0000000659 00000 n\r\n\
+    0000000008 65535 f\r\n\
+    0000000000 65535 f\r\n\
+    0000000802 00000 n\r\n\
+    0000001017 00000 n\r\n\
+    trailer\r\n\
+    <</Size 11/Root 1 0 R/Info 9 0 R/ID[<B5A1C3E2F0D94E4A9A0B8D9E61E2F7A1><B5A1C3E2F0D94E4A9A0B8D9E61E2F7A1>] /XRefStm 1017>>\r\n\
+    startxref\r\n\
+    1230\r\n\
+    %%EOF\r\n\
+    3 0 obj\r\n\
+    <</Type/Page/Parent 2 0 R/Resources<</Font<</F1 5 0 R>>/ProcSet[/PDF/Text]>>/MediaBox[ 0 0 595.32 841.92] /Contents 4 0 R/Annots[ 11 0 R] /Group<</Type/Group/S/Transparency/CS/DeviceRGB>>/Tabs/S/StructParents 0>>\r\n\
+    endobj\r\n\
+    9 0 obj\r\n\
+    <</Author(Maria Silva) /CreationDate(D:20240305101500-03'00') /ModDate(D:20240311174205-03'00') /Producer(Microsoft\\256 Word 2016; modified using iText 5.5.13) >>\r\n\
+    endobj\r\n\
+    11 0 obj\r\n\
+    <</Type/Annot/Subtype/Text/Rect[ 500 760 520 780] /Contents(Revisar cl\\341usula 4) /P 3 0 R>>\r\n\
+    endobj\r\n\
+    xref\r\n\
+    0 1\r\n\
+    0000000000 65535 f\r\n\
+    3 1\r\n\
+    0000001618 00000 n\r\n\
+    9 1\r\n\
+    0000001849 00000 n\r\n\
+    11 1\r\n\
+    0000002030 00000 n\r\n\
+    trailer\r\n\
+    <</Size 12/Root 1 0 R/Info 9 0 R/ID[<B5A1C3E2F0D94E4A9A0B8D9E61E2F7A1><0C6E1F29D3B54A4C8F8E0D6A2B7C9E11>] /Prev 1230>>\r\n\
+    startxref\r\n\
+    2143\r\n\
+    %%EOF\r\n";
+
+    fn sample_bytes(page_count: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        test_support::build_sample_pdf(page_count).save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    /// Posição da entrada de `object_number` na primeira subseção da tabela clássica
+    fn table_entry_position(bytes: &[u8], object_number: usize) -> usize {
+        let startxref = find_startxref(bytes).unwrap() as usize;
+        let mut cursor = Cursor::new(bytes, startxref);
+        assert!(cursor.consume_keyword(b"xref"));
+        cursor.skip_whitespace();
+        cursor.read_unsigned().unwrap();
+        cursor.skip_whitespace();
+        cursor.read_unsigned().unwrap();
+        cursor.skip_whitespace();
+        cursor.pos + object_number * 20
+    }
+
+    /// Confere que cada entrada em uso aponta para o cabeçalho do próprio objeto
+    fn assert_entries_point_to_headers(bytes: &[u8], section: &XrefSection) {
+        for entry in &section.entries {
+            if let XrefEntryKind::InUse { offset } = entry.kind {
+                assert_eq!(
+                    object_header_at(bytes, offset),
+                    Some((entry.object_number, entry.generation)),
+                    "entry for object {}",
+                    entry.object_number
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_analyze_valid_table() {
+        let doc = test_support::build_sample_pdf(3);
+        let bytes = sample_bytes(3);
+        let report = analyze(&bytes);
+
+        assert!(report.is_valid(), "{:?}", report.problems);
+        assert_eq!(report.startxref, find_startxref(&bytes));
+        assert_eq!(report.sections.len(), 1);
+
+        let section = &report.sections[0];
+        assert_eq!(section.kind, XrefSectionKind::Table);
+        assert_eq!(section.offset, report.startxref.unwrap());
+        assert_eq!(section.size, Some(doc.max_id + 1));
+        assert_eq!(section.prev, None);
+        assert_eq!(section.entries[0], XrefEntry { object_number: 0, generation: 65535, kind: XrefEntryKind::Free });
+
+        let numbers: Vec<u32> = section.entries.iter().map(|e| e.object_number).collect();
+        assert_eq!(numbers, (0..=doc.max_id).collect::<Vec<_>>());
+        assert_entries_point_to_headers(&bytes, section);
+    }
+
+    #[test]
+    fn test_analyze_valid_lopdf_xref_stream() {
+        let mut doc = test_support::build_sample_pdf(3);
+        doc.reference_table.cross_reference_type = lopdf::xref::XrefType::CrossReferenceStream;
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+
+        let report = analyze(&bytes);
+        assert!(report.is_valid(), "{:?}", report.problems);
+        assert_eq!(report.sections.len(), 1);
+
+        let section = &report.sections[0];
+        assert_eq!(section.kind, XrefSectionKind::Stream);
+        assert_eq!(section.xref_stream, None);
+        assert_entries_point_to_headers(&bytes, section);
+
+        // A própria xref stream tem uma entrada, no offset do `startxref`
+        let own = section.entries.iter().find(|e| e.kind == XrefEntryKind::InUse { offset: section.offset });
+        assert_eq!(own.map(|e| object_header_at(&bytes, section.offset) == Some((e.object_number, 0))), Some(true));
+    }
+
+    #[test]
+    fn test_analyze_reports_shifted_offset() {
+        let mut bytes = sample_bytes(3);
+        let position = table_entry_position(&bytes, 2);
+        let original: u64 = std::str::from_utf8(&bytes[position..position + 10]).unwrap().parse().unwrap();
+        let shifted = format!("{:010}", original + 3);
+        bytes[position..position + 10].copy_from_slice(shifted.as_bytes());
+
+        let report = analyze(&bytes);
+        assert_eq!(
+            report.problems,
+            vec![XrefProblem::OffsetMismatch {
+                object_number: 2,
+                generation: 0,
+                offset: original + 3,
+                found: None,
+            }]
+        );
+        assert_eq!(report.problems[0].location(), format!("object 2, offset {}", original + 3));
+    }
+
+    #[test]
+    fn test_analyze_reports_offset_beyond_eof() {
+        let mut bytes = sample_bytes(1);
+        let position = table_entry_position(&bytes, 1);
+        bytes[position..position + 10].copy_from_slice(b"9999999999");
+
+        let report = analyze(&bytes);
+        assert!(matches!(
+            report.problems.as_slice(),
+            [XrefProblem::OffsetOutOfBounds { object_number: 1, offset: 9_999_999_999, .. }]
+        ));
+    }
+
+    #[test]
+    fn test_analyze_follows_prev_chain() {
+        let mut bytes = sample_bytes(2);
+        let previous = find_startxref(&bytes).unwrap();
+
+        let object_offset = bytes.len() + 1;
+        bytes.extend_from_slice(b"\n99 0 obj\n(updated)\nendobj\n");
+        let xref_offset = bytes.len();
+        write!(
+            bytes,
+            "xref\n99 1\n{:010} 00000 n \ntrailer\n<< /Size 100 /Prev {} >>\nstartxref\n{}\n%%EOF\n",
+            object_offset, previous, xref_offset
+        )
+        .unwrap();
+
+        let report = analyze(&bytes);
+        assert!(report.is_valid(), "{:?}", report.problems);
+        assert_eq!(report.sections.len(), 2);
+        assert_eq!(report.sections[0].prev, Some(previous));
+        assert_eq!(report.sections[1].offset, previous);
+    }
+
+    #[test]
+    fn test_analyze_detects_prev_loop() {
+        let mut bytes = sample_bytes(1);
+        let xref_offset = bytes.len();
+        write!(
+            bytes,
+            "xref\n0 1\n0000000000 65535 f \ntrailer\n<< /Size 1 /Prev {} >>\nstartxref\n{}\n%%EOF\n",
+            xref_offset, xref_offset
+        )
+        .unwrap();
+
+        let report = analyze(&bytes);
+        assert_eq!(report.problems, vec![XrefProblem::PrevLoop { offset: xref_offset as u64 }]);
+    }
+
+    #[test]
+    fn test_analyze_word_hybrid_file_with_update() {
+        let bytes = WORD_HYBRID_UPDATED;
+        let report = analyze(bytes);
+        assert!(report.is_valid(), "{:?}", report.problems);
+        assert_eq!(report.startxref, Some(2143));
+
+        // Atualização, tabela original e a xref stream referenciada por ela
+        let layout: Vec<(u64, XrefSectionKind, Option<u32>, Option<u64>, Option<u64>)> = report
+            .sections
+            .iter()
+            .map(|s| (s.offset, s.kind, s.size, s.prev, s.xref_stream))
+            .collect();
+        assert_eq!(layout, vec![
+            (2143, XrefSectionKind::Table, Some(12), Some(1230), None),
+            (1230, XrefSectionKind::Table, Some(11), None, Some(1017)),
+            (1017, XrefSectionKind::Stream, Some(11), None, None),
+        ]);
+
+        let entry = |object_number: u32, generation: u16, kind: XrefEntryKind| XrefEntry { object_number, generation, kind };
+        let in_use = |offset: u64| XrefEntryKind::InUse { offset };
+        assert_eq!(report.sections[0].entries, vec![
+            entry(0, 65535, XrefEntryKind::Free),
+            entry(3, 0, in_use(1618)),
+            entry(9, 0, in_use(1849)),
+            entry(11, 0, in_use(2030)),
+        ]);
+        assert_eq!(report.sections[1].entries, vec![
+            entry(0, 65535, XrefEntryKind::Free),
+            entry(1, 0, in_use(17)),
+            entry(2, 0, in_use(124)),
+            entry(3, 0, in_use(180)),
+            entry(4, 0, in_use(394)),
+            entry(5, 0, in_use(534)),
+            entry(6, 0, in_use(659)),
+            entry(7, 65535, XrefEntryKind::Free),
+            entry(8, 65535, XrefEntryKind::Free),
+            entry(9, 0, in_use(802)),
+            entry(10, 0, in_use(1017)),
+        ]);
+        assert_eq!(report.sections[2].entries, vec![
+            entry(7, 0, XrefEntryKind::Compressed { stream_object: 6, index: 0 }),
+            entry(8, 0, XrefEntryKind::Compressed { stream_object: 6, index: 1 }),
+            entry(10, 0, in_use(1017)),
+        ]);
+
+        // O lopdf lê a mesma estrutura: os objetos comprimidos e o Info da atualização
+        let doc = lopdf::Document::load_mem(bytes).unwrap();
+        let tree = doc.get_dictionary((7, 0)).unwrap();
+        assert_eq!(tree.get(b"Type").unwrap().as_name().unwrap(), b"StructTreeRoot");
+        let info = doc.get_dictionary((9, 0)).unwrap();
+        assert_eq!(info.get(b"Producer").unwrap().as_str().unwrap(), b"Microsoft\xAE Word 2016; modified using iText 5.5.13");
+    }
+
+    #[test]
+    fn test_stream_extent_measures_data() {
+        let bytes = b"%PDF-1.4\n4 0 obj\n<< /Length 99 >>\nstream\nhello\nendstream\nendobj\n";
+        let extent = stream_extent(bytes, 9).unwrap();
+
+        assert_eq!(extent.declared_length, StreamLength::Direct(99));
+        assert_eq!(extent.actual_length, 5);
+        assert!(extent.matches(5));
+        assert!(extent.matches(6));
+        assert!(!extent.matches(99));
+        assert!(stream_extent(bytes, 0).is_none());
+    }
+
+    #[test]
+    fn test_analyze_missing_startxref() {
+        let report = analyze(b"%PDF-1.4\n1 0 obj\n<< >>\nendobj\n");
+        assert_eq!(report.problems, vec![XrefProblem::MissingStartxref]);
+    }
+
+    #[test]
+    fn test_analyze_invalid_startxref_target() {
+        let mut bytes = sample_bytes(1);
+        let end = bytes.len();
+        write!(bytes, "\nstartxref\n{}\n%%EOF\n", end + 500).unwrap();
+
+        let report = analyze(&bytes);
+        assert_eq!(
+            report.problems,
+            vec![XrefProblem::InvalidSection {
+                offset: (end + 500) as u64,
+                reason: "offset is beyond the end of the file".to_string(),
+            }]
+        );
+        assert!(report.sections.is_empty());
+    }
+
+    #[test]
+    fn test_analyze_rejects_overflowing_subsection() {
+        for header in [format!("{} 2", u64::MAX), format!("{} 2", u32::MAX)] {
+            let mut bytes = b"%PDF-1.4\n".to_vec();
+            let xref = bytes.len();
+            write!(
+                bytes,
+                "xref\n{}\n0000000009 00000 n \n0000000009 00000 n \ntrailer\n<< /Size 1 >>\nstartxref\n{}\n%%EOF\n",
+                header, xref
+            )
+            .unwrap();
+
+            let report = analyze(&bytes);
+            match report.problems.as_slice() {
+                [XrefProblem::InvalidSection { offset, reason }] => {
+                    assert_eq!(*offset, xref as u64);
+                    assert!(reason.contains("exceeds the object number range"), "{}", reason);
+                }
+                problems => panic!("unexpected problems for '{}': {:?}", header, problems),
+            }
+        }
+    }
+
+    #[test]
+    fn test_analyze_xref_stream_with_png_predictor() {
+        let mut bytes = b"%PDF-1.5\n".to_vec();
+        let catalog_offset = bytes.len();
+        bytes.extend_from_slice(b"1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n");
+        let pages_offset = bytes.len();
+        bytes.extend_from_slice(b"2 0 obj\n<< /Type /Pages /Kids [] /Count 0 >>\nendobj\n");
+        let xref_offset = bytes.len();
+
+        // Entradas com /W [1 2 1]: livre, catálogo, páginas, a própria stream e um objeto comprimido em 9
+        let rows: Vec<[u8; 4]> = vec![
+            [0, 0, 0, 255],
+            [1, (catalog_offset >> 8) as u8, catalog_offset as u8, 0],
+            [1, (pages_offset >> 8) as u8, pages_offset as u8, 0],
+            [1, (xref_offset >> 8) as u8, xref_offset as u8, 0],
+            [2, 0, 9, 0],
+        ];
+
+        // Codifica com o preditor PNG "Up" (filtro 2)
+        let mut predicted = Vec::new();
+        let mut previous = [0u8; 4];
+        for row in &rows {
+            predicted.push(2);
+            predicted.extend(row.iter().zip(previous).map(|(b, p)| b.wrapping_sub(p)));
+            previous = *row;
+        }
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&predicted).unwrap();
+        let data = encoder.finish().unwrap();
+
+        write!(
+            bytes,
+            "3 0 obj\n<< /Type /XRef /Size 5 /W [1 2 1] /Root 1 0 R /Filter /FlateDecode \
+             /DecodeParms << /Predictor 12 /Columns 4 >> /Length {} >>\nstream\n",
+            data.len()
+        )
+        .unwrap();
+        bytes.extend_from_slice(&data);
+        write!(bytes, "\nendstream\nendobj\nstartxref\n{}\n%%EOF\n", xref_offset).unwrap();
+
+        let report = analyze(&bytes);
+        assert_eq!(report.sections.len(), 1);
+        assert_eq!(report.sections[0].kind, XrefSectionKind::Stream);
+        assert_eq!(report.sections[0].entries.len(), 5);
+        assert_eq!(
+            report.sections[0].entries[1].kind,
+            XrefEntryKind::InUse { offset: catalog_offset as u64 }
+        );
+        assert_eq!(
+            report.problems,
+            vec![XrefProblem::MissingObjectStream { object_number: 4, stream_object: 9 }]
+        );
+    }
+
+    #[test]
+    fn test_analyze_rejects_oversized_predictor_columns() {
+        let mut bytes = b"%PDF-1.5\n".to_vec();
+        let xref_offset = bytes.len();
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&[2, 0, 0, 0, 0]).unwrap();
+        let data = encoder.finish().unwrap();
+
+        // /Columns forjado: a linha do preditor teria terabytes
+        write!(
+            bytes,
+            "1 0 obj\n<< /Type /XRef /Size 1 /W [1 2 1] /Filter /FlateDecode \
+             /DecodeParms << /Predictor 12 /Columns 4398046511104 >> /Length {} >>\nstream\n",
+            data.len()
+        )
+        .unwrap();
+        bytes.extend_from_slice(&data);
+        write!(bytes, "\nendstream\nendobj\nstartxref\n{}\n%%EOF\n", xref_offset).unwrap();
+
+        let report = analyze(&bytes);
+        assert!(matches!(
+            report.problems.as_slice(),
+            [XrefProblem::InvalidSection { reason, .. }] if reason.contains("predictor")
+        ), "{:?}", report.problems);
+    }
+
+    #[test]
+    fn test_linearization_reads_first_object() {
+        let bytes = b"%PDF-1.7\n%\xE2\xE3\xCF\xD3\n7 0 obj\n<< /Linearized 1 /L 5000 /O 9 /E 1200 /N 3 /T 4800 /H [ 600 90 ] >>\nendobj\n";
+        let params = linearization(bytes).unwrap();
+
+        assert_eq!(params.object_number, 7);
+        assert_eq!(params.file_length, Some(5000));
+        assert_eq!(params.first_page_object, Some(9));
+        assert_eq!(params.page_count, Some(3));
+        assert!(params.matches_length(5000));
+        assert!(!params.matches_length(5100));
+
+        // /Linearized no catálogo não torna o arquivo linearizado
+        assert!(linearization(&sample_bytes(2)).is_none());
+        assert!(linearization(b"%PDF-1.4\n1 0 obj\n<< /Type /Catalog /Linearized true >>\nendobj\n").is_none());
+    }
+}
@@ -0,0 +1,80 @@
+//! Dependências de execução compartilhadas pelos processadores
+//!
+//! Todo processador lê arquivos pelo `FileHandler` e executa o trabalho pesado
+//! no pool de CPU; `ProcessorRuntime` junta os dois para que cada processador
+//! guarde um único campo e use os mesmos construtores (ver
+//! `processor_constructors!`).
+
+// Devolve o `Result` do crate; o tamanho de `AppError` é definido em `error_handling`
+#![allow(clippy::result_large_err)]
+
+use rayon::ThreadPool;
+use std::sync::Arc;
+
+use crate::api::file_handlers::FileHandler;
+use crate::utils::compute;
+use crate::utils::config::PerformanceConfig;
+use crate::utils::error_handling::Result;
+
+/// Acesso a arquivos e pool de CPU de um processador
+#[derive(Debug, Clone)]
+pub struct ProcessorRuntime {
+    file_handler: FileHandler,
+    /// Threads do pool de CPU onde o processamento é executado
+    cpu_threads: usize,
+}
+
+impl ProcessorRuntime {
+    /// Cria um runtime com o FileHandler e a configuração de performance padrão
+    pub fn new() -> Self {
+        Self::with_file_handler(FileHandler::new())
+    }
+
+    /// Cria um runtime com um FileHandler específico
+    pub fn with_file_handler(file_handler: FileHandler) -> Self {
+        Self::from_parts(file_handler, &PerformanceConfig::default())
+    }
+
+    /// Cria um runtime com o paralelismo definido na configuração de performance
+    pub fn with_performance_config(performance: &PerformanceConfig) -> Self {
+        Self::from_parts(FileHandler::new(), performance)
+    }
+
+    /// Cria um runtime a partir das duas dependências
+    pub fn from_parts(file_handler: FileHandler, performance: &PerformanceConfig) -> Self {
+        Self {
+            file_handler,
+            cpu_threads: performance.cpu_threads,
+        }
+    }
+
+    /// FileHandler usado para validar e acessar arquivos
+    pub fn file_handler(&self) -> &FileHandler {
+        &self.file_handler
+    }
+
+    /// Threads do pool de CPU
+    pub fn cpu_threads(&self) -> usize {
+        self.cpu_threads
+    }
+
+    /// Pool compartilhado com o número de threads configurado
+    pub fn thread_pool(&self) -> Result<Arc<ThreadPool>> {
+        compute::thread_pool(self.cpu_threads)
+    }
+
+    /// Executa `job` no pool de CPU sem bloquear o runtime tokio (ver `compute::spawn`)
+    pub async fn spawn<T, F>(&self, job: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        compute::spawn(self.cpu_threads, job).await
+    }
+}
+
+impl Default for ProcessorRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
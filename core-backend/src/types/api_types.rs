@@ -42,6 +42,30 @@ pub enum ApiAction {
     Validate,
     /// Obtenção de metadados de PDF
     GetMetadata,
+    /// Cópia de PDF sem conteúdo ativo
+    Sanitize,
+    /// Listagem das anotações de um PDF
+    ListAnnotations,
+    /// Histórico de atualizações incrementais de um PDF
+    AnalyzeRevisions,
+    /// Verificação offline das assinaturas digitais de um PDF
+    VerifySignatures,
+    /// Assinatura digital PAdES com certificado PKCS#12
+    Sign,
+    /// Listagem dos arquivos anexados a um PDF
+    ListAttachments,
+    /// Extração dos arquivos anexados para um diretório
+    ExtractAttachments,
+    /// Inclusão de arquivo anexado
+    AddAttachment,
+    /// Remoção de arquivos anexados
+    RemoveAttachments,
+    /// Edição de metadados (Info e XMP)
+    SetMetadata,
+    /// Edição de metadados de vários arquivos com o mesmo patch
+    SetMetadataBulk,
+    /// Extração de texto das páginas
+    ExtractText,
     /// Verificação de saúde do backend
     HealthCheck,
     /// Listagem de arquivos em diretório
@@ -68,6 +92,18 @@ impl ApiAction {
             "split" => Ok(Self::Split),
             "validate" => Ok(Self::Validate),
             "get_metadata" => Ok(Self::GetMetadata),
+            "sanitize" => Ok(Self::Sanitize),
+            "list_annotations" => Ok(Self::ListAnnotations),
+            "analyze_revisions" => Ok(Self::AnalyzeRevisions),
+            "verify_signatures" => Ok(Self::VerifySignatures),
+            "sign" => Ok(Self::Sign),
+            "list_attachments" => Ok(Self::ListAttachments),
+            "extract_attachments" => Ok(Self::ExtractAttachments),
+            "add_attachment" => Ok(Self::AddAttachment),
+            "remove_attachments" => Ok(Self::RemoveAttachments),
+            "set_metadata" => Ok(Self::SetMetadata),
+            "set_metadata_bulk" => Ok(Self::SetMetadataBulk),
+            "extract_text" => Ok(Self::ExtractText),
             "health_check" | "health" => Ok(Self::HealthCheck),
             "list_files" => Ok(Self::ListFiles),
             "create_directory" => Ok(Self::CreateDirectory),
@@ -87,6 +123,18 @@ impl ApiAction {
             Self::Split => "split",
             Self::Validate => "validate",
             Self::GetMetadata => "get_metadata",
+            Self::Sanitize => "sanitize",
+            Self::ListAnnotations => "list_annotations",
+            Self::AnalyzeRevisions => "analyze_revisions",
+            Self::VerifySignatures => "verify_signatures",
+            Self::Sign => "sign",
+            Self::ListAttachments => "list_attachments",
+            Self::ExtractAttachments => "extract_attachments",
+            Self::AddAttachment => "add_attachment",
+            Self::RemoveAttachments => "remove_attachments",
+            Self::SetMetadata => "set_metadata",
+            Self::SetMetadataBulk => "set_metadata_bulk",
+            Self::ExtractText => "extract_text",
             Self::HealthCheck => "health_check",
             Self::ListFiles => "list_files",
             Self::CreateDirectory => "create_directory",
@@ -375,7 +423,7 @@ impl std::fmt::Display for ApiError {
 impl std::error::Error for ApiError {}
 
 /// Tipo de erro
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ErrorType {
     /// Erro de validação de entrada
     Validation,
@@ -663,8 +711,9 @@ fn generate_request_id() -> String {
 /// Lista de ações suportadas (para mensagens de erro)
 fn supported_actions_list() -> String {
     vec![
-        "merge", "split", "validate", "get_metadata",
-        "health_check", "list_files", "create_directory", "remove_path"
+        "merge", "split", "validate", "get_metadata", "sanitize", "list_annotations",
+        "analyze_revisions", "verify_signatures", "sign", "list_attachments", "extract_attachments",
+        "add_attachment", "remove_attachments", "set_metadata", "set_metadata_bulk", "extract_text", "health_check", "list_files", "create_directory", "remove_path"
     ].join(", ")
 }
 
@@ -780,7 +829,7 @@ mod tests {
     #[test]
     fn test_api_response_error() {
         let error = ApiError::validation("Invalid input", Some("details".to_string()));
-        let response = ApiResponse::error("req123", error);
+        let response: ApiResponse = ApiResponse::error("req123", error);
         
         assert_eq!(response.request_id, "req123");
         assert!(!response.success);
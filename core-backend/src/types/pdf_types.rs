@@ -323,7 +323,7 @@ impl Default for PdfMetadata {
             creation_date: None,
             modification_date: None,
             encrypted: false,
-            permissions: PdfPermissions::default(),
+            permissions: PdfPermissions::unrestricted(),
             pages: Vec::new(),
             embedded_fonts: Vec::new(),
             images: Vec::new(),
@@ -362,7 +362,7 @@ pub struct PdfSummary {
 }
 
 /// Permissões do PDF (se criptografado)
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PdfPermissions {
     /// Permite impressão
     pub allow_printing: bool,
@@ -380,6 +380,57 @@ pub struct PdfPermissions {
     pub allow_document_assembly: bool,
 }
 
+impl PdfPermissions {
+    /// Todas as operações permitidas (documento sem criptografia)
+    pub fn unrestricted() -> Self {
+        Self {
+            allow_printing: true,
+            allow_modification: true,
+            allow_copy_text: true,
+            allow_annotations: true,
+            allow_form_filling: true,
+            allow_content_extraction: true,
+            allow_document_assembly: true,
+        }
+    }
+
+    /// Interpreta os bits de `/P` do dicionário `/Encrypt`
+    ///
+    /// Na revisão 2 os bits 9-12 não existem: formulários seguem o bit 6,
+    /// a extração segue o bit 5 e a montagem segue o bit 4.
+    pub fn from_bits(bits: i32, revision: i64) -> Self {
+        let bit = |position: u32| bits & (1 << (position - 1)) != 0;
+        let extended = revision >= 3;
+
+        Self {
+            allow_printing: bit(3),
+            allow_modification: bit(4),
+            allow_copy_text: bit(5),
+            allow_annotations: bit(6),
+            allow_form_filling: bit(6) || (extended && bit(9)),
+            allow_content_extraction: if extended { bit(10) || bit(5) } else { bit(5) },
+            allow_document_assembly: if extended { bit(11) } else { bit(4) },
+        }
+    }
+
+    /// Operações bloqueadas, em inglês, para mensagens ao usuário
+    pub fn restrictions(&self) -> Vec<&'static str> {
+        [
+            (self.allow_printing, "printing"),
+            (self.allow_modification, "modification"),
+            (self.allow_copy_text, "copying text"),
+            (self.allow_annotations, "annotations"),
+            (self.allow_form_filling, "form filling"),
+            (self.allow_content_extraction, "content extraction"),
+            (self.allow_document_assembly, "page assembly"),
+        ]
+        .into_iter()
+        .filter(|(allowed, _)| !allowed)
+        .map(|(_, name)| name)
+        .collect()
+    }
+}
+
 /// Informações de uma página específica
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PageInfo {
@@ -389,7 +440,19 @@ pub struct PageInfo {
     pub dimensions: (f64, f64),
     /// Rotação (0, 90, 180, 270)
     pub rotation: u32,
-    /// Conteúdo da página (texto extraído, se disponível)
+    /// `/MediaBox` efetivo (x1, y1, x2, y2), após herança
+    #[serde(default)]
+    pub media_box: (f64, f64, f64, f64),
+    /// `/CropBox` efetivo (x1, y1, x2, y2), limitado ao `/MediaBox`
+    #[serde(default)]
+    pub crop_box: (f64, f64, f64, f64),
+    /// Orientação como exibida (considera a rotação)
+    #[serde(default)]
+    pub orientation: PageOrientation,
+    /// Formato de papel reconhecido (ex.: "A4", "Letter")
+    #[serde(default)]
+    pub paper_size: Option<String>,
+    /// Texto extraído da página (apenas na análise detalhada, via `pdf_text`)
     pub content: Option<String>,
     /// Número de objetos na página
     pub object_count: usize,
@@ -399,6 +462,28 @@ pub struct PageInfo {
     pub has_text: bool,
 }
 
+/// Orientação de uma página
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PageOrientation {
+    #[default]
+    Portrait,
+    Landscape,
+    Square,
+}
+
+impl PageOrientation {
+    /// Orientação de uma área exibida com `width` x `height`
+    pub fn from_dimensions(width: f64, height: f64) -> Self {
+        if (width - height).abs() < 1.0 {
+            Self::Square
+        } else if width > height {
+            Self::Landscape
+        } else {
+            Self::Portrait
+        }
+    }
+}
+
 /// Informações de fonte incorporada
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FontInfo {
@@ -406,10 +491,13 @@ pub struct FontInfo {
     pub font_type: FontType,
     pub embedded: bool,
     pub subset: bool,
+    /// Páginas em que a fonte é usada (1-indexed, em ordem)
+    #[serde(default)]
+    pub pages: Vec<u32>,
 }
 
 /// Tipo de fonte
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum FontType {
     Type0,
     Type1,
@@ -421,6 +509,22 @@ pub enum FontType {
     Unknown,
 }
 
+impl FontType {
+    /// Converte o valor de `/Subtype` de um dicionário de fonte
+    pub fn from_subtype(subtype: &[u8]) -> Self {
+        match subtype {
+            b"Type0" => Self::Type0,
+            b"Type1" => Self::Type1,
+            b"MMType1" => Self::MMType1,
+            b"Type3" => Self::Type3,
+            b"TrueType" => Self::TrueType,
+            b"CIDFontType0" => Self::CIDFontType0,
+            b"CIDFontType2" => Self::CIDFontType2,
+            _ => Self::Unknown,
+        }
+    }
+}
+
 /// Informações de imagem
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageInfo {
@@ -431,10 +535,54 @@ pub struct ImageInfo {
     pub bits_per_component: u8,
     pub compressed: bool,
     pub size_bytes: Option<u64>,
+    /// Objeto do Image XObject (`None` para imagens inline)
+    #[serde(default)]
+    pub object_id: Option<u32>,
+    /// Imagem inline (`BI ... EI`) no content stream
+    #[serde(default)]
+    pub inline: bool,
+    /// Onde a imagem é desenhada, uma entrada por uso
+    #[serde(default)]
+    pub placements: Vec<ImagePlacement>,
 }
 
-/// Formato de imagem
+impl ImageInfo {
+    /// Maior resolução efetiva entre os usos da imagem
+    pub fn max_dpi(&self) -> Option<f64> {
+        self.placements
+            .iter()
+            .filter_map(ImagePlacement::effective_dpi)
+            .fold(None, |max, dpi| Some(max.map_or(dpi, |max: f64| max.max(dpi))))
+    }
+}
+
+/// Uso de uma imagem em uma página
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImagePlacement {
+    /// Página (1-indexed)
+    pub page: u32,
+    /// Largura desenhada em pontos
+    pub width_pt: f64,
+    /// Altura desenhada em pontos
+    pub height_pt: f64,
+    /// Resolução horizontal efetiva (pixels por polegada)
+    pub dpi_x: Option<f64>,
+    /// Resolução vertical efetiva (pixels por polegada)
+    pub dpi_y: Option<f64>,
+}
+
+impl ImagePlacement {
+    /// Resolução efetiva: a menor das duas direções
+    pub fn effective_dpi(&self) -> Option<f64> {
+        match (self.dpi_x, self.dpi_y) {
+            (Some(x), Some(y)) => Some(x.min(y)),
+            (x, y) => x.or(y),
+        }
+    }
+}
+
+/// Formato de imagem
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ImageFormat {
     JPEG,
     JPEG2000,
@@ -444,8 +592,24 @@ pub enum ImageFormat {
     Unknown,
 }
 
+impl ImageFormat {
+    /// Deduz o formato a partir do codec de imagem da cadeia `/Filter`
+    pub fn from_filters<T: AsRef<[u8]>>(filters: &[T]) -> Self {
+        filters
+            .iter()
+            .find_map(|filter| match filter.as_ref() {
+                b"DCTDecode" | b"DCT" => Some(Self::JPEG),
+                b"JPXDecode" => Some(Self::JPEG2000),
+                b"CCITTFaxDecode" | b"CCF" => Some(Self::CCITTFax),
+                b"JBIG2Decode" => Some(Self::JBIG2),
+                _ => None,
+            })
+            .unwrap_or(Self::Raw)
+    }
+}
+
 /// Espaço de cor
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ColorSpace {
     DeviceGray,
     DeviceRGB,
@@ -461,6 +625,33 @@ pub enum ColorSpace {
     Unknown,
 }
 
+impl ColorSpace {
+    /// Converte o nome da família do espaço de cor (ex.: `/DeviceRGB`, `/ICCBased`)
+    pub fn from_name(name: &[u8]) -> Self {
+        match name {
+            b"DeviceGray" | b"G" => Self::DeviceGray,
+            b"DeviceRGB" | b"RGB" => Self::DeviceRGB,
+            b"DeviceCMYK" | b"CMYK" => Self::DeviceCMYK,
+            b"CalGray" => Self::CalGray,
+            b"CalRGB" => Self::CalRGB,
+            b"Lab" => Self::Lab,
+            b"ICCBased" => Self::ICCBased,
+            b"Indexed" | b"I" => Self::Indexed,
+            b"Pattern" => Self::Pattern,
+            b"Separation" => Self::Separation,
+            b"DeviceN" => Self::DeviceN,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Espaço de cor com mais de um componente de cor
+    ///
+    /// `ICCBased` e `Indexed` dependem do perfil/base e são tratados como cor.
+    pub fn is_color(&self) -> bool {
+        !matches!(self, Self::DeviceGray | Self::CalGray | Self::Unknown)
+    }
+}
+
 /// Informações de anotação
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnnotationInfo {
@@ -470,10 +661,13 @@ pub struct AnnotationInfo {
     pub contents: Option<String>,
     pub author: Option<String>,
     pub creation_date: Option<String>,
+    /// Objeto da anotação (`None` para dicionários diretos em `/Annots`)
+    #[serde(default)]
+    pub object_id: Option<u32>,
 }
 
 /// Tipo de anotação
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum AnnotationType {
     Text,
     Link,
@@ -504,11 +698,48 @@ pub enum AnnotationType {
     Unknown,
 }
 
+impl AnnotationType {
+    /// Converte o valor de `/Subtype` de um dicionário de anotação
+    pub fn from_subtype(subtype: &[u8]) -> Self {
+        match subtype {
+            b"Text" => Self::Text,
+            b"Link" => Self::Link,
+            b"FreeText" => Self::FreeText,
+            b"Line" => Self::Line,
+            b"Square" => Self::Square,
+            b"Circle" => Self::Circle,
+            b"Polygon" => Self::Polygon,
+            b"PolyLine" => Self::PolyLine,
+            b"Highlight" => Self::Highlight,
+            b"Underline" => Self::Underline,
+            b"Squiggly" => Self::Squiggly,
+            b"StrikeOut" => Self::StrikeOut,
+            b"Stamp" => Self::Stamp,
+            b"Caret" => Self::Caret,
+            b"Ink" => Self::Ink,
+            b"Popup" => Self::Popup,
+            b"FileAttachment" => Self::FileAttachment,
+            b"Sound" => Self::Sound,
+            b"Movie" => Self::Movie,
+            b"Widget" => Self::Widget,
+            b"Screen" => Self::Screen,
+            b"PrinterMark" => Self::PrinterMark,
+            b"TrapNet" => Self::TrapNet,
+            b"Watermark" => Self::Watermark,
+            b"3D" => Self::ThreeD,
+            b"Redact" => Self::Redact,
+            _ => Self::Unknown,
+        }
+    }
+}
+
 /// Informações de bookmark
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BookmarkInfo {
     pub title: String,
+    /// Profundidade na árvore (1 = primeiro nível)
     pub level: u32,
+    /// Página de destino (1-indexed; 0 quando o destino não é uma página do documento)
     pub page: u32,
     pub children: Vec<BookmarkInfo>,
 }
@@ -917,6 +1148,9 @@ mod tests {
                 bits_per_component: 8,
                 compressed: true,
                 size_bytes: Some(102400),
+                object_id: Some(5),
+                inline: false,
+                placements: vec![],
             }],
             annotations: vec![],
             bookmarks: vec![],
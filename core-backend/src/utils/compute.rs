@@ -0,0 +1,112 @@
+//! Pool de processamento CPU do DocHub
+//!
+//! Centraliza os pools de threads usados pelas operações pesadas (split,
+//! merge, validação), evitando que cada requisição crie suas próprias threads.
+//!
+//! ## Funcionalidades:
+//! - Pools `rayon` compartilhados, um por tamanho configurado
+//! - Tamanho derivado de `PerformanceConfig::cpu_threads`
+//! - Ponte assíncrona (`spawn`) para tirar trabalho CPU do runtime tokio
+
+// Os jobs falham com `AppError`, cujo tamanho é decisão de `error_handling`
+#![allow(clippy::result_large_err)]
+
+use once_cell::sync::Lazy;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+use tracing::{error, info, Span};
+
+use crate::utils::error_handling::{AppError, Result};
+
+/// Pools já criados, indexados pelo número de threads
+static POOLS: Lazy<Mutex<HashMap<usize, Arc<ThreadPool>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Retorna o pool compartilhado com `threads` threads (mínimo 1)
+pub fn thread_pool(threads: usize) -> Result<Arc<ThreadPool>> {
+    let threads = threads.max(1);
+    let mut pools = POOLS
+        .lock()
+        .map_err(|_| AppError::processing("Compute pool registry poisoned"))?;
+
+    if let Some(pool) = pools.get(&threads) {
+        return Ok(Arc::clone(pool));
+    }
+
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|index| format!("dochub-cpu-{}", index))
+        .build()
+        .map_err(|e| AppError::processing(format!("Failed to build compute pool: {}", e)))?;
+
+    info!(threads, "Compute pool created");
+
+    let pool = Arc::new(pool);
+    pools.insert(threads, Arc::clone(&pool));
+    Ok(pool)
+}
+
+/// Executa `job` no pool de CPU e aguarda o resultado sem bloquear o runtime
+///
+/// O span atual é propagado para a thread de trabalho, e um panic no job vira
+/// um erro de processamento em vez de derrubar o processo.
+pub async fn spawn<T, F>(threads: usize, job: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let pool = thread_pool(threads)?;
+    let (sender, receiver) = oneshot::channel();
+    let span = Span::current();
+
+    pool.spawn(move || {
+        let _entered = span.enter();
+        let result = panic::catch_unwind(AssertUnwindSafe(job)).unwrap_or_else(|_| {
+            error!("Compute job panicked");
+            Err(AppError::processing("Compute job panicked"))
+        });
+        // O receptor pode ter sido descartado se a requisição foi cancelada
+        let _ = sender.send(result);
+    });
+
+    receiver
+        .await
+        .map_err(|_| AppError::processing("Compute job was dropped before completing"))?
+}
+
+// ==================== TESTES ====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_pool_is_shared_by_size() -> Result<()> {
+        let first = thread_pool(2)?;
+        let second = thread_pool(2)?;
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(first.current_num_threads(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_thread_pool_minimum_size() -> Result<()> {
+        assert_eq!(thread_pool(0)?.current_num_threads(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spawn_runs_off_runtime_thread() -> Result<()> {
+        let name = spawn(1, || Ok(std::thread::current().name().map(String::from))).await?;
+        assert_eq!(name.as_deref(), Some("dochub-cpu-0"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spawn_converts_panic_into_error() {
+        let result: Result<()> = spawn(1, || panic!("boom")).await;
+        assert!(result.is_err());
+    }
+}
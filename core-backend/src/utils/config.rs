@@ -1050,7 +1050,6 @@ pub async fn get_config() -> Result<Arc<AppConfig>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::NamedTempFile;
 
     #[test]
     fn test_environment_detection() {
@@ -1127,10 +1126,11 @@ mod tests {
 
     #[tokio::test]
     async fn test_config_file_creation() -> Result<()> {
-        let temp_file = NamedTempFile::new()?;
+        let temp_file = tempfile::Builder::new().suffix(".toml").tempfile()?;
         let config = AppConfig::default()?;
         
-        let toml = toml::to_string_pretty(&config)?;
+        let toml = toml::to_string_pretty(&config)
+            .map_err(|e| AppError::serialization(format!("Failed to serialize config: {}", e)))?;
         fs::write(temp_file.path(), toml)?;
         
         // Tenta carregar o arquivo
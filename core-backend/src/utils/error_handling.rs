@@ -10,7 +10,7 @@
 //! 
 //! ## Uso:
 //! ```rust
-//! use crate::utils::error_handling::{Result, AppError, ValidationError};
+//! use dochub_backend::utils::error_handling::{Result, AppError};
 //! 
 //! fn validate_input(input: &str) -> Result<()> {
 //!     if input.is_empty() {
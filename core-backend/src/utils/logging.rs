@@ -12,14 +12,15 @@
 //!
 //! ## Uso:
 //! ```rust
-//! use crate::utils::logging::{init_logging, log_info};
+//! use dochub_backend::utils::logging::{init_logging, log_info};
 //!
 //! // Inicializar logging no main
 //! init_logging()?;
 //!
 //! // Usar macros de logging
 //! log_info("Application started");
-//! tracing::info!("User logged in", user_id = 123);
+//! tracing::info!(user_id = 123, "User logged in");
+//! # Ok::<(), dochub_backend::AppError>(())
 //! ```
 
 use tracing::{debug, info, warn, error, Level};
@@ -4,6 +4,7 @@
 //! Inclui configuração, tratamento de erros, logging e outras funcionalidades comuns.
 //!
 //! ## Organização:
+//! - `compute`: Pools de threads para processamento CPU
 //! - `config`: Sistema de configuração com hot-reloading
 //! - `error_handling`: Hierarquia de erros tipados
 //! - `logging`: Utilitários de logging estruturado
@@ -14,6 +15,7 @@
 //! 3. Documentação completa
 //! 4. Testabilidade
 
+pub mod compute;
 pub mod config;
 pub mod error_handling;
 pub mod logging;